@@ -0,0 +1,33 @@
+//! `jsonschemars bundle` subcommand: pull external `$ref` targets into one schema document.
+
+use super::utils::{
+    SourceFormat, SourceText, parse_schema_source, read_source, write_schema_output,
+};
+use json_schema_rs::{JsonSchema, JsonSchemaSettings, bundle, bundle_from_path};
+use std::path::Path;
+
+/// Bundles the schema at `schema_path` (or stdin in `stdin_format` for `-`, with refs relative to
+/// the current directory) and writes it to `output`, or stdout when `None`.
+pub(crate) fn run_bundle(
+    schema_path: &Path,
+    stdin_format: SourceFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let bundled: JsonSchema = bundle_schema(schema_path, stdin_format)?;
+    write_schema_output(&bundled, output)
+}
+
+/// Bundles the schema at `schema_path`; shared with `deref`, which inlines the bundled result.
+pub(crate) fn bundle_schema(
+    schema_path: &Path,
+    stdin_format: SourceFormat,
+) -> Result<JsonSchema, String> {
+    let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+    if schema_path.as_os_str() == "-" {
+        let source: SourceText = read_source(schema_path, "schema", stdin_format)?;
+        let schema: JsonSchema = parse_schema_source(&source, &settings)?;
+        bundle(&schema, Path::new("."), &settings).map_err(|e| e.to_string())
+    } else {
+        bundle_from_path(schema_path, &settings).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,36 @@
+//! `jsonschemars check-schema` subcommand: validate schema documents against their meta-schema.
+
+use super::utils::{
+    SourceFormat, SourceText, parse_document_source, read_source, with_pointer_excerpt,
+};
+use json_schema_rs::{JsonSchema, JsonSchemaSettings};
+use std::path::PathBuf;
+
+/// Checks each schema file against the meta-schema of its draft (from `$schema`, default
+/// 2020-12). `-` reads stdin in `stdin_format`. Every error is printed to stderr with the
+/// offending source line.
+pub(crate) fn run_check_schema(
+    schema_paths: &[PathBuf],
+    stdin_format: SourceFormat,
+) -> Result<(), String> {
+    let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+    let mut error_count: usize = 0;
+    for path in schema_paths {
+        let source: SourceText = read_source(path, "schema", stdin_format)?;
+        let (document, source_map) = parse_document_source(&source, "schema")?;
+        if let Err(errors) = JsonSchema::validate_against_metaschema(&document, &settings) {
+            for e in &errors {
+                eprintln!(
+                    "{}",
+                    with_pointer_excerpt(&e.to_string(), &source, &source_map, e.instance_path())
+                );
+            }
+            error_count += errors.len();
+        }
+    }
+    if error_count == 0 {
+        Ok(())
+    } else {
+        Err(format!("schema check failed with {error_count} error(s)"))
+    }
+}
@@ -0,0 +1,18 @@
+//! `jsonschemars deref` subcommand: inline every `$ref`, keeping only cyclic ones.
+
+use super::bundle::bundle_schema;
+use super::utils::{SourceFormat, write_schema_output};
+use json_schema_rs::{JsonSchema, deref};
+use std::path::Path;
+
+/// Bundles the schema at `schema_path` (so refs to other files work; `-` reads stdin in
+/// `stdin_format`), inlines its refs, and writes it to `output`, or stdout when `None`.
+pub(crate) fn run_deref(
+    schema_path: &Path,
+    stdin_format: SourceFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let bundled: JsonSchema = bundle_schema(schema_path, stdin_format)?;
+    let dereferenced: JsonSchema = deref(&bundled).map_err(|e| e.to_string())?;
+    write_schema_output(&dereferenced, output)
+}
@@ -0,0 +1,44 @@
+//! `jsonschemars diff` subcommand: classify the changes between two schema versions.
+
+use super::utils::{SourceFormat, SourceText, parse_schema_source, read_source};
+use json_schema_rs::{Compatibility, JsonSchema, JsonSchemaSettings, SchemaChange, diff};
+use std::path::Path;
+
+/// Diffs `old_path` against `new_path` (`-` reads stdin in `stdin_format`) and prints every
+/// change to stdout as a JSON array of `{path, kind, compatibility, message}` objects. Fails when
+/// any change is breaking.
+pub(crate) fn run_diff(
+    old_path: &Path,
+    new_path: &Path,
+    stdin_format: SourceFormat,
+) -> Result<(), String> {
+    let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+    let old_source: SourceText = read_source(old_path, "old schema", stdin_format)?;
+    let old: JsonSchema = parse_schema_source(&old_source, &settings)?;
+    let new_source: SourceText = read_source(new_path, "new schema", stdin_format)?;
+    let new: JsonSchema = parse_schema_source(&new_source, &settings)?;
+    let changes: Vec<SchemaChange> = diff(&old, &new);
+    let report: Vec<serde_json::Value> = changes
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "path": c.schema_path().as_str(),
+                "kind": c.kind(),
+                "compatibility": c.compatibility().to_string(),
+                "message": c.to_string(),
+            })
+        })
+        .collect();
+    let json: String = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("failed to serialize diff: {e}"))?;
+    println!("{json}");
+    let breaking: usize = changes
+        .iter()
+        .filter(|c| c.compatibility() == Compatibility::Breaking)
+        .count();
+    if breaking > 0 {
+        Err(format!("found {breaking} breaking change(s)"))
+    } else {
+        Ok(())
+    }
+}
@@ -1,24 +1,34 @@
 //! `jsonschemars generate` subcommand: generate Rust from JSON Schema files.
 
 use super::utils::{
-    collect_schema_entries, read_schema_from_path, shared_import_prefix_for_output_relative,
-    write_mod_rs_files,
+    SourceFormat, collect_schema_entries, read_schema_from_path,
+    shared_import_prefix_for_output_relative, write_mod_rs_files,
 };
 use json_schema_rs::{
-    CodeGenSettings, DedupeMode, JsonSchema, JsonSchemaSettings, ModelNameSource, generate_rust,
+    CodeGenSettings, DedupeMode, JsonSchema, JsonSchemaSettings, ModelNameSource,
+    NameCollisionStrategy, generate_rust,
 };
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-#[expect(clippy::too_many_lines)]
+#[expect(clippy::too_many_arguments, clippy::too_many_lines)]
 pub(crate) fn run_generate(
     lang: &str,
     inputs: &[String],
+    stdin_format: SourceFormat,
     output_dir: &Path,
     jss_disallow_unknown_fields: bool,
     cgs_model_name_source: Option<&str>,
     cgs_dedupe_mode: Option<&str>,
+    cgs_name_collision: Option<&str>,
+    cgs_emit_all_defs: bool,
+    cgs_enforce_constraints: bool,
+    cgs_derives: &[String],
+    cgs_attributes: &[String],
+    cgs_type_derives: &[String],
+    cgs_type_attributes: &[String],
+    cgs_type_mappings: &[String],
 ) -> Result<(), String> {
     if !lang.eq_ignore_ascii_case("rust") {
         return Err(format!("unsupported language: {lang}; supported: rust"));
@@ -51,6 +61,32 @@ pub(crate) fn run_generate(
                 _ => DedupeMode::Full,
             });
         }
+        if let Some(strategy) = cgs_name_collision {
+            b = b.name_collision(match strategy {
+                "parent-prefix" => NameCollisionStrategy::ParentPrefix,
+                _ => NameCollisionStrategy::NumericSuffix,
+            });
+        }
+        b = b.emit_all_defs(cgs_emit_all_defs);
+        b = b.enforce_constraints(cgs_enforce_constraints);
+        for derive in cgs_derives {
+            b = b.derive(derive);
+        }
+        for attribute in cgs_attributes {
+            b = b.attribute(attribute);
+        }
+        for entry in cgs_type_derives {
+            let (key, derive) = split_type_setting("--cgs-type-derive", entry)?;
+            b = b.type_derive(key, derive);
+        }
+        for entry in cgs_type_attributes {
+            let (key, attribute) = split_type_setting("--cgs-type-attribute", entry)?;
+            b = b.type_attribute(key, attribute);
+        }
+        for entry in cgs_type_mappings {
+            let (key, rust_type) = split_type_setting("--cgs-type-mapping", entry)?;
+            b = b.type_mapping(key, rust_type);
+        }
         b.build()
     };
 
@@ -58,7 +94,7 @@ pub(crate) fn run_generate(
     let mut successful: Vec<(JsonSchema, PathBuf)> = Vec::with_capacity(entries.len());
     let mut had_errors = false;
     for (input_path, output_relative) in &entries {
-        match read_schema_from_path(input_path, &schema_settings) {
+        match read_schema_from_path(input_path, &schema_settings, stdin_format) {
             Ok(schema) => successful.push((schema, output_relative.clone())),
             Err(e) => {
                 let path_display = if input_path.as_os_str() == "-" {
@@ -81,6 +117,17 @@ pub(crate) fn run_generate(
     let (schemas, output_relatives): (Vec<JsonSchema>, Vec<PathBuf>) =
         successful.into_iter().unzip();
     let output = generate_rust(&schemas, &code_gen_settings).map_err(|e| e.to_string())?;
+    for skipped in &output.skipped_derives {
+        eprintln!("warning: {skipped}");
+    }
+    for skipped in &output.skipped_defs {
+        eprintln!("warning: {skipped}");
+    }
+    for selector in &output.unmatched_type_selectors {
+        eprintln!(
+            "warning: type selector \"{selector}\" matches no generated type; its derives and attributes were not applied"
+        );
+    }
     assert_eq!(
         output.per_schema.len(),
         output_relatives.len(),
@@ -134,3 +181,11 @@ pub(crate) fn run_generate(
     write_mod_rs_files(output_dir, &mod_relatives)?;
     Ok(())
 }
+
+/// Splits a `TYPE=VALUE` argument of `flag` at the first `=`.
+fn split_type_setting<'a>(flag: &str, entry: &'a str) -> Result<(&'a str, &'a str), String> {
+    entry
+        .split_once('=')
+        .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+        .ok_or_else(|| format!("{flag} expects TYPE=VALUE, got \"{entry}\""))
+}
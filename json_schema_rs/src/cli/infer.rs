@@ -0,0 +1,53 @@
+//! `jsonschemars infer` subcommand: infer a schema from sample JSON or NDJSON files.
+
+use super::utils::write_schema_output;
+use json_schema_rs::{InferSettings, JsonSchema, SchemaInferrer};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Merges every sample in `inputs` into one inferred schema and writes it to `output`, or stdout
+/// when `None`. Formats and string enums are inferred per `detect_formats` and
+/// `max_enum_values` (see [`InferSettings`]). Each input is one JSON document, or one sample per line when `ndjson` is set or
+/// its extension is `.ndjson` or `.jsonl`; `-` reads stdin.
+pub(crate) fn run_infer(
+    inputs: &[PathBuf],
+    ndjson: bool,
+    detect_formats: bool,
+    max_enum_values: Option<usize>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let mut settings_builder = InferSettings::builder().detect_formats(detect_formats);
+    if let Some(max) = max_enum_values {
+        settings_builder = settings_builder.max_enum_values(max);
+    }
+    let mut inferrer: SchemaInferrer = SchemaInferrer::new(settings_builder.build());
+    for path in inputs {
+        let (label, mut reader): (String, Box<dyn BufRead>) = if path.as_os_str() == "-" {
+            ("stdin".to_string(), Box::new(io::stdin().lock()))
+        } else {
+            let file: File =
+                File::open(path).map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+            (path.display().to_string(), Box::new(BufReader::new(file)))
+        };
+        let is_ndjson: bool = ndjson
+            || path
+                .extension()
+                .is_some_and(|ext| ext == "ndjson" || ext == "jsonl");
+        if is_ndjson {
+            inferrer
+                .add_ndjson(reader)
+                .map_err(|e| format!("{label}: {e}"))?;
+        } else {
+            let mut buf: Vec<u8> = Vec::new();
+            reader
+                .read_to_end(&mut buf)
+                .map_err(|e| format!("failed to read {label}: {e}"))?;
+            let sample: serde_json::Value =
+                serde_json::from_slice(&buf).map_err(|e| format!("{label}: invalid JSON: {e}"))?;
+            inferrer.add_sample(&sample);
+        }
+    }
+    let schema: JsonSchema = inferrer.finish();
+    write_schema_output(&schema, output)
+}
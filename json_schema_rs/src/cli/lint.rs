@@ -0,0 +1,51 @@
+//! `jsonschemars lint` subcommand: report unsatisfiable, contradictory, and suspicious schemas.
+
+use super::utils::{
+    SourceFormat, SourceText, parse_schema_source, read_source, source_map_for,
+    with_pointer_excerpt,
+};
+use json_schema_rs::{
+    JsonSchema, JsonSchemaSettings, LintDiagnostic, LintSeverity, SourceMap, lint,
+};
+use std::path::PathBuf;
+
+/// Lints each schema file (`-` reads stdin in `stdin_format`) and prints every diagnostic to
+/// stderr with its severity and the offending source line. Fails when any error is found, or any
+/// warning with `deny_warnings`.
+pub(crate) fn run_lint(
+    schema_paths: &[PathBuf],
+    stdin_format: SourceFormat,
+    deny_warnings: bool,
+) -> Result<(), String> {
+    let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+    let mut error_count: usize = 0;
+    let mut warning_count: usize = 0;
+    for path in schema_paths {
+        let source: SourceText = read_source(path, "schema", stdin_format)?;
+        let schema: JsonSchema = parse_schema_source(&source, &settings)?;
+        let source_map: SourceMap = source_map_for(&source);
+        let diagnostics: Vec<LintDiagnostic> = lint(&schema);
+        for d in &diagnostics {
+            match d.severity() {
+                LintSeverity::Error => error_count += 1,
+                LintSeverity::Warning => warning_count += 1,
+            }
+            eprintln!(
+                "{}",
+                with_pointer_excerpt(
+                    &format!("{}: {d}", d.severity()),
+                    &source,
+                    &source_map,
+                    d.schema_path()
+                )
+            );
+        }
+    }
+    if error_count > 0 || (deny_warnings && warning_count > 0) {
+        Err(format!(
+            "lint failed with {error_count} error(s) and {warning_count} warning(s)"
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -1,16 +1,39 @@
 //! CLI entry and subcommand dispatch for jsonschemars.
 
+mod bundle;
+mod check_schema;
+mod deref;
+mod diff;
 mod generate;
+mod infer;
+mod lint;
 mod utils;
 mod validate;
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
 use std::path::PathBuf;
+use utils::SourceFormat;
+
+/// `--{id}`: the syntax of the `what` read from stdin, which has no extension to go by.
+fn stdin_format_arg(id: &'static str, what: &str) -> Arg {
+    Arg::new(id)
+        .long(id)
+        .value_name("FORMAT")
+        .value_parser(["json", "yaml"])
+        .help(format!(
+            "Syntax of the {what} read from stdin (default: json; yaml needs the yaml feature); files go by extension"
+        ))
+}
+
+/// The value of a [`stdin_format_arg`].
+fn stdin_format(matches: &ArgMatches, id: &str) -> SourceFormat {
+    SourceFormat::from_flag(matches.get_one::<String>(id).map(String::as_str))
+}
 
 #[expect(clippy::too_many_lines)]
 pub fn run() {
     let cmd = Command::new("jsonschemars")
-        .about("JSON Schema tooling: generate Rust types, validate JSON")
+        .about("JSON Schema tooling: generate Rust types, validate JSON, check, lint, bundle, dereference, diff, and infer schemas")
         .subcommand(
             Command::new("generate")
                 .about("Generate Rust from one or more JSON Schema files or directories")
@@ -33,8 +56,9 @@ pub fn run() {
                         .required(true)
                         .value_name("INPUT")
                         .num_args(1..)
-                        .help("JSON Schema file(s), directory(ies) to search for .json, or \"-\" for stdin"),
+                        .help("JSON Schema file(s), directory(ies) to search for .json (and .yaml/.yml with the yaml feature), or \"-\" for stdin"),
                 )
+                .arg(stdin_format_arg("schema-format", "schema"))
                 .arg(
                     Arg::new("jss-disallow-unknown-fields")
                         .long("jss-disallow-unknown-fields")
@@ -54,6 +78,60 @@ pub fn run() {
                         .value_name("MODE")
                         .value_parser(["disabled", "functional", "full"])
                         .help("Codegen Settings: dedupe identical object schemas (default: full)"),
+                )
+                .arg(
+                    Arg::new("cgs-name-collision")
+                        .long("cgs-name-collision")
+                        .value_name("STRATEGY")
+                        .value_parser(["numeric-suffix", "parent-prefix"])
+                        .help("Codegen Settings: how to rename distinct types that would share a name (default: numeric-suffix)"),
+                )
+                .arg(
+                    Arg::new("cgs-emit-all-defs")
+                        .long("cgs-emit-all-defs")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Codegen Settings: generate a type for every $defs/definitions entry, even when unreferenced"),
+                )
+                .arg(
+                    Arg::new("cgs-enforce-constraints")
+                        .long("cgs-enforce-constraints")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Codegen Settings: generate validated newtypes and validate() methods that enforce schema constraints on deserialize"),
+                )
+                .arg(
+                    Arg::new("cgs-derive")
+                        .long("cgs-derive")
+                        .value_name("TRAIT")
+                        .action(clap::ArgAction::Append)
+                        .help("Codegen Settings: extra derive for every generated type (repeatable); skipped with a warning where fields cannot support it"),
+                )
+                .arg(
+                    Arg::new("cgs-attribute")
+                        .long("cgs-attribute")
+                        .value_name("ATTR")
+                        .action(clap::ArgAction::Append)
+                        .help("Codegen Settings: attribute written verbatim on every generated type, e.g. \"#[non_exhaustive]\" (repeatable)"),
+                )
+                .arg(
+                    Arg::new("cgs-type-derive")
+                        .long("cgs-type-derive")
+                        .value_name("TYPE=TRAIT")
+                        .action(clap::ArgAction::Append)
+                        .help("Codegen Settings: extra derive for one type, selected by type name or JSON Pointer (repeatable)"),
+                )
+                .arg(
+                    Arg::new("cgs-type-attribute")
+                        .long("cgs-type-attribute")
+                        .value_name("TYPE=ATTR")
+                        .action(clap::ArgAction::Append)
+                        .help("Codegen Settings: attribute for one type, selected by type name or JSON Pointer (repeatable)"),
+                )
+                .arg(
+                    Arg::new("cgs-type-mapping")
+                        .long("cgs-type-mapping")
+                        .value_name("KEY=PATH")
+                        .action(clap::ArgAction::Append)
+                        .help("Codegen Settings: use an existing Rust type for schemas selected by JSON Pointer, $ref, $id, or title instead of generating one (repeatable)"),
                 ),
         )
         .subcommand(
@@ -65,20 +143,162 @@ pub fn run() {
                         .long("schema")
                         .value_name("FILE")
                         .required(true)
-                        .help("Path to the JSON Schema file. Use \"-\" for stdin."),
+                        .help("Path to the JSON Schema file (.yaml/.yml read as YAML). Use \"-\" for stdin."),
                 )
                 .arg(
                     Arg::new("payload")
                         .short('p')
                         .long("payload")
                         .value_name("FILE")
-                        .help("Path to the JSON payload to validate. If omitted, read from stdin."),
+                        .help("Path to the JSON payload to validate (.yaml/.yml read as YAML). If omitted, read from stdin."),
                 )
+                .arg(stdin_format_arg("schema-format", "schema"))
+                .arg(stdin_format_arg("payload-format", "payload"))
                 .arg(
                     Arg::new("jss-disallow-unknown-fields")
                         .long("jss-disallow-unknown-fields")
                         .action(clap::ArgAction::SetTrue)
                         .help("JSON Schema Settings: reject schema definitions with unknown keys"),
+                )
+                .arg(
+                    Arg::new("vs-format-mode")
+                        .long("vs-format-mode")
+                        .value_name("MODE")
+                        .value_parser(["annotation", "assertion"])
+                        .help("Validator Settings: treat format as annotation only or assert it (default: assertion)"),
+                )
+                .arg(
+                    Arg::new("vs-unknown-format")
+                        .long("vs-unknown-format")
+                        .value_name("MODE")
+                        .value_parser(["ignore", "error"])
+                        .help("Validator Settings: ignore unknown formats or report them as errors (default: ignore)"),
+                )
+                .arg(
+                    Arg::new("vs-formats-file")
+                        .long("vs-formats-file")
+                        .value_name("PATH")
+                        .help("Validator Settings: JSON file mapping custom format names to regexes"),
+                ),
+        )
+        .subcommand(
+            Command::new("check-schema")
+                .about("Check JSON Schema files against the official meta-schema of their draft")
+                .arg(
+                    Arg::new("schemas")
+                        .required(true)
+                        .value_name("FILE")
+                        .num_args(1..)
+                        .help("JSON Schema file(s), or \"-\" for stdin; the draft comes from $schema (default: 2020-12)"),
+                )
+                .arg(stdin_format_arg("schema-format", "schema")),
+        )
+        .subcommand(
+            Command::new("bundle")
+                .about("Bundle a schema and every schema file it references into one document under $defs")
+                .arg(
+                    Arg::new("schema")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("JSON Schema file, or \"-\" for stdin (refs then resolve against the current directory)"),
+                )
+                .arg(stdin_format_arg("schema-format", "schema"))
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write the result to FILE instead of stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("deref")
+                .about("Inline every $ref in a schema (after bundling), keeping only cyclic refs")
+                .arg(
+                    Arg::new("schema")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("JSON Schema file, or \"-\" for stdin (refs then resolve against the current directory)"),
+                )
+                .arg(stdin_format_arg("schema-format", "schema"))
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write the result to FILE instead of stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two schema versions and classify each change as backward-compatible, forward-compatible, or breaking")
+                .arg(
+                    Arg::new("old")
+                        .required(true)
+                        .value_name("OLD")
+                        .help("Old JSON Schema file, or \"-\" for stdin"),
+                )
+                .arg(
+                    Arg::new("new")
+                        .required(true)
+                        .value_name("NEW")
+                        .help("New JSON Schema file, or \"-\" for stdin"),
+                )
+                .arg(stdin_format_arg("schema-format", "schema")),
+        )
+        .subcommand(
+            Command::new("infer")
+                .about("Infer a JSON Schema from sample JSON or NDJSON files")
+                .arg(
+                    Arg::new("inputs")
+                        .required(true)
+                        .value_name("FILE")
+                        .num_args(1..)
+                        .help("Sample JSON file(s), NDJSON file(s) (.ndjson/.jsonl), or \"-\" for stdin"),
+                )
+                .arg(
+                    Arg::new("ndjson")
+                        .long("ndjson")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Read every input as newline-delimited JSON, one sample per line"),
+                )
+                .arg(
+                    Arg::new("detect-formats")
+                        .long("detect-formats")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Infer format uuid, date-time, or date when every sample string matches"),
+                )
+                .arg(
+                    Arg::new("max-enum-values")
+                        .long("max-enum-values")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Infer a string enum when a position has at most N distinct, repeated values"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write the schema to FILE instead of stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Lint JSON Schema files for unsatisfiable, contradictory, and suspicious constructs")
+                .arg(
+                    Arg::new("schemas")
+                        .required(true)
+                        .value_name("FILE")
+                        .num_args(1..)
+                        .help("JSON Schema file(s), or \"-\" for stdin"),
+                )
+                .arg(stdin_format_arg("schema-format", "schema"))
+                .arg(
+                    Arg::new("deny-warnings")
+                        .long("deny-warnings")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Fail when any warning is reported, not only errors"),
                 ),
         );
     let matches = cmd.get_matches();
@@ -104,13 +324,38 @@ pub fn run() {
             let cgs_dedupe_mode: Option<&str> = gen_m
                 .get_one::<String>("cgs-dedupe-mode")
                 .map(String::as_str);
+            let cgs_name_collision: Option<&str> = gen_m
+                .get_one::<String>("cgs-name-collision")
+                .map(String::as_str);
+            let cgs_emit_all_defs: bool = gen_m.get_flag("cgs-emit-all-defs");
+            let cgs_enforce_constraints: bool = gen_m.get_flag("cgs-enforce-constraints");
+            let many = |id: &str| -> Vec<String> {
+                gen_m
+                    .get_many::<String>(id)
+                    .map(|it| it.map(String::from).collect())
+                    .unwrap_or_default()
+            };
+            let cgs_derives: Vec<String> = many("cgs-derive");
+            let cgs_attributes: Vec<String> = many("cgs-attribute");
+            let cgs_type_derives: Vec<String> = many("cgs-type-derive");
+            let cgs_type_attributes: Vec<String> = many("cgs-type-attribute");
+            let cgs_type_mappings: Vec<String> = many("cgs-type-mapping");
             generate::run_generate(
                 lang,
                 &inputs,
+                stdin_format(gen_m, "schema-format"),
                 &output_dir,
                 jss_disallow_unknown_fields,
                 cgs_model_name_source,
                 cgs_dedupe_mode,
+                cgs_name_collision,
+                cgs_emit_all_defs,
+                cgs_enforce_constraints,
+                &cgs_derives,
+                &cgs_attributes,
+                &cgs_type_derives,
+                &cgs_type_attributes,
+                &cgs_type_mappings,
             )
         }
         Some(("validate", val_m)) => {
@@ -122,10 +367,93 @@ pub fn run() {
                 .get_one::<String>("payload")
                 .map(|s| PathBuf::from(s.as_str()));
             let jss_disallow_unknown_fields: bool = val_m.get_flag("jss-disallow-unknown-fields");
-            validate::run_validate(&schema, payload, jss_disallow_unknown_fields)
+            let vs_format_mode: Option<&str> = val_m
+                .get_one::<String>("vs-format-mode")
+                .map(String::as_str);
+            let vs_unknown_format: Option<&str> = val_m
+                .get_one::<String>("vs-unknown-format")
+                .map(String::as_str);
+            let vs_formats_file: Option<PathBuf> = val_m
+                .get_one::<String>("vs-formats-file")
+                .map(|s| PathBuf::from(s.as_str()));
+            validate::run_validate(
+                &schema,
+                stdin_format(val_m, "schema-format"),
+                payload.as_deref(),
+                stdin_format(val_m, "payload-format"),
+                jss_disallow_unknown_fields,
+                vs_format_mode,
+                vs_unknown_format,
+                vs_formats_file,
+            )
+        }
+        Some(("check-schema", check_m)) => {
+            let schemas: Vec<PathBuf> = check_m
+                .get_many::<String>("schemas")
+                .map(|it| it.map(PathBuf::from).collect())
+                .unwrap_or_default();
+            check_schema::run_check_schema(&schemas, stdin_format(check_m, "schema-format"))
+        }
+        Some((name @ ("bundle" | "deref"), ref_m)) => {
+            let schema: PathBuf = ref_m
+                .get_one::<String>("schema")
+                .map(|s| PathBuf::from(s.as_str()))
+                .expect("required FILE");
+            let output: Option<PathBuf> = ref_m
+                .get_one::<String>("output")
+                .map(|s| PathBuf::from(s.as_str()));
+            let schema_format: SourceFormat = stdin_format(ref_m, "schema-format");
+            if name == "bundle" {
+                bundle::run_bundle(&schema, schema_format, output.as_deref())
+            } else {
+                deref::run_deref(&schema, schema_format, output.as_deref())
+            }
+        }
+        Some(("diff", diff_m)) => {
+            let old: PathBuf = diff_m
+                .get_one::<String>("old")
+                .map(|s| PathBuf::from(s.as_str()))
+                .expect("required OLD");
+            let new: PathBuf = diff_m
+                .get_one::<String>("new")
+                .map(|s| PathBuf::from(s.as_str()))
+                .expect("required NEW");
+            diff::run_diff(&old, &new, stdin_format(diff_m, "schema-format"))
+        }
+        Some(("infer", infer_m)) => {
+            let inputs: Vec<PathBuf> = infer_m
+                .get_many::<String>("inputs")
+                .map(|it| it.map(PathBuf::from).collect())
+                .unwrap_or_default();
+            let max_enum_values: Option<usize> =
+                infer_m.get_one::<usize>("max-enum-values").copied();
+            let output: Option<PathBuf> = infer_m
+                .get_one::<String>("output")
+                .map(|s| PathBuf::from(s.as_str()));
+            infer::run_infer(
+                &inputs,
+                infer_m.get_flag("ndjson"),
+                infer_m.get_flag("detect-formats"),
+                max_enum_values,
+                output.as_deref(),
+            )
+        }
+        Some(("lint", lint_m)) => {
+            let schemas: Vec<PathBuf> = lint_m
+                .get_many::<String>("schemas")
+                .map(|it| it.map(PathBuf::from).collect())
+                .unwrap_or_default();
+            let deny_warnings: bool = lint_m.get_flag("deny-warnings");
+            lint::run_lint(
+                &schemas,
+                stdin_format(lint_m, "schema-format"),
+                deny_warnings,
+            )
         }
         _ => {
-            eprintln!("expected subcommand: generate or validate");
+            eprintln!(
+                "expected subcommand: generate, validate, check-schema, lint, bundle, deref, diff, or infer"
+            );
             std::process::exit(1);
         }
     };
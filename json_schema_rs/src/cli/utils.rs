@@ -1,7 +1,9 @@
 //! CLI helpers: schema/payload I/O, schema file discovery, mod.rs emission.
 
 use json_schema_rs::sanitizers::{sanitize_output_relative, sanitize_path_component};
-use json_schema_rs::{JsonSchema, JsonSchemaSettings};
+use json_schema_rs::{
+    JsonPointer, JsonSchema, JsonSchemaParseError, JsonSchemaSettings, SourceMap, Span,
+};
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
@@ -9,41 +11,260 @@ use std::path::{Path, PathBuf};
 
 pub(crate) const STDIN_OUTPUT_NAME: &str = "stdin.rs";
 
-pub(crate) fn read_schema_from_reader<R: Read>(
-    mut r: R,
+/// Syntax of a document read by the CLI, chosen by file extension, or for stdin by the
+/// `--schema-format`/`--payload-format` flag (JSON when absent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SourceFormat {
+    Json,
+    /// `.yaml` / `.yml`; reading it requires the `yaml` feature.
+    Yaml,
+}
+
+impl SourceFormat {
+    /// The format named by a `--schema-format`/`--payload-format` value: `yaml`, else JSON.
+    pub(crate) fn from_flag(value: Option<&str>) -> Self {
+        match value {
+            Some("yaml") => SourceFormat::Yaml,
+            _ => SourceFormat::Json,
+        }
+    }
+
+    pub(crate) fn from_path(path: &Path) -> Self {
+        let is_yaml: bool = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        if is_yaml {
+            SourceFormat::Yaml
+        } else {
+            SourceFormat::Json
+        }
+    }
+}
+
+/// A JSON or YAML document read by the CLI, kept with its text so errors can quote the offending
+/// line.
+pub(crate) struct SourceText {
+    /// File path as given, or `stdin`.
+    pub(crate) label: String,
+    pub(crate) text: String,
+    pub(crate) format: SourceFormat,
+}
+
+/// Reads a schema or payload (`what`) from `path`, or from stdin in `stdin_format` when `path` is
+/// `-`.
+pub(crate) fn read_source(
+    path: &Path,
+    what: &str,
+    stdin_format: SourceFormat,
+) -> Result<SourceText, String> {
+    let mut buf: Vec<u8> = Vec::new();
+    let is_stdin: bool = path.as_os_str() == "-";
+    let format: SourceFormat = if is_stdin {
+        stdin_format
+    } else {
+        SourceFormat::from_path(path)
+    };
+    let label: String = if is_stdin {
+        io::stdin()
+            .read_to_end(&mut buf)
+            .map_err(|e| format!("failed to read {what}: {e}"))?;
+        "stdin".to_string()
+    } else {
+        File::open(path)
+            .map_err(|e| format!("failed to open {what} file: {e}"))?
+            .read_to_end(&mut buf)
+            .map_err(|e| format!("failed to read {what}: {e}"))?;
+        path.display().to_string()
+    };
+    let text: String =
+        String::from_utf8(buf).map_err(|e| format!("{what} is not valid UTF-8: {e}"))?;
+    Ok(SourceText {
+        label,
+        text,
+        format,
+    })
+}
+
+/// Renders the source line at 1-based `line`/`column` with `width` carets under it:
+///
+/// ```text
+///  --> schema.json:3:18
+///   |
+/// 3 |   "minLength": -1
+///   |                ^^
+/// ```
+pub(crate) fn render_excerpt(
+    source: &SourceText,
+    line: usize,
+    column: usize,
+    width: usize,
+) -> String {
+    let Some(line_text) = source.text.lines().nth(line.saturating_sub(1)) else {
+        return format!(" --> {}:{line}:{column}", source.label);
+    };
+    let gutter: String = " ".repeat(line.to_string().len());
+    let available: usize = line_text.chars().count().saturating_sub(column - 1).max(1);
+    let carets: String = "^".repeat(width.clamp(1, available));
+    let indent: String = line_text
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    format!(
+        "{gutter}--> {}:{line}:{column}\n{gutter} |\n{line} | {line_text}\n{gutter} | {indent}{carets}",
+        source.label
+    )
+}
+
+/// Renders the excerpt for the value at `pointer` (or its closest existing ancestor), underlining
+/// the value's first line. `None` when the document has no value there.
+pub(crate) fn render_pointer_excerpt(
+    source: &SourceText,
+    source_map: &SourceMap,
+    pointer: &JsonPointer,
+) -> Option<String> {
+    let span: Span = source_map.get_or_ancestor(pointer)?;
+    let width: usize = source.text[span.start..span.end]
+        .lines()
+        .next()
+        .map_or(1, |first| first.chars().count());
+    Some(render_excerpt(source, span.line, span.column, width))
+}
+
+/// `message` followed by the excerpt for `pointer`, when one is available.
+pub(crate) fn with_pointer_excerpt(
+    message: &str,
+    source: &SourceText,
+    source_map: &SourceMap,
+    pointer: &JsonPointer,
+) -> String {
+    match render_pointer_excerpt(source, source_map, pointer) {
+        Some(excerpt) => format!("{message}\n{excerpt}"),
+        None => message.to_string(),
+    }
+}
+
+/// `message` for a JSON syntax or shape error, followed by an excerpt at the error's position.
+fn with_serde_excerpt(message: &str, source: &SourceText, e: &serde_json::Error) -> String {
+    if e.line() == 0 {
+        return message.to_string();
+    }
+    format!(
+        "{message}\n{}",
+        render_excerpt(source, e.line(), e.column().max(1), 1)
+    )
+}
+
+/// Converts a YAML document read with [`read_source`] (`what` is e.g. `schema`) to JSON. Syntax
+/// errors quote the offending line; values JSON cannot represent are reported by pointer.
+#[cfg(feature = "yaml")]
+fn parse_yaml_source(source: &SourceText, what: &str) -> Result<serde_json::Value, String> {
+    use json_schema_rs::YamlError;
+    json_schema_rs::yaml::from_str(&source.text).map_err(|e| {
+        let message: String = format!("invalid YAML {what}: {e}");
+        match e {
+            YamlError::Syntax {
+                line: Some(line),
+                column: Some(column),
+                ..
+            } => format!("{message}\n{}", render_excerpt(source, line, column, 1)),
+            YamlError::Syntax { .. } | YamlError::Unsupported { .. } => message,
+        }
+    })
+}
+
+#[cfg(not(feature = "yaml"))]
+fn parse_yaml_source(source: &SourceText, what: &str) -> Result<serde_json::Value, String> {
+    Err(format!(
+        "{}: reading a YAML {what} requires jsonschemars built with the `yaml` feature",
+        source.label
+    ))
+}
+
+/// Source positions for `source`. YAML documents get an empty map, so errors about them carry
+/// their JSON Pointer but no excerpt.
+pub(crate) fn source_map_for(source: &SourceText) -> SourceMap {
+    match source.format {
+        SourceFormat::Json => SourceMap::from_json(&source.text),
+        SourceFormat::Yaml => SourceMap::default(),
+    }
+}
+
+/// Parses a schema read with [`read_source`]. Errors quote the offending source line.
+pub(crate) fn parse_schema_source(
+    source: &SourceText,
     schema_settings: &JsonSchemaSettings,
 ) -> Result<JsonSchema, String> {
-    let mut buf: Vec<u8> = Vec::new();
-    r.read_to_end(&mut buf)
-        .map_err(|e| format!("failed to read schema: {e}"))?;
-    JsonSchema::new_from_slice(&buf, schema_settings).map_err(|e| e.to_string())
+    if source.format == SourceFormat::Yaml {
+        let document: serde_json::Value = parse_yaml_source(source, "schema")?;
+        return JsonSchema::new_from_serde_value(&document, schema_settings)
+            .map_err(|e| e.to_string());
+    }
+    JsonSchema::new_from_str(&source.text, schema_settings).map_err(|e| match &e {
+        JsonSchemaParseError::Serde(serde_error) => {
+            with_serde_excerpt(&e.to_string(), source, serde_error)
+        }
+        JsonSchemaParseError::MetaSchema(errors) => {
+            let source_map: SourceMap = SourceMap::from_json(&source.text);
+            let mut out: String = "schema does not conform to its meta-schema:".to_string();
+            for error in errors {
+                out.push('\n');
+                out.push_str(&with_pointer_excerpt(
+                    &error.to_string(),
+                    source,
+                    &source_map,
+                    error.instance_path(),
+                ));
+            }
+            out
+        }
+        JsonSchemaParseError::UnknownField { .. } | JsonSchemaParseError::Io(_) => e.to_string(),
+        #[cfg(feature = "yaml")]
+        JsonSchemaParseError::Yaml(_) => e.to_string(),
+    })
 }
 
 pub(crate) fn read_schema_from_path(
-    path: &PathBuf,
+    path: &Path,
     schema_settings: &JsonSchemaSettings,
+    stdin_format: SourceFormat,
 ) -> Result<JsonSchema, String> {
-    if path.as_os_str() == "-" {
-        read_schema_from_reader(io::stdin(), schema_settings)
-    } else {
-        let f = File::open(path).map_err(|e| format!("failed to open schema file: {e}"))?;
-        read_schema_from_reader(f, schema_settings)
-    }
+    let source: SourceText = read_source(path, "schema", stdin_format)?;
+    parse_schema_source(&source, schema_settings)
 }
 
-pub(crate) fn read_payload_from_reader<R: Read>(mut r: R) -> Result<serde_json::Value, String> {
-    let mut buf: Vec<u8> = Vec::new();
-    r.read_to_end(&mut buf)
-        .map_err(|e| format!("failed to read payload: {e}"))?;
-    serde_json::from_slice(&buf).map_err(|e| format!("invalid JSON payload: {e}"))
+/// Parses a JSON or YAML document (`what` is e.g. `payload`) read with [`read_source`],
+/// recording where each value sits (see [`source_map_for`]).
+pub(crate) fn parse_document_source(
+    source: &SourceText,
+    what: &str,
+) -> Result<(serde_json::Value, SourceMap), String> {
+    match source.format {
+        SourceFormat::Json => SourceMap::parse(&source.text)
+            .map_err(|e| with_serde_excerpt(&format!("invalid JSON {what}: {e}"), source, &e)),
+        SourceFormat::Yaml => Ok((parse_yaml_source(source, what)?, SourceMap::default())),
+    }
 }
 
-pub(crate) fn read_payload_from_path(path: &PathBuf) -> Result<serde_json::Value, String> {
-    let f = File::open(path).map_err(|e| format!("failed to open payload file: {e}"))?;
-    read_payload_from_reader(f)
+/// Writes `schema` as pretty-printed JSON to `output`, or to stdout when `None`.
+pub(crate) fn write_schema_output(
+    schema: &JsonSchema,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let mut json: String = serde_json::to_string_pretty(schema)
+        .map_err(|e| format!("failed to serialize schema: {e}"))?;
+    json.push('\n');
+    match output {
+        Some(path) => {
+            fs::write(path, json).map_err(|e| format!("failed to write {}: {e}", path.display()))
+        }
+        None => io::stdout()
+            .write_all(json.as_bytes())
+            .map_err(|e| format!("failed to write to stdout: {e}")),
+    }
 }
 
-/// Recursively find all `.json` files under `dir`. Returns (`input_path`, `output_relative`) for each,
+/// Recursively find all `.json` files (and `.yaml`/`.yml` with the `yaml` feature) under `dir`. Returns (`input_path`, `output_relative`) for each,
 /// where `output_relative` is the path under the output dir with `.rs` extension (e.g. `a/b/c.json` -> `a/b/c.rs`).
 /// Uses an explicit stack to avoid recursion.
 fn find_schema_files_under(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>, String> {
@@ -57,7 +278,9 @@ fn find_schema_files_under(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>, String
             let path: PathBuf = entry.path();
             if path.is_dir() {
                 stack.push(path);
-            } else if path.extension().is_some_and(|e| e == "json") {
+            } else if path.extension().is_some_and(|e| e == "json")
+                || (cfg!(feature = "yaml") && SourceFormat::from_path(&path) == SourceFormat::Yaml)
+            {
                 let relative = path
                     .strip_prefix(dir)
                     .map_err(|e| format!("schema path not under input dir: {e}"))?;
@@ -195,6 +418,32 @@ pub(crate) fn write_mod_rs_files(
 mod tests {
     use super::*;
 
+    fn source(text: &str) -> SourceText {
+        SourceText {
+            label: "doc.json".to_string(),
+            text: text.to_string(),
+            format: SourceFormat::Json,
+        }
+    }
+
+    #[test]
+    fn render_excerpt_points_caret_at_column() {
+        let doc: SourceText = source("{\n  \"minLength\": -1\n}");
+        let expected = " --> doc.json:2:16\n  |\n2 |   \"minLength\": -1\n  |                ^^";
+        let actual: String = render_excerpt(&doc, 2, 16, 2);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn render_pointer_excerpt_underlines_first_line_of_value() {
+        let doc: SourceText = source("{\"a\": {\n  \"b\": 1\n}}");
+        let map: SourceMap = SourceMap::from_json(&doc.text);
+        let pointer: JsonPointer = JsonPointer::try_from("/a").unwrap();
+        let expected = " --> doc.json:1:7\n  |\n1 | {\"a\": {\n  |       ^";
+        let actual: Option<String> = render_pointer_excerpt(&doc, &map, &pointer);
+        assert_eq!(Some(expected.to_string()), actual);
+    }
+
     #[test]
     fn shared_import_prefix_root_file_is_super() {
         let output_relative = PathBuf::from("a.rs");
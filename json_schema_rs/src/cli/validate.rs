@@ -1,28 +1,89 @@
-//! `jsonschemars validate` subcommand: validate a JSON instance against a JSON Schema.
+//! `jsonschemars validate` subcommand: validate a JSON (or YAML) instance against a JSON Schema.
 
-use super::utils::{read_payload_from_path, read_payload_from_reader, read_schema_from_path};
-use json_schema_rs::{JsonSchemaSettings, validate};
-use std::io;
-use std::path::PathBuf;
+use super::utils::{
+    SourceFormat, SourceText, parse_document_source, read_schema_from_path, read_source,
+    with_pointer_excerpt,
+};
+use json_schema_rs::{
+    FormatMode, JsonSchemaSettings, UnknownFormatMode, ValidatorSettings, validate_with_settings,
+};
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
+/// Reads a formats file: a JSON object mapping each custom format name to an ECMA-262 regex
+/// (e.g. `{"semver": "^\\d+\\.\\d+\\.\\d+$"}`). A string conforms when the regex matches
+/// anywhere in it, as with `pattern`; anchor the regex to match the whole string.
+fn read_regex_formats(path: &PathBuf) -> Result<Vec<(String, regress::Regex)>, String> {
+    let f = File::open(path).map_err(|e| format!("failed to open formats file: {e}"))?;
+    let map: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_reader(f).map_err(|e| format!("invalid formats file: {e}"))?;
+    let mut out: Vec<(String, regress::Regex)> = Vec::with_capacity(map.len());
+    for (name, pattern) in map {
+        let Some(pattern) = pattern.as_str() else {
+            return Err(format!(
+                "invalid formats file: format \"{name}\" must map to a regex string"
+            ));
+        };
+        let regex = regress::Regex::new(pattern).map_err(|e| {
+            format!("invalid formats file: format \"{name}\" has an invalid regex: {e}")
+        })?;
+        out.push((name, regex));
+    }
+    Ok(out)
+}
+
+/// Validates the payload at `payload_path` (stdin when `None` or `-`) against the schema at
+/// `schema_path`; documents read from stdin are parsed as `schema_format`/`payload_format`.
+#[expect(clippy::too_many_arguments)]
 pub(crate) fn run_validate(
-    schema_path: &PathBuf,
-    payload_path: Option<PathBuf>,
+    schema_path: &Path,
+    schema_format: SourceFormat,
+    payload_path: Option<&Path>,
+    payload_format: SourceFormat,
     jss_disallow_unknown_fields: bool,
+    vs_format_mode: Option<&str>,
+    vs_unknown_format: Option<&str>,
+    vs_formats_file: Option<PathBuf>,
 ) -> Result<(), String> {
     let schema_settings: JsonSchemaSettings = JsonSchemaSettings::builder()
         .disallow_unknown_fields(jss_disallow_unknown_fields)
         .build();
-    let schema = read_schema_from_path(schema_path, &schema_settings)?;
-    let instance: serde_json::Value = match payload_path {
-        Some(p) => read_payload_from_path(&p)?,
-        None => read_payload_from_reader(io::stdin())?,
+    let validator_settings: ValidatorSettings = {
+        let mut b = ValidatorSettings::builder();
+        if let Some(mode) = vs_format_mode {
+            b = b.format_mode(match mode {
+                "annotation" => FormatMode::Annotation,
+                _ => FormatMode::Assertion,
+            });
+        }
+        if let Some(mode) = vs_unknown_format {
+            b = b.unknown_format_mode(match mode {
+                "error" => UnknownFormatMode::Error,
+                _ => UnknownFormatMode::Ignore,
+            });
+        }
+        if let Some(path) = vs_formats_file {
+            for (name, regex) in read_regex_formats(&path)? {
+                b = b.custom_format(name, move |s: &str| regex.find(s).is_some());
+            }
+        }
+        b.build()
     };
-    match validate(&schema, &instance) {
+    let schema = read_schema_from_path(schema_path, &schema_settings, schema_format)?;
+    let payload: SourceText = read_source(
+        payload_path.unwrap_or(Path::new("-")),
+        "payload",
+        payload_format,
+    )?;
+    let (instance, source_map) = parse_document_source(&payload, "payload")?;
+    match validate_with_settings(&schema, &instance, &validator_settings) {
         Ok(()) => Ok(()),
         Err(errors) => {
             for e in &errors {
-                eprintln!("{e}");
+                eprintln!(
+                    "{}",
+                    with_pointer_excerpt(&e.to_string(), &payload, &source_map, e.instance_path())
+                );
             }
             Err(format!("validation failed with {} error(s)", errors.len()))
         }
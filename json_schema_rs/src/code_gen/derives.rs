@@ -0,0 +1,407 @@
+//! Extra derives and attributes on generated Rust types ([`CodeGenSettings::type_attributes`]).
+//!
+//! Runs over finished module source, like the `use` line insertion in the Rust backend: each
+//! `#[derive(...)]` line of a generated struct or enum gets the requested derives, and the
+//! requested attributes are written below it. `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash`,
+//! `Default`, and `Copy` are only kept where every field type supports them (an `f64` field rules
+//! out `Eq` and `Hash`, a `Vec` rules out `Copy`, an enum rules out `Default`); the rest are
+//! skipped and reported as [`SkippedDerive`]s. Support is decided for all types of a module at
+//! once, so a struct holding another generated type only keeps `Hash` if that type keeps it.
+//!
+//! [`CodeGenSettings::type_attributes`]: super::CodeGenSettings::type_attributes
+
+use super::SkippedDerive;
+use super::settings::TypeAttributes;
+use std::collections::BTreeMap;
+
+/// Derives whose support is checked against field types. Any other derive is added as requested.
+const CHECKED: [&str; 7] = [
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+    "Default",
+    "Copy",
+];
+
+/// The derives a derive needs on the same type.
+fn prerequisites(derive: &str) -> &'static [&'static str] {
+    match derive {
+        "Eq" | "PartialOrd" => &["PartialEq"],
+        "Ord" => &["Eq", "PartialOrd"],
+        "Copy" => &["Clone"],
+        _ => &[],
+    }
+}
+
+/// Final derive lists of a module's types, by type name.
+pub(crate) type DeriveSets = BTreeMap<String, Vec<String>>;
+
+/// A generated struct or enum found in module source.
+struct TypeDef {
+    name: String,
+    is_enum: bool,
+    /// A newtype with a private field: a validated newtype, built only through `TryFrom`.
+    is_validated: bool,
+    /// Line index of the `#[derive(...)]` line.
+    derive_line: usize,
+    /// Derives the generator emitted.
+    base: Vec<String>,
+    /// (field or variant name, Rust type) for every value the type holds.
+    fields: Vec<(String, String)>,
+}
+
+/// A Rust type expression split into its path and generic arguments.
+struct TypeExpr<'a> {
+    path: &'a str,
+    args: Vec<TypeExpr<'a>>,
+}
+
+fn parse_type(ty: &str) -> TypeExpr<'_> {
+    let ty: &str = ty.trim();
+    let (Some(open), Some(close)) = (ty.find('<'), ty.rfind('>')) else {
+        return TypeExpr {
+            path: ty,
+            args: Vec::new(),
+        };
+    };
+    let mut args: Vec<TypeExpr<'_>> = Vec::new();
+    let inner: &str = &ty[open + 1..close];
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(parse_type(&inner[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(parse_type(&inner[start..]));
+    TypeExpr {
+        path: ty[..open].trim(),
+        args,
+    }
+}
+
+/// Whether a type the generator emits but does not define implements `derive`. Unknown types
+/// (e.g. the target of a type mapping) are assumed to.
+fn leaf_supports(path: &str, derive: &str) -> bool {
+    match path {
+        "f32" | "f64" => matches!(derive, "PartialEq" | "PartialOrd" | "Default" | "Copy"),
+        "String" => derive != "Copy",
+        "serde_json::Value" => matches!(derive, "PartialEq" | "Eq" | "Hash" | "Default"),
+        "std::net::Ipv4Addr" | "std::net::Ipv6Addr" => derive != "Default",
+        _ => true,
+    }
+}
+
+/// Whether `ty` implements `derive`, given the derives of the generated types in scope.
+fn type_supports(
+    ty: &TypeExpr<'_>,
+    derive: &str,
+    derives_of: &dyn Fn(&str) -> Option<bool>,
+) -> bool {
+    let args_support = || ty.args.iter().all(|a| type_supports(a, derive, derives_of));
+    match ty.path {
+        "Option" => derive == "Default" || args_support(),
+        "Vec" | "BTreeMap" => derive != "Copy" && (derive == "Default" || args_support()),
+        "HashSet" => matches!(derive, "PartialEq" | "Eq" | "Default"),
+        "Box" => derive != "Copy" && args_support(),
+        "chrono::DateTime" => args_support(),
+        path => derives_of(path).unwrap_or_else(|| leaf_supports(path, derive)),
+    }
+}
+
+/// Parses the derive list of a `#[derive(...)]` line.
+fn derive_list(line: &str) -> Option<Vec<String>> {
+    let list: &str = line.strip_prefix("#[derive(")?.strip_suffix(")]")?;
+    Some(list.split(',').map(|d| d.trim().to_string()).collect())
+}
+
+/// (name, type) of a `name: Type,` field line, with any `pub ` already stripped.
+fn field(line: &str) -> Option<(String, String)> {
+    let (name, ty) = line.split_once(": ")?;
+    Some((
+        name.to_string(),
+        ty.strip_suffix(',').unwrap_or(ty).to_string(),
+    ))
+}
+
+/// Values held by the enum whose body starts at `lines[start]`: tuple variant payloads and
+/// struct variant fields.
+fn enum_fields(lines: &[&str], start: usize) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = Vec::new();
+    for line in lines[start..].iter().take_while(|l| **l != "}") {
+        let line: &str = line.trim();
+        if line.starts_with("//") || line.starts_with("#[") || line.ends_with('{') || line == "}," {
+            continue;
+        }
+        if let (Some(open), Some(close)) = (line.find('('), line.rfind(')')) {
+            out.push((line[..open].to_string(), line[open + 1..close].to_string()));
+        } else if let Some(f) = field(line) {
+            out.push(f);
+        }
+    }
+    out
+}
+
+/// Every generated struct and enum in `lines`.
+fn type_defs(lines: &[&str]) -> Vec<TypeDef> {
+    let mut out: Vec<TypeDef> = Vec::new();
+    for (derive_line, line) in lines.iter().enumerate() {
+        let Some(base) = derive_list(line) else {
+            continue;
+        };
+        let Some(item) = lines[derive_line + 1..]
+            .iter()
+            .position(|l| !l.starts_with("#["))
+            .map(|i| derive_line + 1 + i)
+        else {
+            continue;
+        };
+        let mut is_validated: bool = false;
+        let (name, is_enum, fields): (&str, bool, Vec<(String, String)>) =
+            if let Some(rest) = lines[item].strip_prefix("pub enum ") {
+                let name: &str = rest.strip_suffix(" {").unwrap_or(rest);
+                (name, true, enum_fields(lines, item + 1))
+            } else if let Some(rest) = lines[item].strip_prefix("pub struct ") {
+                if let Some((name, tuple)) = rest.split_once('(') {
+                    let ty: &str = tuple.strip_suffix(");").unwrap_or(tuple);
+                    is_validated = !ty.starts_with("pub ");
+                    let ty: &str = ty.strip_prefix("pub ").unwrap_or(ty);
+                    (name, false, vec![("0".to_string(), ty.to_string())])
+                } else {
+                    let name: &str = rest.strip_suffix(" {").unwrap_or(rest);
+                    let fields: Vec<(String, String)> = lines[item + 1..]
+                        .iter()
+                        .take_while(|l| **l != "}")
+                        .filter_map(|l| l.trim().strip_prefix("pub "))
+                        .filter_map(field)
+                        .collect();
+                    (name, false, fields)
+                }
+            } else {
+                continue;
+            };
+        out.push(TypeDef {
+            name: name.to_string(),
+            is_enum,
+            is_validated,
+            derive_line,
+            base,
+            fields,
+        });
+    }
+    out
+}
+
+/// Appends `derive` to `out` after its prerequisites, skipping any already in `out`.
+fn push_with_prerequisites(out: &mut Vec<String>, derive: &str) {
+    for p in prerequisites(derive) {
+        push_with_prerequisites(out, p);
+    }
+    if !out.iter().any(|d| d == derive) {
+        out.push(derive.to_string());
+    }
+}
+
+/// Why `def` cannot derive `derive` given the derive lists in `sets` and `imported`, or `None`
+/// when it can.
+fn unsupported(
+    def: &TypeDef,
+    derive: &str,
+    sets: &DeriveSets,
+    imported: &DeriveSets,
+) -> Option<String> {
+    if derive == "Default" && def.is_enum {
+        return Some("enums have no default variant".to_string());
+    }
+    if derive == "Default" && def.is_validated {
+        return Some("validated newtypes are only built from checked values".to_string());
+    }
+    let list: &[String] = &sets[&def.name];
+    if let Some(p) = prerequisites(derive)
+        .iter()
+        .find(|p| !list.iter().any(|d| d == *p))
+    {
+        return Some(format!("it needs {p}, which was skipped"));
+    }
+    let derives_of = |name: &str| -> Option<bool> {
+        sets.get(name)
+            .or_else(|| imported.get(name))
+            .map(|list| list.iter().any(|d| d == derive))
+    };
+    def.fields
+        .iter()
+        .find(|(_, ty)| !type_supports(&parse_type(ty), derive, &derives_of))
+        .map(|(name, ty)| format!("`{name}: {ty}` does not implement {derive}"))
+}
+
+/// Adds the requested derives and attributes to every generated type in `source`. `global`
+/// applies to every type, `by_type` to the types it names. `imported` holds the derive lists of
+/// types defined in another module (the shared module). Derives a type cannot support are left
+/// out and reported in `skipped`. Returns the new source and the final derive list of each type.
+pub(crate) fn apply_type_attributes(
+    source: Vec<u8>,
+    global: &TypeAttributes,
+    by_type: &BTreeMap<String, TypeAttributes>,
+    imported: &DeriveSets,
+    skipped: &mut Vec<SkippedDerive>,
+) -> (Vec<u8>, DeriveSets) {
+    let text: String = String::from_utf8_lossy(&source).into_owned();
+    let lines: Vec<&str> = text.lines().collect();
+    let defs: Vec<TypeDef> = type_defs(&lines);
+
+    let requested = |name: &str| std::iter::once(global).chain(by_type.get(name));
+    let mut sets: DeriveSets = BTreeMap::new();
+    let mut extras: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for def in &defs {
+        let mut list: Vec<String> = def.base.clone();
+        for d in requested(&def.name).flat_map(|r| &r.derives) {
+            push_with_prerequisites(&mut list, d.trim());
+        }
+        extras.insert(&def.name, list[def.base.len()..].to_vec());
+        sets.insert(def.name.clone(), list);
+    }
+
+    // Drop unsupported derives until every remaining one holds; types start out assuming they
+    // get everything requested, so recursive types keep derives their fields support.
+    loop {
+        let mut changed: bool = false;
+        for def in &defs {
+            for derive in extras[def.name.as_str()]
+                .iter()
+                .filter(|d| CHECKED.contains(&d.as_str()))
+            {
+                if !sets[&def.name].contains(derive) {
+                    continue;
+                }
+                if let Some(reason) = unsupported(def, derive, &sets, imported) {
+                    sets.get_mut(&def.name)
+                        .expect("every def has a set")
+                        .retain(|d| d != derive);
+                    skipped.push(SkippedDerive {
+                        type_name: def.name.clone(),
+                        derive: derive.clone(),
+                        reason,
+                    });
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut rewritten: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for def in &defs {
+        let list: &[String] = &sets[&def.name];
+        let mut attributes: Vec<&str> = Vec::new();
+        for a in requested(&def.name).flat_map(|r| &r.attributes) {
+            if !attributes.contains(&a.as_str()) {
+                attributes.push(a);
+            }
+        }
+        if list.len() == def.base.len() && attributes.is_empty() {
+            continue;
+        }
+        // Extra derives go before `Serialize`, keeping serde and `ToJsonSchema` last.
+        let added: Vec<&String> = list[def.base.len()..].iter().collect();
+        let at: usize = def
+            .base
+            .iter()
+            .position(|d| d == "Serialize")
+            .unwrap_or(def.base.len());
+        let ordered: Vec<&String> = def.base[..at]
+            .iter()
+            .chain(added)
+            .chain(&def.base[at..])
+            .collect();
+        let derive_line: String = format!(
+            "#[derive({})]",
+            ordered
+                .iter()
+                .map(|d| d.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let mut new_lines: Vec<String> = vec![derive_line];
+        new_lines.extend(attributes.iter().map(|a| (*a).to_string()));
+        rewritten.insert(def.derive_line, new_lines);
+    }
+    if rewritten.is_empty() {
+        return (source, sets);
+    }
+    let mut out: String = String::with_capacity(text.len());
+    for (i, line) in lines.iter().enumerate() {
+        let new_lines: &[String] = rewritten.get(&i).map_or(&[], Vec::as_slice);
+        if new_lines.is_empty() {
+            out.push_str(line);
+            out.push('\n');
+        }
+        for l in new_lines {
+            out.push_str(l);
+            out.push('\n');
+        }
+    }
+    (out.into_bytes(), sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeriveSets, apply_type_attributes};
+    use crate::code_gen::SkippedDerive;
+    use crate::code_gen::settings::TypeAttributes;
+    use std::collections::BTreeMap;
+
+    fn apply(source: &str, derives: &[&str]) -> (String, Vec<String>) {
+        let global: TypeAttributes = TypeAttributes {
+            derives: derives.iter().map(ToString::to_string).collect(),
+            attributes: Vec::new(),
+        };
+        let mut skipped: Vec<SkippedDerive> = Vec::new();
+        let (out, _) = apply_type_attributes(
+            source.as_bytes().to_vec(),
+            &global,
+            &BTreeMap::new(),
+            &DeriveSets::new(),
+            &mut skipped,
+        );
+        (
+            String::from_utf8(out).unwrap(),
+            skipped.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn recursive_struct_keeps_supported_derives() {
+        let source = "#[derive(Debug, Clone, Serialize)]\npub struct Node {\n    pub next: Option<Box<Node>>,\n    pub tags: Vec<String>,\n}\n";
+        let (actual, skipped) = apply(source, &["Ord", "Hash", "Copy"]);
+        let expected = "#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]\npub struct Node {\n    pub next: Option<Box<Node>>,\n    pub tags: Vec<String>,\n}\n";
+        assert_eq!(expected, actual);
+        let expected: Vec<String> = vec![
+            "skipped derive(Copy) on Node: `next: Option<Box<Node>>` does not implement Copy"
+                .to_string(),
+        ];
+        assert_eq!(expected, skipped);
+    }
+
+    #[test]
+    fn enum_variants_and_newtypes_are_checked() {
+        let source = "#[derive(Debug, Clone, Serialize)]\n#[serde(tag = \"kind\")]\npub enum Shape {\n    Circle {\n        #[serde(default)]\n        radius: f64,\n    },\n    Empty {},\n}\n\n#[derive(Debug, Clone, Serialize)]\n#[serde(transparent)]\npub struct Id(pub serde_json::Value);\n";
+        let (actual, skipped) = apply(source, &["Hash", "Default"]);
+        let expected = "#[derive(Debug, Clone, Serialize)]\n#[serde(tag = \"kind\")]\npub enum Shape {\n    Circle {\n        #[serde(default)]\n        radius: f64,\n    },\n    Empty {},\n}\n\n#[derive(Debug, Clone, Hash, Default, Serialize)]\n#[serde(transparent)]\npub struct Id(pub serde_json::Value);\n";
+        assert_eq!(expected, actual);
+        let expected: Vec<String> = vec![
+            "skipped derive(Hash) on Shape: `radius: f64` does not implement Hash".to_string(),
+            "skipped derive(Default) on Shape: enums have no default variant".to_string(),
+        ];
+        assert_eq!(expected, skipped);
+    }
+}
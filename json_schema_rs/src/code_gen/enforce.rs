@@ -0,0 +1,500 @@
+//! Generated code for [`CodeGenSettings::enforce_constraints`]: validated newtypes for constrained
+//! scalars, deserializers that check `minItems`/`maxItems`, and a
+//! [`Validate`](crate::constraints::Validate) impl for every generated type.
+//!
+//! The field emitters record what they wrote in a [`ConstrainedFields`]; the newtypes, the
+//! deserializers, and the `Validate` impl are written after the type from that record, so they
+//! always agree with the emitted field types.
+//!
+//! [`CodeGenSettings::enforce_constraints`]: super::CodeGenSettings::enforce_constraints
+
+use super::CodeGenResult;
+use crate::json_schema::JsonSchema;
+use heck::ToSnakeCase;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+/// A validated newtype to emit: `pub struct {name}({inner});` checked against `schema`.
+pub(crate) struct Newtype {
+    pub name: String,
+    pub inner: String,
+    pub schema: JsonSchema,
+}
+
+/// A field the struct emitters wrote.
+pub(crate) struct EmittedField {
+    /// The property key (error locations use it).
+    pub key: String,
+    pub field_name: String,
+    pub ty: String,
+    /// `minItems` and `maxItems` of an array field, checked by a generated deserializer.
+    pub items: Option<(Option<u64>, Option<u64>)>,
+}
+
+/// What the field emitters wrote for one struct (or one tagged enum variant).
+#[derive(Default)]
+pub(crate) struct ConstrainedFields {
+    pub fields: Vec<EmittedField>,
+    pub newtypes: Vec<Newtype>,
+}
+
+impl ConstrainedFields {
+    /// Records a validated newtype and returns its name.
+    pub(crate) fn newtype(&mut self, name: String, inner: &str, schema: &JsonSchema) -> String {
+        self.newtypes.push(Newtype {
+            name: name.clone(),
+            inner: inner.to_string(),
+            schema: schema.clone(),
+        });
+        name
+    }
+
+    /// Records a field.
+    pub(crate) fn field(&mut self, key: &str, field_name: &str, ty: &str) {
+        self.fields.push(EmittedField {
+            key: key.to_string(),
+            field_name: field_name.to_string(),
+            ty: ty.to_string(),
+            items: None,
+        });
+    }
+
+    /// Records an array field whose length is checked on deserialize.
+    pub(crate) fn array_field(
+        &mut self,
+        key: &str,
+        field_name: &str,
+        ty: &str,
+        min_items: Option<u64>,
+        max_items: Option<u64>,
+    ) {
+        self.fields.push(EmittedField {
+            key: key.to_string(),
+            field_name: field_name.to_string(),
+            ty: ty.to_string(),
+            items: Some((min_items, max_items)),
+        });
+    }
+}
+
+/// Name of the deserializer that checks the length of array field `field_name` of `owner`.
+pub(crate) fn items_deserializer_name(owner: &str, field_name: &str) -> String {
+    format!(
+        "deserialize_{}_{}",
+        owner.to_snake_case(),
+        field_name.to_snake_case()
+    )
+}
+
+/// Types a generated field may hold that implement `Validate` trivially.
+const ALWAYS_VALID: [&str; 25] = [
+    "Option",
+    "Vec",
+    "HashSet",
+    "BTreeMap",
+    "Box",
+    "String",
+    "bool",
+    "i8",
+    "u8",
+    "i16",
+    "u16",
+    "i32",
+    "u32",
+    "i64",
+    "u64",
+    "f32",
+    "f64",
+    "serde_json",
+    "Value",
+    "std",
+    "net",
+    "Ipv4Addr",
+    "Ipv6Addr",
+    "Uuid",
+    "chrono",
+];
+
+/// The type paths in a type (`Vec<crate::Money>` holds `Vec` and `crate::Money`).
+fn type_paths(ty: &str) -> impl Iterator<Item = &str> {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .filter(|t| !t.is_empty())
+}
+
+/// True when `ty` holds a generated type whose `validate` may fail: anything but the types above,
+/// the `newtypes` (valid by construction), and the `mapped` types (existing types the user maps
+/// schemas to, which are not required to implement `Validate`).
+fn needs_validate(ty: &str, newtypes: &[Newtype], mapped: &BTreeSet<String>) -> bool {
+    type_paths(ty)
+        .filter(|path| !mapped.iter().any(|m| type_paths(m).any(|p| p == *path)))
+        .flat_map(|path| path.split("::"))
+        .filter(|t| !t.is_empty())
+        .any(|t| {
+            !ALWAYS_VALID.contains(&t)
+                && !matches!(t, "DateTime" | "FixedOffset" | "NaiveDate")
+                && !newtypes.iter().any(|n| n.name == t)
+        })
+}
+
+fn option_literal<T: std::fmt::Debug>(value: Option<T>) -> String {
+    value.map_or_else(|| "None".to_string(), |v| format!("Some({v:?})"))
+}
+
+/// `serde_json::json!` tokens for a JSON value.
+fn json_literal(value: &serde_json::Value) -> String {
+    format!(
+        "serde_json::json!({})",
+        super::rust_backend::json_macro_tokens(value)
+    )
+}
+
+/// Emits a validated newtype: it deserializes through `TryFrom<inner>`, which checks the value
+/// against the newtype's schema (the constraint keywords of `schema`).
+pub(crate) fn emit_newtype(out: &mut impl Write, newtype: &Newtype) -> CodeGenResult<()> {
+    let Newtype { name, inner, .. } = newtype;
+    let hashable: bool = !matches!(inner.as_str(), "f32" | "f64");
+    let eq_hash: &str = if hashable { "Eq, Hash, " } else { "" };
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, PartialEq, {eq_hash}Serialize, Deserialize)]"
+    )?;
+    writeln!(out, "#[serde(try_from = \"{inner}\")]")?;
+    writeln!(out, "pub struct {name}({inner});")?;
+    writeln!(out)?;
+    writeln!(out, "impl {name} {{")?;
+    writeln!(out, "    /// The validated value.")?;
+    writeln!(out, "    pub fn get(&self) -> &{inner} {{")?;
+    writeln!(out, "        &self.0")?;
+    writeln!(out, "    }}")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Unwraps the validated value.")?;
+    writeln!(out, "    pub fn into_inner(self) -> {inner} {{")?;
+    writeln!(out, "        self.0")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl TryFrom<{inner}> for {name} {{")?;
+    writeln!(
+        out,
+        "    type Error = ::json_schema_rs::constraints::ConstraintError;"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    fn try_from(value: {inner}) -> Result<Self, Self::Error> {{"
+    )?;
+    writeln!(
+        out,
+        "        ::json_schema_rs::constraints::check(&value, &<Self as ::json_schema_rs::ToJsonSchema>::json_schema())?;"
+    )?;
+    writeln!(out, "        Ok(Self(value))")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    emit_newtype_schema(out, newtype)?;
+    writeln!(out)?;
+    emit_always_valid(out, name)
+}
+
+/// Emits the `ToJsonSchema` impl of a validated newtype: its inner type's schema with the
+/// constraint keywords of `newtype.schema`.
+fn emit_newtype_schema(out: &mut impl Write, newtype: &Newtype) -> CodeGenResult<()> {
+    let Newtype {
+        name,
+        inner,
+        schema,
+    } = newtype;
+    writeln!(out, "impl ::json_schema_rs::ToJsonSchema for {name} {{")?;
+    writeln!(
+        out,
+        "    fn json_schema() -> ::json_schema_rs::JsonSchema {{"
+    )?;
+    writeln!(out, "        ::json_schema_rs::JsonSchema {{")?;
+    if let Some(ref t) = schema.type_ {
+        writeln!(out, "            type_: Some({t:?}.to_string()),")?;
+    }
+    for (field, value) in [
+        ("title", &schema.title),
+        ("description", &schema.description),
+    ] {
+        if let Some(v) = value {
+            writeln!(out, "            {field}: Some({v:?}.to_string()),")?;
+        }
+    }
+    if let Some(ref values) = schema.enum_values {
+        let values: Vec<String> = values.iter().map(json_literal).collect();
+        writeln!(
+            out,
+            "            enum_values: Some(vec![{}]),",
+            values.join(", ")
+        )?;
+    }
+    if let Some(ref value) = schema.const_value {
+        writeln!(
+            out,
+            "            const_value: Some({}),",
+            json_literal(value)
+        )?;
+    }
+    if schema.minimum.is_some() || schema.maximum.is_some() {
+        writeln!(
+            out,
+            "            minimum: {},",
+            option_literal(schema.minimum)
+        )?;
+        writeln!(
+            out,
+            "            maximum: {},",
+            option_literal(schema.maximum)
+        )?;
+    }
+    if schema.min_length.is_some() || schema.max_length.is_some() {
+        writeln!(
+            out,
+            "            min_length: {},",
+            option_literal(schema.min_length)
+        )?;
+        writeln!(
+            out,
+            "            max_length: {},",
+            option_literal(schema.max_length)
+        )?;
+    }
+    if let Some(ref p) = schema.pattern {
+        writeln!(out, "            pattern: Some({p:?}.to_string()),")?;
+    }
+    writeln!(
+        out,
+        "            ..<{inner} as ::json_schema_rs::ToJsonSchema>::json_schema()"
+    )?;
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Emits `impl Validate` that always passes, for types that hold no constrained values (string
+/// enums, unconstrained root newtypes) and validated newtypes (valid by construction).
+pub(crate) fn emit_always_valid(out: &mut impl Write, name: &str) -> CodeGenResult<()> {
+    writeln!(out, "impl ::json_schema_rs::Validate for {name} {{")?;
+    writeln!(
+        out,
+        "    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {{"
+    )?;
+    writeln!(out, "        Ok(())")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Emits the newtypes and array deserializers recorded for `owner` (a struct or a tagged enum
+/// variant's `{enum}{variant}` name).
+fn emit_field_types(
+    out: &mut impl Write,
+    owner: &str,
+    record: &ConstrainedFields,
+) -> CodeGenResult<()> {
+    for newtype in &record.newtypes {
+        emit_newtype(out, newtype)?;
+    }
+    for f in &record.fields {
+        let Some((min_items, max_items)) = f.items else {
+            continue;
+        };
+        let fn_name: String = items_deserializer_name(owner, &f.field_name);
+        writeln!(
+            out,
+            "fn {fn_name}<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<{}, D::Error> {{",
+            f.ty
+        )?;
+        writeln!(
+            out,
+            "    let value: {} = Deserialize::deserialize(deserializer)?;",
+            f.ty
+        )?;
+        let check = |len: &str| {
+            format!(
+                "::json_schema_rs::constraints::check_items({len}, {}, {})\n        .map_err(|e| serde::de::Error::custom(e.within({:?})))?;",
+                option_literal(min_items),
+                option_literal(max_items),
+                f.key
+            )
+        };
+        if f.ty.starts_with("Option<") {
+            writeln!(out, "    if let Some(items) = &value {{")?;
+            writeln!(
+                out,
+                "        {}",
+                check("items.len()").replace('\n', "\n    ")
+            )?;
+            writeln!(out, "    }}")?;
+        } else {
+            writeln!(out, "    {}", check("value.len()"))?;
+        }
+        writeln!(out, "    Ok(value)")?;
+        writeln!(out, "}}")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Statements of a `validate` body checking `fields`, at `indent`. `access` gives the place and a
+/// reference for a field (`self.name` and `&self.name`, or a variant binding twice).
+fn validate_statements(
+    out: &mut impl Write,
+    indent: &str,
+    record: &ConstrainedFields,
+    mapped: &BTreeSet<String>,
+    access: impl Fn(&str) -> (String, String),
+) -> CodeGenResult<()> {
+    for f in &record.fields {
+        let (place, value): (String, String) = access(&f.field_name);
+        let key: &String = &f.key;
+        if let Some((min_items, max_items)) = f.items {
+            let check = |len: &str| {
+                format!(
+                    "::json_schema_rs::constraints::check_items({len}, {}, {}).map_err(|e| e.within({key:?}))?;",
+                    option_literal(min_items),
+                    option_literal(max_items),
+                )
+            };
+            if f.ty.starts_with("Option<") {
+                writeln!(out, "{indent}if let Some(items) = {value} {{")?;
+                writeln!(out, "{indent}    {}", check("items.len()"))?;
+                writeln!(out, "{indent}}}")?;
+            } else {
+                writeln!(out, "{indent}{}", check(&format!("{place}.len()")))?;
+            }
+        }
+        if needs_validate(&f.ty, &record.newtypes, mapped) {
+            writeln!(
+                out,
+                "{indent}::json_schema_rs::Validate::validate({value}).map_err(|e| e.within({key:?}))?;"
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Emits what a struct needs after its definition: its newtypes and array deserializers, and an
+/// `impl Validate` checking every field but those holding `mapped` types.
+pub(crate) fn emit_for_struct(
+    out: &mut impl Write,
+    name: &str,
+    record: &ConstrainedFields,
+    mapped: &BTreeSet<String>,
+) -> CodeGenResult<()> {
+    emit_field_types(out, name, record)?;
+    let mut body: Vec<u8> = Vec::new();
+    validate_statements(&mut body, "        ", record, mapped, |f| {
+        (format!("self.{f}"), format!("&self.{f}"))
+    })?;
+    if body.is_empty() {
+        return emit_always_valid(out, name);
+    }
+    writeln!(out, "impl ::json_schema_rs::Validate for {name} {{")?;
+    writeln!(
+        out,
+        "    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {{"
+    )?;
+    out.write_all(&body)?;
+    writeln!(out, "        Ok(())")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Emits what an internally tagged enum needs after its definition: the newtypes and array
+/// deserializers of every variant, and an `impl Validate` matching on the variant.
+/// `variants` holds (variant name, owner name used for its field types, record).
+pub(crate) fn emit_for_tagged_enum(
+    out: &mut impl Write,
+    name: &str,
+    variants: &[(String, String, ConstrainedFields)],
+    mapped: &BTreeSet<String>,
+) -> CodeGenResult<()> {
+    let mut arms: Vec<u8> = Vec::new();
+    let mut unmatched: bool = false;
+    for (variant, owner, record) in variants {
+        emit_field_types(out, owner, record)?;
+        let mut body: Vec<u8> = Vec::new();
+        validate_statements(&mut body, "                ", record, mapped, |f| {
+            (f.to_string(), f.to_string())
+        })?;
+        if body.is_empty() {
+            unmatched = true;
+            continue;
+        }
+        let bindings: Vec<&str> = record
+            .fields
+            .iter()
+            .filter(|f| f.items.is_some() || needs_validate(&f.ty, &record.newtypes, mapped))
+            .map(|f| f.field_name.as_str())
+            .collect();
+        writeln!(
+            arms,
+            "            Self::{variant} {{ {}, .. }} => {{",
+            bindings.join(", ")
+        )?;
+        arms.write_all(&body)?;
+        writeln!(arms, "            }}")?;
+    }
+    if arms.is_empty() {
+        return emit_always_valid(out, name);
+    }
+    writeln!(out, "impl ::json_schema_rs::Validate for {name} {{")?;
+    writeln!(
+        out,
+        "    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {{"
+    )?;
+    writeln!(out, "        match self {{")?;
+    out.write_all(&arms)?;
+    if unmatched {
+        writeln!(out, "            _ => {{}}")?;
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "        Ok(())")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Emits an `impl Validate` for an untagged union enum, validating the matched variant's value
+/// unless it holds one of the `mapped` types.
+pub(crate) fn emit_for_union(
+    out: &mut impl Write,
+    name: &str,
+    variants: &[(String, String)],
+    mapped: &BTreeSet<String>,
+) -> CodeGenResult<()> {
+    if !variants
+        .iter()
+        .any(|(_, ty)| needs_validate(ty, &[], mapped))
+    {
+        return emit_always_valid(out, name);
+    }
+    writeln!(out, "impl ::json_schema_rs::Validate for {name} {{")?;
+    writeln!(
+        out,
+        "    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {{"
+    )?;
+    writeln!(out, "        match self {{")?;
+    for (variant, ty) in variants {
+        if needs_validate(ty, &[], mapped) {
+            writeln!(
+                out,
+                "            Self::{variant}(value) => ::json_schema_rs::Validate::validate(value),"
+            )?;
+        } else {
+            writeln!(out, "            Self::{variant}(_) => Ok(()),")?;
+        }
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    Ok(())
+}
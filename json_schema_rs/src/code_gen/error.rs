@@ -8,7 +8,8 @@ pub type CodeGenResult<T> = Result<T, CodeGenError>;
 /// Errors that can occur during code generation.
 #[derive(Debug)]
 pub enum CodeGenError {
-    /// Root schema is not an object with properties.
+    /// Root schema has no shape a type can be generated for: it is not an object with properties,
+    /// an array with items, a map, a scalar, a string enum or an anyOf/oneOf.
     RootNotObject,
     /// I/O error while writing output.
     Io(std::io::Error),
@@ -45,6 +46,12 @@ pub enum CodeGenError {
     OneOfEmpty,
     /// `$ref` could not be resolved (or is unsupported in this crate).
     RefResolution { ref_str: String, reason: String },
+    /// Two different types in one module would share a name taken from `title`, which codegen
+    /// does not rename.
+    TypeNameCollision { name: String },
+    /// A type mapping (a `type_mappings` value, or `x-rust-type` in the schema at JSON Pointer
+    /// `key`) is not a Rust type path.
+    InvalidTypeMapping { key: String, rust_type: String },
 }
 
 impl fmt::Display for CodeGenError {
@@ -52,7 +59,7 @@ impl fmt::Display for CodeGenError {
         match self {
             CodeGenError::RootNotObject => write!(
                 f,
-                "root schema must have type \"object\" and non-empty properties"
+                "root schema must be an object with properties, an array with items, a map (additionalProperties schema), a scalar, a string enum, or an anyOf/oneOf"
             ),
             CodeGenError::Io(e) => write!(f, "io error: {e}"),
             CodeGenError::Batch { index, source } => {
@@ -106,6 +113,14 @@ impl fmt::Display for CodeGenError {
             CodeGenError::RefResolution { ref_str, reason } => {
                 write!(f, "could not resolve $ref \"{ref_str}\": {reason}")
             }
+            CodeGenError::TypeNameCollision { name } => write!(
+                f,
+                "two different types are both named \"{name}\" by their title; rename one of the titles or use the property-key model name source"
+            ),
+            CodeGenError::InvalidTypeMapping { key, rust_type } => write!(
+                f,
+                "type mapping \"{key}\" is not a Rust type path (like crate::money::Money or Vec<crate::Id>): {rust_type}"
+            ),
         }
     }
 }
@@ -125,7 +140,9 @@ impl std::error::Error for CodeGenError {
             | CodeGenError::AllOfMergeUnsupportedSubschema { .. }
             | CodeGenError::AnyOfEmpty
             | CodeGenError::OneOfEmpty
-            | CodeGenError::RefResolution { .. } => None,
+            | CodeGenError::RefResolution { .. }
+            | CodeGenError::TypeNameCollision { .. }
+            | CodeGenError::InvalidTypeMapping { .. } => None,
             CodeGenError::Batch { source, .. } => Some(source.as_ref()),
         }
     }
@@ -4,15 +4,24 @@
 //! source as bytes. The CLI matches on the language argument and calls the
 //! appropriate backend (e.g. the Rust backend).
 
+mod derives;
+mod enforce;
 mod error;
+mod nullable;
 mod rust_backend;
 mod settings;
+mod type_mappings;
 
 pub use error::{CodeGenError, CodeGenResult};
+pub(crate) use rust_backend::merge_all_of;
 pub use rust_backend::{RustBackend, generate_rust};
-pub use settings::{CodeGenSettings, CodeGenSettingsBuilder, DedupeMode, ModelNameSource};
+pub use settings::{
+    CodeGenSettings, CodeGenSettingsBuilder, DedupeMode, ModelNameSource, NameCollisionStrategy,
+    TypeAttributes,
+};
 
 use crate::json_schema::JsonSchema;
+use std::fmt;
 
 /// Output of Rust code generation: optional shared buffer plus one buffer per input schema.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,6 +30,56 @@ pub struct GenerateRustOutput {
     pub shared: Option<Vec<u8>>,
     /// One UTF-8 Rust source buffer per input schema; length equals number of schemas.
     pub per_schema: Vec<Vec<u8>>,
+    /// Requested derives left off a type because a field type does not support them (see
+    /// [`CodeGenSettings::type_attributes`]).
+    pub skipped_derives: Vec<SkippedDerive>,
+    /// Keys of [`CodeGenSettings::type_attributes_by_type`] (type names or JSON Pointers) that
+    /// match no generated type, so their derives and attributes were not applied.
+    pub unmatched_type_selectors: Vec<String>,
+    /// `$defs`/`definitions` entries that [`CodeGenSettings::emit_all_defs`] generated no type
+    /// for.
+    pub skipped_defs: Vec<SkippedDef>,
+}
+
+/// A requested derive that was not added to a generated type, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDerive {
+    /// The generated type.
+    pub type_name: String,
+    /// The derive that was left out (e.g. `"Hash"`).
+    pub derive: String,
+    /// Why the type cannot derive it (e.g. ``"`price: f64` does not implement Hash"``).
+    pub reason: String,
+}
+
+impl fmt::Display for SkippedDerive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "skipped derive({}) on {}: {}",
+            self.derive, self.type_name, self.reason
+        )
+    }
+}
+
+/// A `$defs`/`definitions` entry that [`CodeGenSettings::emit_all_defs`] generated no type for:
+/// arrays, maps and scalars only become a type where a field uses them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedDef {
+    /// 0-based index of the schema in the batch.
+    pub schema_index: usize,
+    /// JSON Pointer to the entry (e.g. `"#/$defs/tags"`).
+    pub pointer: String,
+}
+
+impl fmt::Display for SkippedDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no type generated for {} in schema {}: only objects with properties, string enums and anyOf/oneOf generate a type of their own",
+            self.pointer, self.schema_index
+        )
+    }
 }
 
 /// Contract for a codegen backend: schemas in, [`GenerateRustOutput`] with optional shared buffer and per-schema buffers.
@@ -29,7 +88,7 @@ pub trait CodeGenBackend {
     ///
     /// # Errors
     ///
-    /// Returns [`CodeGenError::RootNotObject`] if a root schema is not an object with properties.
+    /// Returns [`CodeGenError::RootNotObject`] if a root schema has no shape a type can be generated for.
     /// Returns [`CodeGenError::Io`] on write failure.
     /// Returns [`CodeGenError::Batch`] with index when one schema in the batch fails.
     fn generate(
@@ -0,0 +1,92 @@
+//! Nullable properties generated as `Option`.
+//!
+//! The model keeps a single `type`, so a property that may be `null` is written as an `anyOf` (or
+//! `oneOf`) of its schema and `{"type": "null"}`; schema inference emits exactly that shape.
+//! [`unwrap_nullable_properties`] rewrites such a property, on a copy of the schema before
+//! codegen, to its non-null branch and drops it from `required`, so the backend generates
+//! `Option<T>` for it instead of a union enum with a `serde_json::Value` variant.
+
+use crate::json_schema::JsonSchema;
+
+/// Returns `root` with every nullable property (see the module docs) replaced by its non-null
+/// branch and left out of `required`. The property's `description` is kept when the branch has
+/// none; a property with any other keyword next to the `anyOf`/`oneOf` is left alone.
+pub(crate) fn unwrap_nullable_properties(mut root: JsonSchema) -> JsonSchema {
+    root.walk_mut(|_, _, schema| {
+        let mut unwrapped: Vec<String> = Vec::new();
+        for (key, prop) in &mut schema.properties {
+            if let Some(inner) = non_null_branch(prop) {
+                *prop = inner;
+                unwrapped.push(key.clone());
+            }
+        }
+        if let Some(required) = &mut schema.required {
+            required.retain(|key| !unwrapped.contains(key));
+        }
+    });
+    root
+}
+
+/// The branch of `schema` that is not `{"type": "null"}`, when `schema` is only an `anyOf` or
+/// `oneOf` of that branch and `{"type": "null"}` (plus an optional `description`).
+fn non_null_branch(schema: &JsonSchema) -> Option<JsonSchema> {
+    let branches: &[JsonSchema] = match (&schema.any_of, &schema.one_of) {
+        (Some(branches), None) | (None, Some(branches)) => branches,
+        _ => return None,
+    };
+    let rest: JsonSchema = JsonSchema {
+        any_of: None,
+        one_of: None,
+        description: None,
+        ..schema.clone()
+    };
+    if rest != JsonSchema::default() {
+        return None;
+    }
+    let null: JsonSchema = JsonSchema {
+        type_: Some("null".to_string()),
+        ..JsonSchema::default()
+    };
+    let inner: &JsonSchema = match branches {
+        [a, b] if *b == null && *a != null => a,
+        [a, b] if *a == null && *b != null => b,
+        _ => return None,
+    };
+    Some(JsonSchema {
+        description: inner
+            .description
+            .clone()
+            .or_else(|| schema.description.clone()),
+        ..inner.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unwrap_nullable_properties;
+    use crate::json_schema::JsonSchema;
+
+    #[test]
+    fn nullable_properties_become_optional() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{
+                "a":{"anyOf":[{"type":"string"},{"type":"null"}],"description":"A."},
+                "b":{"oneOf":[{"type":"null"},{"type":"integer"}]},
+                "c":{"anyOf":[{"type":"string"},{"type":"integer"},{"type":"null"}]},
+                "d":{"anyOf":[{"type":"string"},{"type":"null"}],"title":"D"}
+            },"required":["a","b","c","d"]}"#,
+        )
+        .unwrap();
+        let actual: JsonSchema = unwrap_nullable_properties(schema);
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{
+                "a":{"type":"string","description":"A."},
+                "b":{"type":"integer"},
+                "c":{"anyOf":[{"type":"string"},{"type":"integer"},{"type":"null"}]},
+                "d":{"anyOf":[{"type":"string"},{"type":"null"}],"title":"D"}
+            },"required":["c","d"]}"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+    }
+}
@@ -4,12 +4,23 @@ use super::CodeGenBackend;
 use super::CodeGenError;
 use super::CodeGenResult;
 use super::GenerateRustOutput;
-use super::settings::{CodeGenSettings, DedupeMode, ModelNameSource};
+use super::SkippedDef;
+use super::SkippedDerive;
+use super::derives::{self, DeriveSets};
+use super::enforce::{self, ConstrainedFields};
+use super::nullable;
+use super::settings::{
+    CodeGenSettings, DedupeMode, ModelNameSource, NameCollisionStrategy, TypeAttributes,
+};
+use super::type_mappings::{self, mapped_type};
+use crate::json_pointer::JsonPointer;
 use crate::json_schema::JsonSchema;
+use crate::json_schema::SchemaKeyword;
 use crate::json_schema::json_schema::AdditionalProperties;
 use crate::json_schema::ref_resolver;
 use crate::sanitizers::{
     enum_variant_names_with_collision_resolution, sanitize_field_name, sanitize_struct_name,
+    to_pascal_case,
 };
 use heck::ToSnakeCase;
 use std::cmp::Ordering;
@@ -26,32 +37,77 @@ impl CodeGenBackend for RustBackend {
         schemas: &[JsonSchema],
         settings: &CodeGenSettings,
     ) -> CodeGenResult<GenerateRustOutput> {
-        match settings.dedupe_mode {
+        let mapped: Vec<JsonSchema> = schemas
+            .iter()
+            .enumerate()
+            .map(|(index, schema)| {
+                type_mappings::apply_type_mappings(schema, settings)
+                    .map(nullable::unwrap_nullable_properties)
+                    .map_err(|e| CodeGenError::Batch {
+                        index,
+                        source: Box::new(e),
+                    })
+            })
+            .collect::<CodeGenResult<Vec<_>>>()?;
+        let schemas: &[JsonSchema] = &mapped;
+        let mut skipped_defs: Vec<SkippedDef> = Vec::new();
+        if settings.emit_all_defs {
+            for (index, schema) in schemas.iter().enumerate() {
+                let pointers: Vec<String> =
+                    defs_without_type(schema, settings).map_err(|e| CodeGenError::Batch {
+                        index,
+                        source: Box::new(e),
+                    })?;
+                skipped_defs.extend(pointers.into_iter().map(|pointer| SkippedDef {
+                    schema_index: index,
+                    pointer,
+                }));
+            }
+        }
+        let output: GenerateRustOutput = match settings.dedupe_mode {
             DedupeMode::Disabled => {
                 let mut per_schema: Vec<Vec<u8>> = Vec::with_capacity(schemas.len());
+                let mut skipped_derives: Vec<SkippedDerive> = Vec::new();
+                let mut matched: BTreeSet<String> = BTreeSet::new();
                 for (index, schema) in schemas.iter().enumerate() {
                     let mut out = Cursor::new(Vec::new());
-                    emit_rust(schema, &mut out, settings).map_err(|e| CodeGenError::Batch {
-                        index,
-                        source: Box::new(e),
-                    })?;
+                    let requested: RequestedAttributes = emit_rust(schema, &mut out, settings)
+                        .map_err(|e| CodeGenError::Batch {
+                            index,
+                            source: Box::new(e),
+                        })?;
+                    let (result, sets) = derives::apply_type_attributes(
+                        out.into_inner(),
+                        &settings.type_attributes,
+                        &requested.by_name,
+                        &DeriveSets::new(),
+                        &mut skipped_derives,
+                    );
+                    matched.extend(requested.matched_in(&sets, &DeriveSets::new()));
                     per_schema.push({
-                        let result = maybe_prepend_btreemap_use(out.into_inner());
+                        let result = maybe_prepend_btreemap_use(result);
                         let result = maybe_prepend_hash_set_use(result);
                         #[cfg(feature = "uuid")]
                         let result = maybe_prepend_uuid_use(result);
                         result
                     });
                 }
-                Ok(GenerateRustOutput {
+                GenerateRustOutput {
                     shared: None,
                     per_schema,
-                })
+                    skipped_derives,
+                    unmatched_type_selectors: unmatched_selectors(settings, &matched),
+                    skipped_defs: Vec::new(),
+                }
             }
             DedupeMode::Functional | DedupeMode::Full => {
-                generate_rust_with_dedupe(schemas, settings)
+                generate_rust_with_dedupe(schemas, settings)?
             }
-        }
+        };
+        Ok(GenerateRustOutput {
+            skipped_defs,
+            ..output
+        })
     }
 }
 
@@ -59,6 +115,19 @@ impl CodeGenBackend for RustBackend {
 struct StructToEmit {
     name: String,
     schema: JsonSchema,
+    claim: NameClaim,
+}
+
+/// The type generated for a document root that is not an object with properties, a string enum
+/// or an anyOf/oneOf: a type alias for an array or map, a newtype struct for a scalar.
+struct RootAlias {
+    name: String,
+    /// The aliased type, or the newtype's field type.
+    ty: String,
+    /// Emit `#[serde(transparent)] pub struct Name(pub Ty);` instead of `pub type Name = Ty;`.
+    newtype: bool,
+    /// The (ref- and allOf-resolved) root schema, for doc comments and container attributes.
+    schema: JsonSchema,
 }
 
 /// Map from enum value list to (name, description, examples) for dedupe path. Used to resolve enum type names and carry description and examples from first occurrence.
@@ -71,18 +140,95 @@ struct EnumToEmit {
     values: Vec<String>,
     description: Option<String>,
     examples: Option<Vec<serde_json::Value>>,
+    claim: NameClaim,
 }
 
 /// One anyOf enum to emit: name and list of (`variant_name`, `rust_type_string`).
+#[derive(Clone)]
 struct AnyOfEnumToEmit {
     name: String,
     variants: Vec<(String, String)>,
+    uses: Vec<UnionUse>,
+    claim: NameClaim,
 }
 
-/// One oneOf enum to emit: name and list of (`variant_name`, `rust_type_string`).
+/// One oneOf enum to emit: name and list of (`variant_name`, `rust_type_string`). A discriminated
+/// oneOf is `tagged`; its `variants` then list each variant's field type names.
+#[derive(Clone)]
 struct OneOfEnumToEmit {
     name: String,
     variants: Vec<(String, String)>,
+    uses: Vec<UnionUse>,
+    claim: NameClaim,
+    tagged: Option<TaggedUnion>,
+}
+
+/// A oneOf whose branches pin one property to distinct strings, emitted as an internally tagged
+/// enum (`#[serde(tag = "...")]`) with one struct variant per branch.
+#[derive(Clone)]
+struct TaggedUnion {
+    property: String,
+    /// (`variant_name`, tag value, branch schema without the tag property) per branch.
+    variants: Vec<(String, String, JsonSchema)>,
+    /// Batch index of the schema whose `$ref`s the branch schemas use.
+    schema_idx: usize,
+}
+
+/// A property whose anyOf/oneOf a union enum was generated for: the property key and its
+/// (ref-resolved) schema. Fields look their enum up by these, since it may have been renamed.
+type UnionUse = (String, JsonSchema);
+
+/// What a schema position found by [`reachable_types`] generates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ReachedKind {
+    /// An object with properties.
+    Struct,
+    /// A string `enum` or `const` with these values.
+    StringEnum(Vec<String>),
+    /// A non-empty `anyOf`.
+    AnyOf,
+    /// A non-empty `oneOf`.
+    OneOf,
+}
+
+/// A generated type found by [`reachable_types`]: its ref- and allOf-resolved schema and the
+/// naming context it was reached in.
+#[derive(Debug, Clone)]
+struct ReachedType {
+    kind: ReachedKind,
+    schema: JsonSchema,
+    from_key: Option<String>,
+    is_root: bool,
+    /// Name of the enclosing struct.
+    parent: Option<String>,
+    /// For a union that is a property's type: that property.
+    site: Option<UnionUse>,
+}
+
+/// A schema position still to be visited by [`reachable_types`].
+struct WalkNode {
+    schema: JsonSchema,
+    /// Name source unless the schema is a `$ref` to a `$defs` entry (then the entry's name).
+    from_key: Option<String>,
+    is_root: bool,
+    parent: Option<String>,
+    /// Property key when this is a property's schema.
+    site_key: Option<String>,
+    /// The document root, or a `$defs` entry walked for [`CodeGenSettings::emit_all_defs`].
+    top_level: bool,
+}
+
+/// A generated type competing for a name within one module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NameClaim {
+    /// Name derived from the title, `$defs` key, or property key.
+    candidate: String,
+    /// The name comes from `title`; it is never renamed.
+    pinned: bool,
+    /// The type is the root struct of a schema; roots keep their name before other unpinned types.
+    is_root: bool,
+    /// Name of the enclosing struct, for [`NameCollisionStrategy::ParentPrefix`].
+    parent: Option<String>,
 }
 
 /// Returns doc comment lines for emission: empty if description is None or whitespace-only; else one line per non-empty trimmed line (no blank lines).
@@ -228,6 +374,7 @@ struct DedupeKey {
     pattern: Option<String>,
     format: Option<String>,
     default_value: Option<serde_json::Value>,
+    extensions: BTreeMap<String, serde_json::Value>,
 }
 
 impl PartialEq for DedupeKey {
@@ -251,6 +398,7 @@ impl PartialEq for DedupeKey {
             && self.pattern == other.pattern
             && self.format == other.format
             && self.default_value == other.default_value
+            && self.extensions == other.extensions
     }
 }
 
@@ -301,6 +449,22 @@ impl Ord for DedupeKey {
             .then_with(|| {
                 compare_option_value(self.default_value.as_ref(), other.default_value.as_ref())
             })
+            .then_with(|| {
+                self.extensions
+                    .keys()
+                    .cmp(other.extensions.keys())
+                    .then_with(|| {
+                        for (a_val, b_val) in
+                            self.extensions.values().zip(other.extensions.values())
+                        {
+                            let c: Ordering = compare_option_value(Some(a_val), Some(b_val));
+                            if c != Ordering::Equal {
+                                return c;
+                            }
+                        }
+                        Ordering::Equal
+                    })
+            })
     }
 }
 
@@ -449,6 +613,91 @@ fn maybe_prepend_hash_set_use(mut buf: Vec<u8>) -> Vec<u8> {
     buf
 }
 
+/// Rust type for a `type: "string"` schema whose `format` maps to a dedicated type: `ipv4` →
+/// `std::net::Ipv4Addr` and `ipv6` → `std::net::Ipv6Addr` always; under an enabled cargo feature,
+/// `uuid` → `Uuid` and (`chrono`) `date-time` → `chrono::DateTime<chrono::FixedOffset>`,
+/// `date` → `chrono::NaiveDate`. Returns `None` when the string is emitted as `String`.
+fn rust_type_for_string_format(schema: &JsonSchema) -> Option<&'static str> {
+    match schema.format.as_deref() {
+        Some("uuid") if cfg!(feature = "uuid") => Some("Uuid"),
+        Some("date-time") if cfg!(feature = "chrono") => {
+            Some("chrono::DateTime<chrono::FixedOffset>")
+        }
+        Some("date") if cfg!(feature = "chrono") => Some("chrono::NaiveDate"),
+        Some("ipv4") => Some("std::net::Ipv4Addr"),
+        Some("ipv6") => Some("std::net::Ipv6Addr"),
+        _ => None,
+    }
+}
+
+/// Rust type a validated newtype wraps for a scalar schema with constraints its plain type cannot
+/// hold: a `String` with `minLength`, `maxLength` or `pattern`, an integer or number with
+/// `minimum` or `maximum`, or a non-string `enum`/`const` (held as the number type, or as
+/// `serde_json::Value` when the schema has no type). `None` for everything else, including string
+/// enums (emitted as Rust enums) and strings with a dedicated format type.
+fn constrained_scalar_inner(schema: &JsonSchema) -> Option<String> {
+    if schema.any_of.as_ref().is_some_and(|v| !v.is_empty())
+        || schema.one_of.as_ref().is_some_and(|v| !v.is_empty())
+        || string_enum_or_const_values(schema).is_some()
+    {
+        return None;
+    }
+    let has_enum: bool =
+        schema.enum_values.as_ref().is_some_and(|v| !v.is_empty()) || schema.const_value.is_some();
+    if schema.is_string() {
+        let constrained: bool = schema.min_length.is_some()
+            || schema.max_length.is_some()
+            || schema.pattern.is_some()
+            || has_enum;
+        return (constrained && rust_type_for_string_format(schema).is_none())
+            .then(|| "String".to_string());
+    }
+    if schema.is_integer() || schema.is_number() {
+        let constrained: bool = schema.minimum.is_some() || schema.maximum.is_some() || has_enum;
+        return constrained.then(|| rust_numeric_type_for_schema(schema));
+    }
+    (has_enum && schema.type_.is_none()).then(|| "serde_json::Value".to_string())
+}
+
+/// With [`CodeGenSettings::enforce_constraints`], the inner type of the validated newtype a
+/// scalar field or item gets (see [`constrained_scalar_inner`]).
+fn validated_inner(settings: &CodeGenSettings, schema: &JsonSchema) -> Option<String> {
+    if !settings.enforce_constraints || mapped_type(schema).is_some() {
+        return None;
+    }
+    constrained_scalar_inner(schema)
+}
+
+/// Name of the validated newtype for the value at `key` of struct `owner` (`UserName` for `name`
+/// of `User`).
+fn constrained_type_name(owner: &str, key: &str) -> String {
+    format!("{owner}{}", to_pascal_case(key))
+}
+
+/// Rust expression of type `inner` for a schema default, or `None` when it has no literal form.
+fn validated_default_expr(value: &serde_json::Value, inner: &str) -> Option<String> {
+    match inner {
+        "serde_json::Value" => Some(format!("serde_json::json!({})", json_macro_tokens(value))),
+        "String" => value.as_str().map(|s| format!("{s:?}.to_string()")),
+        "f32" | "f64" => value.as_f64().map(|n| format!("{n:?}")),
+        _ => value
+            .as_i64()
+            .map(|n| n.to_string())
+            .or_else(|| value.as_u64().map(|n| n.to_string())),
+    }
+}
+
+/// Whether a field holding a validated newtype (of `inner`) gets a default function: the
+/// default must satisfy the field's schema, or the newtype could not be built from it.
+fn validated_default(
+    prop_schema: &JsonSchema,
+    value: &serde_json::Value,
+    inner: &str,
+) -> Option<String> {
+    crate::validator::validate(prop_schema, value).ok()?;
+    validated_default_expr(value, inner)
+}
+
 /// If the buffer contains "`Uuid`", insert `use uuid::Uuid;` after the HashSet use line (if present) or after the serde use line.
 #[cfg(feature = "uuid")]
 fn maybe_prepend_uuid_use(mut buf: Vec<u8>) -> Vec<u8> {
@@ -481,6 +730,21 @@ fn emit_struct_derive_and_attrs(
     name: &str,
     schema: &JsonSchema,
 ) -> CodeGenResult<()> {
+    emit_type_derive_and_attrs(out, schema)?;
+    if schema
+        .additional_properties
+        .as_ref()
+        .is_some_and(|ap| matches!(ap, AdditionalProperties::Forbid))
+    {
+        writeln!(out, "#[serde(deny_unknown_fields)]")?;
+    }
+    writeln!(out, "pub struct {name} {{")?;
+    Ok(())
+}
+
+/// Doc comment, `#[deprecated]`, derive and `#[json_schema(...)]` container attributes shared by
+/// generated structs and root newtype structs.
+fn emit_type_derive_and_attrs(out: &mut impl Write, schema: &JsonSchema) -> CodeGenResult<()> {
     for line in doc_lines(schema.description.as_deref()) {
         writeln!(out, "/// {line}")?;
     }
@@ -503,14 +767,52 @@ fn emit_struct_derive_and_attrs(
         let escaped = i.replace('\\', "\\\\").replace('"', "\\\"");
         writeln!(out, "#[json_schema(id = \"{escaped}\")]")?;
     }
-    if schema
-        .additional_properties
-        .as_ref()
-        .is_some_and(|ap| matches!(ap, AdditionalProperties::Forbid))
+    emit_extension_attr(out, "", &schema.extensions)?;
+    Ok(())
+}
+
+/// Emits a [`RootAlias`]: `pub type Name = Ty;`, or a transparent newtype struct deriving
+/// `ToJsonSchema` for a scalar root.
+fn emit_root_alias(
+    out: &mut impl Write,
+    alias: &RootAlias,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<()> {
+    if let Some(inner) = alias
+        .newtype
+        .then(|| validated_inner(settings, &alias.schema))
+        .flatten()
     {
-        writeln!(out, "#[serde(deny_unknown_fields)]")?;
+        for line in doc_lines(alias.schema.description.as_deref()) {
+            writeln!(out, "/// {line}")?;
+        }
+        emit_struct_deprecated_attr(out, &alias.schema)?;
+        return enforce::emit_newtype(
+            out,
+            &enforce::Newtype {
+                name: alias.name.clone(),
+                inner,
+                schema: alias.schema.clone(),
+            },
+        );
     }
-    writeln!(out, "pub struct {name} {{")?;
+    if alias.newtype {
+        emit_type_derive_and_attrs(out, &alias.schema)?;
+        writeln!(out, "#[serde(transparent)]")?;
+        writeln!(out, "pub struct {}(pub {});", alias.name, alias.ty)?;
+        if settings.enforce_constraints {
+            writeln!(out)?;
+            enforce::emit_always_valid(out, &alias.name)?;
+            return Ok(());
+        }
+    } else {
+        for line in doc_lines(alias.schema.description.as_deref()) {
+            writeln!(out, "/// {line}")?;
+        }
+        emit_struct_deprecated_attr(out, &alias.schema)?;
+        writeln!(out, "pub type {} = {};", alias.name, alias.ty)?;
+    }
+    writeln!(out)?;
     Ok(())
 }
 
@@ -604,6 +906,16 @@ impl DedupeKey {
             pattern,
             format,
             default_value: schema.default_value.clone(),
+            extensions: match mode {
+                DedupeMode::Full => schema.extensions.clone(),
+                // The mapped type decides the field type, so it is functional.
+                DedupeMode::Functional | DedupeMode::Disabled => schema
+                    .extensions
+                    .get_key_value(type_mappings::RUST_TYPE_KEYWORD)
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .into_iter()
+                    .collect(),
+            },
         }
     }
 }
@@ -635,6 +947,80 @@ fn struct_name_from(
         })
 }
 
+/// Returns true when [`struct_name_from`] takes the name from `title`.
+fn name_is_from_title(
+    title: Option<&str>,
+    from_key: Option<&str>,
+    settings: &CodeGenSettings,
+) -> bool {
+    let has_title: bool = title.is_some_and(|t| !t.trim().is_empty());
+    match settings.model_name_source {
+        ModelNameSource::TitleFirst => has_title,
+        ModelNameSource::PropertyKeyFirst => has_title && from_key.is_none(),
+    }
+}
+
+/// Name claim for a type found at (`title`, `from_key`) inside the struct named `parent`; a
+/// struct without a parent is a schema root. `is_root` is passed on to [`struct_name_from`].
+fn struct_name_claim(
+    title: Option<&str>,
+    from_key: Option<&str>,
+    is_root: bool,
+    parent: Option<String>,
+    settings: &CodeGenSettings,
+) -> NameClaim {
+    NameClaim {
+        candidate: struct_name_from(title, from_key, is_root, settings),
+        pinned: name_is_from_title(title, from_key, settings),
+        is_root: parent.is_none(),
+        parent,
+    }
+}
+
+/// Assigns each claim a name that no other claim and nothing in `reserved` uses, returned in
+/// claim order. Pinned claims are served first, then roots, then the rest in the given order; a
+/// claim whose name is taken is renamed per `strategy`, or fails with
+/// [`CodeGenError::TypeNameCollision`] when it is pinned.
+fn resolve_type_names(
+    claims: &[&NameClaim],
+    reserved: &BTreeSet<String>,
+    strategy: NameCollisionStrategy,
+) -> CodeGenResult<Vec<String>> {
+    let mut order: Vec<usize> = (0..claims.len()).collect();
+    order.sort_by_key(|&i| (!claims[i].pinned, !claims[i].is_root));
+    let mut taken: BTreeSet<String> = reserved.clone();
+    let mut names: Vec<String> = vec![String::new(); claims.len()];
+    for i in order {
+        let claim: &NameClaim = claims[i];
+        let name: String = if !taken.contains(&claim.candidate) {
+            claim.candidate.clone()
+        } else if claim.pinned {
+            return Err(CodeGenError::TypeNameCollision {
+                name: claim.candidate.clone(),
+            });
+        } else {
+            let base: String = match (strategy, &claim.parent) {
+                (NameCollisionStrategy::ParentPrefix, Some(parent)) => {
+                    format!("{parent}{}", claim.candidate)
+                }
+                _ => claim.candidate.clone(),
+            };
+            if taken.contains(&base) {
+                let mut suffix: usize = 2;
+                while taken.contains(&format!("{base}{suffix}")) {
+                    suffix += 1;
+                }
+                format!("{base}{suffix}")
+            } else {
+                base
+            }
+        };
+        taken.insert(name.clone());
+        names[i] = name;
+    }
+    Ok(names)
+}
+
 const I64_MAX_AS_F64: f64 = 9_223_372_036_854_775_807.0_f64; // i64::MAX, exactly representable
 
 /// Returns the Rust type string for an integer or number schema using `minimum` and `maximum` when both present and valid; otherwise fallback to `i64` or `f64`.
@@ -927,13 +1313,17 @@ fn rust_type_for_item_schema(
     settings: &CodeGenSettings,
     mode: DedupeMode,
 ) -> CodeGenResult<String> {
+    if let Some(ty) = mapped_type(schema) {
+        return Ok(ty.to_string());
+    }
     let mut def_key: Option<String> = None;
+    let mut refs_root: bool = false;
     let schema: &JsonSchema = if let Some(ref_str) = schema.ref_.as_deref() {
         match ref_resolver::parse_ref(ref_str) {
             Ok(
                 ref_resolver::ParsedRef::Defs(name) | ref_resolver::ParsedRef::Definitions(name),
             ) => def_key = Some(name),
-            Ok(ref_resolver::ParsedRef::Root) => {}
+            Ok(ref_resolver::ParsedRef::Root) => refs_root = true,
             Err(e) => {
                 return Err(CodeGenError::RefResolution {
                     ref_str: ref_str.to_string(),
@@ -961,11 +1351,10 @@ fn rust_type_for_item_schema(
         || (schema.enum_values.as_ref().is_some_and(|v| !v.is_empty()) && !schema.is_string_enum())
         || (schema.const_value.is_some() && !schema.is_string_const())
     {
-        #[cfg(feature = "uuid")]
+        if schema.is_string()
+            && let Some(format_ty) = rust_type_for_string_format(schema)
         {
-            if schema.format.as_deref() == Some("uuid") {
-                return Ok("Uuid".to_string());
-            }
+            return Ok(format_ty.to_string());
         }
         return Ok("String".to_string());
     }
@@ -979,16 +1368,19 @@ fn rust_type_for_item_schema(
         return Ok("bool".to_string());
     }
     if schema.is_object_with_properties() {
-        if let Some(key) = def_key.as_deref() {
-            return Ok(sanitize_struct_name(key));
-        }
-        let name: String = if let Some(m) = key_to_name {
-            let key = DedupeKey::from_schema(schema, mode);
-            m.get(&key).cloned().unwrap_or_else(|| {
-                struct_name_from(schema.title.as_deref(), from_key, false, settings)
-            })
+        let name: String = if let Some(m) = key_to_name
+            && let Some(name) = m.get(&DedupeKey::from_schema(schema, mode))
+        {
+            name.clone()
+        } else if refs_root {
+            root_struct_name(root, settings)?
         } else {
-            struct_name_from(schema.title.as_deref(), from_key, false, settings)
+            struct_name_from(
+                schema.title.as_deref(),
+                def_key.as_deref().or(from_key),
+                false,
+                settings,
+            )
         };
         return Ok(name);
     }
@@ -1014,1697 +1406,4595 @@ fn rust_type_for_item_schema(
     Ok("serde_json::Value".to_string())
 }
 
-/// Collect all string enums from a schema (and nested properties). Dedupe by value list; first occurrence wins the name, description, and examples.
-fn collect_enums(
+/// Name of the struct generated for the document root, as [`collect_structs`] names it.
+fn root_struct_name(root: &JsonSchema, settings: &CodeGenSettings) -> CodeGenResult<String> {
+    let root_resolved: JsonSchema = resolve_all_of_for_codegen(root)?;
+    let (effective, from_key) = resolve_ref_for_codegen(root, &root_resolved, None)?;
+    Ok(struct_name_from(
+        effective.title.as_deref(),
+        from_key.as_deref(),
+        from_key.is_none(),
+        settings,
+    ))
+}
+
+/// The [`RootAlias`] for the document `root`, or `None` when the root generates a struct or an
+/// enum. Array items and map values are named after the root type (`RootItem`, `RootValue`), as
+/// [`reachable_types`] names them.
+///
+/// # Errors
+///
+/// Returns [`CodeGenError::RootNotObject`] when the root is none of these shapes (e.g. `{}`, or an
+/// object without properties or an `additionalProperties` schema).
+fn root_alias(
+    root: &JsonSchema,
+    settings: &CodeGenSettings,
+    enum_values_to_name: &BTreeMap<Vec<String>, String>,
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+) -> CodeGenResult<Option<RootAlias>> {
+    let root_resolved: JsonSchema = resolve_all_of_for_codegen(root)?;
+    let (target, from_key) = resolve_ref_for_codegen(root, &root_resolved, None)?;
+    let schema: JsonSchema = resolve_all_of_for_codegen(&target)?;
+    if schema.is_object_with_properties()
+        || schema.any_of.as_ref().is_some_and(|v| !v.is_empty())
+        || schema.one_of.as_ref().is_some_and(|v| !v.is_empty())
+        || string_enum_or_const_values(&schema).is_some()
+    {
+        return Ok(None);
+    }
+    let name: String = struct_name_from(
+        schema.title.as_deref(),
+        from_key.as_deref(),
+        from_key.is_none(),
+        settings,
+    );
+    let type_for = |sub: &JsonSchema, key: Option<&str>| {
+        rust_type_for_item_schema(
+            root,
+            sub,
+            key,
+            Some(enum_values_to_name),
+            key_to_name,
+            settings,
+            mode,
+        )
+    };
+    let is_scalar: bool = schema.is_string()
+        || schema.is_integer()
+        || schema.is_number()
+        || schema.is_boolean()
+        || schema.enum_values.as_ref().is_some_and(|v| !v.is_empty())
+        || schema.const_value.is_some();
+    let (ty, newtype): (String, bool) = if schema.is_array_with_items() {
+        (type_for(&schema, Some(&format!("{name}Item")))?, false)
+    } else if let Some(values) = map_value_schema(&schema) {
+        let value_ty: String = type_for(values, Some(&format!("{name}Value")))?;
+        (format!("BTreeMap<String, {value_ty}>"), false)
+    } else if is_scalar {
+        (type_for(&schema, None)?, true)
+    } else {
+        return Err(CodeGenError::RootNotObject);
+    };
+    Ok(Some(RootAlias {
+        name,
+        ty,
+        newtype,
+        schema,
+    }))
+}
+
+/// Struct name for an object-valued property: the dedupe canonical name when known, the root
+/// struct for `$ref: "#"`, otherwise from the title and the `$defs` name (or property key).
+fn nested_struct_type_name(
     root: &JsonSchema,
-    schema: &JsonSchema,
+    prop_schema: &JsonSchema,
+    key: &str,
     settings: &CodeGenSettings,
-) -> CodeGenResult<Vec<EnumToEmit>> {
-    let mut key_to_name_desc_examples: EnumValuesToNameMap = BTreeMap::new();
-    let mut stack: Vec<JsonSchema> = vec![schema.clone()];
-    while let Some(node) = stack.pop() {
-        let (node, _) = resolve_ref_for_codegen(root, &node, None)?;
-        for (key, prop_schema) in &node.properties {
-            let (prop_effective, from_key) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
-            if let Some(values) = string_enum_or_const_values(&prop_effective) {
-                key_to_name_desc_examples
-                    .entry(values.clone())
-                    .or_insert_with(|| {
-                        let name: String = struct_name_from(
-                            prop_effective.title.as_deref(),
-                            from_key.as_deref(),
-                            false,
-                            settings,
-                        );
-                        let description: Option<String> = prop_effective
-                            .description
-                            .as_ref()
-                            .filter(|s| !s.trim().is_empty())
-                            .cloned();
-                        let examples: Option<Vec<serde_json::Value>> =
-                            prop_effective.examples.clone();
-                        (name, description, examples)
-                    });
-            }
-            if prop_effective.is_object_with_properties() {
-                stack.push(prop_effective.clone());
-            }
-            if prop_effective.is_array_with_items()
-                && let Some(ref items) = prop_effective.items
-            {
-                let (items_effective, items_from_key) =
-                    resolve_ref_for_codegen(root, items.as_ref(), Some(key))?;
-                if let Some(values) = string_enum_or_const_values(&items_effective) {
-                    key_to_name_desc_examples
-                        .entry(values.clone())
-                        .or_insert_with(|| {
-                            let name: String = struct_name_from(
-                                items_effective.title.as_deref(),
-                                items_from_key.as_deref(),
-                                false,
-                                settings,
-                            );
-                            let description: Option<String> = items_effective
-                                .description
-                                .as_ref()
-                                .filter(|s| !s.trim().is_empty())
-                                .cloned();
-                            let examples: Option<Vec<serde_json::Value>> =
-                                items_effective.examples.clone();
-                            (name, description, examples)
-                        });
-                }
-                if items_effective.is_object_with_properties() {
-                    stack.push(items_effective);
-                }
-            }
-        }
-        if let Some(ref any_of) = node.any_of {
-            for sub in any_of {
-                stack.push(sub.clone());
-            }
-        }
-        if let Some(ref one_of) = node.one_of {
-            for sub in one_of {
-                stack.push(sub.clone());
-            }
-        }
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+) -> CodeGenResult<String> {
+    let (effective, from_key) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
+    if let Some(name) = key_to_name.and_then(|m| m.get(&DedupeKey::from_schema(&effective, mode))) {
+        return Ok(name.clone());
     }
-    Ok(key_to_name_desc_examples
-        .into_iter()
-        .map(|(values, (name, description, examples))| EnumToEmit {
-            name,
-            values,
-            description,
-            examples,
-        })
-        .collect())
+    let refs_root: bool = prop_schema.ref_.as_deref().is_some_and(|r| {
+        matches!(
+            ref_resolver::parse_ref(r),
+            Ok(ref_resolver::ParsedRef::Root)
+        )
+    });
+    if refs_root {
+        return root_struct_name(root, settings);
+    }
+    Ok(struct_name_from(
+        effective.title.as_deref(),
+        from_key.as_deref(),
+        false,
+        settings,
+    ))
 }
 
-/// Collect all anyOf enums from a schema (root and nested). Each node with non-empty anyOf produces one enum.
-fn collect_anyof_enums(
+/// Type names a struct holds by value: nested structs and anyOf/oneOf enums behind plain or
+/// `Option` fields. `Vec`, `HashSet`, and map values are already heap-allocated and not listed.
+fn inline_field_types(
     root: &JsonSchema,
     schema: &JsonSchema,
     settings: &CodeGenSettings,
-    enum_values_to_name: &BTreeMap<Vec<String>, String>,
-) -> CodeGenResult<Vec<AnyOfEnumToEmit>> {
-    let mut out: Vec<AnyOfEnumToEmit> = vec![];
-    let mut stack: Vec<(JsonSchema, Option<String>)> = vec![(schema.clone(), None)];
-    while let Some((node, from_key)) = stack.pop() {
-        let (node, from_key) = resolve_ref_for_codegen(root, &node, from_key.as_deref())?;
-        if let Some(ref any_of) = node.any_of {
-            if any_of.is_empty() {
-                return Err(CodeGenError::AnyOfEmpty);
-            }
-            let name = match &from_key {
-                Some(k) => sanitize_struct_name(k) + "AnyOf",
-                None => node.title.as_deref().map_or_else(
-                    || "RootAnyOf".to_string(),
-                    |t| sanitize_struct_name(t) + "AnyOf",
-                ),
-            };
-            let mut variants = Vec::with_capacity(any_of.len());
-            for (i, sub) in any_of.iter().enumerate() {
-                let resolved = resolve_all_of_for_codegen(sub)?;
-                let variant_from_key =
-                    format!("{}_Variant{i}", from_key.as_deref().unwrap_or("Root"));
-                let ty = rust_type_for_item_schema(
-                    root,
-                    &resolved,
-                    Some(&variant_from_key),
-                    Some(enum_values_to_name),
-                    None,
-                    settings,
-                    DedupeMode::Full,
-                )?;
-                variants.push((format!("Variant{i}"), ty));
-            }
-            out.push(AnyOfEnumToEmit { name, variants });
-            for sub in any_of {
-                let resolved = resolve_all_of_for_codegen(sub)?;
-                stack.push((resolved, None));
-            }
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+) -> CodeGenResult<BTreeSet<String>> {
+    let mut out: BTreeSet<String> = BTreeSet::new();
+    for (key, prop_schema) in &schema.properties {
+        let (effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
+        if mapped_type(&effective).is_some() {
+            continue;
         }
-        for (key, prop_schema) in &node.properties {
-            stack.push((prop_schema.clone(), Some(key.clone())));
+        if effective.any_of.as_ref().is_some_and(|v| !v.is_empty())
+            || effective.one_of.as_ref().is_some_and(|v| !v.is_empty())
+        {
+            out.insert(union_enum_name(key, &effective, anyof_enums, oneof_enums));
+        } else if string_enum_or_const_values(&effective).is_none()
+            && effective.is_object_with_properties()
+        {
+            out.insert(nested_struct_type_name(
+                root,
+                prop_schema,
+                key,
+                settings,
+                key_to_name,
+                mode,
+            )?);
         }
     }
     Ok(out)
 }
 
-/// Collect all oneOf enums from a schema (root and nested). Each node with non-empty oneOf produces one enum.
-fn collect_oneof_enums(
-    root: &JsonSchema,
-    schema: &JsonSchema,
-    settings: &CodeGenSettings,
-    enum_values_to_name: &BTreeMap<Vec<String>, String>,
-) -> CodeGenResult<Vec<OneOfEnumToEmit>> {
-    let mut out: Vec<OneOfEnumToEmit> = vec![];
-    let mut stack: Vec<(JsonSchema, Option<String>)> = vec![(schema.clone(), None)];
-    while let Some((node, from_key)) = stack.pop() {
-        let (node, from_key) = resolve_ref_for_codegen(root, &node, from_key.as_deref())?;
-        if let Some(ref one_of) = node.one_of {
-            if one_of.is_empty() {
-                return Err(CodeGenError::OneOfEmpty);
-            }
-            let name = match &from_key {
-                Some(k) => sanitize_struct_name(k) + "OneOf",
-                None => node.title.as_deref().map_or_else(
-                    || "RootOneOf".to_string(),
-                    |t| sanitize_struct_name(t) + "OneOf",
-                ),
-            };
-            let mut variants = Vec::with_capacity(one_of.len());
-            for (i, sub) in one_of.iter().enumerate() {
-                let resolved = resolve_all_of_for_codegen(sub)?;
-                let variant_from_key =
-                    format!("{}_Variant{i}", from_key.as_deref().unwrap_or("Root"));
-                let ty = rust_type_for_item_schema(
-                    root,
-                    &resolved,
-                    Some(&variant_from_key),
-                    Some(enum_values_to_name),
-                    None,
-                    settings,
-                    DedupeMode::Full,
-                )?;
-                variants.push((format!("Variant{i}"), ty));
-            }
-            out.push(OneOfEnumToEmit { name, variants });
-            for sub in one_of {
-                let resolved = resolve_all_of_for_codegen(sub)?;
-                stack.push((resolved, None));
+/// Finds the by-value edges that close a cycle in `deps` (type name to the types it holds by
+/// value) and must become `Box<T>`: for each type, the structs in `struct_names` it holds that
+/// lead back to it. Self-references are included; edges into enums are not, since every cycle
+/// through an anyOf/oneOf enum also passes through a struct.
+fn recursive_edges(
+    deps: &BTreeMap<String, BTreeSet<String>>,
+    struct_names: &BTreeSet<String>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut out: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (from, targets) in deps {
+        for to in targets {
+            if struct_names.contains(to) && reaches(deps, to, from) {
+                out.entry(from.clone()).or_default().insert(to.clone());
             }
         }
-        for (key, prop_schema) in &node.properties {
-            stack.push((prop_schema.clone(), Some(key.clone())));
-        }
     }
-    Ok(out)
+    out
 }
 
-/// Collect all object schemas that need a struct in topological order (children before parents).
-/// Uses an explicit stack to avoid recursion and stack overflow on deep schemas.
-/// Resolves allOf for each node before use (merge on-the-fly).
-fn resolve_ref_for_codegen(
-    root: &JsonSchema,
-    schema: &JsonSchema,
-    fallback_from_key: Option<&str>,
-) -> CodeGenResult<(JsonSchema, Option<String>)> {
-    let mut from_key: Option<String> = fallback_from_key.map(String::from);
-    let Some(ref_str) = schema.ref_.as_deref() else {
-        return Ok((schema.clone(), from_key));
-    };
-
-    match ref_resolver::parse_ref(ref_str) {
-        Ok(ref_resolver::ParsedRef::Defs(name) | ref_resolver::ParsedRef::Definitions(name)) => {
-            from_key = Some(name);
+/// Returns true when `to` is reachable from `from` along `deps` (including `from == to`).
+fn reaches(deps: &BTreeMap<String, BTreeSet<String>>, from: &str, to: &str) -> bool {
+    let mut visited: BTreeSet<&str> = BTreeSet::new();
+    let mut stack: Vec<&str> = vec![from];
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
         }
-        Ok(ref_resolver::ParsedRef::Root) => {}
-        Err(e) => {
-            return Err(CodeGenError::RefResolution {
-                ref_str: ref_str.to_string(),
-                reason: format!("{e:?}"),
-            });
+        if visited.insert(node)
+            && let Some(next) = deps.get(node)
+        {
+            stack.extend(next.iter().map(String::as_str));
         }
     }
+    false
+}
 
-    let resolved: &JsonSchema =
-        ref_resolver::resolve_schema_ref_transitive(root, schema).map_err(|e| {
-            CodeGenError::RefResolution {
-                ref_str: ref_str.to_string(),
-                reason: format!("{e:?}"),
-            }
-        })?;
-    Ok((resolved.clone(), from_key))
+/// Wraps `name` in `Box` when it is one of the recursive edges in `boxed_types`.
+fn boxed_if_recursive(name: String, boxed_types: &BTreeSet<String>) -> String {
+    if boxed_types.contains(&name) {
+        format!("Box<{name}>")
+    } else {
+        name
+    }
+}
+
+/// String enums among `reached`. Dedupe by value list; first occurrence wins the name, description, and examples.
+fn collect_enums(reached: &[ReachedType], settings: &CodeGenSettings) -> Vec<EnumToEmit> {
+    let mut by_values: BTreeMap<Vec<String>, EnumToEmit> = BTreeMap::new();
+    for r in reached {
+        if let ReachedKind::StringEnum(values) = &r.kind {
+            by_values.entry(values.clone()).or_insert_with(|| {
+                enum_to_emit(
+                    values.clone(),
+                    &r.schema,
+                    r.from_key.as_deref(),
+                    r.is_root,
+                    r.parent.clone(),
+                    settings,
+                )
+            });
+        }
+    }
+    by_values.into_values().collect()
 }
 
-#[expect(clippy::too_many_lines)]
-fn collect_structs(
-    root: &JsonSchema,
+/// The enum for string `values` first seen on `schema` (a property or array items) inside the
+/// struct named `parent`, or on the document root (`is_root`), which names it like a root struct.
+fn enum_to_emit(
+    values: Vec<String>,
     schema: &JsonSchema,
     from_key: Option<&str>,
-    out: &mut Vec<StructToEmit>,
-    seen: &mut BTreeSet<String>,
+    is_root: bool,
+    parent: Option<String>,
     settings: &CodeGenSettings,
-) -> CodeGenResult<()> {
-    let (schema, from_key_opt) = resolve_ref_for_codegen(root, schema, from_key)?;
-    if !schema.is_object_with_properties() {
-        return Ok(());
-    }
-
-    // Phase 1: iterative post-order DFS to collect (schema, from_key) so children come before parents.
-    let mut post_order: Vec<(JsonSchema, Option<String>, bool)> = Vec::new();
-    let mut stack: Vec<(JsonSchema, Option<String>, usize, bool)> = Vec::new();
-    stack.push((
-        schema.clone(),
-        from_key_opt.clone(),
-        0,
-        from_key_opt.is_none(),
-    ));
-
-    while let Some((schema_node, from_key_opt, index, is_root)) = stack.pop() {
-        let keys: Vec<String> = schema_node.properties.keys().cloned().collect();
-        if index < keys.len() {
-            let key: String = keys.get(index).unwrap().clone();
-            let child: JsonSchema = schema_node.properties.get(&key).unwrap().clone();
-            let child_resolved = resolve_all_of_for_codegen(&child)?;
-            stack.push((schema_node, from_key_opt, index + 1, is_root));
-            if child_resolved
-                .any_of
-                .as_ref()
-                .is_some_and(|v| !v.is_empty())
-            {
-                for (i, sub) in child_resolved.any_of.as_ref().unwrap().iter().enumerate() {
-                    let sub_resolved = resolve_all_of_for_codegen(sub)?;
-                    let variant_key = format!("{key}_Variant{i}");
-                    let (sub_effective, sub_from_key) =
-                        resolve_ref_for_codegen(root, &sub_resolved, Some(&variant_key))?;
-                    if sub_effective.is_object_with_properties() {
-                        stack.push((sub_effective, sub_from_key, 0, false));
-                    } else if sub_resolved.is_array_with_items()
-                        && let Some(ref items) = sub_resolved.items
-                    {
-                        let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                        let (items_effective, items_from_key) =
-                            resolve_ref_for_codegen(root, &items_resolved, Some(&variant_key))?;
-                        if items_effective.is_object_with_properties() {
-                            stack.push((items_effective, items_from_key, 0, false));
-                        }
-                    }
-                }
-            } else if child_resolved
-                .one_of
-                .as_ref()
-                .is_some_and(|v| !v.is_empty())
-            {
-                for (i, sub) in child_resolved.one_of.as_ref().unwrap().iter().enumerate() {
-                    let sub_resolved = resolve_all_of_for_codegen(sub)?;
-                    let variant_key = format!("{key}_Variant{i}");
-                    let (sub_effective, sub_from_key) =
-                        resolve_ref_for_codegen(root, &sub_resolved, Some(&variant_key))?;
-                    if sub_effective.is_object_with_properties() {
-                        stack.push((sub_effective, sub_from_key, 0, false));
-                    } else if sub_resolved.is_array_with_items()
-                        && let Some(ref items) = sub_resolved.items
-                    {
-                        let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                        let (items_effective, items_from_key) =
-                            resolve_ref_for_codegen(root, &items_resolved, Some(&variant_key))?;
-                        if items_effective.is_object_with_properties() {
-                            stack.push((items_effective, items_from_key, 0, false));
-                        }
-                    }
-                }
-            } else {
-                let (child_effective, child_from_key) =
-                    resolve_ref_for_codegen(root, &child_resolved, Some(&key))?;
-                if child_effective.is_object_with_properties() {
-                    stack.push((child_effective, child_from_key, 0, false));
-                } else if child_effective.is_array_with_items()
-                    && let Some(ref items) = child_effective.items
-                {
-                    let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                    let (items_effective, items_from_key) =
-                        resolve_ref_for_codegen(root, &items_resolved, Some(&key))?;
-                    if items_effective.is_object_with_properties() {
-                        stack.push((items_effective, items_from_key, 0, false));
-                    }
+) -> EnumToEmit {
+    let claim: NameClaim = NameClaim {
+        is_root,
+        ..struct_name_claim(schema.title.as_deref(), from_key, is_root, parent, settings)
+    };
+    EnumToEmit {
+        name: claim.candidate.clone(),
+        values,
+        description: schema
+            .description
+            .as_ref()
+            .filter(|s| !s.trim().is_empty())
+            .cloned(),
+        examples: schema.examples.clone(),
+        claim,
+    }
+}
+
+/// Sort key of an anyOf/oneOf branch; see [`branch_rank`].
+type BranchRank = (u8, std::cmp::Reverse<usize>);
+
+/// How narrow an anyOf/oneOf branch is, lowest first: objects with properties (more required
+/// properties first), then string enums, formatted strings, booleans, and integers, then arrays,
+/// then numbers and plain strings, then free-form maps, then anything else (`serde_json::Value`).
+fn branch_rank(branch: &JsonSchema) -> BranchRank {
+    let required: usize = branch.required.as_ref().map_or(0, Vec::len);
+    let class: u8 = if branch.is_object_with_properties() {
+        0
+    } else if string_enum_or_const_values(branch).is_some()
+        || (branch.is_string() && rust_type_for_string_format(branch).is_some())
+        || branch.is_boolean()
+        || branch.is_integer()
+    {
+        1
+    } else if branch.is_array() {
+        2
+    } else if branch.is_number() || branch.is_string() {
+        3
+    } else if branch.type_.as_deref() == Some("object") {
+        4
+    } else {
+        5
+    };
+    (class, std::cmp::Reverse(required))
+}
+
+/// A discriminated oneOf: the tag property and each (ref-resolved) branch with its tag value.
+type Discriminated = (String, Vec<(String, JsonSchema)>);
+
+/// The discriminator of a oneOf whose branches are all objects with properties: a property each
+/// branch pins to a distinct string with `const` (or a one-value `enum`). The `OpenAPI`
+/// `discriminator` keyword names the property; its `mapping`, or else the last segment of the
+/// branch's `$ref`, gives the value for a branch that does not pin it. Returns the property and
+/// each (ref-resolved) branch with its tag value, or `None` when the oneOf is not discriminated.
+fn discriminator_for(
+    root: &JsonSchema,
+    union: &JsonSchema,
+) -> CodeGenResult<Option<Discriminated>> {
+    let Some(branches) = union.one_of.as_deref() else {
+        return Ok(None);
+    };
+    let mut resolved: Vec<(Option<&str>, JsonSchema)> = Vec::with_capacity(branches.len());
+    for branch in branches {
+        let (target, _) = resolve_ref_for_codegen(root, branch, None)?;
+        let target: JsonSchema = resolve_all_of_for_codegen(&target)?;
+        if !target.is_object_with_properties() || mapped_type(&target).is_some() {
+            return Ok(None);
+        }
+        resolved.push((branch.ref_.as_deref(), target));
+    }
+    let discriminator: Option<&serde_json::Value> = union.extensions.get("discriminator");
+    let mapping: Option<&serde_json::Map<String, serde_json::Value>> = discriminator
+        .and_then(|d| d.get("mapping"))
+        .and_then(serde_json::Value::as_object);
+    let candidates: Vec<String> = match discriminator
+        .and_then(|d| d.get("propertyName"))
+        .and_then(serde_json::Value::as_str)
+    {
+        Some(property) => vec![property.to_string()],
+        None => resolved
+            .first()
+            .map(|(_, first)| first.properties.keys().cloned().collect())
+            .unwrap_or_default(),
+    };
+    'candidates: for property in candidates {
+        let mut tagged: Vec<(String, JsonSchema)> = Vec::with_capacity(resolved.len());
+        for (ref_, branch) in &resolved {
+            let pinned: Option<String> = match branch.properties.get(&property) {
+                Some(prop) => {
+                    let (effective, _) = resolve_ref_for_codegen(root, prop, None)?;
+                    string_enum_or_const_values(&effective)
+                        .filter(|values| values.len() == 1)
+                        .and_then(|values| values.into_iter().next())
                 }
+                None => None,
+            };
+            let implied = || -> Option<String> {
+                let ref_: &str = (*ref_)?;
+                discriminator?;
+                mapping
+                    .and_then(|m| m.iter().find(|(_, target)| target.as_str() == Some(ref_)))
+                    .map(|(value, _)| value.clone())
+                    .or_else(|| ref_.rsplit('/').next().map(String::from))
+            };
+            let Some(value) = pinned.or_else(implied) else {
+                continue 'candidates;
+            };
+            if tagged.iter().any(|(existing, _)| *existing == value) {
+                continue 'candidates;
             }
-        } else {
-            post_order.push((schema_node, from_key_opt, is_root));
+            tagged.push((value, branch.clone()));
         }
+        return Ok(Some((property, tagged)));
     }
+    Ok(None)
+}
 
-    // Phase 2: emit in post-order, dedupe by name (first occurrence wins).
-    for (schema_node, from_key_opt, is_root) in post_order {
-        let name: String = struct_name_from(
-            schema_node.title.as_deref(),
-            from_key_opt.as_deref(),
-            is_root,
+/// The [`TaggedUnion`] for a discriminated oneOf, with `variants` listing each variant's field
+/// type names (for module placement and imports in the dedupe path).
+fn tagged_union(
+    root: &JsonSchema,
+    property: String,
+    branches: Vec<(String, JsonSchema)>,
+    settings: &CodeGenSettings,
+    enum_values_to_name: &BTreeMap<Vec<String>, String>,
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+) -> CodeGenResult<(TaggedUnion, Vec<(String, String)>)> {
+    let values: Vec<String> = branches.iter().map(|(value, _)| value.clone()).collect();
+    let no_names: BTreeMap<DedupeKey, String> = BTreeMap::new();
+    let mut variants: Vec<(String, String, JsonSchema)> = Vec::with_capacity(branches.len());
+    let mut field_types: Vec<(String, String)> = Vec::with_capacity(branches.len());
+    for ((value, variant_name), (_, mut branch)) in
+        enum_variant_names_with_collision_resolution(&values)
+            .into_iter()
+            .zip(branches)
+    {
+        branch.properties.remove(&property);
+        if let Some(required) = &mut branch.required {
+            required.retain(|key| *key != property);
+        }
+        let types: BTreeSet<String> = referenced_type_names(
+            root,
+            &branch,
             settings,
-        );
-
-        if seen.contains(&name) {
-            continue;
+            key_to_name.unwrap_or(&no_names),
+            mode,
+            enum_values_to_name,
+            &[],
+            &[],
+        )?;
+        field_types.push((
+            variant_name.clone(),
+            types.into_iter().collect::<Vec<String>>().join(", "),
+        ));
+        variants.push((variant_name, value, branch));
+    }
+    let tagged: TaggedUnion = TaggedUnion {
+        property,
+        variants,
+        schema_idx: 0,
+    };
+    Ok((tagged, field_types))
+}
+
+/// Walk nodes for the fields of a discriminated oneOf's variants: each branch's properties except
+/// the tag, and its `additionalProperties` schema.
+fn tagged_union_children(
+    property: &str,
+    branches: &[(String, JsonSchema)],
+    parent: Option<&String>,
+) -> Vec<WalkNode> {
+    let mut children: Vec<WalkNode> = Vec::new();
+    for (_, branch) in branches {
+        for (key, prop_schema) in &branch.properties {
+            if key == property {
+                continue;
+            }
+            children.push(WalkNode {
+                schema: prop_schema.clone(),
+                from_key: Some(key.clone()),
+                is_root: false,
+                parent: parent.cloned(),
+                site_key: Some(key.clone()),
+                top_level: false,
+            });
+        }
+        if let Some(AdditionalProperties::Schema(sub)) = &branch.additional_properties {
+            children.push(WalkNode {
+                schema: sub.as_ref().clone(),
+                from_key: Some("additional".to_string()),
+                is_root: false,
+                parent: parent.cloned(),
+                site_key: None,
+                top_level: false,
+            });
         }
-        seen.insert(name.clone());
-
-        out.push(StructToEmit {
-            name,
-            schema: schema_node,
-        });
     }
-    Ok(())
+    children
 }
 
-/// Collect (`schema_idx`, `candidate_name`, schema) for every struct from all schemas in post-order
-/// (children before parents) per schema. No name dedupe.
-#[expect(clippy::too_many_lines)]
-fn collect_structs_all_schemas(
-    schemas: &[JsonSchema],
+/// Variants of an untagged union enum: (`VariantN`, Rust type) per branch, narrowest first.
+fn untagged_variants(
+    root: &JsonSchema,
+    branches: &[JsonSchema],
+    variant_base: &str,
     settings: &CodeGenSettings,
-) -> CodeGenResult<Vec<(usize, String, JsonSchema)>> {
-    let mut out: Vec<(usize, String, JsonSchema)> = Vec::new();
-    for (schema_idx, schema_root) in schemas.iter().enumerate() {
-        let (effective_root, root_from_key) =
-            resolve_ref_for_codegen(schema_root, schema_root, None)?;
-        if !effective_root.is_object_with_properties() {
-            continue;
-        }
+    enum_values_to_name: &BTreeMap<Vec<String>, String>,
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+) -> CodeGenResult<Vec<(String, String)>> {
+    let mut ranked: Vec<(BranchRank, String, String)> = Vec::with_capacity(branches.len());
+    for (i, sub) in branches.iter().enumerate() {
+        let resolved: JsonSchema = resolve_all_of_for_codegen(sub)?;
+        let ty: String = rust_type_for_item_schema(
+            root,
+            &resolved,
+            Some(&format!("{variant_base}_Variant{i}")),
+            Some(enum_values_to_name),
+            key_to_name,
+            settings,
+            mode,
+        )?;
+        let (target, _) = resolve_ref_for_codegen(root, &resolved, None)?;
+        let target: JsonSchema = resolve_all_of_for_codegen(&target)?;
+        ranked.push((branch_rank(&target), format!("Variant{i}"), ty));
+    }
+    // Untagged enums try variants in order, so the narrowest branches go first.
+    ranked.sort_by_key(|(rank, _, _)| *rank);
+    Ok(ranked
+        .into_iter()
+        .map(|(_, variant, ty)| (variant, ty))
+        .collect())
+}
 
-        let mut post_order: Vec<(JsonSchema, Option<String>, bool)> = Vec::new();
-        let mut stack: Vec<(JsonSchema, Option<String>, usize, bool)> = Vec::new();
-        let is_root: bool = root_from_key.is_none();
-        stack.push((effective_root.clone(), root_from_key, 0, is_root));
-
-        while let Some((schema_node, from_key_opt, index, is_root)) = stack.pop() {
-            let keys: Vec<String> = schema_node.properties.keys().cloned().collect();
-            if index < keys.len() {
-                let key: String = keys[index].clone();
-                let child: JsonSchema = schema_node.properties.get(&key).unwrap().clone();
-                let child_resolved = resolve_all_of_for_codegen(&child)?;
-
-                stack.push((schema_node, from_key_opt, index + 1, is_root));
-
-                if child_resolved
-                    .any_of
-                    .as_ref()
-                    .is_some_and(|v| !v.is_empty())
-                {
-                    for (i, sub) in child_resolved.any_of.as_ref().unwrap().iter().enumerate() {
-                        let sub_resolved = resolve_all_of_for_codegen(sub)?;
-                        let variant_key = format!("{key}_Variant{i}");
-                        let (sub_effective, sub_from_key) = resolve_ref_for_codegen(
-                            schema_root,
-                            &sub_resolved,
-                            Some(&variant_key),
-                        )?;
-                        if sub_effective.is_object_with_properties() {
-                            stack.push((sub_effective, sub_from_key, 0, false));
-                        } else if sub_effective.is_array_with_items()
-                            && let Some(ref items) = sub_effective.items
-                        {
-                            let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                            let (items_effective, items_from_key) = resolve_ref_for_codegen(
-                                schema_root,
-                                &items_resolved,
-                                Some(&variant_key),
-                            )?;
-                            if items_effective.is_object_with_properties() {
-                                stack.push((items_effective, items_from_key, 0, false));
-                            }
-                        }
-                    }
-                } else if child_resolved
-                    .one_of
-                    .as_ref()
-                    .is_some_and(|v| !v.is_empty())
-                {
-                    for (i, sub) in child_resolved.one_of.as_ref().unwrap().iter().enumerate() {
-                        let sub_resolved = resolve_all_of_for_codegen(sub)?;
-                        let variant_key = format!("{key}_Variant{i}");
-                        let (sub_effective, sub_from_key) = resolve_ref_for_codegen(
-                            schema_root,
-                            &sub_resolved,
-                            Some(&variant_key),
-                        )?;
-                        if sub_effective.is_object_with_properties() {
-                            stack.push((sub_effective, sub_from_key, 0, false));
-                        } else if sub_effective.is_array_with_items()
-                            && let Some(ref items) = sub_effective.items
-                        {
-                            let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                            let (items_effective, items_from_key) = resolve_ref_for_codegen(
-                                schema_root,
-                                &items_resolved,
-                                Some(&variant_key),
-                            )?;
-                            if items_effective.is_object_with_properties() {
-                                stack.push((items_effective, items_from_key, 0, false));
-                            }
-                        }
-                    }
-                } else {
-                    let (child_effective, child_from_key) =
-                        resolve_ref_for_codegen(schema_root, &child_resolved, Some(&key))?;
-                    if child_effective.is_object_with_properties() {
-                        stack.push((child_effective, child_from_key, 0, false));
-                    } else if child_effective.is_array_with_items()
-                        && let Some(ref items) = child_effective.items
-                    {
-                        let items_resolved = resolve_all_of_for_codegen(items.as_ref())?;
-                        let (items_effective, items_from_key) =
-                            resolve_ref_for_codegen(schema_root, &items_resolved, Some(&key))?;
-                        if items_effective.is_object_with_properties() {
-                            stack.push((items_effective, items_from_key, 0, false));
-                        }
-                    }
-                }
+/// anyOf and oneOf enums among `reached`, with variant types named through `enum_values_to_name`
+/// and `key_to_name`. Identical enums (same name and variants) reached from different properties
+/// are emitted once.
+fn collect_union_enums(
+    root: &JsonSchema,
+    reached: &[ReachedType],
+    settings: &CodeGenSettings,
+    enum_values_to_name: &BTreeMap<Vec<String>, String>,
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+    schema_idx: usize,
+) -> CodeGenResult<(Vec<AnyOfEnumToEmit>, Vec<OneOfEnumToEmit>)> {
+    let mut anyof_enums: Vec<AnyOfEnumToEmit> = Vec::new();
+    let mut oneof_enums: Vec<OneOfEnumToEmit> = Vec::new();
+    for r in reached {
+        let (suffix, branches): (&str, &[JsonSchema]) = match r.kind {
+            ReachedKind::AnyOf => ("AnyOf", r.schema.any_of.as_deref().unwrap_or_default()),
+            ReachedKind::OneOf => ("OneOf", r.schema.one_of.as_deref().unwrap_or_default()),
+            _ => continue,
+        };
+        let name: String = match &r.from_key {
+            Some(k) => sanitize_struct_name(k) + suffix,
+            None => r.schema.title.as_deref().map_or_else(
+                || format!("Root{suffix}"),
+                |t| sanitize_struct_name(t) + suffix,
+            ),
+        };
+        let discriminated: Option<Discriminated> = if r.kind == ReachedKind::OneOf {
+            discriminator_for(root, &r.schema)?
+        } else {
+            None
+        };
+        let (variants, tagged): (Vec<(String, String)>, Option<TaggedUnion>) =
+            if let Some((property, branches)) = discriminated {
+                let (mut tagged, field_types) = tagged_union(
+                    root,
+                    property,
+                    branches,
+                    settings,
+                    enum_values_to_name,
+                    key_to_name,
+                    mode,
+                )?;
+                tagged.schema_idx = schema_idx;
+                (field_types, Some(tagged))
+            } else {
+                let variant_base: &str = r.from_key.as_deref().unwrap_or("Root");
+                let variants: Vec<(String, String)> = untagged_variants(
+                    root,
+                    branches,
+                    variant_base,
+                    settings,
+                    enum_values_to_name,
+                    key_to_name,
+                    mode,
+                )?;
+                (variants, None)
+            };
+        let uses: Vec<UnionUse> = r.site.iter().cloned().collect();
+        let claim: NameClaim = NameClaim {
+            candidate: name.clone(),
+            pinned: false,
+            is_root: false,
+            parent: r.parent.clone(),
+        };
+        if r.kind == ReachedKind::AnyOf {
+            if let Some(existing) = anyof_enums
+                .iter_mut()
+                .find(|e| e.name == name && e.variants == variants)
+            {
+                existing.uses.extend(uses);
             } else {
-                post_order.push((schema_node, from_key_opt, is_root));
+                anyof_enums.push(AnyOfEnumToEmit {
+                    name,
+                    variants,
+                    uses,
+                    claim,
+                });
             }
-        }
-
-        for (schema_node, from_key_opt, is_root) in post_order {
-            let name: String = struct_name_from(
-                schema_node.title.as_deref(),
-                from_key_opt.as_deref(),
-                is_root,
-                settings,
-            );
-            out.push((schema_idx, name, schema_node));
+        } else if let Some(existing) = oneof_enums
+            .iter_mut()
+            .find(|e| e.name == name && e.variants == variants)
+        {
+            existing.uses.extend(uses);
+        } else {
+            oneof_enums.push(OneOfEnumToEmit {
+                name,
+                variants,
+                uses,
+                claim,
+                tagged,
+            });
         }
     }
-    Ok(out)
+    Ok((anyof_enums, oneof_enums))
 }
 
-/// Generate Rust with dedupe (Functional or Full mode). Returns shared buffer (if any) and per-schema buffers.
-#[expect(clippy::too_many_lines)]
-#[expect(clippy::type_complexity)]
-fn generate_rust_with_dedupe(
-    schemas: &[JsonSchema],
+/// Every type the document `root` needs, in dependency order (each type after the types it
+/// uses). One walk over properties, `additionalProperties`, array items and anyOf/oneOf branches
+/// finds structs, string enums and union enums alike; each `$ref` target is walked once. With
+/// [`CodeGenSettings::emit_all_defs`] every `$defs`/`definitions` entry is walked first, so
+/// unreferenced entries are generated too. A union only generates a type where a field or the
+/// document root uses it.
+fn reachable_types(
+    root: &JsonSchema,
     settings: &CodeGenSettings,
-) -> CodeGenResult<GenerateRustOutput> {
-    let mode: DedupeMode = settings.dedupe_mode;
-
-    let resolved_schemas: Vec<JsonSchema> = schemas
-        .iter()
-        .enumerate()
-        .map(|(i, s)| {
-            resolve_all_of_for_codegen(s).map_err(|e| CodeGenError::Batch {
-                index: i,
-                source: Box::new(e),
-            })
-        })
-        .collect::<CodeGenResult<Vec<_>>>()?;
-
-    let mut enum_values_to_name: EnumValuesToNameMap = BTreeMap::new();
-    for schema in &resolved_schemas {
-        for e in collect_enums(schema, schema, settings)? {
-            enum_values_to_name
-                .entry(e.values.clone())
-                .or_insert_with(|| (e.name.clone(), e.description.clone(), e.examples.clone()));
+) -> CodeGenResult<Vec<ReachedType>> {
+    let mut starts: Vec<WalkNode> = Vec::new();
+    if settings.emit_all_defs {
+        starts.extend(def_pointers(root).map(def_walk_node));
+    }
+    starts.push(WalkNode {
+        schema: root.clone(),
+        from_key: None,
+        is_root: true,
+        parent: None,
+        site_key: None,
+        top_level: true,
+    });
+    starts.reverse();
+
+    let mut expanded: BTreeSet<String> = initial_expansions(root);
+    let mut out: Vec<ReachedType> = Vec::new();
+    // Each frame holds the type being visited (if it generates one) and its unvisited children,
+    // last child first; the type is listed once all of its children are.
+    let mut stack: Vec<(Option<ReachedType>, Vec<WalkNode>)> = vec![(None, starts)];
+    while let Some((_, pending)) = stack.last_mut() {
+        if let Some(node) = pending.pop() {
+            if !node.is_root && !first_expansion(&node.schema, &mut expanded) {
+                out.extend(revisited_union(root, node)?);
+                continue;
+            }
+            let (reached, mut children) = expand_walk_node(root, node, settings)?;
+            children.reverse();
+            stack.push((reached, children));
+        } else if let Some((reached, _)) = stack.pop() {
+            out.extend(reached);
         }
     }
-    let all_enums: Vec<EnumToEmit> = enum_values_to_name
-        .iter()
-        .map(|(values, (name, description, examples))| EnumToEmit {
-            name: name.clone(),
-            values: values.clone(),
-            description: description.clone(),
-            examples: examples.clone(),
-        })
-        .collect();
+    Ok(out)
+}
 
-    let collected: Vec<(usize, String, JsonSchema)> =
-        collect_structs_all_schemas(&resolved_schemas, settings)?;
+/// JSON Pointers to every root `$defs`/`definitions` entry.
+fn def_pointers(root: &JsonSchema) -> impl Iterator<Item = String> + '_ {
+    [
+        ("#/$defs/", &root.defs),
+        ("#/definitions/", &root.definitions),
+    ]
+    .into_iter()
+    .flat_map(|(prefix, defs)| {
+        defs.iter()
+            .flat_map(BTreeMap::keys)
+            .map(move |name| format!("{prefix}{name}"))
+    })
+}
 
-    // Build BTreeMap: DedupeKey -> (canonical_name, schema, occurrences)
-    let mut map: BTreeMap<DedupeKey, (String, JsonSchema, Vec<(usize, String)>)> = BTreeMap::new();
-    for (schema_idx, name, schema) in &collected {
-        let key: DedupeKey = DedupeKey::from_schema(schema, mode);
-        map.entry(key)
-            .or_insert_with(|| (name.clone(), schema.clone(), Vec::new()))
-            .2
-            .push((*schema_idx, name.clone()));
+/// The walk start for the `$defs`/`definitions` entry at `pointer`.
+fn def_walk_node(pointer: String) -> WalkNode {
+    WalkNode {
+        schema: JsonSchema {
+            ref_: Some(pointer),
+            ..JsonSchema::default()
+        },
+        from_key: None,
+        is_root: false,
+        parent: None,
+        site_key: None,
+        top_level: true,
+    }
+}
+
+/// Pointers to the `$defs`/`definitions` entries of `root` that generate no type of their own
+/// with [`CodeGenSettings::emit_all_defs`] (arrays, maps and scalars). Mapped entries use an
+/// existing type on purpose and are not listed.
+fn defs_without_type(root: &JsonSchema, settings: &CodeGenSettings) -> CodeGenResult<Vec<String>> {
+    let mut out: Vec<String> = Vec::new();
+    for pointer in def_pointers(root) {
+        let node: WalkNode = def_walk_node(pointer.clone());
+        let (target, _) = resolve_ref_for_codegen(root, &node.schema, None)?;
+        if mapped_type(&target).is_none() && expand_walk_node(root, node, settings)?.0.is_none() {
+            out.push(pointer);
+        }
     }
+    Ok(out)
+}
 
-    let shared_names: BTreeSet<String> = map
-        .iter()
-        .filter(|(_, (_, _, occs))| occs.len() > 1)
-        .map(|(_, (canonical_name, _, _))| canonical_name.clone())
-        .collect();
-
-    let canonical_name_to_first_schema_idx: BTreeMap<String, usize> = {
-        let mut out: BTreeMap<String, usize> = BTreeMap::new();
-        for (canonical_name, _, occs) in map.values() {
-            let first_idx: usize = occs.iter().map(|(i, _)| *i).min().unwrap_or(0);
-            out.entry(canonical_name.clone())
-                .and_modify(|v| *v = (*v).min(first_idx))
-                .or_insert(first_idx);
+/// Resolves one node of [`reachable_types`]: the type it generates, if any, and the positions
+/// below it still to walk.
+#[expect(clippy::too_many_lines)]
+fn expand_walk_node(
+    root: &JsonSchema,
+    node: WalkNode,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<(Option<ReachedType>, Vec<WalkNode>)> {
+    let (target, from_key) = resolve_ref_for_codegen(root, &node.schema, node.from_key.as_deref())?;
+    // A mapped schema uses an existing type: nothing in it is generated.
+    if !node.is_root && mapped_type(&target).is_some() {
+        return Ok((None, Vec::new()));
+    }
+    let schema: JsonSchema = resolve_all_of_for_codegen(&target)?;
+    let is_root: bool = node.is_root && from_key.is_none();
+    let union: Option<(ReachedKind, &[JsonSchema])> = if let Some(any_of) = &schema.any_of {
+        if any_of.is_empty() {
+            return Err(CodeGenError::AnyOfEmpty);
         }
-        out
+        Some((ReachedKind::AnyOf, any_of))
+    } else if let Some(one_of) = &schema.one_of {
+        if one_of.is_empty() {
+            return Err(CodeGenError::OneOfEmpty);
+        }
+        Some((ReachedKind::OneOf, one_of))
+    } else {
+        None
     };
-
-    let key_to_canonical_name: BTreeMap<DedupeKey, String> = map
-        .iter()
-        .map(|(k, (canonical, _, _))| (k.clone(), canonical.clone()))
-        .collect();
-
-    let key_to_canonical: BTreeMap<DedupeKey, (String, JsonSchema)> = map
-        .iter()
-        .map(|(k, (cn, schema, _))| (k.clone(), (cn.clone(), schema.clone())))
-        .collect();
-
-    if shared_names.is_empty() {
-        let mut per_schema: Vec<Vec<u8>> = Vec::with_capacity(resolved_schemas.len());
-        for (index, schema) in resolved_schemas.iter().enumerate() {
-            let mut out = Cursor::new(Vec::new());
-            emit_rust(schema, &mut out, settings).map_err(|e| CodeGenError::Batch {
-                index,
-                source: Box::new(e),
-            })?;
-            per_schema.push({
-                let result = maybe_prepend_btreemap_use(out.into_inner());
-                let result = maybe_prepend_hash_set_use(result);
-                #[cfg(feature = "uuid")]
-                let result = maybe_prepend_uuid_use(result);
-                result
-            });
+    if let Some((kind, branches)) = union {
+        if node.site_key.is_none() && !node.top_level {
+            return Ok((None, Vec::new()));
         }
-        return Ok(GenerateRustOutput {
-            shared: None,
-            per_schema,
-        });
+        let variant_base: &str = from_key.as_deref().unwrap_or("Root");
+        let discriminated: Option<Discriminated> = if kind == ReachedKind::OneOf {
+            discriminator_for(root, &schema)?
+        } else {
+            None
+        };
+        let children: Vec<WalkNode> = match discriminated {
+            Some((property, tagged)) => {
+                tagged_union_children(&property, &tagged, node.parent.as_ref())
+            }
+            None => branches
+                .iter()
+                .enumerate()
+                .map(|(i, sub)| WalkNode {
+                    schema: sub.clone(),
+                    from_key: Some(format!("{variant_base}_Variant{i}")),
+                    is_root: false,
+                    parent: node.parent.clone(),
+                    site_key: None,
+                    top_level: false,
+                })
+                .collect(),
+        };
+        let reached: ReachedType = ReachedType {
+            kind,
+            site: node.site_key.map(|key| (key, target.clone())),
+            schema,
+            from_key,
+            is_root,
+            parent: node.parent,
+        };
+        return Ok((Some(reached), children));
     }
-
-    // Shared structs: (canonical_name, schema) in dependency order
-    let shared_structs: Vec<(String, JsonSchema)> = {
-        let mut v: Vec<(String, JsonSchema)> = key_to_canonical
+    if let Some(values) = string_enum_or_const_values(&schema) {
+        let reached: ReachedType = ReachedType {
+            kind: ReachedKind::StringEnum(values),
+            schema,
+            from_key,
+            is_root,
+            parent: node.parent,
+            site: None,
+        };
+        return Ok((Some(reached), Vec::new()));
+    }
+    if schema.is_object_with_properties() {
+        let name: String = struct_name_from(
+            schema.title.as_deref(),
+            from_key.as_deref(),
+            is_root,
+            settings,
+        );
+        let mut children: Vec<WalkNode> = schema
+            .properties
             .iter()
-            .filter(|(_, (cn, _))| shared_names.contains(cn))
-            .map(|(_, (cn, s))| (cn.clone(), s.clone()))
+            .map(|(key, prop_schema)| WalkNode {
+                schema: prop_schema.clone(),
+                from_key: Some(key.clone()),
+                is_root: false,
+                parent: Some(name.clone()),
+                site_key: Some(key.clone()),
+                top_level: false,
+            })
             .collect();
-        let order: Vec<String> = v.iter().map(|(n, _)| n.clone()).collect();
-        let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
-        for (name, schema) in &v {
-            let mut set: BTreeSet<String> = BTreeSet::new();
-            for prop_schema in schema.properties.values() {
-                if prop_schema.is_object_with_properties() {
-                    let prop_key = DedupeKey::from_schema(prop_schema, mode);
-                    if let Some(cn) = key_to_canonical_name.get(&prop_key)
-                        && shared_names.contains(cn)
-                    {
-                        set.insert(cn.clone());
-                    }
-                }
-                if prop_schema.is_array_with_items()
-                    && let Some(ref items) = prop_schema.items
-                    && items.is_object_with_properties()
-                {
-                    let item_key = DedupeKey::from_schema(items, mode);
-                    if let Some(cn) = key_to_canonical_name.get(&item_key)
-                        && shared_names.contains(cn)
-                    {
-                        set.insert(cn.clone());
-                    }
-                }
-            }
-            deps.insert(name.clone(), set);
+        if let Some(AdditionalProperties::Schema(sub)) = &schema.additional_properties {
+            children.push(WalkNode {
+                schema: sub.as_ref().clone(),
+                from_key: Some("additional".to_string()),
+                is_root: false,
+                parent: Some(name),
+                site_key: None,
+                top_level: false,
+            });
         }
-        topo_sort_by_deps(&order, &deps, &mut v);
-        v
+        let reached: ReachedType = ReachedType {
+            kind: ReachedKind::Struct,
+            schema,
+            from_key,
+            is_root,
+            parent: node.parent,
+            site: None,
+        };
+        return Ok((Some(reached), children));
+    }
+    // The items of a root array and the values of a root map are named after the root type.
+    let root_name = || {
+        struct_name_from(
+            schema.title.as_deref(),
+            from_key.as_deref(),
+            is_root,
+            settings,
+        )
     };
+    if schema.is_array_with_items()
+        && let Some(items) = &schema.items
+    {
+        let child: WalkNode = WalkNode {
+            schema: items.as_ref().clone(),
+            from_key: if node.is_root {
+                Some(root_name() + "Item")
+            } else {
+                node.from_key
+            },
+            is_root: false,
+            parent: node.parent,
+            site_key: None,
+            top_level: false,
+        };
+        return Ok((None, vec![child]));
+    }
+    if node.is_root
+        && let Some(values) = map_value_schema(&schema)
+    {
+        let child: WalkNode = WalkNode {
+            schema: values.clone(),
+            from_key: Some(root_name() + "Value"),
+            is_root: false,
+            parent: node.parent,
+            site_key: None,
+            top_level: false,
+        };
+        return Ok((None, vec![child]));
+    }
+    Ok((None, Vec::new()))
+}
 
-    let shared_buffer: Vec<u8> = {
-        let mut out = Cursor::new(Vec::new());
-        writeln!(
-            out,
-            "//! Generated by json-schema-rs. Do not edit manually."
-        )?;
-        writeln!(out)?;
-        writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
-        writeln!(out)?;
-        for e in &all_enums {
-            let pairs: Vec<(String, String)> =
-                enum_variant_names_with_collision_resolution(&e.values);
-            emit_enum_from_pairs(
-                &mut out,
-                &e.name,
-                &pairs,
-                e.description.as_deref(),
-                e.examples.as_deref(),
-            )?;
-        }
-        for (name, schema) in &shared_structs {
-            let root_idx: usize = *canonical_name_to_first_schema_idx
-                .get(name)
-                .expect("root schema index for shared struct");
-            let root_schema: &JsonSchema = resolved_schemas.get(root_idx).expect("root schema");
-            emit_default_functions_for_struct(&mut out, name, schema)?;
-            emit_struct_derive_and_attrs(&mut out, name, schema)?;
-            emit_struct_fields_with_resolver(
-                root_schema,
-                name,
-                schema,
-                &mut out,
-                settings,
-                Some(&key_to_canonical_name),
-                mode,
-                Some(&enum_values_to_name),
-            )?;
-            writeln!(out, "}}")?;
-            writeln!(out)?;
-        }
-        {
-            let result = maybe_prepend_btreemap_use(out.into_inner());
-            let result = maybe_prepend_hash_set_use(result);
-            #[cfg(feature = "uuid")]
-            let result = maybe_prepend_uuid_use(result);
-            result
-        }
+/// The value schema of a map: an object schema without properties whose `additionalProperties`
+/// is a schema.
+fn map_value_schema(schema: &JsonSchema) -> Option<&JsonSchema> {
+    if !schema.properties.is_empty() || schema.type_.as_deref().is_some_and(|t| t != "object") {
+        return None;
+    }
+    match &schema.additional_properties {
+        Some(AdditionalProperties::Schema(sub)) => Some(sub.as_ref()),
+        _ => None,
+    }
+}
+
+/// A `$ref`'d anyOf/oneOf reached again from another property is not walked again, but is listed
+/// once more so that property's field finds its enum.
+fn revisited_union(root: &JsonSchema, node: WalkNode) -> CodeGenResult<Option<ReachedType>> {
+    let Some(key) = node.site_key else {
+        return Ok(None);
+    };
+    let (target, from_key) = resolve_ref_for_codegen(root, &node.schema, Some(&key))?;
+    if mapped_type(&target).is_some() {
+        return Ok(None);
+    }
+    let schema: JsonSchema = resolve_all_of_for_codegen(&target)?;
+    let kind: ReachedKind = if schema.any_of.as_ref().is_some_and(|v| !v.is_empty()) {
+        ReachedKind::AnyOf
+    } else if schema.one_of.as_ref().is_some_and(|v| !v.is_empty()) {
+        ReachedKind::OneOf
+    } else {
+        return Ok(None);
     };
+    Ok(Some(ReachedType {
+        kind,
+        schema,
+        from_key,
+        is_root: false,
+        parent: node.parent,
+        site: Some((key, target)),
+    }))
+}
 
-    let per_schema: Vec<Vec<u8>> = (0..schemas.len())
-        .map(|schema_idx| {
-            let mut local_structs: Vec<(String, JsonSchema)> = map
-                .iter()
-                .filter(|(_, (canonical_name, _, occs))| {
-                    occs.len() == 1
-                        && occs[0].0 == schema_idx
-                        && !shared_names.contains(canonical_name)
-                })
-                .map(|(_, (name, schema, _))| (name.clone(), schema.clone()))
-                .collect();
-            let local_names: BTreeSet<String> =
-                local_structs.iter().map(|(n, _)| n.clone()).collect();
-            let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
-            for (name, schema) in &local_structs {
-                let mut set: BTreeSet<String> = BTreeSet::new();
-                for prop_schema in schema.properties.values() {
-                    if prop_schema.is_object_with_properties() {
-                        let prop_key = DedupeKey::from_schema(prop_schema, mode);
-                        if let Some(cn) = key_to_canonical_name.get(&prop_key)
-                            && (local_names.contains(cn) || shared_names.contains(cn))
-                        {
-                            set.insert(cn.clone());
-                        }
-                    }
-                    if prop_schema.is_array_with_items()
-                        && let Some(ref items) = prop_schema.items
-                        && items.is_object_with_properties()
-                    {
-                        let item_key = DedupeKey::from_schema(items, mode);
-                        if let Some(cn) = key_to_canonical_name.get(&item_key)
-                            && (local_names.contains(cn) || shared_names.contains(cn))
-                        {
-                            set.insert(cn.clone());
-                        }
-                    }
-                }
-                deps.insert(name.clone(), set);
-            }
-            let order: Vec<String> = local_structs.iter().map(|(n, _)| n.clone()).collect();
-            topo_sort_by_deps(&order, &deps, &mut local_structs);
-
-            let mut used_shared: BTreeSet<String> = BTreeSet::new();
-            for (_, schema) in &local_structs {
-                for prop_schema in schema.properties.values() {
-                    if prop_schema.is_object_with_properties() {
-                        let prop_key = DedupeKey::from_schema(prop_schema, mode);
-                        if let Some(cn) = key_to_canonical_name.get(&prop_key)
-                            && shared_names.contains(cn)
-                        {
-                            used_shared.insert(cn.clone());
-                        }
-                    }
-                    if prop_schema.is_array_with_items()
-                        && let Some(ref items) = prop_schema.items
-                        && items.is_object_with_properties()
-                    {
-                        let item_key = DedupeKey::from_schema(items, mode);
-                        if let Some(cn) = key_to_canonical_name.get(&item_key)
-                            && shared_names.contains(cn)
-                        {
-                            used_shared.insert(cn.clone());
-                        }
-                    }
-                    if let Some(values) = string_enum_or_const_values(prop_schema)
-                        && let Some((enum_name, _, _)) = enum_values_to_name.get(&values)
-                    {
-                        used_shared.insert(enum_name.clone());
-                    }
-                }
-            }
-            let root_for_schema = collected
-                .iter()
-                .rev()
-                .find(|(idx, _, _)| *idx == schema_idx)
-                .map(|(_, _, s)| DedupeKey::from_schema(s, mode));
-            if let Some(root_key) = root_for_schema
-                && let Some(cn) = key_to_canonical_name.get(&root_key)
-                && shared_names.contains(cn)
-            {
-                used_shared.insert(cn.clone());
-            }
+/// Collect all object schemas that need a struct in topological order (children before parents).
+/// Uses an explicit stack to avoid recursion and stack overflow on deep schemas.
+/// Resolves allOf for each node before use (merge on-the-fly).
+fn resolve_ref_for_codegen(
+    root: &JsonSchema,
+    schema: &JsonSchema,
+    fallback_from_key: Option<&str>,
+) -> CodeGenResult<(JsonSchema, Option<String>)> {
+    let mut from_key: Option<String> = fallback_from_key.map(String::from);
+    let Some(ref_str) = schema.ref_.as_deref() else {
+        return Ok((schema.clone(), from_key));
+    };
+    // A mapped `$ref` stands for its Rust type, so its target may be outside the document. When
+    // the target is here, it still tells the shape (e.g. for ranking union branches).
+    if let Some(ty) = mapped_type(schema) {
+        let mut target: JsonSchema = ref_resolver::resolve_schema_ref_transitive(root, schema)
+            .map_or_else(|_| schema.clone(), JsonSchema::clone);
+        type_mappings::set_mapped_type(&mut target, ty);
+        return Ok((target, from_key));
+    }
 
-            let mut buf = Cursor::new(Vec::new());
-            writeln!(
-                buf,
-                "//! Generated by json-schema-rs. Do not edit manually."
-            )
-            .ok();
-            writeln!(buf).ok();
-            writeln!(buf, "use serde::{{Deserialize, Serialize}};").ok();
-            for u in &used_shared {
-                writeln!(buf, "pub use crate::{u};").ok();
-            }
-            if !used_shared.is_empty() {
-                writeln!(buf).ok();
-            }
-            let root_schema: &JsonSchema = resolved_schemas
-                .get(schema_idx)
-                .expect("root schema for local emission");
-            for (name, schema) in &local_structs {
-                emit_default_functions_for_struct(&mut buf, name, schema).ok();
-                emit_struct_derive_and_attrs(&mut buf, name, schema).ok();
-                emit_struct_fields_with_resolver(
-                    root_schema,
-                    name,
-                    schema,
-                    &mut buf,
-                    settings,
-                    Some(&key_to_canonical_name),
-                    mode,
-                    Some(&enum_values_to_name),
-                )
-                .ok();
-                writeln!(buf, "}}").ok();
-                writeln!(buf).ok();
-            }
-            {
-                let result = maybe_prepend_btreemap_use(buf.into_inner());
-                let result = maybe_prepend_hash_set_use(result);
-                #[cfg(feature = "uuid")]
-                let result = maybe_prepend_uuid_use(result);
-                result
+    match ref_resolver::parse_ref(ref_str) {
+        Ok(ref_resolver::ParsedRef::Defs(name) | ref_resolver::ParsedRef::Definitions(name)) => {
+            from_key = Some(name);
+        }
+        Ok(ref_resolver::ParsedRef::Root) => {}
+        Err(e) => {
+            return Err(CodeGenError::RefResolution {
+                ref_str: ref_str.to_string(),
+                reason: format!("{e:?}"),
+            });
+        }
+    }
+
+    let resolved: &JsonSchema =
+        ref_resolver::resolve_schema_ref_transitive(root, schema).map_err(|e| {
+            CodeGenError::RefResolution {
+                ref_str: ref_str.to_string(),
+                reason: format!("{e:?}"),
             }
-        })
-        .collect();
+        })?;
+    Ok((resolved.clone(), from_key))
+}
 
-    Ok(GenerateRustOutput {
-        shared: Some(shared_buffer),
-        per_schema,
-    })
+/// Returns false when `schema` is a `$ref` whose target was already expanded, so recursive
+/// schemas (`$ref: "#"`, a `$defs` entry referring to itself) are walked once instead of forever.
+fn first_expansion(schema: &JsonSchema, expanded: &mut BTreeSet<String>) -> bool {
+    schema
+        .ref_
+        .as_ref()
+        .is_none_or(|ref_str| expanded.insert(ref_str.clone()))
 }
 
-fn topo_sort_by_deps(
-    order: &[String],
-    deps: &BTreeMap<String, BTreeSet<String>>,
-    v: &mut Vec<(String, JsonSchema)>,
-) {
-    let mut sorted: Vec<String> = Vec::new();
-    let mut visited: BTreeSet<String> = BTreeSet::new();
-    for name in order {
-        visit_topo(name, deps, &mut visited, &mut sorted);
-    }
-    let name_to_pair: BTreeMap<String, (String, JsonSchema)> =
-        v.drain(..).map(|(n, s)| (n.clone(), (n, s))).collect();
-    for n in &sorted {
-        if let Some(pair) = name_to_pair.get(n) {
-            v.push(pair.clone());
-        }
+/// Refs already expanded before the walk starts: the document root and, when `schema` is itself
+/// a `$ref`, its target.
+fn initial_expansions(schema: &JsonSchema) -> BTreeSet<String> {
+    let mut expanded: BTreeSet<String> = BTreeSet::from(["#".to_string()]);
+    if let Some(ref_str) = schema.ref_.as_ref() {
+        expanded.insert(ref_str.clone());
     }
+    expanded
 }
 
-fn visit_topo(
-    name: &str,
-    deps: &BTreeMap<String, BTreeSet<String>>,
-    visited: &mut BTreeSet<String>,
-    out: &mut Vec<String>,
-) {
-    if visited.contains(name) {
-        return;
-    }
-    visited.insert(name.to_string());
-    if let Some(d) = deps.get(name) {
-        for dep in d {
-            visit_topo(dep, deps, visited, out);
+/// Structs among `reached`, named, in dependency order (children before parents). A struct
+/// reached twice (same name and shape) is emitted once; different shapes under one name are
+/// kept and renamed later.
+fn collect_structs(reached: &[ReachedType], settings: &CodeGenSettings) -> Vec<StructToEmit> {
+    let mut out: Vec<StructToEmit> = Vec::new();
+    let mut seen: BTreeSet<(String, DedupeKey)> = BTreeSet::new();
+    for r in reached {
+        if r.kind != ReachedKind::Struct {
+            continue;
+        }
+        let claim: NameClaim = struct_name_claim(
+            r.schema.title.as_deref(),
+            r.from_key.as_deref(),
+            r.is_root,
+            r.parent.clone(),
+            settings,
+        );
+        let identity: (String, DedupeKey) = (
+            claim.candidate.clone(),
+            DedupeKey::from_schema(&r.schema, DedupeMode::Full),
+        );
+        if seen.insert(identity) {
+            out.push(StructToEmit {
+                name: claim.candidate.clone(),
+                schema: r.schema.clone(),
+                claim,
+            });
         }
     }
-    out.push(name.to_string());
+    out
 }
 
-/// Emit a single Rust enum (unit variants). Pairs are (`json_value`, `variant_name`); serde rename when they differ.
-/// Emits enum doc comment from description and examples when present.
-fn emit_enum_from_pairs(
-    out: &mut impl Write,
-    name: &str,
-    pairs: &[(String, String)],
-    description: Option<&str>,
-    examples: Option<&[serde_json::Value]>,
-) -> CodeGenResult<()> {
-    for line in doc_lines(description) {
-        writeln!(out, "/// {line}")?;
-    }
-    for line in examples_doc_lines(examples) {
-        writeln!(out, "/// {line}")?;
+/// Collect (`schema_idx`, name claim, schema) for every struct reached from each schema, children
+/// before parents and each schema's root last. No name dedupe.
+fn collect_structs_all_schemas(
+    reached_per_schema: &[Vec<ReachedType>],
+    settings: &CodeGenSettings,
+) -> Vec<(usize, NameClaim, JsonSchema)> {
+    let mut out: Vec<(usize, NameClaim, JsonSchema)> = Vec::new();
+    for (schema_idx, reached) in reached_per_schema.iter().enumerate() {
+        for r in reached.iter().filter(|r| r.kind == ReachedKind::Struct) {
+            let claim: NameClaim = struct_name_claim(
+                r.schema.title.as_deref(),
+                r.from_key.as_deref(),
+                r.is_root,
+                r.parent.clone(),
+                settings,
+            );
+            out.push((schema_idx, claim, r.schema.clone()));
+        }
     }
-    writeln!(
-        out,
-        "#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]"
-    )?;
-    writeln!(out, "pub enum {name} {{")?;
-    for (value, variant_name) in pairs {
-        if value != variant_name {
-            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
-            writeln!(out, "    #[serde(rename = \"{escaped}\")]")?;
+    out
+}
+
+/// Dedupe map: key -> (first-occurrence name, schema, occurrences as (schema index, name)).
+type DedupeMap = BTreeMap<DedupeKey, (String, JsonSchema, Vec<(usize, String)>)>;
+
+/// The structs of a batch grouped by dedupe key.
+struct DedupeIndex {
+    map: DedupeMap,
+    /// Position in the collected list and name claim of each key's first occurrence.
+    key_claims: BTreeMap<DedupeKey, (usize, NameClaim)>,
+    /// Keys that occur more than once; their structs go to the shared module.
+    shared_keys: BTreeSet<DedupeKey>,
+}
+
+impl DedupeIndex {
+    fn new(collected: &[(usize, NameClaim, JsonSchema)], mode: DedupeMode) -> Self {
+        let mut map: DedupeMap = BTreeMap::new();
+        let mut key_claims: BTreeMap<DedupeKey, (usize, NameClaim)> = BTreeMap::new();
+        for (position, (schema_idx, claim, schema)) in collected.iter().enumerate() {
+            let key: DedupeKey = DedupeKey::from_schema(schema, mode);
+            key_claims
+                .entry(key.clone())
+                .or_insert_with(|| (position, claim.clone()));
+            map.entry(key)
+                .or_insert_with(|| (claim.candidate.clone(), schema.clone(), Vec::new()))
+                .2
+                .push((*schema_idx, claim.candidate.clone()));
+        }
+        let shared_keys: BTreeSet<DedupeKey> = map
+            .iter()
+            .filter(|(_, (_, _, occs))| occs.len() > 1)
+            .map(|(key, _)| key.clone())
+            .collect();
+        Self {
+            map,
+            key_claims,
+            shared_keys,
         }
-        writeln!(out, "    {variant_name},")?;
     }
-    writeln!(out, "}}")?;
-    writeln!(out)?;
-    Ok(())
-}
 
-/// Emit a single anyOf enum (union) to `out`.
-/// We do not derive `ToJsonSchema` (macro supports only unit enums) or PartialEq/Eq (variant structs may not implement them).
-fn emit_anyof_enum(out: &mut impl Write, a: &AnyOfEnumToEmit) -> CodeGenResult<()> {
-    writeln!(out, "#[derive(Debug, Clone, Serialize, Deserialize)]")?;
-    writeln!(out, "pub enum {} {{", a.name)?;
-    for (variant_name, ty) in &a.variants {
-        writeln!(out, "    {variant_name}({ty}),")?;
+    /// Keys of the structs only schema `schema_idx` uses, in first-occurrence order.
+    fn local_keys(&self, schema_idx: usize) -> Vec<&DedupeKey> {
+        let mut keys: Vec<&DedupeKey> = self
+            .map
+            .iter()
+            .filter(|(_, (_, _, occs))| occs.len() == 1 && occs[0].0 == schema_idx)
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort_by_key(|key| self.key_claims[*key].0);
+        keys
     }
-    writeln!(out, "}}")?;
-    writeln!(out)?;
-    Ok(())
 }
 
-/// Emit a single oneOf enum (union) to `out`.
-/// We do not derive `ToJsonSchema` (macro supports only unit enums) or PartialEq/Eq (variant structs may not implement them).
-fn emit_oneof_enum(out: &mut impl Write, a: &OneOfEnumToEmit) -> CodeGenResult<()> {
-    writeln!(out, "#[derive(Debug, Clone, Serialize, Deserialize)]")?;
-    writeln!(out, "pub enum {} {{", a.name)?;
-    for (variant_name, ty) in &a.variants {
-        writeln!(out, "    {variant_name}({ty}),")?;
-    }
-    writeln!(out, "}}")?;
-    writeln!(out)?;
-    Ok(())
+/// Key of the root struct of schema `schema_idx`; the collector lists each root last.
+fn root_key_for_schema(
+    collected: &[(usize, NameClaim, JsonSchema)],
+    schema_idx: usize,
+    mode: DedupeMode,
+) -> Option<DedupeKey> {
+    collected
+        .iter()
+        .rev()
+        .find(|(idx, _, _)| *idx == schema_idx)
+        .map(|(_, _, s)| DedupeKey::from_schema(s, mode))
 }
 
-/// Emits `#[serde(default)]` or `#[serde(default = "fn")]` when the property has a default value.
-/// Default functions are emitted at module level by `emit_default_functions_for_struct`.
-fn emit_default_attr(
-    out: &mut impl Write,
-    struct_name: &str,
-    field_name: &str,
-    prop_schema: &JsonSchema,
-    ty: &str,
-    is_required: bool,
-) -> CodeGenResult<()> {
-    let Some(ref value) = prop_schema.default_value else {
-        return Ok(());
-    };
-    let is_optional: bool = !is_required;
-    if json_value_equals_rust_type_default(value, ty, is_optional) {
-        writeln!(out, "    #[serde(default)]")?;
-        return Ok(());
-    }
-    let Some(_expr) = json_default_to_rust_expr(value, ty, is_optional) else {
-        return Ok(());
-    };
-    let fn_name: String = default_function_name(struct_name, field_name);
-    writeln!(out, "    #[serde(default = \"{fn_name}\")]")?;
-    Ok(())
+/// The identifiers in a generated Rust type (`Option<Vec<Foo>>` gives `Option`, `Vec`, `Foo`).
+fn type_name_tokens(ty: &str) -> impl Iterator<Item = &str> {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| !t.is_empty())
 }
 
-/// Emits module-level default functions for all properties of the struct that have a custom default value.
-fn emit_default_functions_for_struct(
-    out: &mut impl Write,
-    struct_name: &str,
+/// Names of the generated types the fields of `schema` use: nested structs, string enums and
+/// anyOf/oneOf enums, including inside `Vec`, `HashSet` and map values.
+#[expect(clippy::too_many_arguments)]
+fn referenced_type_names(
+    root: &JsonSchema,
     schema: &JsonSchema,
-) -> CodeGenResult<()> {
+    settings: &CodeGenSettings,
+    key_to_name: &BTreeMap<DedupeKey, String>,
+    mode: DedupeMode,
+    enum_values_to_name: &BTreeMap<Vec<String>, String>,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+) -> CodeGenResult<BTreeSet<String>> {
+    let mut types: Vec<String> = Vec::new();
     for (key, prop_schema) in &schema.properties {
-        let Some(ref value) = prop_schema.default_value else {
-            continue;
-        };
-        let field_name = sanitize_field_name(key);
-        let is_required = schema.is_required(key);
-        let is_optional = !is_required;
-        let ty: String = if prop_schema.is_string() {
-            if is_required {
-                "String".to_string()
-            } else {
-                "Option<String>".to_string()
-            }
-        } else if prop_schema.is_integer() || prop_schema.is_number() {
-            let inner = rust_numeric_type_for_schema(prop_schema);
-            if is_required {
-                inner
-            } else {
-                format!("Option<{inner}>")
-            }
-        } else if prop_schema.is_boolean() {
-            if is_required {
-                "bool".to_string()
-            } else {
-                "Option<bool>".to_string()
-            }
-        } else {
-            continue;
-        };
-        if json_value_equals_rust_type_default(value, &ty, is_optional) {
-            continue;
-        }
-        let Some(expr) = json_default_to_rust_expr(value, &ty, is_optional) else {
-            continue;
-        };
-        let fn_name = default_function_name(struct_name, &field_name);
-        writeln!(out, "fn {fn_name}() -> {ty} {{ {expr} }}")?;
-        writeln!(out)?;
-    }
-    Ok(())
-}
-
-/// Emit struct fields; when resolver is Some (dedupe mode), use canonical type names for nested objects.
-#[expect(clippy::too_many_lines, clippy::too_many_arguments)]
-fn emit_struct_fields_with_resolver(
-    root: &JsonSchema,
-    struct_name: &str,
-    schema: &JsonSchema,
-    out: &mut impl Write,
-    settings: &CodeGenSettings,
-    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
-    mode: DedupeMode,
-    enum_values_to_name: Option<&EnumValuesToNameMap>,
-) -> CodeGenResult<()> {
-    let enum_names_simple: Option<BTreeMap<Vec<String>, String>> = enum_values_to_name.map(|m| {
-        m.iter()
-            .map(|(k, (n, _, _))| (k.clone(), n.clone()))
-            .collect()
-    });
-    for (key, prop_schema) in &schema.properties {
-        let (prop_schema_effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
-        let prop_schema: &JsonSchema = &prop_schema_effective;
-
-        for line in doc_lines(prop_schema.description.as_deref()) {
-            writeln!(out, "    /// {line}")?;
-        }
-        emit_deprecated_attr(out, prop_schema)?;
-        let field_name = sanitize_field_name(key);
-        let needs_rename = field_name != *key;
-
-        if let Some(values) = string_enum_or_const_values(prop_schema) {
-            let enum_name: &String = enum_values_to_name
-                .and_then(|m| m.get(&values).map(|(n, _, _)| n))
-                .expect("enum name for string enum");
-            let ty = if schema.is_required(key) {
-                enum_name.clone()
-            } else {
-                format!("Option<{enum_name}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_string()
-            || (prop_schema
-                .enum_values
-                .as_ref()
-                .is_some_and(|v| !v.is_empty())
-                && !prop_schema.is_string_enum())
+        let (effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
+        if mapped_type(&effective).is_none()
+            && (effective.any_of.as_ref().is_some_and(|v| !v.is_empty())
+                || effective.one_of.as_ref().is_some_and(|v| !v.is_empty()))
         {
-            #[cfg(feature = "uuid")]
-            if prop_schema.is_string() && prop_schema.format.as_deref() == Some("uuid") {
-                let ty = if schema.is_required(key) {
-                    "Uuid".to_string()
-                } else {
-                    "Option<Uuid>".to_string()
-                };
-                if needs_rename {
-                    writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-                }
-                writeln!(out, "    pub {field_name}: {ty},")?;
-                continue;
-            }
-            // String type, or non-string/mixed enum fallback per design.
-            let ty = if schema.is_required(key) {
-                "String".to_string()
-            } else {
-                "Option<String>".to_string()
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            if prop_schema.min_length.is_some()
-                || prop_schema.max_length.is_some()
-                || prop_schema.pattern.is_some()
-            {
-                let mut attrs: Vec<String> = Vec::new();
-                if let Some(n) = prop_schema.min_length {
-                    attrs.push(format!("min_length = {n}"));
-                }
-                if let Some(n) = prop_schema.max_length {
-                    attrs.push(format!("max_length = {n}"));
-                }
-                if let Some(ref p) = prop_schema.pattern {
-                    let escaped = p.replace('\\', "\\\\").replace('"', "\\\"");
-                    attrs.push(format!("pattern = \"{escaped}\""));
-                }
-                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
-            }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
-            )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_integer() || prop_schema.is_number() {
-            let inner: String = rust_numeric_type_for_schema(prop_schema);
-            let ty = if schema.is_required(key) {
-                inner
-            } else {
-                format!("Option<{inner}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
-            )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_boolean() {
-            let ty: String = if schema.is_required(key) {
-                "bool".to_string()
-            } else {
-                "Option<bool>".to_string()
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
-            )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_array_with_items() {
-            let item_schema: &JsonSchema = prop_schema
-                .items
-                .as_ref()
-                .expect("array with items")
-                .as_ref();
-            let inner: String = rust_type_for_item_schema(
+            types.push(union_enum_name(key, &effective, anyof_enums, oneof_enums));
+        } else {
+            types.push(rust_type_for_item_schema(
                 root,
-                item_schema,
+                prop_schema,
                 Some(key),
-                enum_names_simple.as_ref(),
-                key_to_name,
+                Some(enum_values_to_name),
+                Some(key_to_name),
                 settings,
                 mode,
-            )?;
-            let use_hash_set: bool =
-                prop_schema.unique_items == Some(true) && item_schema_is_hashable(item_schema);
-            let container: &str = if use_hash_set { "HashSet" } else { "Vec" };
-            let ty = if schema.is_required(key) {
-                format!("{container}<{inner}>")
-            } else {
-                format!("Option<{container}<{inner}>>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            if prop_schema.min_items.is_some() || prop_schema.max_items.is_some() {
-                let mut attrs: Vec<String> = Vec::new();
-                if let Some(n) = prop_schema.min_items {
-                    attrs.push(format!("min_items = {n}"));
-                }
-                if let Some(n) = prop_schema.max_items {
-                    attrs.push(format!("max_items = {n}"));
-                }
-                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_object_with_properties() {
-            let nested_name: String = if let Some(m) = key_to_name {
-                let prop_key = DedupeKey::from_schema(prop_schema, mode);
-                m.get(&prop_key).cloned().unwrap_or_else(|| {
-                    struct_name_from(prop_schema.title.as_deref(), Some(key), false, settings)
-                })
-            } else {
-                struct_name_from(prop_schema.title.as_deref(), Some(key), false, settings)
-            };
-            let ty = if schema.is_required(key) {
-                nested_name.clone()
-            } else {
-                format!("Option<{nested_name}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
+            )?);
         }
     }
     if let Some(AdditionalProperties::Schema(sub)) = &schema.additional_properties {
-        let value_ty: String = rust_type_for_item_schema(
+        types.push(rust_type_for_item_schema(
             root,
             sub,
             Some("additional"),
-            enum_names_simple.as_ref(),
-            key_to_name,
+            Some(enum_values_to_name),
+            Some(key_to_name),
             settings,
             mode,
-        )?;
-        writeln!(out, "    #[serde(default)]")?;
-        writeln!(out, "    pub additional: BTreeMap<String, {value_ty}>,")?;
+        )?);
     }
-    Ok(())
+    Ok(types
+        .iter()
+        .flat_map(|ty| type_name_tokens(ty))
+        .map(String::from)
+        .collect())
 }
 
-/// Emit a single struct's fields to `out`.
-#[expect(clippy::too_many_lines, clippy::too_many_arguments)]
-fn emit_struct_fields(
-    root: &JsonSchema,
-    struct_name: &str,
-    schema: &JsonSchema,
-    out: &mut impl Write,
+/// Final names for deduped structs and enums. Shared structs and all enums live in one module and
+/// are named together; each schema's local structs are then named around the shared names that
+/// schema uses. Returns the final struct name per key and renames enums in place.
+fn name_deduped_types(
+    index: &DedupeIndex,
+    collected: &[(usize, NameClaim, JsonSchema)],
+    resolved_schemas: &[JsonSchema],
+    enum_values_to_name: &mut EnumValuesToNameMap,
+    enum_claims: &BTreeMap<Vec<String>, NameClaim>,
     settings: &CodeGenSettings,
-    enum_values_to_name: Option<&BTreeMap<Vec<String>, String>>,
-    _anyof_enums: Option<&[AnyOfEnumToEmit]>,
-    _oneof_enums: Option<&[OneOfEnumToEmit]>,
-) -> CodeGenResult<()> {
-    for (key, prop_schema) in &schema.properties {
-        let (prop_schema_effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
-        let prop_schema: &JsonSchema = &prop_schema_effective;
+) -> CodeGenResult<BTreeMap<DedupeKey, String>> {
+    let mode: DedupeMode = settings.dedupe_mode;
+    let mut key_to_canonical_name: BTreeMap<DedupeKey, String> = index
+        .map
+        .iter()
+        .map(|(k, (canonical, _, _))| (k.clone(), canonical.clone()))
+        .collect();
 
-        for line in doc_lines(prop_schema.description.as_deref()) {
-            writeln!(out, "    /// {line}")?;
+    let mut shared_order: Vec<&DedupeKey> = index.shared_keys.iter().collect();
+    shared_order.sort_by_key(|key| index.key_claims[*key].0);
+    let enum_values: Vec<Vec<String>> = enum_values_to_name.keys().cloned().collect();
+    let shared_claims: Vec<&NameClaim> = shared_order
+        .iter()
+        .map(|key| &index.key_claims[*key].1)
+        .chain(enum_values.iter().map(|values| &enum_claims[values]))
+        .collect();
+    let names: Vec<String> =
+        resolve_type_names(&shared_claims, &BTreeSet::new(), settings.name_collision)?;
+    for (key, name) in shared_order.iter().zip(&names) {
+        key_to_canonical_name.insert((*key).clone(), name.clone());
+    }
+    for (values, name) in enum_values.iter().zip(&names[shared_order.len()..]) {
+        if let Some(entry) = enum_values_to_name.get_mut(values) {
+            entry.0.clone_from(name);
         }
-        emit_deprecated_attr(out, prop_schema)?;
-        let field_name = sanitize_field_name(key);
-        let needs_rename = field_name != *key;
+    }
+    let shared_module_names: BTreeSet<String> = names.into_iter().collect();
+    let enum_names: BTreeMap<Vec<String>, String> = enum_values_to_name
+        .iter()
+        .map(|(values, (name, _, _))| (values.clone(), name.clone()))
+        .collect();
 
-        if prop_schema.any_of.as_ref().is_some_and(|v| !v.is_empty()) {
-            let enum_name = sanitize_struct_name(key) + "AnyOf";
-            let ty = if schema.is_required(key) {
-                enum_name.clone()
-            } else {
-                format!("Option<{enum_name}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.one_of.as_ref().is_some_and(|v| !v.is_empty()) {
-            let enum_name = sanitize_struct_name(key) + "OneOf";
-            let ty = if schema.is_required(key) {
-                enum_name.clone()
-            } else {
-                format!("Option<{enum_name}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if let Some(values) = string_enum_or_const_values(prop_schema) {
-            let enum_name: &String = enum_values_to_name
-                .and_then(|m| m.get(&values))
-                .expect("enum name for string enum");
-            let ty = if schema.is_required(key) {
-                enum_name.clone()
-            } else {
-                format!("Option<{enum_name}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_string()
-            || (prop_schema
-                .enum_values
-                .as_ref()
-                .is_some_and(|v| !v.is_empty())
-                && !prop_schema.is_string_enum())
+    for (schema_idx, root) in resolved_schemas.iter().enumerate() {
+        let batch = |e: CodeGenError| CodeGenError::Batch {
+            index: schema_idx,
+            source: Box::new(e),
+        };
+        let local_keys: Vec<&DedupeKey> = index.local_keys(schema_idx);
+        let mut used: BTreeSet<String> = BTreeSet::new();
+        for key in &local_keys {
+            used.extend(
+                referenced_type_names(
+                    root,
+                    &index.map[*key].1,
+                    settings,
+                    &key_to_canonical_name,
+                    mode,
+                    &enum_names,
+                    &[],
+                    &[],
+                )
+                .map_err(batch)?,
+            );
+        }
+        if let Some(root_key) = root_key_for_schema(collected, schema_idx, mode)
+            && index.shared_keys.contains(&root_key)
         {
-            #[cfg(feature = "uuid")]
-            if prop_schema.is_string() && prop_schema.format.as_deref() == Some("uuid") {
-                let ty = if schema.is_required(key) {
-                    "Uuid".to_string()
-                } else {
-                    "Option<Uuid>".to_string()
-                };
-                if needs_rename {
-                    writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-                }
-                writeln!(out, "    pub {field_name}: {ty},")?;
+            used.insert(key_to_canonical_name[&root_key].clone());
+        }
+        let reserved: BTreeSet<String> = used.intersection(&shared_module_names).cloned().collect();
+        let local_claims: Vec<&NameClaim> = local_keys
+            .iter()
+            .map(|key| &index.key_claims[*key].1)
+            .collect();
+        let names: Vec<String> =
+            resolve_type_names(&local_claims, &reserved, settings.name_collision).map_err(batch)?;
+        for (key, name) in local_keys.into_iter().zip(names) {
+            key_to_canonical_name.insert(key.clone(), name);
+        }
+    }
+    Ok(key_to_canonical_name)
+}
+
+/// Splits a schema's union enums into those that mention one of its `local_names` (and so must
+/// live in its own module) and the rest, which are merged into the shared `shared_anyof` and
+/// `shared_oneof` (an identical enum from another schema is kept once).
+fn split_union_enums(
+    anyof_enums: Vec<AnyOfEnumToEmit>,
+    oneof_enums: Vec<OneOfEnumToEmit>,
+    local_names: &BTreeSet<String>,
+    shared_anyof: &mut Vec<AnyOfEnumToEmit>,
+    shared_oneof: &mut Vec<OneOfEnumToEmit>,
+) -> (Vec<AnyOfEnumToEmit>, Vec<OneOfEnumToEmit>) {
+    let is_local = |variants: &[(String, String)]| {
+        variants
+            .iter()
+            .any(|(_, ty)| type_name_tokens(ty).any(|t| local_names.contains(t)))
+    };
+    let mut local_anyof: Vec<AnyOfEnumToEmit> = Vec::new();
+    for a in anyof_enums {
+        if is_local(&a.variants) {
+            local_anyof.push(a);
+        } else if let Some(existing) = shared_anyof
+            .iter_mut()
+            .find(|e| e.name == a.name && e.variants == a.variants)
+        {
+            existing.uses.extend(a.uses);
+        } else {
+            shared_anyof.push(a);
+        }
+    }
+    let mut local_oneof: Vec<OneOfEnumToEmit> = Vec::new();
+    for o in oneof_enums {
+        if is_local(&o.variants) {
+            local_oneof.push(o);
+        } else if let Some(existing) = shared_oneof
+            .iter_mut()
+            .find(|e| e.name == o.name && e.variants == o.variants)
+        {
+            existing.uses.extend(o.uses);
+        } else {
+            shared_oneof.push(o);
+        }
+    }
+    (local_anyof, local_oneof)
+}
+
+/// Generate Rust with dedupe (Functional or Full mode). Returns shared buffer (if any) and per-schema buffers.
+#[expect(clippy::too_many_lines)]
+fn generate_rust_with_dedupe(
+    schemas: &[JsonSchema],
+    settings: &CodeGenSettings,
+) -> CodeGenResult<GenerateRustOutput> {
+    let mode: DedupeMode = settings.dedupe_mode;
+
+    let resolved_schemas: Vec<JsonSchema> = schemas
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            resolve_all_of_for_codegen(s).map_err(|e| CodeGenError::Batch {
+                index: i,
+                source: Box::new(e),
+            })
+        })
+        .collect::<CodeGenResult<Vec<_>>>()?;
+    let mapped: BTreeSet<String> = type_mappings::mapped_types(schemas);
+    let reached_per_schema: Vec<Vec<ReachedType>> = resolved_schemas
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let (root, _) = resolve_ref_for_codegen(s, s, None)?;
+            check_root_unions(&root).map_err(|e| CodeGenError::Batch {
+                index: i,
+                source: Box::new(e),
+            })?;
+            reachable_types(s, settings)
+        })
+        .collect::<CodeGenResult<Vec<_>>>()?;
+
+    let mut enum_values_to_name: EnumValuesToNameMap = BTreeMap::new();
+    let mut enum_claims: BTreeMap<Vec<String>, NameClaim> = BTreeMap::new();
+    for reached in &reached_per_schema {
+        for e in collect_enums(reached, settings) {
+            enum_claims
+                .entry(e.values.clone())
+                .or_insert_with(|| e.claim.clone());
+            enum_values_to_name
+                .entry(e.values.clone())
+                .or_insert_with(|| (e.name.clone(), e.description.clone(), e.examples.clone()));
+        }
+    }
+
+    let collected: Vec<(usize, NameClaim, JsonSchema)> =
+        collect_structs_all_schemas(&reached_per_schema, settings);
+    let index: DedupeIndex = DedupeIndex::new(&collected, mode);
+
+    if index.shared_keys.is_empty() {
+        let mut per_schema: Vec<Vec<u8>> = Vec::with_capacity(resolved_schemas.len());
+        let mut skipped_derives: Vec<SkippedDerive> = Vec::new();
+        let mut matched: BTreeSet<String> = BTreeSet::new();
+        for (index, schema) in resolved_schemas.iter().enumerate() {
+            let mut out = Cursor::new(Vec::new());
+            let requested: RequestedAttributes =
+                emit_rust(schema, &mut out, settings).map_err(|e| CodeGenError::Batch {
+                    index,
+                    source: Box::new(e),
+                })?;
+            let (result, sets) = derives::apply_type_attributes(
+                out.into_inner(),
+                &settings.type_attributes,
+                &requested.by_name,
+                &DeriveSets::new(),
+                &mut skipped_derives,
+            );
+            matched.extend(requested.matched_in(&sets, &DeriveSets::new()));
+            per_schema.push({
+                let result = maybe_prepend_btreemap_use(result);
+                let result = maybe_prepend_hash_set_use(result);
+                #[cfg(feature = "uuid")]
+                let result = maybe_prepend_uuid_use(result);
+                result
+            });
+        }
+        return Ok(GenerateRustOutput {
+            shared: None,
+            per_schema,
+            skipped_derives,
+            unmatched_type_selectors: unmatched_selectors(settings, &matched),
+            skipped_defs: Vec::new(),
+        });
+    }
+
+    let key_to_canonical_name: BTreeMap<DedupeKey, String> = name_deduped_types(
+        &index,
+        &collected,
+        &resolved_schemas,
+        &mut enum_values_to_name,
+        &enum_claims,
+        settings,
+    )?;
+    let shared_names: BTreeSet<String> = index
+        .shared_keys
+        .iter()
+        .map(|key| key_to_canonical_name[key].clone())
+        .collect();
+    let all_enums: Vec<EnumToEmit> = enum_values_to_name
+        .iter()
+        .map(|(values, (name, description, examples))| EnumToEmit {
+            name: name.clone(),
+            values: values.clone(),
+            description: description.clone(),
+            examples: examples.clone(),
+            claim: enum_claims[values].clone(),
+        })
+        .collect();
+    let enum_names: BTreeMap<Vec<String>, String> = all_enums
+        .iter()
+        .map(|e| (e.values.clone(), e.name.clone()))
+        .collect();
+
+    let canonical_name_to_first_schema_idx: BTreeMap<String, usize> = {
+        let mut out: BTreeMap<String, usize> = BTreeMap::new();
+        for (key, (_, _, occs)) in &index.map {
+            let canonical_name: &String = &key_to_canonical_name[key];
+            let first_idx: usize = occs.iter().map(|(i, _)| *i).min().unwrap_or(0);
+            out.entry(canonical_name.clone())
+                .and_modify(|v| *v = (*v).min(first_idx))
+                .or_insert(first_idx);
+        }
+        out
+    };
+    let root_for_struct = |name: &str| -> &JsonSchema {
+        let root_idx: usize = canonical_name_to_first_schema_idx[name];
+        &resolved_schemas[root_idx]
+    };
+
+    // Union enums: those mentioning a schema's local structs stay in its module, the rest are
+    // shared like string enums.
+    let mut shared_anyof: Vec<AnyOfEnumToEmit> = Vec::new();
+    let mut shared_oneof: Vec<OneOfEnumToEmit> = Vec::new();
+    let mut local_unions: Vec<(Vec<AnyOfEnumToEmit>, Vec<OneOfEnumToEmit>)> =
+        Vec::with_capacity(schemas.len());
+    for (schema_idx, reached) in reached_per_schema.iter().enumerate() {
+        let local_names: BTreeSet<String> = index
+            .local_keys(schema_idx)
+            .into_iter()
+            .map(|key| key_to_canonical_name[key].clone())
+            .collect();
+        let (anyof_enums, oneof_enums) = collect_union_enums(
+            &resolved_schemas[schema_idx],
+            reached,
+            settings,
+            &enum_names,
+            Some(&key_to_canonical_name),
+            mode,
+            schema_idx,
+        )
+        .map_err(|e| CodeGenError::Batch {
+            index: schema_idx,
+            source: Box::new(e),
+        })?;
+        local_unions.push(split_union_enums(
+            anyof_enums,
+            oneof_enums,
+            &local_names,
+            &mut shared_anyof,
+            &mut shared_oneof,
+        ));
+    }
+    let mut shared_module_names: BTreeSet<String> = shared_names
+        .iter()
+        .cloned()
+        .chain(enum_names.values().cloned())
+        .collect();
+    rename_union_enums(
+        &shared_module_names,
+        &mut shared_anyof,
+        &mut shared_oneof,
+        settings,
+    )?;
+    shared_module_names.extend(
+        shared_anyof
+            .iter()
+            .map(|a| a.name.clone())
+            .chain(shared_oneof.iter().map(|o| o.name.clone())),
+    );
+
+    // Shared structs: (canonical_name, schema) in dependency order
+    let mut shared_structs: Vec<(String, JsonSchema)> = index
+        .shared_keys
+        .iter()
+        .map(|key| (key_to_canonical_name[key].clone(), index.map[key].1.clone()))
+        .collect();
+    let mut shared_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut shared_inline: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (name, schema) in &shared_structs {
+        let root_schema: &JsonSchema = root_for_struct(name);
+        let referenced: BTreeSet<String> = referenced_type_names(
+            root_schema,
+            schema,
+            settings,
+            &key_to_canonical_name,
+            mode,
+            &enum_names,
+            &shared_anyof,
+            &shared_oneof,
+        )?;
+        shared_deps.insert(
+            name.clone(),
+            referenced.intersection(&shared_names).cloned().collect(),
+        );
+        let inline: BTreeSet<String> = inline_field_types(
+            root_schema,
+            schema,
+            settings,
+            Some(&key_to_canonical_name),
+            mode,
+            &shared_anyof,
+            &shared_oneof,
+        )?;
+        shared_inline.insert(name.clone(), inline);
+    }
+    let order: Vec<String> = shared_structs.iter().map(|(n, _)| n.clone()).collect();
+    topo_sort_by_deps(&order, &shared_deps, &mut shared_structs);
+
+    let no_boxed: BTreeSet<String> = BTreeSet::new();
+    shared_inline.extend(tagged_union_inline_types(
+        &resolved_schemas,
+        &shared_oneof,
+        settings,
+        &key_to_canonical_name,
+        mode,
+        &shared_anyof,
+        &shared_oneof,
+    )?);
+    let shared_boxed: BTreeMap<String, BTreeSet<String>> = box_recursive_edges(
+        shared_inline,
+        &shared_names,
+        &mut shared_anyof,
+        &mut shared_oneof,
+    );
+
+    let shared_buffer: Vec<u8> = {
+        let mut out = Cursor::new(Vec::new());
+        writeln!(
+            out,
+            "//! Generated by json-schema-rs. Do not edit manually."
+        )?;
+        writeln!(out)?;
+        writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
+        writeln!(out)?;
+        for e in &all_enums {
+            let pairs: Vec<(String, String)> =
+                enum_variant_names_with_collision_resolution(&e.values);
+            emit_enum_from_pairs(
+                &mut out,
+                &e.name,
+                &pairs,
+                e.description.as_deref(),
+                e.examples.as_deref(),
+                settings,
+            )?;
+        }
+        for a in &shared_anyof {
+            emit_anyof_enum(&mut out, a, settings, &mapped)?;
+        }
+        for o in &shared_oneof {
+            let Some(tagged) = &o.tagged else {
+                emit_oneof_enum(&mut out, o, settings, &mapped)?;
                 continue;
-            }
-            // String type, or non-string/mixed enum fallback per design.
-            let ty = if schema.is_required(key) {
-                "String".to_string()
-            } else {
-                "Option<String>".to_string()
             };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            if prop_schema.min_length.is_some()
-                || prop_schema.max_length.is_some()
-                || prop_schema.pattern.is_some()
-            {
-                let mut attrs: Vec<String> = Vec::new();
-                if let Some(n) = prop_schema.min_length {
-                    attrs.push(format!("min_length = {n}"));
-                }
-                if let Some(n) = prop_schema.max_length {
-                    attrs.push(format!("max_length = {n}"));
-                }
-                if let Some(ref p) = prop_schema.pattern {
-                    let escaped = p.replace('\\', "\\\\").replace('"', "\\\"");
-                    attrs.push(format!("pattern = \"{escaped}\""));
-                }
-                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
-            }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
+            emit_tagged_enum(
+                &mut out,
+                &o.name,
+                tagged,
+                settings,
+                &mapped,
+                |struct_name, variant, buf, constrained| {
+                    emit_struct_fields_with_resolver(
+                        &resolved_schemas[tagged.schema_idx],
+                        struct_name,
+                        variant,
+                        buf,
+                        settings,
+                        Some(&key_to_canonical_name),
+                        mode,
+                        Some(&enum_values_to_name),
+                        &shared_anyof,
+                        &shared_oneof,
+                        shared_boxed.get(&o.name).unwrap_or(&no_boxed),
+                        constrained,
+                    )
+                },
             )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_integer() || prop_schema.is_number() {
-            let inner: String = rust_numeric_type_for_schema(prop_schema);
-            let ty = if schema.is_required(key) {
-                inner
-            } else {
-                format!("Option<{inner}>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
+        }
+        for (name, schema) in &shared_structs {
+            emit_default_functions_for_struct(&mut out, name, schema, settings)?;
+            emit_struct_derive_and_attrs(&mut out, name, schema)?;
+            let mut constrained: ConstrainedFields = ConstrainedFields::default();
+            emit_struct_fields_with_resolver(
+                root_for_struct(name),
+                name,
+                schema,
+                &mut out,
+                settings,
+                Some(&key_to_canonical_name),
+                mode,
+                Some(&enum_values_to_name),
+                &shared_anyof,
+                &shared_oneof,
+                shared_boxed.get(name).unwrap_or(&no_boxed),
+                &mut constrained,
             )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_boolean() {
-            let ty: String = if schema.is_required(key) {
-                "bool".to_string()
-            } else {
-                "Option<bool>".to_string()
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            writeln!(out, "}}")?;
+            writeln!(out)?;
+            if settings.enforce_constraints {
+                enforce::emit_for_struct(&mut out, name, &constrained, &mapped)?;
             }
-            emit_default_attr(
-                out,
-                struct_name,
-                &field_name,
-                prop_schema,
-                &ty,
-                schema.is_required(key),
-            )?;
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_array_with_items() {
-            let item_schema: &JsonSchema = prop_schema
-                .items
-                .as_ref()
-                .expect("array with items")
-                .as_ref();
-            let inner: String = rust_type_for_item_schema(
-                root,
-                item_schema,
-                Some(key),
-                enum_values_to_name,
-                None,
+        }
+        out.into_inner()
+    };
+
+    // Derives and attributes are added once every module is emitted: the shared module applies
+    // every module's requests, and each module sees which derives the shared types kept.
+    let mut raw_per_schema: Vec<(Vec<u8>, RequestedAttributes)> = Vec::with_capacity(schemas.len());
+    for (schema_idx, (mut local_anyof, mut local_oneof)) in local_unions.into_iter().enumerate() {
+        let batch = |e: CodeGenError| CodeGenError::Batch {
+            index: schema_idx,
+            source: Box::new(e),
+        };
+        let root_schema: &JsonSchema = &resolved_schemas[schema_idx];
+        let mut local_structs: Vec<(String, JsonSchema)> = index
+            .local_keys(schema_idx)
+            .into_iter()
+            .map(|key| (key_to_canonical_name[key].clone(), index.map[key].1.clone()))
+            .collect();
+        let local_names: BTreeSet<String> = local_structs.iter().map(|(n, _)| n.clone()).collect();
+        let taken: BTreeSet<String> = local_names.union(&shared_module_names).cloned().collect();
+        rename_union_enums(&taken, &mut local_anyof, &mut local_oneof, settings).map_err(batch)?;
+        // Field lookups see this module's union enums and the shared ones.
+        let module_anyof: Vec<AnyOfEnumToEmit> =
+            local_anyof.iter().chain(&shared_anyof).cloned().collect();
+        let module_oneof: Vec<OneOfEnumToEmit> =
+            local_oneof.iter().chain(&shared_oneof).cloned().collect();
+
+        let mut used: BTreeSet<String> = BTreeSet::new();
+        let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut inline_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (name, schema) in &local_structs {
+            let referenced: BTreeSet<String> = referenced_type_names(
+                root_schema,
+                schema,
                 settings,
-                DedupeMode::Full,
-            )?;
-            let use_hash_set: bool =
-                prop_schema.unique_items == Some(true) && item_schema_is_hashable(item_schema);
-            let container: &str = if use_hash_set { "HashSet" } else { "Vec" };
-            let ty = if schema.is_required(key) {
-                format!("{container}<{inner}>")
-            } else {
-                format!("Option<{container}<{inner}>>")
-            };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
-            }
-            if prop_schema.min_items.is_some() || prop_schema.max_items.is_some() {
-                let mut attrs: Vec<String> = Vec::new();
-                if let Some(n) = prop_schema.min_items {
-                    attrs.push(format!("min_items = {n}"));
-                }
-                if let Some(n) = prop_schema.max_items {
-                    attrs.push(format!("max_items = {n}"));
-                }
-                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
+                &key_to_canonical_name,
+                mode,
+                &enum_names,
+                &module_anyof,
+                &module_oneof,
+            )
+            .map_err(batch)?;
+            deps.insert(
+                name.clone(),
+                referenced.intersection(&local_names).cloned().collect(),
+            );
+            used.extend(referenced);
+            let inline: BTreeSet<String> = inline_field_types(
+                root_schema,
+                schema,
+                settings,
+                Some(&key_to_canonical_name),
+                mode,
+                &module_anyof,
+                &module_oneof,
+            )
+            .map_err(batch)?;
+            inline_deps.insert(name.clone(), inline);
+        }
+        let order: Vec<String> = local_structs.iter().map(|(n, _)| n.clone()).collect();
+        topo_sort_by_deps(&order, &deps, &mut local_structs);
+        inline_deps.extend(
+            tagged_union_inline_types(
+                &resolved_schemas,
+                &local_oneof,
+                settings,
+                &key_to_canonical_name,
+                mode,
+                &module_anyof,
+                &module_oneof,
+            )
+            .map_err(batch)?,
+        );
+        let local_boxed: BTreeMap<String, BTreeSet<String>> = box_recursive_edges(
+            inline_deps,
+            &local_names,
+            &mut local_anyof,
+            &mut local_oneof,
+        );
+        for (_, ty) in local_anyof
+            .iter()
+            .flat_map(|a| &a.variants)
+            .chain(local_oneof.iter().flat_map(|o| &o.variants))
+        {
+            used.extend(type_name_tokens(ty).map(String::from));
+        }
+        if let Some(root_key) = root_key_for_schema(&collected, schema_idx, mode)
+            && index.shared_keys.contains(&root_key)
+        {
+            used.insert(key_to_canonical_name[&root_key].clone());
+        }
+        let alias: Option<RootAlias> = root_alias(
+            root_schema,
+            settings,
+            &enum_names,
+            Some(&key_to_canonical_name),
+            mode,
+        )
+        .map_err(batch)?;
+        if let Some(alias) = &alias {
+            used.extend(type_name_tokens(&alias.ty).map(String::from));
+        }
+        // A root string enum lives in the shared module with the other enums.
+        for r in reached_per_schema[schema_idx].iter().filter(|r| r.is_root) {
+            if let ReachedKind::StringEnum(values) = &r.kind {
+                used.insert(enum_names[values].clone());
             }
-            writeln!(out, "    pub {field_name}: {ty},")?;
-        } else if prop_schema.is_object_with_properties() {
-            let nested_name: String =
-                struct_name_from(prop_schema.title.as_deref(), Some(key), false, settings);
-            let ty = if schema.is_required(key) {
-                nested_name.clone()
-            } else {
-                format!("Option<{nested_name}>")
+        }
+        let used_shared: BTreeSet<&String> = used.intersection(&shared_module_names).collect();
+
+        let mut buf = Cursor::new(Vec::new());
+        writeln!(
+            buf,
+            "//! Generated by json-schema-rs. Do not edit manually."
+        )?;
+        writeln!(buf)?;
+        writeln!(buf, "use serde::{{Deserialize, Serialize}};")?;
+        for u in &used_shared {
+            writeln!(buf, "pub use crate::{u};")?;
+        }
+        if !used_shared.is_empty() {
+            writeln!(buf)?;
+        }
+        for a in &local_anyof {
+            emit_anyof_enum(&mut buf, a, settings, &mapped)?;
+        }
+        for o in &local_oneof {
+            let Some(tagged) = &o.tagged else {
+                emit_oneof_enum(&mut buf, o, settings, &mapped)?;
+                continue;
             };
-            if needs_rename {
-                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            emit_tagged_enum(
+                &mut buf,
+                &o.name,
+                tagged,
+                settings,
+                &mapped,
+                |struct_name, variant, fields, constrained| {
+                    emit_struct_fields_with_resolver(
+                        root_schema,
+                        struct_name,
+                        variant,
+                        fields,
+                        settings,
+                        Some(&key_to_canonical_name),
+                        mode,
+                        Some(&enum_values_to_name),
+                        &module_anyof,
+                        &module_oneof,
+                        local_boxed.get(&o.name).unwrap_or(&no_boxed),
+                        constrained,
+                    )
+                },
+            )
+            .map_err(batch)?;
+        }
+        for (name, schema) in &local_structs {
+            emit_default_functions_for_struct(&mut buf, name, schema, settings)?;
+            emit_struct_derive_and_attrs(&mut buf, name, schema)?;
+            let mut constrained: ConstrainedFields = ConstrainedFields::default();
+            emit_struct_fields_with_resolver(
+                root_schema,
+                name,
+                schema,
+                &mut buf,
+                settings,
+                Some(&key_to_canonical_name),
+                mode,
+                Some(&enum_values_to_name),
+                &module_anyof,
+                &module_oneof,
+                local_boxed.get(name).unwrap_or(&no_boxed),
+                &mut constrained,
+            )
+            .map_err(batch)?;
+            writeln!(buf, "}}")?;
+            writeln!(buf)?;
+            if settings.enforce_constraints {
+                enforce::emit_for_struct(&mut buf, name, &constrained, &mapped)?;
             }
-            writeln!(out, "    pub {field_name}: {ty},")?;
         }
+        if let Some(alias) = &alias {
+            emit_root_alias(&mut buf, alias, settings)?;
+        }
+        let requested: RequestedAttributes = requested_type_attributes(
+            root_schema,
+            settings,
+            &ModuleTypeNames {
+                enum_values_to_name: &enum_names,
+                key_to_name: Some(&key_to_canonical_name),
+                mode,
+                anyof_enums: &module_anyof,
+                oneof_enums: &module_oneof,
+                alias: alias.as_ref(),
+            },
+        )
+        .map_err(batch)?;
+        raw_per_schema.push((buf.into_inner(), requested));
+    }
+
+    let mut skipped_derives: Vec<SkippedDerive> = Vec::new();
+    let mut shared_requested: BTreeMap<String, TypeAttributes> = BTreeMap::new();
+    for (name, attrs) in raw_per_schema
+        .iter()
+        .flat_map(|(_, requested)| &requested.by_name)
+    {
+        let entry: &mut TypeAttributes = shared_requested.entry(name.clone()).or_default();
+        entry.derives.extend(attrs.derives.iter().cloned());
+        entry.attributes.extend(attrs.attributes.iter().cloned());
+    }
+    let (shared_buffer, shared_derives) = derives::apply_type_attributes(
+        shared_buffer,
+        &settings.type_attributes,
+        &shared_requested,
+        &DeriveSets::new(),
+        &mut skipped_derives,
+    );
+    let shared_buffer: Vec<u8> = {
+        let result = maybe_prepend_btreemap_use(shared_buffer);
+        let result = maybe_prepend_hash_set_use(result);
+        #[cfg(feature = "uuid")]
+        let result = maybe_prepend_uuid_use(result);
+        result
+    };
+    let mut per_schema: Vec<Vec<u8>> = Vec::with_capacity(schemas.len());
+    let mut matched: BTreeSet<String> = BTreeSet::new();
+    for (buf, requested) in raw_per_schema {
+        let (result, sets) = derives::apply_type_attributes(
+            buf,
+            &settings.type_attributes,
+            &requested.by_name,
+            &shared_derives,
+            &mut skipped_derives,
+        );
+        matched.extend(requested.matched_in(&sets, &shared_derives));
+        per_schema.push({
+            let result = maybe_prepend_btreemap_use(result);
+            let result = maybe_prepend_hash_set_use(result);
+            #[cfg(feature = "uuid")]
+            let result = maybe_prepend_uuid_use(result);
+            result
+        });
+    }
+
+    Ok(GenerateRustOutput {
+        shared: Some(shared_buffer),
+        per_schema,
+        skipped_derives,
+        unmatched_type_selectors: unmatched_selectors(settings, &matched),
+        skipped_defs: Vec::new(),
+    })
+}
+
+fn topo_sort_by_deps(
+    order: &[String],
+    deps: &BTreeMap<String, BTreeSet<String>>,
+    v: &mut Vec<(String, JsonSchema)>,
+) {
+    let mut sorted: Vec<String> = Vec::new();
+    let mut visited: BTreeSet<String> = BTreeSet::new();
+    for name in order {
+        visit_topo(name, deps, &mut visited, &mut sorted);
+    }
+    let name_to_pair: BTreeMap<String, (String, JsonSchema)> =
+        v.drain(..).map(|(n, s)| (n.clone(), (n, s))).collect();
+    for n in &sorted {
+        if let Some(pair) = name_to_pair.get(n) {
+            v.push(pair.clone());
+        }
+    }
+}
+
+fn visit_topo(
+    name: &str,
+    deps: &BTreeMap<String, BTreeSet<String>>,
+    visited: &mut BTreeSet<String>,
+    out: &mut Vec<String>,
+) {
+    if visited.contains(name) {
+        return;
+    }
+    visited.insert(name.to_string());
+    if let Some(d) = deps.get(name) {
+        for dep in d {
+            visit_topo(dep, deps, visited, out);
+        }
+    }
+    out.push(name.to_string());
+}
+
+/// Emit a single Rust enum (unit variants). Pairs are (`json_value`, `variant_name`); serde rename when they differ.
+/// Emits enum doc comment from description and examples when present.
+fn emit_enum_from_pairs(
+    out: &mut impl Write,
+    name: &str,
+    pairs: &[(String, String)],
+    description: Option<&str>,
+    examples: Option<&[serde_json::Value]>,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<()> {
+    for line in doc_lines(description) {
+        writeln!(out, "/// {line}")?;
+    }
+    for line in examples_doc_lines(examples) {
+        writeln!(out, "/// {line}")?;
+    }
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]"
+    )?;
+    writeln!(out, "pub enum {name} {{")?;
+    for (value, variant_name) in pairs {
+        if value != variant_name {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(out, "    #[serde(rename = \"{escaped}\")]")?;
+        }
+        writeln!(out, "    {variant_name},")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    if settings.enforce_constraints {
+        enforce::emit_always_valid(out, name)?;
+    }
+    Ok(())
+}
+
+/// Emit a single anyOf enum (union) to `out`.
+/// Untagged, so a plain value deserializes into the first variant that accepts it. We do not derive
+/// PartialEq/Eq (variant structs may not implement them).
+fn emit_anyof_enum(
+    out: &mut impl Write,
+    a: &AnyOfEnumToEmit,
+    settings: &CodeGenSettings,
+    mapped: &BTreeSet<String>,
+) -> CodeGenResult<()> {
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]"
+    )?;
+    writeln!(out, "#[serde(untagged)]")?;
+    writeln!(out, "pub enum {} {{", a.name)?;
+    for (variant_name, ty) in &a.variants {
+        writeln!(out, "    {variant_name}({ty}),")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    if settings.enforce_constraints {
+        enforce::emit_for_union(out, &a.name, &a.variants, mapped)?;
+    }
+    Ok(())
+}
+
+/// Emit a single oneOf enum (union) to `out`.
+/// Same shape as [`emit_anyof_enum`]; `#[json_schema(one_of)]` makes the derive emit `oneOf`.
+fn emit_oneof_enum(
+    out: &mut impl Write,
+    a: &OneOfEnumToEmit,
+    settings: &CodeGenSettings,
+    mapped: &BTreeSet<String>,
+) -> CodeGenResult<()> {
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]"
+    )?;
+    writeln!(out, "#[serde(untagged)]")?;
+    writeln!(out, "#[json_schema(one_of)]")?;
+    writeln!(out, "pub enum {} {{", a.name)?;
+    for (variant_name, ty) in &a.variants {
+        writeln!(out, "    {variant_name}({ty}),")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    if settings.enforce_constraints {
+        enforce::emit_for_union(out, &a.name, &a.variants, mapped)?;
+    }
+    Ok(())
+}
+
+/// Emits a discriminated oneOf as an internally tagged enum: one struct variant per branch, whose
+/// fields `emit_fields` writes as for a struct of the given name (the enum name plus the variant
+/// name, which also names the variant's default functions).
+fn emit_tagged_enum(
+    out: &mut impl Write,
+    name: &str,
+    tagged: &TaggedUnion,
+    settings: &CodeGenSettings,
+    mapped: &BTreeSet<String>,
+    mut emit_fields: impl FnMut(
+        &str,
+        &JsonSchema,
+        &mut Vec<u8>,
+        &mut ConstrainedFields,
+    ) -> CodeGenResult<()>,
+) -> CodeGenResult<()> {
+    for (variant_name, _, schema) in &tagged.variants {
+        emit_default_functions_for_struct(out, &format!("{name}{variant_name}"), schema, settings)?;
+    }
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]"
+    )?;
+    let escaped: String = tagged.property.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(out, "#[serde(tag = \"{escaped}\")]")?;
+    writeln!(out, "pub enum {name} {{")?;
+    let mut records: Vec<(String, String, ConstrainedFields)> = Vec::new();
+    for (variant_name, value, schema) in &tagged.variants {
+        if value != variant_name {
+            let escaped: String = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(out, "    #[serde(rename = \"{escaped}\")]")?;
+        }
+        let owner: String = format!("{name}{variant_name}");
+        let mut fields: Vec<u8> = Vec::new();
+        let mut constrained: ConstrainedFields = ConstrainedFields::default();
+        emit_fields(&owner, schema, &mut fields, &mut constrained)?;
+        records.push((variant_name.clone(), owner, constrained));
+        if fields.is_empty() {
+            writeln!(out, "    {variant_name} {{}},")?;
+            continue;
+        }
+        writeln!(out, "    {variant_name} {{")?;
+        for line in String::from_utf8_lossy(&fields).lines() {
+            let line: &str = line.strip_prefix("    ").unwrap_or(line);
+            let line: &str = line.strip_prefix("pub ").unwrap_or(line);
+            writeln!(out, "        {line}")?;
+        }
+        writeln!(out, "    }},")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    if settings.enforce_constraints {
+        enforce::emit_for_tagged_enum(out, name, &records, mapped)?;
+    }
+    Ok(())
+}
+
+/// The types each tagged union in `tagged` holds by value: the inline field types of all its
+/// variants, looked up against `roots[schema_idx]`.
+fn tagged_union_inline_types(
+    roots: &[JsonSchema],
+    tagged: &[OneOfEnumToEmit],
+    settings: &CodeGenSettings,
+    key_to_name: &BTreeMap<DedupeKey, String>,
+    mode: DedupeMode,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+) -> CodeGenResult<BTreeMap<String, BTreeSet<String>>> {
+    let mut out: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for o in tagged {
+        let Some(t) = &o.tagged else {
+            continue;
+        };
+        let mut types: BTreeSet<String> = BTreeSet::new();
+        for (_, _, schema) in &t.variants {
+            types.extend(inline_field_types(
+                &roots[t.schema_idx],
+                schema,
+                settings,
+                Some(key_to_name),
+                mode,
+                anyof_enums,
+                oneof_enums,
+            )?);
+        }
+        out.insert(o.name.clone(), types);
+    }
+    Ok(out)
+}
+
+/// Emits `#[serde(default)]` or `#[serde(default = "fn")]` when the property has a default value.
+/// Default functions are emitted at module level by `emit_default_functions_for_struct`.
+/// `validated` is the inner type when the field holds a validated newtype, which has no
+/// `Default`.
+fn emit_default_attr(
+    out: &mut impl Write,
+    struct_name: &str,
+    field_name: &str,
+    prop_schema: &JsonSchema,
+    ty: &str,
+    is_required: bool,
+    validated: Option<&str>,
+) -> CodeGenResult<()> {
+    let Some(ref value) = prop_schema.default_value else {
+        return Ok(());
+    };
+    let is_optional: bool = !is_required;
+    if let Some(inner) = validated {
+        if is_optional && value.is_null() {
+            writeln!(out, "    #[serde(default)]")?;
+        } else if validated_default(prop_schema, value, inner).is_some() {
+            let fn_name: String = default_function_name(struct_name, field_name);
+            writeln!(out, "    #[serde(default = \"{fn_name}\")]")?;
+        }
+        return Ok(());
+    }
+    if json_value_equals_rust_type_default(value, ty, is_optional) {
+        writeln!(out, "    #[serde(default)]")?;
+        return Ok(());
+    }
+    let Some(_expr) = json_default_to_rust_expr(value, ty, is_optional) else {
+        return Ok(());
+    };
+    let fn_name: String = default_function_name(struct_name, field_name);
+    writeln!(out, "    #[serde(default = \"{fn_name}\")]")?;
+    Ok(())
+}
+
+/// Emits module-level default functions for all properties of the struct that have a custom default value.
+/// Under [`CodeGenSettings::enforce_constraints`] a default of a validated newtype field is built
+/// through `TryFrom`, after checking at generation time that it satisfies the schema.
+fn emit_default_functions_for_struct(
+    out: &mut impl Write,
+    struct_name: &str,
+    schema: &JsonSchema,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<()> {
+    for (key, prop_schema) in &schema.properties {
+        let Some(ref value) = prop_schema.default_value else {
+            continue;
+        };
+        if mapped_type(prop_schema).is_some() {
+            continue;
+        }
+        let field_name = sanitize_field_name(key);
+        let is_required = schema.is_required(key);
+        let is_optional = !is_required;
+        if let Some(inner) = validated_inner(settings, prop_schema) {
+            if is_optional && value.is_null() {
+                continue;
+            }
+            let Some(expr) = validated_default(prop_schema, value, &inner) else {
+                continue;
+            };
+            let newtype: String = constrained_type_name(struct_name, key);
+            let built: String =
+                format!("{newtype}::try_from({expr}).expect(\"default satisfies the schema\")");
+            let (ty, expr): (String, String) = if is_required {
+                (newtype, built)
+            } else {
+                (format!("Option<{newtype}>"), format!("Some({built})"))
+            };
+            let fn_name = default_function_name(struct_name, &field_name);
+            writeln!(out, "fn {fn_name}() -> {ty} {{ {expr} }}")?;
+            writeln!(out)?;
+            continue;
+        }
+        let ty: String = if prop_schema.is_string() {
+            if is_required {
+                "String".to_string()
+            } else {
+                "Option<String>".to_string()
+            }
+        } else if prop_schema.is_integer() || prop_schema.is_number() {
+            let inner = rust_numeric_type_for_schema(prop_schema);
+            if is_required {
+                inner
+            } else {
+                format!("Option<{inner}>")
+            }
+        } else if prop_schema.is_boolean() {
+            if is_required {
+                "bool".to_string()
+            } else {
+                "Option<bool>".to_string()
+            }
+        } else {
+            continue;
+        };
+        if json_value_equals_rust_type_default(value, &ty, is_optional) {
+            continue;
+        }
+        let Some(expr) = json_default_to_rust_expr(value, &ty, is_optional) else {
+            continue;
+        };
+        let fn_name = default_function_name(struct_name, &field_name);
+        writeln!(out, "fn {fn_name}() -> {ty} {{ {expr} }}")?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Renders a JSON value as tokens accepted by `serde_json::json!`: JSON text, except that strings
+/// use Rust escapes so every string is a valid Rust literal. Iterative (no recursion).
+pub(crate) fn json_macro_tokens(value: &serde_json::Value) -> String {
+    use std::fmt::Write as _;
+
+    enum Piece<'a> {
+        Value(&'a serde_json::Value),
+        Text(String),
+    }
+    let mut out: String = String::new();
+    let mut stack: Vec<Piece<'_>> = vec![Piece::Value(value)];
+    while let Some(piece) = stack.pop() {
+        let value: &serde_json::Value = match piece {
+            Piece::Text(t) => {
+                out.push_str(&t);
+                continue;
+            }
+            Piece::Value(v) => v,
+        };
+        match value {
+            serde_json::Value::String(s) => {
+                write!(out, "{s:?}").expect("writing to a String cannot fail");
+            }
+            serde_json::Value::Array(items) => {
+                out.push('[');
+                stack.push(Piece::Text("]".to_string()));
+                for (i, item) in items.iter().enumerate().rev() {
+                    stack.push(Piece::Value(item));
+                    if i > 0 {
+                        stack.push(Piece::Text(", ".to_string()));
+                    }
+                }
+            }
+            serde_json::Value::Object(map) => {
+                out.push('{');
+                stack.push(Piece::Text("}".to_string()));
+                for (i, (k, v)) in map.iter().enumerate().rev() {
+                    stack.push(Piece::Value(v));
+                    let separator: &str = if i > 0 { ", " } else { "" };
+                    stack.push(Piece::Text(format!("{separator}{k:?}: ")));
+                }
+            }
+            serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+                out.push_str(&value.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// Emits `#[json_schema(extension("x-key" = value, ...))]` when the schema has extension keywords,
+/// so the derive emits them back. `indent` is prepended (empty for structs, four spaces for fields).
+fn emit_extension_attr(
+    out: &mut impl Write,
+    indent: &str,
+    extensions: &BTreeMap<String, serde_json::Value>,
+) -> CodeGenResult<()> {
+    let pairs: Vec<String> = extensions
+        .iter()
+        .filter(|(k, _)| k.as_str() != type_mappings::RUST_TYPE_KEYWORD)
+        .map(|(k, v)| format!("{k:?} = {}", json_macro_tokens(v)))
+        .collect();
+    if pairs.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "{indent}#[json_schema(extension({}))]",
+        pairs.join(", ")
+    )?;
+    Ok(())
+}
+
+/// Emit struct fields; when resolver is Some (dedupe mode), use canonical type names for nested objects.
+#[expect(clippy::too_many_lines, clippy::too_many_arguments)]
+fn emit_struct_fields_with_resolver(
+    root: &JsonSchema,
+    struct_name: &str,
+    schema: &JsonSchema,
+    out: &mut impl Write,
+    settings: &CodeGenSettings,
+    key_to_name: Option<&BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+    enum_values_to_name: Option<&EnumValuesToNameMap>,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+    boxed_types: &BTreeSet<String>,
+    constrained: &mut ConstrainedFields,
+) -> CodeGenResult<()> {
+    let enum_names_simple: Option<BTreeMap<Vec<String>, String>> = enum_values_to_name.map(|m| {
+        m.iter()
+            .map(|(k, (n, _, _))| (k.clone(), n.clone()))
+            .collect()
+    });
+    for (key, prop_schema) in &schema.properties {
+        // Extensions on an object property are emitted on its struct; otherwise on the field.
+        let field_extensions: Option<&BTreeMap<String, serde_json::Value>> =
+            (!prop_schema.is_object_with_properties() || mapped_type(prop_schema).is_some())
+                .then_some(&prop_schema.extensions);
+        let raw_prop_schema: &JsonSchema = prop_schema;
+        let (prop_schema_effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
+        let prop_schema: &JsonSchema = &prop_schema_effective;
+
+        for line in doc_lines(prop_schema.description.as_deref()) {
+            writeln!(out, "    /// {line}")?;
+        }
+        emit_deprecated_attr(out, prop_schema)?;
+        if let Some(extensions) = field_extensions {
+            emit_extension_attr(out, "    ", extensions)?;
+        }
+        let field_name = sanitize_field_name(key);
+        let needs_rename = field_name != *key;
+
+        if let Some(mapped) = mapped_type(prop_schema) {
+            let ty = if schema.is_required(key) {
+                mapped.to_string()
+            } else {
+                format!("Option<{mapped}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.any_of.as_ref().is_some_and(|v| !v.is_empty())
+            || prop_schema.one_of.as_ref().is_some_and(|v| !v.is_empty())
+        {
+            let enum_name = union_enum_name(key, prop_schema, anyof_enums, oneof_enums);
+            let ty = if schema.is_required(key) {
+                enum_name.clone()
+            } else {
+                format!("Option<{enum_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if let Some(values) = string_enum_or_const_values(prop_schema) {
+            let enum_name: &String = enum_values_to_name
+                .and_then(|m| m.get(&values).map(|(n, _, _)| n))
+                .expect("enum name for string enum");
+            let ty = if schema.is_required(key) {
+                enum_name.clone()
+            } else {
+                format!("Option<{enum_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_string()
+            || (prop_schema
+                .enum_values
+                .as_ref()
+                .is_some_and(|v| !v.is_empty())
+                && !prop_schema.is_string_enum())
+        {
+            if prop_schema.is_string()
+                && let Some(format_ty) = rust_type_for_string_format(prop_schema)
+            {
+                let ty = if schema.is_required(key) {
+                    format_ty.to_string()
+                } else {
+                    format!("Option<{format_ty}>")
+                };
+                if needs_rename {
+                    writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+                }
+                writeln!(out, "    pub {field_name}: {ty},")?;
+                constrained.field(key, &field_name, &ty);
+                continue;
+            }
+            // String type, or non-string/mixed enum fallback per design.
+            let validated: Option<String> = validated_inner(settings, prop_schema);
+            let inner: String = match validated {
+                Some(ref v) => {
+                    constrained.newtype(constrained_type_name(struct_name, key), v, prop_schema)
+                }
+                None => "String".to_string(),
+            };
+            let ty = if schema.is_required(key) {
+                inner
+            } else {
+                format!("Option<{inner}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            if prop_schema.min_length.is_some()
+                || prop_schema.max_length.is_some()
+                || prop_schema.pattern.is_some()
+            {
+                let mut attrs: Vec<String> = Vec::new();
+                if let Some(n) = prop_schema.min_length {
+                    attrs.push(format!("min_length = {n}"));
+                }
+                if let Some(n) = prop_schema.max_length {
+                    attrs.push(format!("max_length = {n}"));
+                }
+                if let Some(ref p) = prop_schema.pattern {
+                    let escaped = p.replace('\\', "\\\\").replace('"', "\\\"");
+                    attrs.push(format!("pattern = \"{escaped}\""));
+                }
+                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                validated.as_deref(),
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_integer() || prop_schema.is_number() {
+            let validated: Option<String> = validated_inner(settings, prop_schema);
+            let inner: String = match validated {
+                Some(ref v) => {
+                    constrained.newtype(constrained_type_name(struct_name, key), v, prop_schema)
+                }
+                None => rust_numeric_type_for_schema(prop_schema),
+            };
+            let ty = if schema.is_required(key) {
+                inner
+            } else {
+                format!("Option<{inner}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                validated.as_deref(),
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_boolean() {
+            let ty: String = if schema.is_required(key) {
+                "bool".to_string()
+            } else {
+                "Option<bool>".to_string()
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                None,
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_array_with_items() {
+            let item_schema: &JsonSchema = prop_schema
+                .items
+                .as_ref()
+                .expect("array with items")
+                .as_ref();
+            let inner: String = rust_type_for_item_schema(
+                root,
+                item_schema,
+                Some(key),
+                enum_names_simple.as_ref(),
+                key_to_name,
+                settings,
+                mode,
+            )?;
+            let (item_schema_effective, _) = resolve_ref_for_codegen(root, item_schema, Some(key))?;
+            let inner: String = match validated_inner(settings, &item_schema_effective) {
+                Some(v) => constrained.newtype(
+                    constrained_type_name(struct_name, &format!("{key}Item")),
+                    &v,
+                    &item_schema_effective,
+                ),
+                None => inner,
+            };
+            let use_hash_set: bool =
+                prop_schema.unique_items == Some(true) && item_schema_is_hashable(item_schema);
+            let container: &str = if use_hash_set { "HashSet" } else { "Vec" };
+            let ty = if schema.is_required(key) {
+                format!("{container}<{inner}>")
+            } else {
+                format!("Option<{container}<{inner}>>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            if prop_schema.min_items.is_some() || prop_schema.max_items.is_some() {
+                let mut attrs: Vec<String> = Vec::new();
+                if let Some(n) = prop_schema.min_items {
+                    attrs.push(format!("min_items = {n}"));
+                }
+                if let Some(n) = prop_schema.max_items {
+                    attrs.push(format!("max_items = {n}"));
+                }
+                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
+            }
+            if settings.enforce_constraints
+                && (prop_schema.min_items.is_some() || prop_schema.max_items.is_some())
+            {
+                let fn_name: String = enforce::items_deserializer_name(struct_name, &field_name);
+                let default: &str = if schema.is_required(key) {
+                    ""
+                } else {
+                    "default, "
+                };
+                writeln!(
+                    out,
+                    "    #[serde({default}deserialize_with = \"{fn_name}\")]"
+                )?;
+                constrained.array_field(
+                    key,
+                    &field_name,
+                    &ty,
+                    prop_schema.min_items,
+                    prop_schema.max_items,
+                );
+            } else {
+                constrained.field(key, &field_name, &ty);
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+        } else if prop_schema.is_object_with_properties() {
+            let nested_name: String = boxed_if_recursive(
+                nested_struct_type_name(root, raw_prop_schema, key, settings, key_to_name, mode)?,
+                boxed_types,
+            );
+            let ty = if schema.is_required(key) {
+                nested_name.clone()
+            } else {
+                format!("Option<{nested_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        }
+    }
+    if let Some(AdditionalProperties::Schema(sub)) = &schema.additional_properties {
+        let value_ty: String = rust_type_for_item_schema(
+            root,
+            sub,
+            Some("additional"),
+            enum_names_simple.as_ref(),
+            key_to_name,
+            settings,
+            mode,
+        )?;
+        let (sub_effective, _) = resolve_ref_for_codegen(root, sub, Some("additional"))?;
+        let value_ty: String = match validated_inner(settings, &sub_effective) {
+            Some(v) => constrained.newtype(
+                constrained_type_name(struct_name, "additional"),
+                &v,
+                &sub_effective,
+            ),
+            None => value_ty,
+        };
+        writeln!(out, "    #[serde(default)]")?;
+        writeln!(out, "    pub additional: BTreeMap<String, {value_ty}>,")?;
+        constrained.field(
+            "additional",
+            "additional",
+            &format!("BTreeMap<String, {value_ty}>"),
+        );
+    }
+    Ok(())
+}
+
+/// Emit a single struct's fields to `out`.
+#[expect(clippy::too_many_lines, clippy::too_many_arguments)]
+fn emit_struct_fields(
+    root: &JsonSchema,
+    struct_name: &str,
+    schema: &JsonSchema,
+    out: &mut impl Write,
+    settings: &CodeGenSettings,
+    enum_values_to_name: Option<&BTreeMap<Vec<String>, String>>,
+    renamed_structs: Option<&BTreeMap<DedupeKey, String>>,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+    boxed_types: &BTreeSet<String>,
+    constrained: &mut ConstrainedFields,
+) -> CodeGenResult<()> {
+    for (key, prop_schema) in &schema.properties {
+        // Extensions on an object property are emitted on its struct; otherwise on the field.
+        let field_extensions: Option<&BTreeMap<String, serde_json::Value>> =
+            (!prop_schema.is_object_with_properties() || mapped_type(prop_schema).is_some())
+                .then_some(&prop_schema.extensions);
+        let raw_prop_schema: &JsonSchema = prop_schema;
+        let (prop_schema_effective, _) = resolve_ref_for_codegen(root, prop_schema, Some(key))?;
+        let prop_schema: &JsonSchema = &prop_schema_effective;
+
+        for line in doc_lines(prop_schema.description.as_deref()) {
+            writeln!(out, "    /// {line}")?;
+        }
+        emit_deprecated_attr(out, prop_schema)?;
+        if let Some(extensions) = field_extensions {
+            emit_extension_attr(out, "    ", extensions)?;
+        }
+        let field_name = sanitize_field_name(key);
+        let needs_rename = field_name != *key;
+
+        if let Some(mapped) = mapped_type(prop_schema) {
+            let ty = if schema.is_required(key) {
+                mapped.to_string()
+            } else {
+                format!("Option<{mapped}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.any_of.as_ref().is_some_and(|v| !v.is_empty())
+            || prop_schema.one_of.as_ref().is_some_and(|v| !v.is_empty())
+        {
+            let enum_name = union_enum_name(key, prop_schema, anyof_enums, oneof_enums);
+            let ty = if schema.is_required(key) {
+                enum_name.clone()
+            } else {
+                format!("Option<{enum_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if let Some(values) = string_enum_or_const_values(prop_schema) {
+            let enum_name: &String = enum_values_to_name
+                .and_then(|m| m.get(&values))
+                .expect("enum name for string enum");
+            let ty = if schema.is_required(key) {
+                enum_name.clone()
+            } else {
+                format!("Option<{enum_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_string()
+            || (prop_schema
+                .enum_values
+                .as_ref()
+                .is_some_and(|v| !v.is_empty())
+                && !prop_schema.is_string_enum())
+        {
+            if prop_schema.is_string()
+                && let Some(format_ty) = rust_type_for_string_format(prop_schema)
+            {
+                let ty = if schema.is_required(key) {
+                    format_ty.to_string()
+                } else {
+                    format!("Option<{format_ty}>")
+                };
+                if needs_rename {
+                    writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+                }
+                writeln!(out, "    pub {field_name}: {ty},")?;
+                constrained.field(key, &field_name, &ty);
+                continue;
+            }
+            // String type, or non-string/mixed enum fallback per design.
+            let validated: Option<String> = validated_inner(settings, prop_schema);
+            let inner: String = match validated {
+                Some(ref v) => {
+                    constrained.newtype(constrained_type_name(struct_name, key), v, prop_schema)
+                }
+                None => "String".to_string(),
+            };
+            let ty = if schema.is_required(key) {
+                inner
+            } else {
+                format!("Option<{inner}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            if prop_schema.min_length.is_some()
+                || prop_schema.max_length.is_some()
+                || prop_schema.pattern.is_some()
+            {
+                let mut attrs: Vec<String> = Vec::new();
+                if let Some(n) = prop_schema.min_length {
+                    attrs.push(format!("min_length = {n}"));
+                }
+                if let Some(n) = prop_schema.max_length {
+                    attrs.push(format!("max_length = {n}"));
+                }
+                if let Some(ref p) = prop_schema.pattern {
+                    let escaped = p.replace('\\', "\\\\").replace('"', "\\\"");
+                    attrs.push(format!("pattern = \"{escaped}\""));
+                }
+                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                validated.as_deref(),
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_integer() || prop_schema.is_number() {
+            let validated: Option<String> = validated_inner(settings, prop_schema);
+            let inner: String = match validated {
+                Some(ref v) => {
+                    constrained.newtype(constrained_type_name(struct_name, key), v, prop_schema)
+                }
+                None => rust_numeric_type_for_schema(prop_schema),
+            };
+            let ty = if schema.is_required(key) {
+                inner
+            } else {
+                format!("Option<{inner}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                validated.as_deref(),
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_boolean() {
+            let ty: String = if schema.is_required(key) {
+                "bool".to_string()
+            } else {
+                "Option<bool>".to_string()
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            emit_default_attr(
+                out,
+                struct_name,
+                &field_name,
+                prop_schema,
+                &ty,
+                schema.is_required(key),
+                None,
+            )?;
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        } else if prop_schema.is_array_with_items() {
+            let item_schema: &JsonSchema = prop_schema
+                .items
+                .as_ref()
+                .expect("array with items")
+                .as_ref();
+            let inner: String = rust_type_for_item_schema(
+                root,
+                item_schema,
+                Some(key),
+                enum_values_to_name,
+                renamed_structs,
+                settings,
+                DedupeMode::Full,
+            )?;
+            let (item_schema_effective, _) = resolve_ref_for_codegen(root, item_schema, Some(key))?;
+            let inner: String = match validated_inner(settings, &item_schema_effective) {
+                Some(v) => constrained.newtype(
+                    constrained_type_name(struct_name, &format!("{key}Item")),
+                    &v,
+                    &item_schema_effective,
+                ),
+                None => inner,
+            };
+            let use_hash_set: bool =
+                prop_schema.unique_items == Some(true) && item_schema_is_hashable(item_schema);
+            let container: &str = if use_hash_set { "HashSet" } else { "Vec" };
+            let ty = if schema.is_required(key) {
+                format!("{container}<{inner}>")
+            } else {
+                format!("Option<{container}<{inner}>>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            if prop_schema.min_items.is_some() || prop_schema.max_items.is_some() {
+                let mut attrs: Vec<String> = Vec::new();
+                if let Some(n) = prop_schema.min_items {
+                    attrs.push(format!("min_items = {n}"));
+                }
+                if let Some(n) = prop_schema.max_items {
+                    attrs.push(format!("max_items = {n}"));
+                }
+                writeln!(out, "    #[json_schema({})]", attrs.join(", "))?;
+            }
+            if settings.enforce_constraints
+                && (prop_schema.min_items.is_some() || prop_schema.max_items.is_some())
+            {
+                let fn_name: String = enforce::items_deserializer_name(struct_name, &field_name);
+                let default: &str = if schema.is_required(key) {
+                    ""
+                } else {
+                    "default, "
+                };
+                writeln!(
+                    out,
+                    "    #[serde({default}deserialize_with = \"{fn_name}\")]"
+                )?;
+                constrained.array_field(
+                    key,
+                    &field_name,
+                    &ty,
+                    prop_schema.min_items,
+                    prop_schema.max_items,
+                );
+            } else {
+                constrained.field(key, &field_name, &ty);
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+        } else if prop_schema.is_object_with_properties() {
+            let nested_name: String = boxed_if_recursive(
+                nested_struct_type_name(
+                    root,
+                    raw_prop_schema,
+                    key,
+                    settings,
+                    renamed_structs,
+                    DedupeMode::Full,
+                )?,
+                boxed_types,
+            );
+            let ty = if schema.is_required(key) {
+                nested_name.clone()
+            } else {
+                format!("Option<{nested_name}>")
+            };
+            if needs_rename {
+                writeln!(out, "    #[serde(rename = \"{key}\")]")?;
+            }
+            writeln!(out, "    pub {field_name}: {ty},")?;
+            constrained.field(key, &field_name, &ty);
+        }
+    }
+    if let Some(AdditionalProperties::Schema(sub)) = &schema.additional_properties {
+        let value_ty: String = rust_type_for_item_schema(
+            root,
+            sub,
+            Some("additional"),
+            enum_values_to_name,
+            renamed_structs,
+            settings,
+            DedupeMode::Full,
+        )?;
+        let (sub_effective, _) = resolve_ref_for_codegen(root, sub, Some("additional"))?;
+        let value_ty: String = match validated_inner(settings, &sub_effective) {
+            Some(v) => constrained.newtype(
+                constrained_type_name(struct_name, "additional"),
+                &v,
+                &sub_effective,
+            ),
+            None => value_ty,
+        };
+        writeln!(out, "    #[serde(default)]")?;
+        writeln!(out, "    pub additional: BTreeMap<String, {value_ty}>,")?;
+        constrained.field(
+            "additional",
+            "additional",
+            &format!("BTreeMap<String, {value_ty}>"),
+        );
+    }
+    Ok(())
+}
+
+/// A type that holds itself by value (directly or through other structs and union enums) has
+/// infinite size. Given the types each struct holds by value (`deps`, see [`inline_field_types`]),
+/// finds the edges that close each cycle, boxes them in the union enums' variant types, and
+/// returns them per struct for the field emitters. Tagged unions come with their entry in `deps`
+/// (see [`tagged_union_inline_types`]) and get their boxed edges returned like structs.
+fn box_recursive_edges(
+    mut deps: BTreeMap<String, BTreeSet<String>>,
+    struct_names: &BTreeSet<String>,
+    anyof_enums: &mut [AnyOfEnumToEmit],
+    oneof_enums: &mut [OneOfEnumToEmit],
+) -> BTreeMap<String, BTreeSet<String>> {
+    let union_variants = anyof_enums
+        .iter_mut()
+        .map(|a| (&a.name, &mut a.variants))
+        .chain(oneof_enums.iter_mut().map(|o| (&o.name, &mut o.variants)));
+    let mut unions: Vec<(&String, &mut Vec<(String, String)>)> = union_variants.collect();
+    for (name, variants) in &unions {
+        let types: BTreeSet<String> = variants.iter().map(|(_, ty)| ty.clone()).collect();
+        deps.entry((*name).clone()).or_insert(types);
+    }
+    let boxed: BTreeMap<String, BTreeSet<String>> = recursive_edges(&deps, struct_names);
+    for (name, variants) in &mut unions {
+        if let Some(boxed_types) = boxed.get(*name) {
+            for (_, ty) in variants.iter_mut() {
+                *ty = boxed_if_recursive(std::mem::take(ty), boxed_types);
+            }
+        }
+    }
+    boxed
+}
+
+/// Gives the structs and enums of one module distinct names (see [`resolve_type_names`]).
+/// Returns the renamed structs keyed by shape, so references to them can be resolved.
+fn rename_structs_and_enums(
+    structs: &mut [StructToEmit],
+    enums: &mut [EnumToEmit],
+    settings: &CodeGenSettings,
+) -> CodeGenResult<BTreeMap<DedupeKey, String>> {
+    let claims: Vec<&NameClaim> = structs
+        .iter()
+        .map(|st| &st.claim)
+        .chain(enums.iter().map(|e| &e.claim))
+        .collect();
+    let names: Vec<String> =
+        resolve_type_names(&claims, &BTreeSet::new(), settings.name_collision)?;
+    let (struct_names, enum_names) = names.split_at(structs.len());
+    let mut renamed: BTreeMap<DedupeKey, String> = BTreeMap::new();
+    for (st, name) in structs.iter_mut().zip(struct_names) {
+        if *name != st.name {
+            renamed.insert(
+                DedupeKey::from_schema(&st.schema, DedupeMode::Full),
+                name.clone(),
+            );
+            st.name.clone_from(name);
+        }
+    }
+    for (e, name) in enums.iter_mut().zip(enum_names) {
+        e.name.clone_from(name);
+    }
+    Ok(renamed)
+}
+
+/// Gives the anyOf/oneOf enums of one module names distinct from each other and from the
+/// module's (already named) structs and enums.
+fn rename_union_enums(
+    taken: &BTreeSet<String>,
+    anyof_enums: &mut [AnyOfEnumToEmit],
+    oneof_enums: &mut [OneOfEnumToEmit],
+    settings: &CodeGenSettings,
+) -> CodeGenResult<()> {
+    let claims: Vec<&NameClaim> = anyof_enums
+        .iter()
+        .map(|a| &a.claim)
+        .chain(oneof_enums.iter().map(|o| &o.claim))
+        .collect();
+    let names: Vec<String> = resolve_type_names(&claims, taken, settings.name_collision)?;
+    let (anyof_names, oneof_names) = names.split_at(anyof_enums.len());
+    for (a, name) in anyof_enums.iter_mut().zip(anyof_names) {
+        a.name.clone_from(name);
+    }
+    for (o, name) in oneof_enums.iter_mut().zip(oneof_names) {
+        o.name.clone_from(name);
+    }
+    Ok(())
+}
+
+/// Name of the anyOf/oneOf enum generated for property `key` whose (ref-resolved) schema is
+/// `prop_schema`.
+fn union_enum_name(
+    key: &str,
+    prop_schema: &JsonSchema,
+    anyof_enums: &[AnyOfEnumToEmit],
+    oneof_enums: &[OneOfEnumToEmit],
+) -> String {
+    let is_use = |uses: &[UnionUse]| uses.iter().any(|(k, s)| k == key && s == prop_schema);
+    if prop_schema.any_of.as_ref().is_some_and(|v| !v.is_empty()) {
+        anyof_enums
+            .iter()
+            .find(|a| is_use(&a.uses))
+            .map_or_else(|| sanitize_struct_name(key) + "AnyOf", |a| a.name.clone())
+    } else {
+        oneof_enums
+            .iter()
+            .find(|o| is_use(&o.uses))
+            .map_or_else(|| sanitize_struct_name(key) + "OneOf", |o| o.name.clone())
+    }
+}
+
+/// [`CodeGenSettings::type_attributes_by_type`] as resolved for one module.
+#[derive(Default)]
+struct RequestedAttributes {
+    /// Requested derives and attributes by type name.
+    by_name: BTreeMap<String, TypeAttributes>,
+    /// The type name each selector resolved to; pointers that match no generated type are left
+    /// out.
+    selected: BTreeMap<String, String>,
+}
+
+impl RequestedAttributes {
+    /// The selectors whose type is defined in the module (`sets`) or the shared module it uses.
+    fn matched_in<'a>(
+        &'a self,
+        sets: &'a DeriveSets,
+        shared: &'a DeriveSets,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.selected
+            .iter()
+            .filter(|(_, name)| sets.contains_key(*name) || shared.contains_key(*name))
+            .map(|(key, _)| key.clone())
+    }
+}
+
+/// The names generated types got in one module, for resolving JSON Pointer keys of
+/// [`CodeGenSettings::type_attributes_by_type`].
+struct ModuleTypeNames<'a> {
+    enum_values_to_name: &'a BTreeMap<Vec<String>, String>,
+    key_to_name: Option<&'a BTreeMap<DedupeKey, String>>,
+    mode: DedupeMode,
+    anyof_enums: &'a [AnyOfEnumToEmit],
+    oneof_enums: &'a [OneOfEnumToEmit],
+    alias: Option<&'a RootAlias>,
+}
+
+/// Name of the type generated for the subschema of `root` at `pointer` (`""` for the root), or
+/// `None` when there is no such subschema or it does not generate a type of its own.
+fn type_name_at_pointer(
+    root: &JsonSchema,
+    pointer: &str,
+    settings: &CodeGenSettings,
+    names: &ModuleTypeNames<'_>,
+) -> CodeGenResult<Option<String>> {
+    let Ok(pointer) = JsonPointer::try_from(pointer) else {
+        return Ok(None);
+    };
+    let Some((_, keyword, sub)) = root.walk().find(|(p, _, _)| *p == pointer) else {
+        return Ok(None);
+    };
+    let is_root: bool = keyword == SchemaKeyword::Root;
+    if is_root && let Some(alias) = names.alias {
+        return Ok(Some(alias.name.clone()));
+    }
+    let key: Option<&str> = matches!(
+        keyword,
+        SchemaKeyword::Properties | SchemaKeyword::Defs | SchemaKeyword::Definitions
+    )
+    .then(|| pointer.segments().last())
+    .flatten();
+    let (target, from_key) = resolve_ref_for_codegen(root, sub, key)?;
+    let target: JsonSchema = resolve_all_of_for_codegen(&target)?;
+    if target.any_of.as_ref().is_some_and(|v| !v.is_empty())
+        || target.one_of.as_ref().is_some_and(|v| !v.is_empty())
+    {
+        let key: &str = from_key
+            .as_deref()
+            .or(target.title.as_deref().filter(|_| is_root))
+            .unwrap_or("Root");
+        return Ok(Some(union_enum_name(
+            key,
+            &target,
+            names.anyof_enums,
+            names.oneof_enums,
+        )));
+    }
+    if is_root
+        && target.is_object_with_properties()
+        && !names
+            .key_to_name
+            .is_some_and(|m| m.contains_key(&DedupeKey::from_schema(&target, names.mode)))
+    {
+        return root_struct_name(root, settings).map(Some);
+    }
+    let ty: String = rust_type_for_item_schema(
+        root,
+        sub,
+        key,
+        Some(names.enum_values_to_name),
+        names.key_to_name,
+        settings,
+        names.mode,
+    )?;
+    Ok(ty
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_')
+        .then_some(ty))
+}
+
+/// [`CodeGenSettings::type_attributes_by_type`] for one module, keyed by type name: JSON Pointer
+/// keys (starting with `#` or `/`) are resolved against `root`; pointers that match no generated
+/// type are dropped.
+fn requested_type_attributes(
+    root: &JsonSchema,
+    settings: &CodeGenSettings,
+    names: &ModuleTypeNames<'_>,
+) -> CodeGenResult<RequestedAttributes> {
+    let mut out: RequestedAttributes = RequestedAttributes::default();
+    for (key, attrs) in &settings.type_attributes_by_type {
+        let name: String = if key.starts_with('#') || key.starts_with('/') {
+            match type_name_at_pointer(root, key.trim_start_matches('#'), settings, names)? {
+                Some(name) => name,
+                None => continue,
+            }
+        } else {
+            key.clone()
+        };
+        out.selected.insert(key.clone(), name.clone());
+        let entry: &mut TypeAttributes = out.by_name.entry(name).or_default();
+        entry.derives.extend(attrs.derives.iter().cloned());
+        entry.attributes.extend(attrs.attributes.iter().cloned());
+    }
+    Ok(out)
+}
+
+/// The `type_attributes_by_type` selectors that no generated type matched, in key order.
+fn unmatched_selectors(settings: &CodeGenSettings, matched: &BTreeSet<String>) -> Vec<String> {
+    settings
+        .type_attributes_by_type
+        .keys()
+        .filter(|key| !matched.contains(*key))
+        .cloned()
+        .collect()
+}
+
+/// Errors when the (ref-resolved) root is an empty anyOf or oneOf.
+fn check_root_unions(root: &JsonSchema) -> CodeGenResult<()> {
+    if root.any_of.as_ref().is_some_and(std::vec::Vec::is_empty) {
+        return Err(CodeGenError::AnyOfEmpty);
+    }
+    if root.one_of.as_ref().is_some_and(std::vec::Vec::is_empty) {
+        return Err(CodeGenError::OneOfEmpty);
+    }
+    Ok(())
+}
+
+/// Emit Rust source from a parsed schema to `out`. Used by [`RustBackend::generate`]. Returns
+/// the module's [`requested_type_attributes`].
+#[expect(clippy::too_many_lines)]
+fn emit_rust(
+    schema: &JsonSchema,
+    out: &mut impl Write,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<RequestedAttributes> {
+    let root_unresolved = resolve_all_of_for_codegen(schema)?;
+    let (root, _) = resolve_ref_for_codegen(schema, &root_unresolved, None)?;
+    check_root_unions(&root)?;
+
+    let reached: Vec<ReachedType> = reachable_types(schema, settings)?;
+    let mapped: BTreeSet<String> = type_mappings::mapped_types(std::slice::from_ref(schema));
+    let mut enums: Vec<EnumToEmit> = collect_enums(&reached, settings);
+    let mut structs: Vec<StructToEmit> = collect_structs(&reached, settings);
+
+    let renamed_structs: BTreeMap<DedupeKey, String> =
+        rename_structs_and_enums(&mut structs, &mut enums, settings)?;
+    let enum_values_to_name: BTreeMap<Vec<String>, String> = enums
+        .iter()
+        .map(|e| (e.values.clone(), e.name.clone()))
+        .collect();
+
+    let (mut anyof_enums, mut oneof_enums) = collect_union_enums(
+        schema,
+        &reached,
+        settings,
+        &enum_values_to_name,
+        Some(&renamed_structs),
+        DedupeMode::Full,
+        0,
+    )?;
+    let taken: BTreeSet<String> = structs
+        .iter()
+        .map(|st| st.name.clone())
+        .chain(enums.iter().map(|e| e.name.clone()))
+        .collect();
+    rename_union_enums(&taken, &mut anyof_enums, &mut oneof_enums, settings)?;
+    let alias: Option<RootAlias> = root_alias(
+        schema,
+        settings,
+        &enum_values_to_name,
+        Some(&renamed_structs),
+        DedupeMode::Full,
+    )?;
+
+    let mut deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for st in &structs {
+        let types: BTreeSet<String> = inline_field_types(
+            schema,
+            &st.schema,
+            settings,
+            Some(&renamed_structs),
+            DedupeMode::Full,
+            &anyof_enums,
+            &oneof_enums,
+        )?;
+        deps.insert(st.name.clone(), types);
+    }
+    deps.extend(tagged_union_inline_types(
+        std::slice::from_ref(schema),
+        &oneof_enums,
+        settings,
+        &renamed_structs,
+        DedupeMode::Full,
+        &anyof_enums,
+        &oneof_enums,
+    )?);
+    let struct_names: BTreeSet<String> = structs.iter().map(|st| st.name.clone()).collect();
+    let boxed: BTreeMap<String, BTreeSet<String>> =
+        box_recursive_edges(deps, &struct_names, &mut anyof_enums, &mut oneof_enums);
+    let no_boxed: BTreeSet<String> = BTreeSet::new();
+    let requested: RequestedAttributes = requested_type_attributes(
+        schema,
+        settings,
+        &ModuleTypeNames {
+            enum_values_to_name: &enum_values_to_name,
+            key_to_name: Some(&renamed_structs),
+            mode: DedupeMode::Full,
+            anyof_enums: &anyof_enums,
+            oneof_enums: &oneof_enums,
+            alias: alias.as_ref(),
+        },
+    )?;
+
+    writeln!(
+        out,
+        "//! Generated by json-schema-rs. Do not edit manually."
+    )?;
+    writeln!(out)?;
+    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
+    writeln!(out)?;
+
+    for e in &enums {
+        let pairs: Vec<(String, String)> = enum_variant_names_with_collision_resolution(&e.values);
+        emit_enum_from_pairs(
+            out,
+            &e.name,
+            &pairs,
+            e.description.as_deref(),
+            e.examples.as_deref(),
+            settings,
+        )?;
+    }
+
+    for a in &anyof_enums {
+        emit_anyof_enum(out, a, settings, &mapped)?;
+    }
+
+    for o in &oneof_enums {
+        let Some(tagged) = &o.tagged else {
+            emit_oneof_enum(out, o, settings, &mapped)?;
+            continue;
+        };
+        emit_tagged_enum(
+            out,
+            &o.name,
+            tagged,
+            settings,
+            &mapped,
+            |struct_name, variant, buf, constrained| {
+                emit_struct_fields(
+                    schema,
+                    struct_name,
+                    variant,
+                    buf,
+                    settings,
+                    Some(&enum_values_to_name),
+                    Some(&renamed_structs),
+                    &anyof_enums,
+                    &oneof_enums,
+                    boxed.get(&o.name).unwrap_or(&no_boxed),
+                    constrained,
+                )
+            },
+        )?;
+    }
+
+    for st in &structs {
+        emit_default_functions_for_struct(out, &st.name, &st.schema, settings)?;
+        emit_struct_derive_and_attrs(out, &st.name, &st.schema)?;
+        let mut constrained: ConstrainedFields = ConstrainedFields::default();
+        emit_struct_fields(
+            schema,
+            &st.name,
+            &st.schema,
+            out,
+            settings,
+            Some(&enum_values_to_name),
+            Some(&renamed_structs),
+            &anyof_enums,
+            &oneof_enums,
+            boxed.get(&st.name).unwrap_or(&no_boxed),
+            &mut constrained,
+        )?;
+        writeln!(out, "}}")?;
+        writeln!(out)?;
+        if settings.enforce_constraints {
+            enforce::emit_for_struct(out, &st.name, &constrained, &mapped)?;
+        }
+    }
+
+    if let Some(alias) = &alias {
+        emit_root_alias(out, alias, settings)?;
+    }
+
+    Ok(requested)
+}
+
+/// Generate Rust source from one or more parsed schemas.
+///
+/// Callers must pass `settings` (use [`CodeGenSettings::builder`] and call [`CodeGenSettingsBuilder::build`]
+/// for all-default settings). Returns [`GenerateRustOutput`] with optional shared buffer and one buffer per schema.
+/// A root that is not an object with properties generates a type alias (array, map), a newtype
+/// struct (scalar) or an enum (string enum, anyOf/oneOf) instead of a struct.
+///
+/// # Errors
+///
+/// Returns [`CodeGenError::RootNotObject`] if a root schema has no shape a type can be generated for.
+/// Returns [`CodeGenError::Io`] on write failure.
+/// Returns [`CodeGenError::Batch`] with index when one schema in the batch fails.
+pub fn generate_rust(
+    schemas: &[JsonSchema],
+    settings: &CodeGenSettings,
+) -> CodeGenResult<GenerateRustOutput> {
+    RustBackend.generate(schemas, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeGenError;
+    use super::{CodeGenBackend, RustBackend, generate_rust, merge_all_of, recursive_edges};
+    use crate::code_gen::settings::{
+        CodeGenSettings, DedupeMode, ModelNameSource, NameCollisionStrategy,
+    };
+    use crate::json_schema::{JsonSchema, PropertyMap};
+
+    fn default_settings() -> CodeGenSettings {
+        CodeGenSettings::builder().build()
+    }
+
+    #[test]
+    fn root_not_object_errors() {
+        let schema: JsonSchema = JsonSchema::default();
+        let settings: CodeGenSettings = default_settings();
+        let actual = generate_rust(&[schema], &settings).unwrap_err();
+        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
+        if let CodeGenError::Batch { source, .. } = actual {
+            assert!(matches!(*source, CodeGenError::RootNotObject));
+        }
+    }
+
+    #[test]
+    fn root_object_empty_properties_errors() {
+        let schema: JsonSchema = JsonSchema {
+            type_: Some("object".to_string()),
+            ..Default::default()
+        };
+        let settings: CodeGenSettings = default_settings();
+        let actual = generate_rust(&[schema], &settings).unwrap_err();
+        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
+        if let CodeGenError::Batch { source, .. } = actual {
+            assert!(matches!(*source, CodeGenError::RootNotObject));
+        }
+    }
+
+    #[test]
+    fn root_array_emits_vec_type_alias() {
+        let json = r#"{"type":"array","items":{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct RootItem {
+    pub id: String,
+}
+
+pub type Root = Vec<RootItem>;
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn root_map_emits_btreemap_type_alias_named_from_title() {
+        let json = r#"{"title":"Prices","description":"Price per item.","type":"object","additionalProperties":{"type":"object","properties":{"amount":{"type":"number"}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct PricesValue {
+    pub amount: Option<f64>,
+}
+
+/// Price per item.
+pub type Prices = BTreeMap<String, PricesValue>;
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn root_string_enum_emits_enum_named_root() {
+        let json = r#"{"type":"string","enum":["red","green"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Root {
+    #[serde(rename = "green")]
+    Green,
+    #[serde(rename = "red")]
+    Red,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn root_scalar_emits_transparent_newtype() {
+        let json = r#"{"title":"UserId","description":"A user id.","type":"string"}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+/// A user id.
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[json_schema(title = "UserId")]
+#[serde(transparent)]
+pub struct UserId(pub String);
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn root_ref_to_array_is_named_after_def() {
+        let json = r##"{"$ref":"#/$defs/Tags","$defs":{"Tags":{"type":"array","uniqueItems":true,"items":{"type":"string","enum":["x","y"]}}}}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(actual.contains("pub enum TagsItem {"), "{actual}");
+        assert!(
+            actual.contains("pub type Tags = HashSet<TagsItem>;\n"),
+            "{actual}"
+        );
+    }
+
+    #[test]
+    fn schema_with_id_emits_id_attribute() {
+        let json = r#"{"$id":"http://example.com/schema","type":"object","properties":{"name":{"type":"string"}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "#[json_schema(id = \"http://example.com/schema\")]\n",
+            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn additional_properties_false_emits_deny_unknown_fields() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string"}},"additionalProperties":false}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "#[serde(deny_unknown_fields)]\n",
+            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deprecated_property_emits_deprecated_attr() {
+        let json =
+            r#"{"type":"object","properties":{"legacy":{"type":"string","deprecated":true}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "pub struct Root {\n",
+            "    #[deprecated]\n",
+            "    pub legacy: Option<String>,\n",
+            "}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deprecated_property_with_description_emits_deprecated_with_message() {
+        let json = r#"{"type":"object","properties":{"old":{"type":"string","deprecated":true,"description":"Use 'new' instead"}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "pub struct Root {\n",
+            "    /// Use 'new' instead\n",
+            "    #[deprecated = \"Use 'new' instead\"]\n",
+            "    pub old: Option<String>,\n",
+            "}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deprecated_false_emits_no_attr() {
+        let json = r#"{"type":"object","properties":{"x":{"type":"string","deprecated":false}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "pub struct Root {\n",
+            "    pub x: Option<String>,\n",
+            "}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn additional_properties_schema_emits_map_field() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string"}},"additionalProperties":{"type":"string"}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n",
+            "use std::collections::BTreeMap;\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "pub struct Root {\n",
+            "    pub name: Option<String>,\n",
+            "    #[serde(default)]\n",
+            "    pub additional: BTreeMap<String, String>,\n",
+            "}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn single_string_property() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string"}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = concat!(
+            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
+            "use serde::{Deserialize, Serialize};\n\n",
+            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
+            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
+        );
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn string_property_with_pattern_emits_attribute() {
+        let json =
+            r#"{"type":"object","properties":{"name":{"type":"string","pattern":"^[a-z]+$"}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    #[json_schema(pattern = "^[a-z]+$")]
+    pub name: Option<String>,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn required_field_emits_without_option() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub id: String,
+}
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn ref_to_defs_object_emits_named_struct_and_field_type() {
+        let json = r##"{
+  "$defs": {
+    "Address": {
+      "type": "object",
+      "properties": { "city": { "type": "string" } },
+      "required": ["city"]
+    }
+  },
+  "type": "object",
+  "properties": { "address": { "$ref": "#/$defs/Address" } },
+  "required": ["address"]
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address {
+    pub city: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub address: Address,
+}
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn ref_to_missing_defs_returns_ref_resolution_error() {
+        let json = r##"{
+  "type": "object",
+  "properties": { "x": { "$ref": "#/$defs/Missing" } }
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let actual: super::CodeGenResult<super::GenerateRustOutput> =
+            generate_rust(&[schema], &settings);
+        assert!(
+            matches!(
+                actual,
+                Err(CodeGenError::RefResolution { ref ref_str, ref reason })
+                if ref_str == "#/$defs/Missing" && reason.contains("DefsMissing")
+            ),
+            "expected RefResolution with DefsMissing, got: {actual:?}"
+        );
+    }
+
+    #[test]
+    fn ref_cycle_in_defs_returns_ref_resolution_error() {
+        let json = r##"{
+  "$defs": {
+    "A": { "$ref": "#/$defs/B" },
+    "B": { "$ref": "#/$defs/A" }
+  },
+  "type": "object",
+  "properties": { "x": { "$ref": "#/$defs/A" } }
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let actual: super::CodeGenResult<super::GenerateRustOutput> =
+            generate_rust(&[schema], &settings);
+        assert!(
+            matches!(
+                actual,
+                Err(CodeGenError::RefResolution { ref ref_str, ref reason })
+                if ref_str == "#/$defs/A" && reason.contains("RefCycle")
+            ),
+            "expected RefResolution with RefCycle, got: {actual:?}"
+        );
+    }
+
+    #[test]
+    fn self_ref_to_root_is_boxed() {
+        let json = r##"{
+  "type": "object",
+  "title": "Node",
+  "properties": {
+    "child": { "$ref": "#" },
+    "value": { "type": "integer" }
+  },
+  "required": ["value"]
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[json_schema(title = "Node")]
+pub struct Node {
+    pub child: Option<Box<Node>>,
+    pub value: i64,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn linked_list_in_defs_boxes_next_and_keeps_vec_children_unboxed() {
+        let json = r##"{
+  "$defs": {
+    "Node": {
+      "type": "object",
+      "properties": {
+        "next": { "$ref": "#/$defs/Node" },
+        "value": { "type": "string" }
+      },
+      "required": ["value"]
+    },
+    "Tree": {
+      "type": "object",
+      "properties": {
+        "children": { "type": "array", "items": { "$ref": "#/$defs/Tree" } }
+      }
+    }
+  },
+  "type": "object",
+  "properties": {
+    "head": { "$ref": "#/$defs/Node" },
+    "tree": { "$ref": "#/$defs/Tree" }
+  },
+  "required": ["head"]
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Node {
+    pub next: Option<Box<Node>>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Tree {
+    pub children: Option<Vec<Tree>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub head: Node,
+    pub tree: Option<Tree>,
+}
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn mutual_recursion_boxes_every_edge_in_the_cycle() {
+        let json = r##"{
+  "$defs": {
+    "A": {
+      "type": "object",
+      "properties": { "b": { "$ref": "#/$defs/B" } },
+      "required": ["b"]
+    },
+    "B": {
+      "type": "object",
+      "properties": {
+        "a": { "$ref": "#/$defs/A" },
+        "either": { "anyOf": [{ "$ref": "#/$defs/A" }, { "type": "string" }] }
+      }
+    }
+  },
+  "type": "object",
+  "properties": { "a": { "$ref": "#/$defs/A" } }
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(untagged)]
+pub enum EitherAnyOf {
+    Variant0(Box<A>),
+    Variant1(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct B {
+    pub a: Option<Box<A>>,
+    pub either: Option<EitherAnyOf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct A {
+    pub b: Box<B>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub a: Option<A>,
+}
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn recursive_edges_skips_acyclic_and_enum_targets() {
+        use std::collections::{BTreeMap, BTreeSet};
+        let deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::from([
+            (
+                "A".to_string(),
+                BTreeSet::from(["B".to_string(), "Leaf".to_string()]),
+            ),
+            ("B".to_string(), BTreeSet::from(["UAnyOf".to_string()])),
+            ("UAnyOf".to_string(), BTreeSet::from(["A".to_string()])),
+            ("Leaf".to_string(), BTreeSet::new()),
+        ]);
+        let struct_names: BTreeSet<String> =
+            BTreeSet::from(["A".to_string(), "B".to_string(), "Leaf".to_string()]);
+        let expected: BTreeMap<String, BTreeSet<String>> = BTreeMap::from([
+            ("A".to_string(), BTreeSet::from(["B".to_string()])),
+            ("UAnyOf".to_string(), BTreeSet::from(["A".to_string()])),
+        ]);
+        let actual: BTreeMap<String, BTreeSet<String>> = recursive_edges(&deps, &struct_names);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn shared_recursive_struct_is_boxed_in_dedupe_mode() {
+        let json = r##"{
+  "$defs": {
+    "Node": {
+      "type": "object",
+      "properties": { "next": { "$ref": "#/$defs/Node" } }
+    }
+  },
+  "type": "object",
+  "properties": { "head": { "$ref": "#/$defs/Node" } }
+}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput =
+            generate_rust(&[schema.clone(), schema], &settings).unwrap();
+        let actual: bool = String::from_utf8(output.shared.unwrap())
+            .unwrap()
+            .contains("    pub next: Option<Box<Node>>,\n");
+        assert!(actual);
+    }
+
+    #[test]
+    fn distinct_enums_with_same_key_get_numeric_suffix() {
+        let json = r#"{"type":"object","properties":{"shipment":{"type":"object","properties":{"status":{"enum":["pending","shipped"]}}},"status":{"enum":["open","closed"]}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Status {
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "open")]
+    Open,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Status2 {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "shipped")]
+    Shipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Shipment {
+    pub status: Option<Status2>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub shipment: Option<Shipment>,
+    pub status: Option<Status>,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn distinct_structs_with_same_key_are_both_emitted_and_referenced() {
+        let json = r#"{"type":"object","properties":{"billing":{"type":"object","properties":{"address":{"type":"object","properties":{"zip":{"type":"string"}}}}},"shipping":{"type":"object","properties":{"address":{"type":"object","properties":{"city":{"type":"string"}}}}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address {
+    pub zip: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Billing {
+    pub address: Option<Address>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address2 {
+    pub city: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Shipping {
+    pub address: Option<Address2>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub billing: Option<Billing>,
+    pub shipping: Option<Shipping>,
+}
+
+";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parent_prefix_strategy_prefixes_colliding_names() {
+        let json = r#"{"type":"object","properties":{"payment":{"anyOf":[{"type":"string"},{"type":"integer"}]},"shipment":{"type":"object","properties":{"payment":{"anyOf":[{"type":"boolean"},{"type":"integer"}]},"status":{"enum":["pending","shipped"]}}},"status":{"enum":["open","closed"]}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .name_collision(NameCollisionStrategy::ParentPrefix)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub enum") || line.contains(": Option<"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub enum Status {",
+            "pub enum ShipmentStatus {",
+            "pub enum PaymentAnyOf {",
+            "pub enum ShipmentPaymentAnyOf {",
+            "    pub payment: Option<ShipmentPaymentAnyOf>,",
+            "    pub status: Option<ShipmentStatus>,",
+            "    pub payment: Option<PaymentAnyOf>,",
+            "    pub shipment: Option<Shipment>,",
+            "    pub status: Option<Status>,",
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn title_pinned_collision_errors() {
+        let json = r#"{"type":"object","properties":{"a":{"type":"object","title":"Item","properties":{"x":{"type":"string"}}},"b":{"type":"object","title":"Item","properties":{"y":{"type":"string"}}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let actual: CodeGenError = generate_rust(&[schema], &settings).unwrap_err();
+        let expected: CodeGenError = CodeGenError::Batch {
+            index: 0,
+            source: Box::new(CodeGenError::TypeNameCollision {
+                name: "Item".to_string(),
+            }),
+        };
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn title_collision_with_property_key_name_renames_the_key_derived_type() {
+        let json = r#"{"type":"object","properties":{"a":{"type":"object","title":"Item","properties":{"x":{"type":"string"}}},"item":{"type":"object","properties":{"y":{"type":"string"}}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub struct") || line.contains(": Option<"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub struct Item {",
+            "    pub x: Option<String>,",
+            "pub struct Item2 {",
+            "    pub y: Option<String>,",
+            "pub struct Root {",
+            "    pub a: Option<Item>,",
+            "    pub item: Option<Item2>,",
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn distinct_enums_in_shared_buffer_get_distinct_names() {
+        let a = r#"{"type":"object","title":"A","properties":{"status":{"enum":["A","B"]},"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let b = r#"{"type":"object","title":"B","properties":{"status":{"enum":["A","C"]},"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let schemas: Vec<JsonSchema> = vec![
+            serde_json::from_str(a).unwrap(),
+            serde_json::from_str(b).unwrap(),
+        ];
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&schemas, &settings).unwrap();
+        let shared = String::from_utf8(output.shared.unwrap()).unwrap();
+        let actual: Vec<&str> = shared
+            .lines()
+            .filter(|line| line.starts_with("pub "))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub enum Status {",
+            "pub enum Status2 {",
+            "pub struct Meta {",
+        ];
+        assert_eq!(expected, actual);
+        let actual: bool = String::from_utf8(output.per_schema[1].clone())
+            .unwrap()
+            .contains("pub use crate::Status2;\n");
+        assert!(actual);
+    }
+
+    #[test]
+    fn title_pinned_enum_collision_in_shared_buffer_errors() {
+        let a = r#"{"type":"object","title":"A","properties":{"status":{"title":"Status","enum":["A","B"]},"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let b = r#"{"type":"object","title":"B","properties":{"state":{"title":"Status","enum":["A","C"]},"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let schemas: Vec<JsonSchema> = vec![
+            serde_json::from_str(a).unwrap(),
+            serde_json::from_str(b).unwrap(),
+        ];
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let actual: CodeGenError = generate_rust(&schemas, &settings).unwrap_err();
+        let expected: CodeGenError = CodeGenError::TypeNameCollision {
+            name: "Status".to_string(),
+        };
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn local_struct_does_not_shadow_imported_shared_name() {
+        let a = r#"{"type":"object","title":"A","properties":{"inner":{"type":"object","properties":{"meta":{"type":"object","properties":{"other":{"type":"string"}}}}},"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let b = r#"{"type":"object","title":"B","properties":{"meta":{"type":"object","properties":{"id":{"type":"string"}}}}}"#;
+        let schemas: Vec<JsonSchema> = vec![
+            serde_json::from_str(a).unwrap(),
+            serde_json::from_str(b).unwrap(),
+        ];
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&schemas, &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub ") || line.contains(": Option<"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub use crate::Meta;",
+            "pub struct Meta2 {",
+            "    pub other: Option<String>,",
+            "pub struct Inner {",
+            "    pub meta: Option<Meta2>,",
+            "pub struct A {",
+            "    pub inner: Option<Inner>,",
+            "    pub meta: Option<Meta>,",
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn required_enum_property_emits_enum_and_struct() {
+        let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"]}},"required":["status"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Status {
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "open")]
+    Open,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub status: Status,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn const_string_property_emits_single_variant_enum() {
+        let json = r#"{"type":"object","properties":{"key":{"const":"only"}},"required":["key"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected: &str = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Key {
+    #[serde(rename = "only")]
+    Only,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub key: Key,
+}
+
+"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn all_of_merge_same_const_ok() {
+        let s1: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","const":"same"}}}"#,
+        )
+        .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","const":"same"}}}"#,
+        )
+        .unwrap();
+        let actual: Result<JsonSchema, _> = merge_all_of(&[s1, s2]);
+        assert!(actual.is_ok());
+        let merged: JsonSchema = actual.unwrap();
+        let x_schema: &JsonSchema = merged.properties.get("x").expect("property x");
+        assert_eq!(
+            x_schema.const_value.as_ref(),
+            Some(&serde_json::Value::String("same".to_string()))
+        );
     }
-    if let Some(AdditionalProperties::Schema(sub)) = &schema.additional_properties {
-        let value_ty: String = rust_type_for_item_schema(
-            root,
-            sub,
-            Some("additional"),
-            enum_values_to_name,
-            None,
-            settings,
-            settings.dedupe_mode,
-        )?;
-        writeln!(out, "    #[serde(default)]")?;
-        writeln!(out, "    pub additional: BTreeMap<String, {value_ty}>,")?;
+
+    #[test]
+    fn all_of_merge_conflicting_const_err() {
+        let s1: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","const":"a"}}}"#,
+        )
+        .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","const":"b"}}}"#,
+        )
+        .unwrap();
+        let actual: Result<JsonSchema, CodeGenError> = merge_all_of(&[s1, s2]);
+        let err = actual.expect_err("expected AllOfMergeConflictingConst");
+        assert!(matches!(
+            err,
+            CodeGenError::AllOfMergeConflictingConst { .. }
+        ));
     }
-    Ok(())
-}
 
-/// Emit Rust source from a parsed schema to `out`. Used by [`RustBackend::generate`].
-fn emit_rust(
-    schema: &JsonSchema,
-    out: &mut impl Write,
-    settings: &CodeGenSettings,
-) -> CodeGenResult<()> {
-    let root_unresolved = resolve_all_of_for_codegen(schema)?;
-    let (root, root_from_key) = resolve_ref_for_codegen(schema, &root_unresolved, None)?;
-    if root.any_of.as_ref().is_some_and(std::vec::Vec::is_empty) {
-        return Err(CodeGenError::AnyOfEmpty);
+    #[test]
+    fn all_of_merge_same_pattern_ok() {
+        let s1: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
+        )
+        .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
+        )
+        .unwrap();
+        let merged: JsonSchema = merge_all_of(&[s1, s2]).expect("merge ok");
+        let actual: JsonSchema = merged.properties.get("x").cloned().expect("property x");
+        let expected: JsonSchema = JsonSchema {
+            type_: Some("string".to_string()),
+            pattern: Some("^[a-z]+$".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(expected, actual);
     }
-    if root.one_of.as_ref().is_some_and(std::vec::Vec::is_empty) {
-        return Err(CodeGenError::OneOfEmpty);
+
+    #[test]
+    fn all_of_merge_conflicting_pattern_err() {
+        let s1: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
+        )
+        .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[0-9]+$"}}}"#,
+        )
+        .unwrap();
+        let actual: Result<JsonSchema, CodeGenError> = merge_all_of(&[s1, s2]);
+        assert!(matches!(
+            actual,
+            Err(CodeGenError::AllOfMergeConflictingPattern { .. })
+        ));
     }
-    let roots_for_structs: Vec<JsonSchema> = if root.one_of.as_ref().is_some_and(|v| !v.is_empty())
-    {
-        root.one_of
-            .as_ref()
-            .unwrap()
-            .iter()
-            .map(resolve_all_of_for_codegen)
-            .collect::<CodeGenResult<Vec<_>>>()?
-    } else if root.any_of.as_ref().is_some_and(|v| !v.is_empty()) {
-        root.any_of
-            .as_ref()
-            .unwrap()
-            .iter()
-            .map(resolve_all_of_for_codegen)
-            .collect::<CodeGenResult<Vec<_>>>()?
-    } else {
-        if !root.is_object_with_properties() {
-            return Err(CodeGenError::RootNotObject);
-        }
-        vec![root.clone()]
-    };
 
-    let enums: Vec<EnumToEmit> = collect_enums(schema, &root, settings)?;
-    let enum_values_to_name: BTreeMap<Vec<String>, String> = enums
-        .iter()
-        .map(|e| (e.values.clone(), e.name.clone()))
-        .collect();
+    #[test]
+    fn all_of_merged_object_golden() {
+        let json = r#"{"allOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}},"required":["b"]}]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
-    let anyof_enums: Vec<AnyOfEnumToEmit> =
-        collect_anyof_enums(schema, &root, settings, &enum_values_to_name)?;
-    let oneof_enums: Vec<OneOfEnumToEmit> =
-        collect_oneof_enums(schema, &root, settings, &enum_values_to_name)?;
-
-    let mut structs: Vec<StructToEmit> = Vec::new();
-    let mut seen: BTreeSet<String> = BTreeSet::new();
-    let root_is_anyof = root.any_of.as_ref().is_some_and(|v| !v.is_empty());
-    let root_is_oneof = root.one_of.as_ref().is_some_and(|v| !v.is_empty());
-    for (i, r) in roots_for_structs.iter().enumerate() {
-        let from_key: Option<String> = if root_is_anyof || root_is_oneof {
-            Some(format!("Root_Variant{i}"))
-        } else {
-            root_from_key.clone()
-        };
-        collect_structs(
-            schema,
-            r,
-            from_key.as_deref(),
-            &mut structs,
-            &mut seen,
-            settings,
-        )?;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub a: Option<String>,
+    pub b: i64,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
-    writeln!(
-        out,
-        "//! Generated by json-schema-rs. Do not edit manually."
-    )?;
-    writeln!(out)?;
-    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
-    writeln!(out)?;
+    #[test]
+    fn anyof_property_golden() {
+        let json = r#"{"type":"object","properties":{"foo":{"anyOf":[{"type":"string"},{"type":"object","properties":{"x":{"type":"integer"}}}]}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
-    for e in &enums {
-        let pairs: Vec<(String, String)> = enum_variant_names_with_collision_resolution(&e.values);
-        emit_enum_from_pairs(
-            out,
-            &e.name,
-            &pairs,
-            e.description.as_deref(),
-            e.examples.as_deref(),
-        )?;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(untagged)]
+pub enum FooAnyOf {
+    Variant1(FooVariant1),
+    Variant0(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct FooVariant1 {
+    pub x: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub foo: Option<FooAnyOf>,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
-    for a in &anyof_enums {
-        emit_anyof_enum(out, a)?;
+    #[test]
+    fn anyof_root_golden() {
+        let json = r#"{"anyOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(untagged)]
+pub enum RootAnyOf {
+    Variant0(RootVariant0),
+    Variant1(RootVariant1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct RootVariant0 {
+    pub a: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct RootVariant1 {
+    pub b: Option<i64>,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
-    for o in &oneof_enums {
-        emit_oneof_enum(out, o)?;
+    #[test]
+    fn anyof_empty_errors() {
+        let schema: JsonSchema = JsonSchema {
+            any_of: Some(vec![]),
+            ..Default::default()
+        };
+        let settings = default_settings();
+        let actual = generate_rust(&[schema], &settings).unwrap_err();
+        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
+        if let CodeGenError::Batch { source, .. } = actual {
+            assert!(matches!(*source, CodeGenError::AnyOfEmpty));
+        }
     }
 
-    for st in &structs {
-        emit_default_functions_for_struct(out, &st.name, &st.schema)?;
-        emit_struct_derive_and_attrs(out, &st.name, &st.schema)?;
-        emit_struct_fields(
-            schema,
-            &st.name,
-            &st.schema,
-            out,
-            settings,
-            Some(&enum_values_to_name),
-            Some(&anyof_enums),
-            Some(&oneof_enums),
-        )?;
-        writeln!(out, "}}")?;
-        writeln!(out)?;
+    #[test]
+    fn union_variants_ordered_narrowest_first() {
+        let json = r#"{"type":"object","properties":{"v":{"anyOf":[{"type":"null"},{"type":"string"},{"type":"number"},{"type":"array","items":{"type":"string"}},{"type":"integer"},{"type":"object","properties":{"a":{"type":"string"}},"required":["a"]},{"type":"object","properties":{"a":{"type":"string"},"b":{"type":"string"}},"required":["a","b"]}]}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("    Variant"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "    Variant6(VVariant6),",
+            "    Variant5(VVariant5),",
+            "    Variant4(i64),",
+            "    Variant3(Vec<String>),",
+            "    Variant1(String),",
+            "    Variant2(f64),",
+            "    Variant0(serde_json::Value),",
+        ];
+        assert_eq!(expected, actual);
     }
 
-    Ok(())
-}
+    #[test]
+    fn oneof_property_golden() {
+        let json = r#"{"type":"object","properties":{"foo":{"oneOf":[{"type":"string"},{"type":"object","properties":{"x":{"type":"integer"}}}]}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
-/// Generate Rust source from one or more parsed schemas.
-///
-/// Callers must pass `settings` (use [`CodeGenSettings::builder`] and call [`CodeGenSettingsBuilder::build`]
-/// for all-default settings). Returns [`GenerateRustOutput`] with optional shared buffer and one buffer per schema.
-/// Root of each schema must have `type: "object"` and non-empty `properties`.
-///
-/// # Errors
-///
-/// Returns [`CodeGenError::RootNotObject`] if a root schema is not an object with properties.
-/// Returns [`CodeGenError::Io`] on write failure.
-/// Returns [`CodeGenError::Batch`] with index when one schema in the batch fails.
-pub fn generate_rust(
-    schemas: &[JsonSchema],
-    settings: &CodeGenSettings,
-) -> CodeGenResult<GenerateRustOutput> {
-    RustBackend.generate(schemas, settings)
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(untagged)]
+#[json_schema(one_of)]
+pub enum FooOneOf {
+    Variant1(FooVariant1),
+    Variant0(String),
 }
 
-#[cfg(test)]
-mod tests {
-    use super::CodeGenError;
-    use super::{CodeGenBackend, RustBackend, generate_rust, merge_all_of};
-    use crate::code_gen::settings::{CodeGenSettings, DedupeMode, ModelNameSource};
-    use crate::json_schema::JsonSchema;
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct FooVariant1 {
+    pub x: Option<i64>,
+}
 
-    fn default_settings() -> CodeGenSettings {
-        CodeGenSettings::builder().build()
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub foo: Option<FooOneOf>,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn root_not_object_errors() {
-        let schema: JsonSchema = JsonSchema::default();
-        let settings: CodeGenSettings = default_settings();
-        let actual = generate_rust(&[schema], &settings).unwrap_err();
-        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
-        if let CodeGenError::Batch { source, .. } = actual {
-            assert!(matches!(*source, CodeGenError::RootNotObject));
-        }
+    fn oneof_root_golden() {
+        let json = r#"{"oneOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(untagged)]
+#[json_schema(one_of)]
+pub enum RootOneOf {
+    Variant0(RootVariant0),
+    Variant1(RootVariant1),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct RootVariant0 {
+    pub a: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct RootVariant1 {
+    pub b: Option<i64>,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn root_object_empty_properties_errors() {
+    fn oneof_empty_errors() {
         let schema: JsonSchema = JsonSchema {
-            type_: Some("object".to_string()),
+            one_of: Some(vec![]),
             ..Default::default()
         };
-        let settings: CodeGenSettings = default_settings();
+        let settings = default_settings();
         let actual = generate_rust(&[schema], &settings).unwrap_err();
         assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
         if let CodeGenError::Batch { source, .. } = actual {
-            assert!(matches!(*source, CodeGenError::RootNotObject));
+            assert!(matches!(*source, CodeGenError::OneOfEmpty));
         }
     }
 
     #[test]
-    fn schema_with_id_emits_id_attribute() {
-        let json = r#"{"$id":"http://example.com/schema","type":"object","properties":{"name":{"type":"string"}}}"#;
+    fn oneof_const_discriminator_golden() {
+        let json = r#"{"type":"object","properties":{"event":{"oneOf":[{"type":"object","properties":{"type":{"const":"created"},"id":{"type":"string"}},"required":["type","id"]},{"type":"object","properties":{"type":{"type":"string","enum":["deleted"]}}}]}},"required":["event"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "#[json_schema(id = \"http://example.com/schema\")]\n",
-            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
-        );
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[serde(tag = "type")]
+pub enum EventOneOf {
+    #[serde(rename = "created")]
+    Created {
+        id: String,
+    },
+    #[serde(rename = "deleted")]
+    Deleted {},
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub event: EventOneOf,
+}
+
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn additional_properties_false_emits_deny_unknown_fields() {
-        let json = r#"{"type":"object","properties":{"name":{"type":"string"}},"additionalProperties":false}"#;
+    fn oneof_discriminator_keyword_uses_mapping_and_ref_names() {
+        let json = r##"{"type":"object","properties":{"pet":{"oneOf":[{"$ref":"#/$defs/Cat"},{"$ref":"#/$defs/Dog"}],"discriminator":{"propertyName":"kind","mapping":{"kitty":"#/$defs/Cat"}}}},"$defs":{"Cat":{"type":"object","properties":{"kind":{"type":"string"},"lives":{"type":"integer"}}},"Dog":{"type":"object","properties":{"kind":{"type":"string"},"good":{"type":"boolean"}}}}}"##;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "#[serde(deny_unknown_fields)]\n",
-            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
-        );
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let start: usize = out.find("#[serde(tag").unwrap();
+        let end: usize = start + out[start..].find("\n}\n").unwrap();
+        let actual: &str = &out[start..end];
+        let expected = r#"#[serde(tag = "kind")]
+pub enum PetOneOf {
+    #[serde(rename = "kitty")]
+    Kitty {
+        lives: Option<i64>,
+    },
+    Dog {
+        good: Option<bool>,
+    },"#;
         assert_eq!(expected, actual);
+        assert!(!out.contains("pub struct Cat"), "{out}");
     }
 
     #[test]
-    fn deprecated_property_emits_deprecated_attr() {
-        let json =
-            r#"{"type":"object","properties":{"legacy":{"type":"string","deprecated":true}}}"#;
+    fn oneof_repeated_const_stays_untagged() {
+        let json = r#"{"type":"object","properties":{"v":{"oneOf":[{"type":"object","properties":{"type":{"const":"a"},"x":{"type":"string"}}},{"type":"object","properties":{"type":{"const":"a"},"y":{"type":"integer"}}}]}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "pub struct Root {\n",
-            "    #[deprecated]\n",
-            "    pub legacy: Option<String>,\n",
-            "}\n\n"
-        );
-        assert_eq!(expected, actual);
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(out.contains("#[serde(untagged)]"), "{out}");
+        assert!(!out.contains("#[serde(tag"), "{out}");
     }
 
     #[test]
-    fn deprecated_property_with_description_emits_deprecated_with_message() {
-        let json = r#"{"type":"object","properties":{"old":{"type":"string","deprecated":true,"description":"Use 'new' instead"}}}"#;
+    fn tagged_union_variant_boxes_recursive_field() {
+        let json = r##"{"type":"object","title":"Node","properties":{"child":{"oneOf":[{"type":"object","properties":{"kind":{"const":"leaf"}}},{"type":"object","properties":{"kind":{"const":"branch"},"node":{"$ref":"#"}},"required":["kind","node"]}]}}}"##;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "pub struct Root {\n",
-            "    /// Use 'new' instead\n",
-            "    #[deprecated = \"Use 'new' instead\"]\n",
-            "    pub old: Option<String>,\n",
-            "}\n\n"
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            out.contains("    Branch {\n        node: Box<Node>,\n    },"),
+            "{out}"
         );
-        assert_eq!(expected, actual);
+        assert!(out.contains("    pub child: Option<ChildOneOf>,"), "{out}");
     }
 
     #[test]
-    fn deprecated_false_emits_no_attr() {
-        let json = r#"{"type":"object","properties":{"x":{"type":"string","deprecated":false}}}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "pub struct Root {\n",
-            "    pub x: Option<String>,\n",
-            "}\n\n"
-        );
+    fn merge_all_of_success_two_object_subschemas() {
+        let s1: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
+                .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"b":{"type":"integer"}},"required":["b"]}"#,
+        )
+        .unwrap();
+        let actual = merge_all_of(&[s1, s2]).unwrap();
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"type":"string"},"b":{"type":"integer"}},"required":["b"]}"#,
+        )
+        .unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn additional_properties_schema_emits_map_field() {
-        let json = r#"{"type":"object","properties":{"name":{"type":"string"}},"additionalProperties":{"type":"string"}}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n",
-            "use std::collections::BTreeMap;\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "pub struct Root {\n",
-            "    pub name: Option<String>,\n",
-            "    #[serde(default)]\n",
-            "    pub additional: BTreeMap<String, String>,\n",
-            "}\n\n"
-        );
+    fn merge_all_of_empty_errors() {
+        let actual = merge_all_of(&[]);
+        assert!(matches!(actual, Err(CodeGenError::AllOfMergeEmpty)));
+    }
+
+    #[test]
+    fn merge_all_of_single_schema() {
+        let s: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"x":{"type":"string"}}}"#)
+                .unwrap();
+        let expected = s.clone();
+        let actual = merge_all_of(std::slice::from_ref(&s)).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn single_string_property() {
-        let json = r#"{"type":"object","properties":{"name":{"type":"string"}}}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = concat!(
-            "//! Generated by json-schema-rs. Do not edit manually.\n\n",
-            "use serde::{Deserialize, Serialize};\n\n",
-            "#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n",
-            "pub struct Root {\n    pub name: Option<String>,\n}\n\n"
+    fn merge_all_of_conflicting_property_type_errors() {
+        let s1: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"k":{"type":"string"}}}"#)
+                .unwrap();
+        let s2: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"k":{"type":"integer"}}}"#)
+                .unwrap();
+        let actual = merge_all_of(&[s1, s2]);
+        assert!(matches!(
+            actual,
+            Err(CodeGenError::AllOfMergeConflictingPropertyType {
+                property_key: ref k,
+                ..
+            }) if k == "k"
+        ));
+    }
+
+    #[test]
+    fn merge_all_of_non_object_subschema_errors() {
+        let s1: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
+                .unwrap();
+        let s2: JsonSchema = serde_json::from_str(r#"{"type":"string"}"#).unwrap();
+        let actual = merge_all_of(&[s1, s2]);
+        assert!(matches!(
+            actual,
+            Err(CodeGenError::AllOfMergeNonObjectSubschema { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn merge_all_of_conflicting_enum_errors() {
+        let s1: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"s":{"enum":["a","b"]}}}"#)
+                .unwrap();
+        let s2: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"s":{"enum":["x","y"]}}}"#)
+                .unwrap();
+        let actual = merge_all_of(&[s1, s2]);
+        assert!(matches!(
+            actual,
+            Err(CodeGenError::AllOfMergeConflictingEnum { property_key: ref k }) if k == "s"
+        ));
+    }
+
+    #[test]
+    fn merge_all_of_conflicting_numeric_bounds_errors() {
+        let s1: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"n":{"type":"integer","minimum":0,"maximum":10}}}"#,
+        )
+        .unwrap();
+        let s2: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"n":{"type":"integer","minimum":20,"maximum":30}}}"#,
+        )
+        .unwrap();
+        let actual = merge_all_of(&[s1, s2]);
+        assert!(matches!(
+            actual,
+            Err(CodeGenError::AllOfMergeConflictingNumericBounds {
+                property_key: ref k,
+                keyword: ref w
+            }) if k == "n" && w == "minimum/maximum"
+        ));
+    }
+
+    #[test]
+    fn batch_error_when_allof_merge_fails_in_second_schema() {
+        let s0: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
+                .unwrap();
+        let s1_bad: JsonSchema = serde_json::from_str(
+            r#"{"allOf":[{"type":"object","properties":{"x":{"type":"string"}}},{"type":"object","properties":{"x":{"type":"integer"}}}]}"#,
+        )
+        .unwrap();
+        let settings = default_settings();
+        let actual = generate_rust(&[s0.clone(), s1_bad], &settings).unwrap_err();
+        assert!(matches!(actual, CodeGenError::Batch { index: 1, .. }));
+    }
+
+    #[test]
+    fn root_all_of_merges_to_empty_object_errors_with_root_not_object() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"allOf":[{"type":"object"},{"type":"object"}]}"#).unwrap();
+        let settings = default_settings();
+        let actual = generate_rust(&[schema], &settings).unwrap_err();
+        assert!(
+            matches!(actual, CodeGenError::Batch { index: 0, source: ref s } if matches!(**s, CodeGenError::RootNotObject)),
+            "expected Batch {{ index: 0, source: RootNotObject }}, got {actual:?}"
         );
-        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn string_property_with_pattern_emits_attribute() {
-        let json =
-            r#"{"type":"object","properties":{"name":{"type":"string","pattern":"^[a-z]+$"}}}"#;
+    fn optional_enum_property_emits_enum_and_struct() {
+        let json = r#"{"type":"object","properties":{"level":{"enum":["low","medium","high"]}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Level {
+    #[serde(rename = "high")]
+    High,
+    #[serde(rename = "low")]
+    Low,
+    #[serde(rename = "medium")]
+    Medium,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    #[json_schema(pattern = "^[a-z]+$")]
-    pub name: Option<String>,
+    pub level: Option<Level>,
 }
 
 "#;
@@ -2712,108 +6002,65 @@ pub struct Root {
     }
 
     #[test]
-    fn required_field_emits_without_option() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
+    fn enum_dedupe_two_properties_same_enum_emits_one_enum() {
+        let json = r#"{"type":"object","properties":{"a":{"enum":["x","y"]},"b":{"enum":["x","y"]}},"required":["a"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum A {
+    #[serde(rename = "x")]
+    X,
+    #[serde(rename = "y")]
+    Y,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub id: String,
+    pub a: A,
+    pub b: Option<A>,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn ref_to_defs_object_emits_named_struct_and_field_type() {
-        let json = r##"{
-  "$defs": {
-    "Address": {
-      "type": "object",
-      "properties": { "city": { "type": "string" } },
-      "required": ["city"]
-    }
-  },
-  "type": "object",
-  "properties": { "address": { "$ref": "#/$defs/Address" } },
-  "required": ["address"]
-}"##;
+    fn enum_collision_emits_suffixed_variants() {
+        let json = r#"{"type":"object","properties":{"t":{"enum":["a","A"]}},"required":["t"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Address {
-    pub city: String,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum T {
+    #[serde(rename = "A")]
+    A0,
+    #[serde(rename = "a")]
+    A1,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub address: Address,
+    pub t: T,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn ref_to_missing_defs_returns_ref_resolution_error() {
-        let json = r##"{
-  "type": "object",
-  "properties": { "x": { "$ref": "#/$defs/Missing" } }
-}"##;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let actual: super::CodeGenResult<super::GenerateRustOutput> =
-            generate_rust(&[schema], &settings);
-        assert!(
-            matches!(
-                actual,
-                Err(CodeGenError::RefResolution { ref ref_str, ref reason })
-                if ref_str == "#/$defs/Missing" && reason.contains("DefsMissing")
-            ),
-            "expected RefResolution with DefsMissing, got: {actual:?}"
-        );
-    }
-
-    #[test]
-    fn ref_cycle_in_defs_returns_ref_resolution_error() {
-        let json = r##"{
-  "$defs": {
-    "A": { "$ref": "#/$defs/B" },
-    "B": { "$ref": "#/$defs/A" }
-  },
-  "type": "object",
-  "properties": { "x": { "$ref": "#/$defs/A" } }
-}"##;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let actual: super::CodeGenResult<super::GenerateRustOutput> =
-            generate_rust(&[schema], &settings);
-        assert!(
-            matches!(
-                actual,
-                Err(CodeGenError::RefResolution { ref ref_str, ref reason })
-                if ref_str == "#/$defs/A" && reason.contains("RefCycle")
-            ),
-            "expected RefResolution with RefCycle, got: {actual:?}"
-        );
-    }
-
-    #[test]
-    fn required_enum_property_emits_enum_and_struct() {
-        let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"]}},"required":["status"]}"#;
+    fn enum_duplicate_values_in_schema_emits_single_variant_per_value() {
+        let json = r#"{"type":"object","properties":{"t":{"enum":["A","A","A","a","a","a","a","a","a","a"]}},"required":["t"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -2823,16 +6070,16 @@ pub struct Root {
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum Status {
-    #[serde(rename = "closed")]
-    Closed,
-    #[serde(rename = "open")]
-    Open,
+pub enum T {
+    #[serde(rename = "A")]
+    A0,
+    #[serde(rename = "a")]
+    A1,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub status: Status,
+    pub t: T,
 }
 
 "#;
@@ -2840,25 +6087,27 @@ pub struct Root {
     }
 
     #[test]
-    fn const_string_property_emits_single_variant_enum() {
-        let json = r#"{"type":"object","properties":{"key":{"const":"only"}},"required":["key"]}"#;
+    fn enum_non_rust_compliant_values_emits_valid_variants() {
+        let json = r#"{"type":"object","properties":{"id":{"enum":["/8633","todd.griffin"]}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected: &str = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum Key {
-    #[serde(rename = "only")]
-    Only,
+pub enum Id {
+    #[serde(rename = "/8633")]
+    E8633,
+    #[serde(rename = "todd.griffin")]
+    ToddGriffin,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub key: Key,
+    pub id: Id,
 }
 
 "#;
@@ -2866,157 +6115,71 @@ pub struct Root {
     }
 
     #[test]
-    fn all_of_merge_same_const_ok() {
-        let s1: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","const":"same"}}}"#,
-        )
-        .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","const":"same"}}}"#,
-        )
-        .unwrap();
-        let actual: Result<JsonSchema, _> = merge_all_of(&[s1, s2]);
-        assert!(actual.is_ok());
-        let merged: JsonSchema = actual.unwrap();
-        let x_schema: &JsonSchema = merged.properties.get("x").expect("property x");
-        assert_eq!(
-            x_schema.const_value.as_ref(),
-            Some(&serde_json::Value::String("same".to_string()))
-        );
-    }
-
-    #[test]
-    fn all_of_merge_conflicting_const_err() {
-        let s1: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","const":"a"}}}"#,
-        )
-        .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","const":"b"}}}"#,
-        )
-        .unwrap();
-        let actual: Result<JsonSchema, CodeGenError> = merge_all_of(&[s1, s2]);
-        let err = actual.expect_err("expected AllOfMergeConflictingConst");
-        assert!(matches!(
-            err,
-            CodeGenError::AllOfMergeConflictingConst { .. }
-        ));
-    }
-
-    #[test]
-    fn all_of_merge_same_pattern_ok() {
-        let s1: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
-        )
-        .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
-        )
-        .unwrap();
-        let merged: JsonSchema = merge_all_of(&[s1, s2]).expect("merge ok");
-        let actual: JsonSchema = merged.properties.get("x").cloned().expect("property x");
-        let expected: JsonSchema = JsonSchema {
-            type_: Some("string".to_string()),
-            pattern: Some("^[a-z]+$".to_string()),
-            ..Default::default()
-        };
-        assert_eq!(expected, actual);
-    }
-
-    #[test]
-    fn all_of_merge_conflicting_pattern_err() {
-        let s1: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[a-z]+$"}}}"#,
-        )
-        .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"x":{"type":"string","pattern":"^[0-9]+$"}}}"#,
-        )
-        .unwrap();
-        let actual: Result<JsonSchema, CodeGenError> = merge_all_of(&[s1, s2]);
-        assert!(matches!(
-            actual,
-            Err(CodeGenError::AllOfMergeConflictingPattern { .. })
-        ));
-    }
-
-    #[test]
-    fn all_of_merged_object_golden() {
-        let json = r#"{"allOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}},"required":["b"]}]}"#;
+    fn camel_case_property_emits_snake_case_field() {
+        let json = r#"{"type":"object","properties":{"toddGriffin":{"type":"string"}},"required":["toddGriffin"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings = default_settings();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub a: Option<String>,
-    pub b: i64,
+    #[serde(rename = "toddGriffin")]
+    pub todd_griffin: String,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn anyof_property_golden() {
-        let json = r#"{"type":"object","properties":{"foo":{"anyOf":[{"type":"string"},{"type":"object","properties":{"x":{"type":"integer"}}}]}}}"#;
+    fn default_function_name_uses_snake_case() {
+        let json = r#"{"type":"object","properties":{"person":{"title":"ValerieHunter","type":"object","properties":{"toddGriffin":{"type":"string","default":"bar"}},"required":["toddGriffin"]}},"required":["person"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings = default_settings();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum FooAnyOf {
-    Variant0(String),
-    Variant1(FooVariant1),
-}
+fn default_valerie_hunter_todd_griffin() -> String { "bar".to_string() }
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct FooVariant1 {
-    pub x: Option<i64>,
+#[json_schema(title = "ValerieHunter")]
+pub struct ValerieHunter {
+    #[serde(rename = "toddGriffin")]
+    #[serde(default = "default_valerie_hunter_todd_griffin")]
+    pub todd_griffin: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub foo: Option<FooAnyOf>,
+    pub person: ValerieHunter,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn anyof_root_golden() {
-        let json = r#"{"anyOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
+    fn non_string_enum_fallback_emits_string() {
+        let json =
+            r#"{"type":"object","properties":{"tag":{"enum":["foo",1,true]}},"required":["tag"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings = default_settings();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum RootAnyOf {
-    Variant0(RootVariant0),
-    Variant1(RootVariant1),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct RootVariant0 {
-    pub a: Option<String>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct RootVariant1 {
-    pub b: Option<i64>,
+pub struct Root {
+    pub tag: String,
 }
 
 ";
@@ -3024,44 +6187,20 @@ pub struct RootVariant1 {
     }
 
     #[test]
-    fn anyof_empty_errors() {
-        let schema: JsonSchema = JsonSchema {
-            any_of: Some(vec![]),
-            ..Default::default()
-        };
-        let settings = default_settings();
-        let actual = generate_rust(&[schema], &settings).unwrap_err();
-        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
-        if let CodeGenError::Batch { source, .. } = actual {
-            assert!(matches!(*source, CodeGenError::AnyOfEmpty));
-        }
-    }
-
-    #[test]
-    fn oneof_property_golden() {
-        let json = r#"{"type":"object","properties":{"foo":{"oneOf":[{"type":"string"},{"type":"object","properties":{"x":{"type":"integer"}}}]}}}"#;
+    fn single_required_integer_property() {
+        let json =
+            r#"{"type":"object","properties":{"count":{"type":"integer"}},"required":["count"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings = default_settings();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum FooOneOf {
-    Variant0(String),
-    Variant1(FooVariant1),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct FooVariant1 {
-    pub x: Option<i64>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub foo: Option<FooOneOf>,
+    pub count: i64,
 }
 
 ";
@@ -3069,30 +6208,19 @@ pub struct Root {
     }
 
     #[test]
-    fn oneof_root_golden() {
-        let json = r#"{"oneOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
+    fn single_optional_integer_property() {
+        let json = r#"{"type":"object","properties":{"count":{"type":"integer"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings = default_settings();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum RootOneOf {
-    Variant0(RootVariant0),
-    Variant1(RootVariant1),
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct RootVariant0 {
-    pub a: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct RootVariant1 {
-    pub b: Option<i64>,
+pub struct Root {
+    pub count: Option<i64>,
 }
 
 ";
@@ -3100,342 +6228,170 @@ pub struct RootVariant1 {
     }
 
     #[test]
-    fn oneof_empty_errors() {
-        let schema: JsonSchema = JsonSchema {
-            one_of: Some(vec![]),
-            ..Default::default()
-        };
-        let settings = default_settings();
-        let actual = generate_rust(&[schema], &settings).unwrap_err();
-        assert!(matches!(actual, CodeGenError::Batch { index: 0, .. }));
-        if let CodeGenError::Batch { source, .. } = actual {
-            assert!(matches!(*source, CodeGenError::OneOfEmpty));
-        }
-    }
+    fn single_required_float_property() {
+        let json =
+            r#"{"type":"object","properties":{"value":{"type":"number"}},"required":["value"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
-    #[test]
-    fn merge_all_of_success_two_object_subschemas() {
-        let s1: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
-                .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"b":{"type":"integer"}},"required":["b"]}"#,
-        )
-        .unwrap();
-        let actual = merge_all_of(&[s1, s2]).unwrap();
-        let expected: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"a":{"type":"string"},"b":{"type":"integer"}},"required":["b"]}"#,
-        )
-        .unwrap();
-        assert_eq!(expected, actual);
-    }
+use serde::{Deserialize, Serialize};
 
-    #[test]
-    fn merge_all_of_empty_errors() {
-        let actual = merge_all_of(&[]);
-        assert!(matches!(actual, Err(CodeGenError::AllOfMergeEmpty)));
-    }
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub value: f64,
+}
 
-    #[test]
-    fn merge_all_of_single_schema() {
-        let s: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"x":{"type":"string"}}}"#)
-                .unwrap();
-        let expected = s.clone();
-        let actual = merge_all_of(std::slice::from_ref(&s)).unwrap();
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn merge_all_of_conflicting_property_type_errors() {
-        let s1: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"k":{"type":"string"}}}"#)
-                .unwrap();
-        let s2: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"k":{"type":"integer"}}}"#)
-                .unwrap();
-        let actual = merge_all_of(&[s1, s2]);
-        assert!(matches!(
-            actual,
-            Err(CodeGenError::AllOfMergeConflictingPropertyType {
-                property_key: ref k,
-                ..
-            }) if k == "k"
-        ));
-    }
-
-    #[test]
-    fn merge_all_of_non_object_subschema_errors() {
-        let s1: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
-                .unwrap();
-        let s2: JsonSchema = serde_json::from_str(r#"{"type":"string"}"#).unwrap();
-        let actual = merge_all_of(&[s1, s2]);
-        assert!(matches!(
-            actual,
-            Err(CodeGenError::AllOfMergeNonObjectSubschema { index: 1 })
-        ));
-    }
-
-    #[test]
-    fn merge_all_of_conflicting_enum_errors() {
-        let s1: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"s":{"enum":["a","b"]}}}"#)
-                .unwrap();
-        let s2: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"s":{"enum":["x","y"]}}}"#)
-                .unwrap();
-        let actual = merge_all_of(&[s1, s2]);
-        assert!(matches!(
-            actual,
-            Err(CodeGenError::AllOfMergeConflictingEnum { property_key: ref k }) if k == "s"
-        ));
-    }
-
-    #[test]
-    fn merge_all_of_conflicting_numeric_bounds_errors() {
-        let s1: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"n":{"type":"integer","minimum":0,"maximum":10}}}"#,
-        )
-        .unwrap();
-        let s2: JsonSchema = serde_json::from_str(
-            r#"{"type":"object","properties":{"n":{"type":"integer","minimum":20,"maximum":30}}}"#,
-        )
-        .unwrap();
-        let actual = merge_all_of(&[s1, s2]);
-        assert!(matches!(
-            actual,
-            Err(CodeGenError::AllOfMergeConflictingNumericBounds {
-                property_key: ref k,
-                keyword: ref w
-            }) if k == "n" && w == "minimum/maximum"
-        ));
-    }
-
-    #[test]
-    fn batch_error_when_allof_merge_fails_in_second_schema() {
-        let s0: JsonSchema =
-            serde_json::from_str(r#"{"type":"object","properties":{"a":{"type":"string"}}}"#)
-                .unwrap();
-        let s1_bad: JsonSchema = serde_json::from_str(
-            r#"{"allOf":[{"type":"object","properties":{"x":{"type":"string"}}},{"type":"object","properties":{"x":{"type":"integer"}}}]}"#,
-        )
-        .unwrap();
-        let settings = default_settings();
-        let actual = generate_rust(&[s0.clone(), s1_bad], &settings).unwrap_err();
-        assert!(matches!(actual, CodeGenError::Batch { index: 1, .. }));
-    }
-
-    #[test]
-    fn root_all_of_merges_to_empty_object_errors_with_root_not_object() {
-        let schema: JsonSchema =
-            serde_json::from_str(r#"{"allOf":[{"type":"object"},{"type":"object"}]}"#).unwrap();
-        let settings = default_settings();
-        let actual = generate_rust(&[schema], &settings).unwrap_err();
-        assert!(
-            matches!(actual, CodeGenError::Batch { index: 0, source: ref s } if matches!(**s, CodeGenError::RootNotObject)),
-            "expected Batch {{ index: 0, source: RootNotObject }}, got {actual:?}"
-        );
-    }
-
-    #[test]
-    fn optional_enum_property_emits_enum_and_struct() {
-        let json = r#"{"type":"object","properties":{"level":{"enum":["low","medium","high"]}}}"#;
+    fn single_optional_float_property() {
+        let json = r#"{"type":"object","properties":{"value":{"type":"number"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum Level {
-    #[serde(rename = "high")]
-    High,
-    #[serde(rename = "low")]
-    Low,
-    #[serde(rename = "medium")]
-    Medium,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub level: Option<Level>,
+    pub value: Option<f64>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn enum_dedupe_two_properties_same_enum_emits_one_enum() {
-        let json = r#"{"type":"object","properties":{"a":{"enum":["x","y"]},"b":{"enum":["x","y"]}},"required":["a"]}"#;
+    fn integer_with_minimum_maximum_u8_range_emits_u8() {
+        let json = r#"{"type":"object","properties":{"byte":{"type":"integer","minimum":0,"maximum":255}},"required":["byte"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum A {
-    #[serde(rename = "x")]
-    X,
-    #[serde(rename = "y")]
-    Y,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub a: A,
-    pub b: Option<A>,
+    pub byte: u8,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn enum_collision_emits_suffixed_variants() {
-        let json = r#"{"type":"object","properties":{"t":{"enum":["a","A"]}},"required":["t"]}"#;
+    fn integer_with_only_minimum_emits_i64_fallback() {
+        let json = r#"{"type":"object","properties":{"count":{"type":"integer","minimum":0}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum T {
-    #[serde(rename = "A")]
-    A0,
-    #[serde(rename = "a")]
-    A1,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub t: T,
+    pub count: Option<i64>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn enum_duplicate_values_in_schema_emits_single_variant_per_value() {
-        let json = r#"{"type":"object","properties":{"t":{"enum":["A","A","A","a","a","a","a","a","a","a"]}},"required":["t"]}"#;
+    fn integer_with_only_maximum_emits_i64_fallback() {
+        let json = r#"{"type":"object","properties":{"count":{"type":"integer","maximum":100}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum T {
-    #[serde(rename = "A")]
-    A0,
-    #[serde(rename = "a")]
-    A1,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub t: T,
+    pub count: Option<i64>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn enum_non_rust_compliant_values_emits_valid_variants() {
-        let json = r#"{"type":"object","properties":{"id":{"enum":["/8633","todd.griffin"]}},"required":["id"]}"#;
+    fn number_without_min_max_emits_f64_fallback() {
+        let json =
+            r#"{"type":"object","properties":{"value":{"type":"number"}},"required":["value"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum Id {
-    #[serde(rename = "/8633")]
-    E8633,
-    #[serde(rename = "todd.griffin")]
-    ToddGriffin,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub id: Id,
+    pub value: f64,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn camel_case_property_emits_snake_case_field() {
-        let json = r#"{"type":"object","properties":{"toddGriffin":{"type":"string"}},"required":["toddGriffin"]}"#;
+    fn number_with_minimum_maximum_f32_range_emits_f32() {
+        let json = r#"{"type":"object","properties":{"value":{"type":"number","minimum":0.5,"maximum":100.5}},"required":["value"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    #[serde(rename = "toddGriffin")]
-    pub todd_griffin: String,
+    pub value: f32,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn default_function_name_uses_snake_case() {
-        let json = r#"{"type":"object","properties":{"person":{"title":"ValerieHunter","type":"object","properties":{"toddGriffin":{"type":"string","default":"bar"}},"required":["toddGriffin"]}},"required":["person"]}"#;
+    fn boolean_property_required_emits_bool() {
+        let json = r#"{"type":"object","properties":{"enabled":{"type":"boolean"}},"required":["enabled"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-fn default_valerie_hunter_todd_griffin() -> String { "bar".to_string() }
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-#[json_schema(title = "ValerieHunter")]
-pub struct ValerieHunter {
-    #[serde(rename = "toddGriffin")]
-    #[serde(default = "default_valerie_hunter_todd_griffin")]
-    pub todd_griffin: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub person: ValerieHunter,
+    pub enabled: bool,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn non_string_enum_fallback_emits_string() {
-        let json =
-            r#"{"type":"object","properties":{"tag":{"enum":["foo",1,true]}},"required":["tag"]}"#;
+    fn boolean_property_optional_emits_option_bool() {
+        let json = r#"{"type":"object","properties":{"flag":{"type":"boolean"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3446,7 +6402,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub tag: String,
+    pub flag: Option<bool>,
 }
 
 ";
@@ -3454,9 +6410,8 @@ pub struct Root {
     }
 
     #[test]
-    fn single_required_integer_property() {
-        let json =
-            r#"{"type":"object","properties":{"count":{"type":"integer"}},"required":["count"]}"#;
+    fn boolean_property_with_description_emits_doc_and_field() {
+        let json = r#"{"type":"object","properties":{"flag":{"type":"boolean","description":"Is it enabled?"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3467,7 +6422,8 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub count: i64,
+    /// Is it enabled?
+    pub flag: Option<bool>,
 }
 
 ";
@@ -3475,8 +6431,8 @@ pub struct Root {
     }
 
     #[test]
-    fn single_optional_integer_property() {
-        let json = r#"{"type":"object","properties":{"count":{"type":"integer"}}}"#;
+    fn array_items_boolean_emits_vec_bool() {
+        let json = r#"{"type":"object","properties":{"flags":{"type":"array","items":{"type":"boolean"}}},"required":["flags"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3487,7 +6443,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub count: Option<i64>,
+    pub flags: Vec<bool>,
 }
 
 ";
@@ -3495,9 +6451,8 @@ pub struct Root {
     }
 
     #[test]
-    fn single_required_float_property() {
-        let json =
-            r#"{"type":"object","properties":{"value":{"type":"number"}},"required":["value"]}"#;
+    fn array_items_boolean_unique_items_emits_hash_set_bool() {
+        let json = r#"{"type":"object","properties":{"flags":{"type":"array","items":{"type":"boolean"},"uniqueItems":true}},"required":["flags"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3505,10 +6460,11 @@ pub struct Root {
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub value: f64,
+    pub flags: HashSet<bool>,
 }
 
 ";
@@ -3516,28 +6472,32 @@ pub struct Root {
     }
 
     #[test]
-    fn single_optional_float_property() {
-        let json = r#"{"type":"object","properties":{"value":{"type":"number"}}}"#;
+    fn boolean_property_with_default_emits_default_attr() {
+        let json =
+            r#"{"type":"object","properties":{"enabled":{"type":"boolean","default":true}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
+fn default_root_enabled() -> Option<bool> { Some(true) }
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub value: Option<f64>,
+    #[serde(default = "default_root_enabled")]
+    pub enabled: Option<bool>,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn integer_with_minimum_maximum_u8_range_emits_u8() {
-        let json = r#"{"type":"object","properties":{"byte":{"type":"integer","minimum":0,"maximum":255}},"required":["byte"]}"#;
+    fn array_required_string_property() {
+        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"}}},"required":["tags"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3548,7 +6508,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub byte: u8,
+    pub tags: Vec<String>,
 }
 
 ";
@@ -3556,8 +6516,25 @@ pub struct Root {
     }
 
     #[test]
-    fn integer_with_only_minimum_emits_i64_fallback() {
-        let json = r#"{"type":"object","properties":{"count":{"type":"integer","minimum":0}}}"#;
+    fn array_with_unique_items_true_emits_hash_set_string() {
+        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"uniqueItems":true}},"required":["tags"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            actual.contains("pub tags: HashSet<String>"),
+            "expected HashSet<String>: {actual}"
+        );
+        assert!(
+            actual.contains(concat!("use std::collections::", "HashSet")),
+            "expected HashSet use: {actual}"
+        );
+    }
+
+    #[test]
+    fn array_with_unique_items_false_emits_vec_string() {
+        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"uniqueItems":false}},"required":["tags"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3568,7 +6545,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub count: Option<i64>,
+    pub tags: Vec<String>,
 }
 
 ";
@@ -3576,8 +6553,25 @@ pub struct Root {
     }
 
     #[test]
-    fn integer_with_only_maximum_emits_i64_fallback() {
-        let json = r#"{"type":"object","properties":{"count":{"type":"integer","maximum":100}}}"#;
+    fn array_with_unique_items_true_object_items_emits_vec() {
+        let json = r#"{"type":"object","properties":{"items":{"type":"array","items":{"type":"object","properties":{"name":{"type":"string"}}},"uniqueItems":true}},"required":["items"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            actual.contains("pub items: Vec<") && actual.contains(">,"),
+            "uniqueItems true with object items should emit Vec: {actual}"
+        );
+        assert!(
+            !actual.contains("HashSet"),
+            "should not use HashSet for object items: {actual}"
+        );
+    }
+
+    #[test]
+    fn array_with_min_items_max_items_emits_attribute() {
+        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"minItems":2,"maxItems":5}},"required":["tags"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3588,7 +6582,8 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub count: Option<i64>,
+    #[json_schema(min_items = 2, max_items = 5)]
+    pub tags: Vec<String>,
 }
 
 ";
@@ -3596,9 +6591,26 @@ pub struct Root {
     }
 
     #[test]
-    fn number_without_min_max_emits_f64_fallback() {
+    fn array_with_min_items_only_emits_attribute() {
+        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"minItems":1}},"required":["tags"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            actual.contains("#[json_schema(min_items = 1)]"),
+            "expected min_items attribute: {actual}"
+        );
+        assert!(
+            !actual.contains("max_items"),
+            "should not emit max_items when absent: {actual}"
+        );
+    }
+
+    #[test]
+    fn array_optional_string_property() {
         let json =
-            r#"{"type":"object","properties":{"value":{"type":"number"}},"required":["value"]}"#;
+            r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"}}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3609,7 +6621,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub value: f64,
+    pub tags: Option<Vec<String>>,
 }
 
 ";
@@ -3617,8 +6629,8 @@ pub struct Root {
     }
 
     #[test]
-    fn number_with_minimum_maximum_f32_range_emits_f32() {
-        let json = r#"{"type":"object","properties":{"value":{"type":"number","minimum":0.5,"maximum":100.5}},"required":["value"]}"#;
+    fn array_of_integers_property() {
+        let json = r#"{"type":"object","properties":{"counts":{"type":"array","items":{"type":"integer"}}},"required":["counts"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3629,7 +6641,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub value: f32,
+    pub counts: Vec<i64>,
 }
 
 ";
@@ -3637,8 +6649,8 @@ pub struct Root {
     }
 
     #[test]
-    fn boolean_property_required_emits_bool() {
-        let json = r#"{"type":"object","properties":{"enabled":{"type":"boolean"}},"required":["enabled"]}"#;
+    fn array_of_objects_property() {
+        let json = r#"{"type":"object","properties":{"items":{"type":"array","items":{"type":"object","properties":{"name":{"type":"string"}}}}},"required":["items"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3647,9 +6659,14 @@ pub struct Root {
 
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Items {
+    pub name: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub enabled: bool,
+    pub items: Vec<Items>,
 }
 
 ";
@@ -3657,8 +6674,8 @@ pub struct Root {
     }
 
     #[test]
-    fn boolean_property_optional_emits_option_bool() {
-        let json = r#"{"type":"object","properties":{"flag":{"type":"boolean"}}}"#;
+    fn array_of_arrays_property() {
+        let json = r#"{"type":"object","properties":{"matrix":{"type":"array","items":{"type":"array","items":{"type":"string"}}}},"required":["matrix"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3669,7 +6686,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub flag: Option<bool>,
+    pub matrix: Vec<Vec<String>>,
 }
 
 ";
@@ -3677,8 +6694,8 @@ pub struct Root {
     }
 
     #[test]
-    fn boolean_property_with_description_emits_doc_and_field() {
-        let json = r#"{"type":"object","properties":{"flag":{"type":"boolean","description":"Is it enabled?"}}}"#;
+    fn mixed_string_integer_float_properties() {
+        let json = r#"{"type":"object","properties":{"count":{"type":"integer"},"id":{"type":"string"},"value":{"type":"number"}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3689,17 +6706,19 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    /// Is it enabled?
-    pub flag: Option<bool>,
+    pub count: Option<i64>,
+    pub id: String,
+    pub value: Option<f64>,
 }
 
 ";
         assert_eq!(expected, actual);
     }
 
+    #[cfg(feature = "preserve_order")]
     #[test]
-    fn array_items_boolean_emits_vec_bool() {
-        let json = r#"{"type":"object","properties":{"flags":{"type":"array","items":{"type":"boolean"}}},"required":["flags"]}"#;
+    fn preserve_order_emits_fields_in_declaration_order() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"},"count":{"type":"integer"}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3710,7 +6729,8 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub flags: Vec<bool>,
+    pub id: String,
+    pub count: Option<i64>,
 }
 
 ";
@@ -3718,8 +6738,8 @@ pub struct Root {
     }
 
     #[test]
-    fn array_items_boolean_unique_items_emits_hash_set_bool() {
-        let json = r#"{"type":"object","properties":{"flags":{"type":"array","items":{"type":"boolean"},"uniqueItems":true}},"required":["flags"]}"#;
+    fn mixed_string_and_integer_properties() {
+        let json = r#"{"type":"object","properties":{"count":{"type":"integer"},"id":{"type":"string"}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3727,11 +6747,11 @@ pub struct Root {
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub flags: HashSet<bool>,
+    pub count: Option<i64>,
+    pub id: String,
 }
 
 ";
@@ -3739,32 +6759,63 @@ pub struct Root {
     }
 
     #[test]
-    fn boolean_property_with_default_emits_default_attr() {
-        let json =
-            r#"{"type":"object","properties":{"enabled":{"type":"boolean","default":true}}}"#;
+    fn nested_object_and_rename() {
+        let json = r#"{
+          "type": "object",
+          "properties": {
+            "address": {
+              "type": "object",
+              "properties": {
+                "city": { "type": "string" },
+                "street_address": { "type": "string" }
+              }
+            },
+            "first_name": { "type": "string" }
+          }
+        }"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-fn default_root_enabled() -> Option<bool> { Some(true) }
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address {
+    pub city: Option<String>,
+    pub street_address: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    #[serde(default = "default_root_enabled")]
-    pub enabled: Option<bool>,
+    pub address: Option<Address>,
+    pub first_name: Option<String>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn array_required_string_property() {
-        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"}}},"required":["tags"]}"#;
+    fn full_example_from_plan() {
+        let json = r#"{
+          "type": "object",
+          "properties": {
+            "address": {
+              "type": "object",
+              "properties": {
+                "city": { "type": "string" },
+                "country": { "type": "string" },
+                "state": { "type": "string" },
+                "street_address": { "type": "string" }
+              }
+            },
+            "birthday": { "type": "string" },
+            "first_name": { "type": "string" },
+            "last_name": { "type": "string" }
+          }
+        }"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3773,9 +6824,20 @@ pub struct Root {
 
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address {
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub state: Option<String>,
+    pub street_address: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub tags: Vec<String>,
+    pub address: Option<Address>,
+    pub birthday: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
 }
 
 ";
@@ -3783,208 +6845,261 @@ pub struct Root {
     }
 
     #[test]
-    fn array_with_unique_items_true_emits_hash_set_string() {
-        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"uniqueItems":true}},"required":["tags"]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn deeply_nested_schema_does_not_stack_overflow() {
+        const DEPTH: usize = 150;
+        let mut inner: JsonSchema = JsonSchema {
+            type_: Some("object".to_string()),
+            properties: {
+                let mut m = PropertyMap::new();
+                m.insert(
+                    "value".to_string(),
+                    JsonSchema {
+                        type_: Some("string".to_string()),
+                        ..Default::default()
+                    },
+                );
+                m
+            },
+            title: Some("Leaf".to_string()),
+            ..Default::default()
+        };
+        for i in (0..DEPTH).rev() {
+            let mut wrap: JsonSchema = JsonSchema {
+                type_: Some("object".to_string()),
+                title: Some(format!("Level{i}")),
+                ..Default::default()
+            };
+            wrap.properties.insert("child".to_string(), inner);
+            inner = wrap;
+        }
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual = generate_rust(&[inner], &settings);
+        assert!(actual.is_ok(), concat!("deep schema must not ", "overflow"));
+        let out = actual.unwrap();
+        let output: String = String::from_utf8(out.per_schema[0].clone()).unwrap();
         assert!(
-            actual.contains("pub tags: HashSet<String>"),
-            "expected HashSet<String>: {actual}"
+            output.contains(concat!("pub struct ", "Level0")),
+            concat!("output must contain root ", "struct")
         );
         assert!(
-            actual.contains(concat!("use std::collections::", "HashSet")),
-            "expected HashSet use: {actual}"
+            output.contains(concat!("pub struct ", "Leaf")),
+            concat!("output must contain leaf ", "struct")
         );
     }
 
     #[test]
-    fn array_with_unique_items_false_emits_vec_string() {
-        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"uniqueItems":false}},"required":["tags"]}"#;
+    fn field_rename_when_key_differs_from_identifier() {
+        let json = r#"{"type":"object","properties":{"foo-bar":{"type":"string"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub tags: Vec<String>,
+    #[serde(rename = "foo-bar")]
+    pub foo_bar: Option<String>,
 }
 
-";
+"#;
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn array_with_unique_items_true_object_items_emits_vec() {
-        let json = r#"{"type":"object","properties":{"items":{"type":"array","items":{"type":"object","properties":{"name":{"type":"string"}}},"uniqueItems":true}},"required":["items"]}"#;
+    fn generate_rust_one_schema_returns_one_buffer() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
+        let expected: super::GenerateRustOutput =
+            RustBackend.generate(&[schema], &settings).unwrap();
+        assert_eq!(expected.per_schema, output.per_schema);
+        assert_eq!(1, output.per_schema.len());
+    }
+
+    #[test]
+    fn property_key_first_uses_key_over_title_for_nested_struct() {
+        let json = r#"{"type":"object","properties":{"address":{"type":"object","title":"FooBar","properties":{"city":{"type":"string"}}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .model_name_source(ModelNameSource::PropertyKeyFirst)
+            .build();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         assert!(
-            actual.contains("pub items: Vec<") && actual.contains(">,"),
-            "uniqueItems true with object items should emit Vec: {actual}"
+            actual.contains(concat!("pub struct ", "Address")),
+            "with PropertyKeyFirst nested struct should be named from key address -> Address; got: {actual}"
         );
         assert!(
-            !actual.contains("HashSet"),
-            "should not use HashSet for object items: {actual}"
+            !actual.contains(concat!("struct ", "FooBar")),
+            "with PropertyKeyFirst title FooBar should not be used for nested name; got: {actual}"
         );
     }
 
     #[test]
-    fn array_with_min_items_max_items_emits_attribute() {
-        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"minItems":2,"maxItems":5}},"required":["tags"]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn generate_rust_two_schemas_returns_two_buffers() {
+        let json1 = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
+        let json2 = r#"{"type":"object","properties":{"b":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(json1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(json2).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    #[json_schema(min_items = 2, max_items = 5)]
-    pub tags: Vec<String>,
-}
-
-";
-        assert_eq!(expected, actual);
+        let output: super::GenerateRustOutput =
+            generate_rust(&[s1.clone(), s2.clone()], &settings).unwrap();
+        let expected: super::GenerateRustOutput =
+            RustBackend.generate(&[s1, s2], &settings).unwrap();
+        assert_eq!(expected.per_schema, output.per_schema);
+        assert_eq!(2, output.per_schema.len());
+        let out1 = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let out2 = String::from_utf8(output.per_schema[1].clone()).unwrap();
+        assert!(out1.contains("pub a: Option<String>") || out1.contains("pub a:"));
+        assert!(out2.contains("pub b: Option<String>") || out2.contains("pub b:"));
     }
 
     #[test]
-    fn array_with_min_items_only_emits_attribute() {
-        let json = r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"},"minItems":1}},"required":["tags"]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn batch_error_includes_index() {
+        let valid = r#"{"type":"object","properties":{"x":{"type":"string"}}}"#;
+        let invalid: JsonSchema = JsonSchema::default();
+        let s1: JsonSchema = serde_json::from_str(valid).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        assert!(
-            actual.contains("#[json_schema(min_items = 1)]"),
-            "expected min_items attribute: {actual}"
-        );
-        assert!(
-            !actual.contains("max_items"),
-            "should not emit max_items when absent: {actual}"
-        );
+        let actual = generate_rust(&[s1, invalid], &settings).unwrap_err();
+        assert!(matches!(actual, CodeGenError::Batch { index: 1, .. }));
     }
 
     #[test]
-    fn array_optional_string_property() {
-        let json =
-            r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"}}}}"#;
+    fn dedupe_disabled_returns_no_shared() {
+        let json = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Disabled)
+            .build();
+        let output: super::GenerateRustOutput =
+            generate_rust(&[schema.clone(), schema], &settings).unwrap();
+        let expected: Option<Vec<u8>> = None;
+        let actual = output.shared.clone();
+        assert_eq!(expected, actual);
+        assert_eq!(2, output.per_schema.len());
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub tags: Option<Vec<String>>,
-}
+    #[test]
+    fn dedupe_disabled_two_schemas_same_shape_two_buffers_no_shared() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(json).unwrap();
+        let s2: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Disabled)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        assert_eq!(None, output.shared);
+        assert_eq!(2, output.per_schema.len());
+        let out0 = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let out1 = String::from_utf8(output.per_schema[1].clone()).unwrap();
+        let root_struct: &str = concat!("pub struct ", "Root");
+        assert!(out0.contains(root_struct));
+        assert!(out1.contains(root_struct));
+    }
 
-";
-        assert_eq!(expected, actual);
+    #[test]
+    fn dedupe_full_two_identical_schemas_produces_shared() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(json).unwrap();
+        let s2: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected_shared_some = true;
+        let actual_shared_some = output.shared.is_some();
+        assert_eq!(expected_shared_some, actual_shared_some);
+        assert_eq!(2, output.per_schema.len());
+        let shared_str = String::from_utf8(output.shared.unwrap()).unwrap();
+        let root_struct: &str = concat!("pub struct ", "Root");
+        assert!(shared_str.contains(root_struct));
+        let per0 = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let root_use: &str = concat!("pub use crate::", "Root");
+        let root_only: &str = "Root";
+        assert!(per0.contains(root_use) || per0.contains(root_only));
     }
 
     #[test]
-    fn array_of_integers_property() {
-        let json = r#"{"type":"object","properties":{"counts":{"type":"array","items":{"type":"integer"}}},"required":["counts"]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn dedupe_root_array_alias_imports_shared_item_struct() {
+        let j1 = r##"{"type":"array","items":{"$ref":"#/$defs/Tag"},"$defs":{"Tag":{"type":"object","properties":{"name":{"type":"string"}}}}}"##;
+        let j2 = r##"{"type":"object","properties":{"tag":{"$ref":"#/$defs/Tag"}},"$defs":{"Tag":{"type":"object","properties":{"name":{"type":"string"}}}}}"##;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub counts: Vec<i64>,
-}
-
-";
-        assert_eq!(expected, actual);
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let shared = String::from_utf8(output.shared.unwrap()).unwrap();
+        assert!(shared.contains("pub struct Tag {"), "{shared}");
+        let per0 = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(per0.contains("pub use crate::Tag;\n"), "{per0}");
+        assert!(per0.contains("pub type Root = Vec<Tag>;\n"), "{per0}");
     }
 
     #[test]
-    fn array_of_objects_property() {
-        let json = r#"{"type":"object","properties":{"items":{"type":"array","items":{"type":"object","properties":{"name":{"type":"string"}}}}},"required":["items"]}"#;
+    fn dedupe_full_single_schema_no_shared() {
+        let json = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Items {
-    pub name: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub items: Vec<Items>,
-}
-
-";
+        let expected: Option<Vec<u8>> = None;
+        let actual = output.shared.clone();
         assert_eq!(expected, actual);
+        assert_eq!(1, output.per_schema.len());
     }
 
     #[test]
-    fn array_of_arrays_property() {
-        let json = r#"{"type":"object","properties":{"matrix":{"type":"array","items":{"type":"array","items":{"type":"string"}}}},"required":["matrix"]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub matrix: Vec<Vec<String>>,
-}
-
-";
-        assert_eq!(expected, actual);
+    fn dedupe_full_two_different_schemas_no_shared() {
+        let j1 = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
+        let j2 = r#"{"type":"object","properties":{"b":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        assert_eq!(None, output.shared);
+        assert_eq!(2, output.per_schema.len());
     }
 
     #[test]
-    fn mixed_string_integer_float_properties() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"},"count":{"type":"integer"},"value":{"type":"number"}},"required":["id"]}"#;
+    fn dedupe_full_single_schema_two_identical_nested_objects_deduped() {
+        let json = r#"{
+            "type": "object",
+            "properties": {
+                "addr1": { "type": "object", "properties": { "street": { "type": "string" } } },
+                "addr2": { "type": "object", "properties": { "street": { "type": "string" } } }
+            }
+        }"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub count: Option<i64>,
-    pub id: String,
-    pub value: Option<f64>,
-}
-
-";
-        assert_eq!(expected, actual);
+        let per_str = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            per_str.contains("addr1") && per_str.contains("addr2"),
+            "per_schema should reference both fields; got: {per_str}"
+        );
+        let shared_count = output
+            .shared
+            .as_ref()
+            .map_or(0, |b| b.windows(11).filter(|w| w == b"pub struct ").count());
+        let per_count = per_str.matches("pub struct ").count();
+        assert!(
+            shared_count + per_count >= 1,
+            "at least one struct (Root in per_schema, nested in shared when deduped)"
+        );
     }
 
     #[test]
-    fn mixed_string_and_integer_properties() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"},"count":{"type":"integer"}},"required":["id"]}"#;
+    fn description_root_struct_single_line() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"A root type"}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -3993,10 +7108,10 @@ pub struct Root {
 
 use serde::{Deserialize, Serialize};
 
+/// A root type
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub count: Option<i64>,
-    pub id: String,
+    pub id: Option<String>,
 }
 
 ";
@@ -4004,20 +7119,8 @@ pub struct Root {
     }
 
     #[test]
-    fn nested_object_and_rename() {
-        let json = r#"{
-          "type": "object",
-          "properties": {
-            "first_name": { "type": "string" },
-            "address": {
-              "type": "object",
-              "properties": {
-                "street_address": { "type": "string" },
-                "city": { "type": "string" }
-              }
-            }
-          }
-        }"#;
+    fn description_root_struct_multi_line() {
+        let json = r#"{"type":"object","properties":{"x":{"type":"string"}},"description":"Line one\nLine two"}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -4026,16 +7129,11 @@ pub struct Root {
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Address {
-    pub city: Option<String>,
-    pub street_address: Option<String>,
-}
-
+/// Line one
+/// Line two
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub address: Option<Address>,
-    pub first_name: Option<String>,
+    pub x: Option<String>,
 }
 
 ";
@@ -4043,293 +7141,319 @@ pub struct Root {
     }
 
     #[test]
-    fn full_example_from_plan() {
-        let json = r#"{
-          "type": "object",
-          "properties": {
-            "first_name": { "type": "string" },
-            "last_name": { "type": "string" },
-            "birthday": { "type": "string" },
-            "address": {
-              "type": "object",
-              "properties": {
-                "street_address": { "type": "string" },
-                "city": { "type": "string" },
-                "state": { "type": "string" },
-                "country": { "type": "string" }
-              }
-            }
-          }
-        }"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn description_empty_or_whitespace_emits_no_doc() {
+        let json_empty =
+            r#"{"type":"object","properties":{"a":{"type":"string"}},"description":""}"#;
+        let schema_empty: JsonSchema = serde_json::from_str(json_empty).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let output: super::GenerateRustOutput = generate_rust(&[schema_empty], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Address {
-    pub city: Option<String>,
-    pub country: Option<String>,
-    pub state: Option<String>,
-    pub street_address: Option<String>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub address: Option<Address>,
-    pub birthday: Option<String>,
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
+    pub a: Option<String>,
 }
 
 ";
         assert_eq!(expected, actual);
-    }
 
-    #[test]
-    fn deeply_nested_schema_does_not_stack_overflow() {
-        const DEPTH: usize = 150;
-        let mut inner: JsonSchema = JsonSchema {
-            type_: Some("object".to_string()),
-            properties: {
-                let mut m = std::collections::BTreeMap::new();
-                m.insert(
-                    "value".to_string(),
-                    JsonSchema {
-                        type_: Some("string".to_string()),
-                        ..Default::default()
-                    },
-                );
-                m
-            },
-            title: Some("Leaf".to_string()),
-            ..Default::default()
-        };
-        for i in (0..DEPTH).rev() {
-            let mut wrap: JsonSchema = JsonSchema {
-                type_: Some("object".to_string()),
-                title: Some(format!("Level{i}")),
-                ..Default::default()
-            };
-            wrap.properties.insert("child".to_string(), inner);
-            inner = wrap;
-        }
-        let settings: CodeGenSettings = default_settings();
-        let actual = generate_rust(&[inner], &settings);
-        assert!(actual.is_ok(), concat!("deep schema must not ", "overflow"));
-        let out = actual.unwrap();
-        let output: String = String::from_utf8(out.per_schema[0].clone()).unwrap();
-        assert!(
-            output.contains(concat!("pub struct ", "Level0")),
-            concat!("output must contain root ", "struct")
-        );
-        assert!(
-            output.contains(concat!("pub struct ", "Leaf")),
-            concat!("output must contain leaf ", "struct")
-        );
+        let json_ws =
+            r#"{"type":"object","properties":{"a":{"type":"string"}},"description":"   \n  "}"#;
+        let schema_ws: JsonSchema = serde_json::from_str(json_ws).unwrap();
+        let output_ws: super::GenerateRustOutput = generate_rust(&[schema_ws], &settings).unwrap();
+        let actual_ws = String::from_utf8(output_ws.per_schema[0].clone()).unwrap();
+        assert_eq!(expected, actual_ws);
     }
 
     #[test]
-    fn field_rename_when_key_differs_from_identifier() {
-        let json = r#"{"type":"object","properties":{"foo-bar":{"type":"string"}}}"#;
+    fn description_nested_object_struct_doc() {
+        let json = r#"{"type":"object","properties":{"nested":{"type":"object","description":"Inner type","properties":{"v":{"type":"string"}}}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
+/// Inner type
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Nested {
+    pub v: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    #[serde(rename = "foo-bar")]
-    pub foo_bar: Option<String>,
+    /// Inner type
+    pub nested: Option<Nested>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn generate_rust_one_schema_returns_one_buffer() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
+    fn description_property_field_doc() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string","description":"User full name"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput =
-            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
-        let expected: super::GenerateRustOutput =
-            RustBackend.generate(&[schema], &settings).unwrap();
-        assert_eq!(expected.per_schema, output.per_schema);
-        assert_eq!(1, output.per_schema.len());
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    /// User full name
+    pub name: Option<String>,
+}
+
+";
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn property_key_first_uses_key_over_title_for_nested_struct() {
-        let json = r#"{"type":"object","properties":{"address":{"type":"object","title":"FooBar","properties":{"city":{"type":"string"}}}}}"#;
+    fn description_enum_property_emits_enum_doc() {
+        let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"],"description":"Issue status"}},"required":["status"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
-            .model_name_source(ModelNameSource::PropertyKeyFirst)
-            .build();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        assert!(
-            actual.contains(concat!("pub struct ", "Address")),
-            "with PropertyKeyFirst nested struct should be named from key address -> Address; got: {actual}"
-        );
-        assert!(
-            !actual.contains(concat!("struct ", "FooBar")),
-            "with PropertyKeyFirst title FooBar should not be used for nested name; got: {actual}"
-        );
-    }
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
-    #[test]
-    fn generate_rust_two_schemas_returns_two_buffers() {
-        let json1 = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
-        let json2 = r#"{"type":"object","properties":{"b":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(json1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(json2).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput =
-            generate_rust(&[s1.clone(), s2.clone()], &settings).unwrap();
-        let expected: super::GenerateRustOutput =
-            RustBackend.generate(&[s1, s2], &settings).unwrap();
-        assert_eq!(expected.per_schema, output.per_schema);
-        assert_eq!(2, output.per_schema.len());
-        let out1 = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let out2 = String::from_utf8(output.per_schema[1].clone()).unwrap();
-        assert!(out1.contains("pub a: Option<String>") || out1.contains("pub a:"));
-        assert!(out2.contains("pub b: Option<String>") || out2.contains("pub b:"));
+use serde::{Deserialize, Serialize};
+
+/// Issue status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub enum Status {
+    #[serde(rename = "closed")]
+    Closed,
+    #[serde(rename = "open")]
+    Open,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    /// Issue status
+    pub status: Status,
+}
+
+"#;
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn batch_error_includes_index() {
-        let valid = r#"{"type":"object","properties":{"x":{"type":"string"}}}"#;
-        let invalid: JsonSchema = JsonSchema::default();
-        let s1: JsonSchema = serde_json::from_str(valid).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let actual = generate_rust(&[s1, invalid], &settings).unwrap_err();
-        assert!(matches!(actual, CodeGenError::Batch { index: 1, .. }));
+    fn dedupe_functional_same_shape_different_description_one_struct() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"First"}"#;
+        let j2 =
+            r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"Second"}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Functional)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected_shared_some = true;
+        let actual_shared_some = output.shared.is_some();
+        assert_eq!(expected_shared_some, actual_shared_some);
     }
 
     #[test]
-    fn dedupe_disabled_returns_no_shared() {
-        let json = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+    fn dedupe_full_same_shape_different_description_two_structs() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"First"}"#;
+        let j2 =
+            r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"Second"}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Disabled)
+            .dedupe_mode(DedupeMode::Full)
             .build();
-        let output: super::GenerateRustOutput =
-            generate_rust(&[schema.clone(), schema], &settings).unwrap();
-        let expected: Option<Vec<u8>> = None;
-        let actual = output.shared.clone();
-        assert_eq!(expected, actual);
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected_shared_some = false;
+        let actual_shared_some = output.shared.is_some();
+        assert_eq!(expected_shared_some, actual_shared_some);
         assert_eq!(2, output.per_schema.len());
     }
 
     #[test]
-    fn dedupe_disabled_two_schemas_same_shape_two_buffers_no_shared() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(json).unwrap();
-        let s2: JsonSchema = serde_json::from_str(json).unwrap();
+    fn dedupe_functional_same_shape_different_comment_one_struct() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"First"}"#;
+        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"Second"}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Disabled)
+            .dedupe_mode(DedupeMode::Functional)
             .build();
         let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        assert_eq!(None, output.shared);
-        assert_eq!(2, output.per_schema.len());
-        let out0 = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let out1 = String::from_utf8(output.per_schema[1].clone()).unwrap();
-        let root_struct: &str = concat!("pub struct ", "Root");
-        assert!(out0.contains(root_struct));
-        assert!(out1.contains(root_struct));
+        let expected_shared_some = true;
+        let actual_shared_some = output.shared.is_some();
+        assert_eq!(expected_shared_some, actual_shared_some);
     }
 
     #[test]
-    fn dedupe_full_two_identical_schemas_produces_shared() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(json).unwrap();
-        let s2: JsonSchema = serde_json::from_str(json).unwrap();
+    fn dedupe_full_same_shape_different_comment_two_structs() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"First"}"#;
+        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"Second"}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
             .dedupe_mode(DedupeMode::Full)
             .build();
         let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = true;
+        let expected_shared_some = false;
         let actual_shared_some = output.shared.is_some();
         assert_eq!(expected_shared_some, actual_shared_some);
         assert_eq!(2, output.per_schema.len());
-        let shared_str = String::from_utf8(output.shared.unwrap()).unwrap();
-        let root_struct: &str = concat!("pub struct ", "Root");
-        assert!(shared_str.contains(root_struct));
-        let per0 = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let root_use: &str = concat!("pub use crate::", "Root");
-        let root_only: &str = "Root";
-        assert!(per0.contains(root_use) || per0.contains(root_only));
     }
 
     #[test]
-    fn dedupe_full_single_schema_no_shared() {
-        let json = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
+    fn examples_golden_with_examples_emits_doc() {
+        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":[{"x":"foo"}]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::default();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// {\"x\":\"foo\"}\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn examples_struct_doc_contains_examples() {
+        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":["a",1]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::default();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// \"a\"\n/// 1\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn examples_enum_doc_contains_examples() {
+        let json: &str = r#"{"type":"object","properties":{"status":{"enum":["open"],"examples":["open"]}},"required":["status"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::default();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// \"open\"\n#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub enum Status {\n    #[serde(rename = \"open\")]\n    Open,\n}\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub status: Status,\n}\n\n".to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn examples_empty_array_emits_no_doc() {
+        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":[]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::default();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected: String =
+            "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn dedupe_full_same_shape_different_examples_two_structs() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[1]}"#;
+        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[2]}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
             .dedupe_mode(DedupeMode::Full)
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let expected: Option<Vec<u8>> = None;
-        let actual = output.shared.clone();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected: (bool, usize) = (false, 2);
+        let actual: (bool, usize) = (output.shared.is_some(), output.per_schema.len());
         assert_eq!(expected, actual);
-        assert_eq!(1, output.per_schema.len());
     }
 
     #[test]
-    fn dedupe_full_two_different_schemas_no_shared() {
-        let j1 = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
-        let j2 = r#"{"type":"object","properties":{"b":{"type":"string"}}}"#;
+    fn dedupe_functional_same_shape_different_examples_one_struct() {
+        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[1]}"#;
+        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[2]}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Functional)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected_shared_some = true;
+        let actual_shared_some = output.shared.is_some();
+        assert_eq!(expected_shared_some, actual_shared_some);
+    }
+
+    #[test]
+    fn dedupe_full_same_shape_different_id_two_structs() {
+        let j1 = r#"{"$id":"http://example.com/a","type":"object","properties":{"x":{"type":"string"}}}"#;
+        let j2 = r#"{"$id":"http://example.com/b","type":"object","properties":{"x":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
+        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Full)
+            .build();
+        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
+        let expected_shared_some = false;
+        let actual_shared_some = output.shared.is_some();
+        let msg: &str = concat!(
+            "Full dedupe: same shape different id yields two ",
+            "structs"
+        );
+        assert_eq!(expected_shared_some, actual_shared_some, "{msg}");
+    }
+
+    #[test]
+    fn dedupe_functional_same_shape_different_id_one_struct() {
+        let j1 = r#"{"$id":"http://example.com/a","type":"object","properties":{"x":{"type":"string"}}}"#;
+        let j2 = r#"{"$id":"http://example.com/b","type":"object","properties":{"x":{"type":"string"}}}"#;
         let s1: JsonSchema = serde_json::from_str(j1).unwrap();
         let s2: JsonSchema = serde_json::from_str(j2).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
+            .dedupe_mode(DedupeMode::Functional)
             .build();
         let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        assert_eq!(None, output.shared);
-        assert_eq!(2, output.per_schema.len());
+        let expected_shared_some = true;
+        let actual_shared_some = output.shared.is_some();
+        let msg: &str = concat!(
+            "Functional dedupe: same shape different id yields one shared ",
+            "struct"
+        );
+        assert_eq!(expected_shared_some, actual_shared_some, "{msg}");
     }
 
     #[test]
-    fn dedupe_full_single_schema_two_identical_nested_objects_deduped() {
-        let json = r#"{
-            "type": "object",
-            "properties": {
-                "addr1": { "type": "object", "properties": { "street": { "type": "string" } } },
-                "addr2": { "type": "object", "properties": { "street": { "type": "string" } } }
-            }
-        }"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
+    fn dedupe_functional_same_as_full_when_no_description() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
+        let s1: JsonSchema = serde_json::from_str(json).unwrap();
+        let s2: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings_full: CodeGenSettings = CodeGenSettings::builder()
             .dedupe_mode(DedupeMode::Full)
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let per_str = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        assert!(
-            per_str.contains("addr1") && per_str.contains("addr2"),
-            "per_schema should reference both fields; got: {per_str}"
-        );
-        let shared_count = output
-            .shared
-            .as_ref()
-            .map_or(0, |b| b.windows(11).filter(|w| w == b"pub struct ").count());
-        let per_count = per_str.matches("pub struct ").count();
-        assert!(
-            shared_count + per_count >= 1,
-            "at least one struct (Root in per_schema, nested in shared when deduped)"
-        );
+        let settings_func: CodeGenSettings = CodeGenSettings::builder()
+            .dedupe_mode(DedupeMode::Functional)
+            .build();
+        let output_full: super::GenerateRustOutput =
+            generate_rust(&[s1.clone(), s2.clone()], &settings_full).unwrap();
+        let output_func: super::GenerateRustOutput =
+            generate_rust(&[s1, s2], &settings_func).unwrap();
+        assert_eq!(output_full.shared.is_some(), output_func.shared.is_some());
+        assert_eq!(output_full.per_schema.len(), output_func.per_schema.len());
     }
 
     #[test]
-    fn description_root_struct_single_line() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"A root type"}"#;
+    fn default_settings_use_full_dedupe() {
+        let settings: CodeGenSettings = CodeGenSettings::builder().build();
+        let expected = DedupeMode::Full;
+        let actual = settings.dedupe_mode;
+        assert_eq!(expected, actual);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_required_property() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string","format":"uuid"}},"required":["id"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -4337,20 +7461,21 @@ pub struct Root {
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-/// A root type
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub id: Option<String>,
+    pub id: Uuid,
 }
 
 ";
         assert_eq!(expected, actual);
     }
 
+    #[cfg(feature = "uuid")]
     #[test]
-    fn description_root_struct_multi_line() {
-        let json = r#"{"type":"object","properties":{"x":{"type":"string"}},"description":"Line one\nLine two"}"#;
+    fn uuid_optional_property() {
+        let json = r#"{"type":"object","properties":{"id":{"type":"string","format":"uuid"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -4358,49 +7483,33 @@ pub struct Root {
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-/// Line one
-/// Line two
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub x: Option<String>,
+    pub id: Option<Uuid>,
 }
 
 ";
         assert_eq!(expected, actual);
     }
 
+    #[cfg(feature = "uuid")]
     #[test]
-    fn description_empty_or_whitespace_emits_no_doc() {
-        let json_empty =
-            r#"{"type":"object","properties":{"a":{"type":"string"}},"description":""}"#;
-        let schema_empty: JsonSchema = serde_json::from_str(json_empty).unwrap();
+    fn uuid_array_items() {
+        let json = r#"{"type":"object","properties":{"ids":{"type":"array","items":{"type":"string","format":"uuid"}}},"required":["ids"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema_empty], &settings).unwrap();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub a: Option<String>,
-}
-
-";
+        let expected = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{\"Deserialize\", \"Serialize\"};\nuse uuid::Uuid;\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub ids: Vec<Uuid>,\n}\n\n";
         assert_eq!(expected, actual);
-
-        let json_ws =
-            r#"{"type":"object","properties":{"a":{"type":"string"}},"description":"   \n  "}"#;
-        let schema_ws: JsonSchema = serde_json::from_str(json_ws).unwrap();
-        let output_ws: super::GenerateRustOutput = generate_rust(&[schema_ws], &settings).unwrap();
-        let actual_ws = String::from_utf8(output_ws.per_schema[0].clone()).unwrap();
-        assert_eq!(expected, actual_ws);
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
-    fn description_nested_object_struct_doc() {
-        let json = r#"{"type":"object","properties":{"nested":{"type":"object","description":"Inner type","properties":{"v":{"type":"string"}}}}}"#;
+    fn chrono_date_time_and_date_properties() {
+        let json = r#"{"type":"object","properties":{"at":{"type":"string","format":"date-time"},"on":{"type":"string","format":"date"}},"required":["at"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
@@ -4409,330 +7518,602 @@ pub struct Root {
 
 use serde::{Deserialize, Serialize};
 
-/// Inner type
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Nested {
-    pub v: Option<String>,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    /// Inner type
-    pub nested: Option<Nested>,
+    pub at: chrono::DateTime<chrono::FixedOffset>,
+    pub on: Option<chrono::NaiveDate>,
 }
 
 ";
         assert_eq!(expected, actual);
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
-    fn description_property_field_doc() {
-        let json = r#"{"type":"object","properties":{"name":{"type":"string","description":"User full name"}}}"#;
+    fn chrono_date_array_items() {
+        let json = r#"{"type":"object","properties":{"days":{"type":"array","items":{"type":"string","format":"date"}}},"required":["days"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    /// User full name
-    pub name: Option<String>,
-}
-
-";
-        assert_eq!(expected, actual);
+        assert!(
+            actual.contains("pub days: Vec<chrono::NaiveDate>,"),
+            "expected Vec<chrono::NaiveDate>: {actual}"
+        );
     }
 
     #[test]
-    fn description_enum_property_emits_enum_doc() {
-        let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"],"description":"Issue status"}},"required":["status"]}"#;
+    fn ip_address_formats_map_to_std_net_types() {
+        let json = r#"{"type":"object","properties":{"peers":{"type":"array","items":{"type":"string","format":"ipv4"}},"v4":{"type":"string","format":"ipv4"},"v6":{"type":"string","format":"ipv6"}},"required":["v4","peers"]}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
         let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
-/// Issue status
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub enum Status {
-    #[serde(rename = "closed")]
-    Closed,
-    #[serde(rename = "open")]
-    Open,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    /// Issue status
-    pub status: Status,
+    pub peers: Vec<std::net::Ipv4Addr>,
+    pub v4: std::net::Ipv4Addr,
+    pub v6: Option<std::net::Ipv6Addr>,
 }
 
-"#;
+";
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn dedupe_functional_same_shape_different_description_one_struct() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"First"}"#;
-        let j2 =
-            r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"Second"}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Functional)
-            .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = true;
-        let actual_shared_some = output.shared.is_some();
-        assert_eq!(expected_shared_some, actual_shared_some);
+    fn extensions_emit_json_schema_extension_attributes() {
+        let json = r#"{"type":"object","x-owner":"team-a","properties":{"id":{"type":"integer","x-db":{"column":"user_id","indexed":true}}},"required":["id"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            actual.contains(r#"#[json_schema(extension("x-owner" = "team-a"))]"#),
+            "expected struct extension attr: {actual}"
+        );
+        assert!(
+            actual.contains(
+                r#"#[json_schema(extension("x-db" = {"column": "user_id", "indexed": true}))]"#
+            ),
+            "expected field extension attr: {actual}"
+        );
     }
 
+    #[cfg(not(feature = "chrono"))]
     #[test]
-    fn dedupe_full_same_shape_different_description_two_structs() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"First"}"#;
-        let j2 =
-            r#"{"type":"object","properties":{"id":{"type":"string"}},"description":"Second"}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
-            .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = false;
-        let actual_shared_some = output.shared.is_some();
-        assert_eq!(expected_shared_some, actual_shared_some);
-        assert_eq!(2, output.per_schema.len());
+    fn date_time_format_without_chrono_feature_is_string() {
+        let json = r#"{"type":"object","properties":{"at":{"type":"string","format":"date-time"}},"required":["at"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            actual.contains("pub at: String,"),
+            "expected String: {actual}"
+        );
     }
 
     #[test]
-    fn dedupe_functional_same_shape_different_comment_one_struct() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"First"}"#;
-        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"Second"}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Functional)
-            .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = true;
-        let actual_shared_some = output.shared.is_some();
-        assert_eq!(expected_shared_some, actual_shared_some);
+    fn types_reachable_only_through_one_of_are_emitted() {
+        let json = r##"{"$defs":{"TypeA":{"type":"object","properties":{"x":{"type":"string"}}},"TypeB":{"type":"object","properties":{"mode":{"enum":["m1","m2"]}}}},"type":"object","title":"Root","properties":{"a":{"$ref":"#/$defs/TypeA"},"choice":{"oneOf":[{"$ref":"#/$defs/TypeB"},{"type":"string"}]}}}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub ") || line.contains("Variant"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub enum Mode {",
+            "pub enum ChoiceOneOf {",
+            "    Variant0(TypeB),",
+            "    Variant1(String),",
+            "pub struct TypeA {",
+            "pub struct TypeB {",
+            "pub struct Root {",
+        ];
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn dedupe_full_same_shape_different_comment_two_structs() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"First"}"#;
-        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"$comment":"Second"}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
-            .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = false;
-        let actual_shared_some = output.shared.is_some();
-        assert_eq!(expected_shared_some, actual_shared_some);
-        assert_eq!(2, output.per_schema.len());
+    fn ref_union_used_by_two_properties_is_emitted_once() {
+        let json = r##"{"$defs":{"U":{"oneOf":[{"type":"string"},{"type":"integer"}]}},"type":"object","properties":{"u1":{"$ref":"#/$defs/U"},"u2":{"$ref":"#/$defs/U"}}}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub enum") || line.contains(": Option<"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub enum UOneOf {",
+            "    pub u1: Option<UOneOf>,",
+            "    pub u2: Option<UOneOf>,",
+        ];
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn examples_golden_with_examples_emits_doc() {
-        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":[{"x":"foo"}]}"#;
+    fn additional_properties_object_struct_is_emitted() {
+        let json = r#"{"type":"object","properties":{"m":{"type":"object","properties":{"n":{"type":"string"}},"additionalProperties":{"type":"object","properties":{"v":{"type":"integer"}}}}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::default();
+        let settings: CodeGenSettings = default_settings();
         let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// {\"x\":\"foo\"}\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
+        let out = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let actual: Vec<&str> = out
+            .lines()
+            .filter(|line| line.starts_with("pub struct"))
+            .collect();
+        let expected: Vec<&str> = vec![
+            "pub struct Additional {",
+            "pub struct M {",
+            "pub struct Root {",
+        ];
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn examples_struct_doc_contains_examples() {
-        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":["a",1]}"#;
+    fn emit_all_defs_emits_unreferenced_defs() {
+        let json = r#"{"$defs":{"Lib":{"type":"object","properties":{"k":{"type":"boolean"}}},"Level":{"enum":["low","high"]}},"type":"object","properties":{"id":{"type":"integer"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::default();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// \"a\"\n/// 1\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
+        let declarations = |settings: &CodeGenSettings| -> Vec<String> {
+            let output: super::GenerateRustOutput =
+                generate_rust(std::slice::from_ref(&schema), settings).unwrap();
+            String::from_utf8(output.per_schema[0].clone())
+                .unwrap()
+                .lines()
+                .filter(|line| line.starts_with("pub "))
+                .map(String::from)
+                .collect()
+        };
+
+        let actual: Vec<String> = declarations(&default_settings());
+        let expected: Vec<String> = vec!["pub struct Root {".to_string()];
+        assert_eq!(expected, actual);
+
+        let settings: CodeGenSettings = CodeGenSettings::builder().emit_all_defs(true).build();
+        let actual: Vec<String> = declarations(&settings);
+        let expected: Vec<String> = vec![
+            "pub enum Level {".to_string(),
+            "pub struct Lib {".to_string(),
+            "pub struct Root {".to_string(),
+        ];
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn examples_enum_doc_contains_examples() {
-        let json: &str = r#"{"type":"object","properties":{"status":{"enum":["open"],"examples":["open"]}},"required":["status"]}"#;
+    fn emit_all_defs_reports_defs_without_a_type() {
+        let json = r#"{"$defs":{
+            "UnusedArr":{"type":"array","items":{"type":"string"}},
+            "Count":{"type":"integer"},
+            "Money":{"type":"string","x-rust-type":"crate::Money"},
+            "Pick":{"anyOf":[{"type":"string"},{"type":"integer"}]},
+            "Lib":{"type":"object","properties":{"k":{"type":"boolean"}}}
+        },"type":"object","properties":{"id":{"type":"integer"}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::default();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected: String = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n/// \n/// # Examples\n/// \n/// \"open\"\n#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub enum Status {\n    #[serde(rename = \"open\")]\n    Open,\n}\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub status: Status,\n}\n\n".to_string();
+        let settings: CodeGenSettings = CodeGenSettings::builder().emit_all_defs(true).build();
+        let output: super::GenerateRustOutput =
+            generate_rust(&[schema.clone(), schema.clone()], &settings).unwrap();
+        let actual: Vec<String> = output
+            .skipped_defs
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let expected: Vec<String> = [0, 1]
+            .into_iter()
+            .flat_map(|index| {
+                ["Count", "UnusedArr"].map(|name| {
+                    format!(
+                        "no type generated for #/$defs/{name} in schema {index}: only objects with properties, string enums and anyOf/oneOf generate a type of their own"
+                    )
+                })
+            })
+            .collect();
         assert_eq!(expected, actual);
+
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &default_settings()).unwrap();
+        assert!(output.skipped_defs.is_empty());
     }
 
     #[test]
-    fn examples_empty_array_emits_no_doc() {
-        let json: &str = r#"{"type":"object","properties":{"x":{"type":"string"}},"required":["x"],"examples":[]}"#;
-        let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = CodeGenSettings::default();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected: String =
-            "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{Deserialize, Serialize};\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub x: String,\n}\n\n".to_string();
-        assert_eq!(expected, actual);
+    fn dedupe_shared_struct_keeps_union_field() {
+        let json = r#"{"type":"object","properties":{"item":{"type":"object","properties":{"v":{"oneOf":[{"type":"string"},{"type":"integer"}]}}}}}"#;
+        let a: JsonSchema = serde_json::from_str(json).unwrap();
+        let b: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings = default_settings();
+        let output: super::GenerateRustOutput = generate_rust(&[a, b], &settings).unwrap();
+        let shared = String::from_utf8(output.shared.unwrap()).unwrap();
+        assert!(
+            shared.contains("pub enum VOneOf {") && shared.contains("    pub v: Option<VOneOf>,"),
+            "expected union enum and field in shared module: {shared}"
+        );
     }
 
     #[test]
-    fn dedupe_full_same_shape_different_examples_two_structs() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[1]}"#;
-        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[2]}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+    fn extra_derives_are_skipped_where_fields_do_not_support_them() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string"},"point":{"type":"object","properties":{"x":{"type":"number"}}},"tags":{"type":"array","items":{"type":"string"}}}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
+            .derive("Eq")
+            .derive("Hash")
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected: (bool, usize) = (false, 2);
-        let actual: (bool, usize) = (output.shared.is_some(), output.per_schema.len());
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Point {
+    pub x: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub name: Option<String>,
+    pub point: Option<Point>,
+    pub tags: Option<Vec<String>>,
+}
+
+";
+        assert_eq!(expected, actual);
+        let actual: Vec<String> = output
+            .skipped_derives
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let expected: Vec<String> = vec![
+            "skipped derive(Eq) on Point: `x: Option<f64>` does not implement Eq".to_string(),
+            "skipped derive(Hash) on Point: `x: Option<f64>` does not implement Hash".to_string(),
+            "skipped derive(Eq) on Root: `point: Option<Point>` does not implement Eq".to_string(),
+            "skipped derive(Hash) on Root: `point: Option<Point>` does not implement Hash"
+                .to_string(),
+        ];
         assert_eq!(expected, actual);
     }
 
     #[test]
-    fn dedupe_functional_same_shape_different_examples_one_struct() {
-        let j1 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[1]}"#;
-        let j2 = r#"{"type":"object","properties":{"id":{"type":"string"}},"examples":[2]}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+    fn type_derives_and_attributes_by_name_and_pointer() {
+        let json = r##"{"$defs":{"address":{"type":"object","properties":{"city":{"type":"string"}}}},"type":"object","properties":{"home":{"$ref":"#/$defs/address"},"kind":{"enum":["a","b"]}},"required":["kind"]}"##;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Functional)
+            .attribute("#[allow(missing_docs)]")
+            .type_derive("#/$defs/address", "Default")
+            .type_derive("#/properties/kind", "Hash")
+            .type_derive("Root", "Default")
+            .type_attribute("Root", "#[non_exhaustive]")
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = true;
-        let actual_shared_some = output.shared.is_some();
-        assert_eq!(expected_shared_some, actual_shared_some);
+        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
+        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[allow(missing_docs)]
+pub enum Kind {
+    #[serde(rename = "a")]
+    A,
+    #[serde(rename = "b")]
+    B,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[allow(missing_docs)]
+pub struct Address {
+    pub city: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub struct Root {
+    pub home: Option<Address>,
+    pub kind: Kind,
+}
+
+"#;
+        assert_eq!(expected, actual);
+        let actual: Vec<String> = output
+            .skipped_derives
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let expected: Vec<String> = vec![
+            "skipped derive(Default) on Root: `kind: Kind` does not implement Default".to_string(),
+        ];
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn dedupe_full_same_shape_different_id_two_structs() {
-        let j1 = r#"{"$id":"http://example.com/a","type":"object","properties":{"x":{"type":"string"}}}"#;
-        let j2 = r#"{"$id":"http://example.com/b","type":"object","properties":{"x":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+    fn type_derives_see_derives_kept_by_shared_types() {
+        let json = r#"{"type":"object","properties":{"point":{"type":"object","properties":{"x":{"type":"number"}}},"label":{"type":"object","properties":{"text":{"type":"string"}}}}}"#;
+        let a: JsonSchema = serde_json::from_str(json).unwrap();
+        let b: JsonSchema = serde_json::from_str(&json.replace("string", "integer")).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
+            .type_derive("Root", "Hash")
+            .type_derive("Label", "Hash")
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = false;
-        let actual_shared_some = output.shared.is_some();
-        let msg: &str = concat!(
-            "Full dedupe: same shape different id yields two ",
-            "structs"
+        let output: super::GenerateRustOutput = generate_rust(&[a, b], &settings).unwrap();
+        let shared = String::from_utf8(output.shared.unwrap()).unwrap();
+        assert!(
+            shared.contains("#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Point {"),
+            "shared Point gets no extra derives: {shared}"
         );
-        assert_eq!(expected_shared_some, actual_shared_some, "{msg}");
+        let first = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(
+            first.contains("#[derive(Debug, Clone, Hash, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Label {"),
+            "local Label derives Hash: {first}"
+        );
+        let actual: Vec<String> = output
+            .skipped_derives
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let expected: Vec<String> = vec![
+            "skipped derive(Hash) on Root: `point: Option<Point>` does not implement Hash"
+                .to_string(),
+            "skipped derive(Hash) on Root: `point: Option<Point>` does not implement Hash"
+                .to_string(),
+        ];
+        assert_eq!(expected, actual);
     }
 
     #[test]
-    fn dedupe_functional_same_shape_different_id_one_struct() {
-        let j1 = r#"{"$id":"http://example.com/a","type":"object","properties":{"x":{"type":"string"}}}"#;
-        let j2 = r#"{"$id":"http://example.com/b","type":"object","properties":{"x":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(j1).unwrap();
-        let s2: JsonSchema = serde_json::from_str(j2).unwrap();
+    fn unmatched_type_selectors_are_reported() {
+        let json = r#"{"type":"object","properties":{"point":{"type":"object","properties":{"x":{"type":"number"}}},"kind":{"type":"string"}}}"#;
+        let a: JsonSchema = serde_json::from_str(json).unwrap();
+        let b: JsonSchema = serde_json::from_str(json).unwrap();
         let settings: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Functional)
+            .type_derive("Point", "PartialEq")
+            .type_derive("#/properties/point", "Default")
+            .type_derive("Nope", "Hash")
+            .type_attribute("#/properties/kind", "#[allow(dead_code)]")
             .build();
-        let output: super::GenerateRustOutput = generate_rust(&[s1, s2], &settings).unwrap();
-        let expected_shared_some = true;
-        let actual_shared_some = output.shared.is_some();
-        let msg: &str = concat!(
-            "Functional dedupe: same shape different id yields one shared ",
-            "struct"
-        );
-        assert_eq!(expected_shared_some, actual_shared_some, "{msg}");
+        let output: super::GenerateRustOutput =
+            generate_rust(&[a.clone(), b.clone()], &settings).unwrap();
+        assert!(output.shared.is_some(), "Point is shared");
+        let expected: Vec<String> = vec!["#/properties/kind".to_string(), "Nope".to_string()];
+        assert_eq!(expected, output.unmatched_type_selectors);
+
+        let settings: CodeGenSettings = CodeGenSettings {
+            dedupe_mode: DedupeMode::Disabled,
+            ..settings
+        };
+        let output: super::GenerateRustOutput = generate_rust(&[a, b], &settings).unwrap();
+        assert_eq!(expected, output.unmatched_type_selectors);
     }
 
     #[test]
-    fn dedupe_functional_same_as_full_when_no_description() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string"}}}"#;
-        let s1: JsonSchema = serde_json::from_str(json).unwrap();
-        let s2: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings_full: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Full)
-            .build();
-        let settings_func: CodeGenSettings = CodeGenSettings::builder()
-            .dedupe_mode(DedupeMode::Functional)
-            .build();
-        let output_full: super::GenerateRustOutput =
-            generate_rust(&[s1.clone(), s2.clone()], &settings_full).unwrap();
-        let output_func: super::GenerateRustOutput =
-            generate_rust(&[s1, s2], &settings_func).unwrap();
-        assert_eq!(output_full.shared.is_some(), output_func.shared.is_some());
-        assert_eq!(output_full.per_schema.len(), output_func.per_schema.len());
+    #[expect(clippy::too_many_lines)]
+    fn enforce_constraints_emits_validated_newtypes() {
+        let json = r#"{"type":"object","properties":{"code":{"type":"integer","maximum":9,"default":42},"name":{"type":"string","minLength":2,"default":"anon"},"tags":{"type":"array","items":{"type":"string"},"minItems":1}},"required":["name","code"]}"#;
+        let schema: JsonSchema = serde_json::from_str(json).unwrap();
+        let settings: CodeGenSettings =
+            CodeGenSettings::builder().enforce_constraints(true).build();
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        // The default of `code` violates its maximum, so it is left out.
+        let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+fn default_root_name() -> RootName { RootName::try_from("anon".to_string()).expect("default satisfies the schema") }
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub code: RootCode,
+    #[json_schema(min_length = 2)]
+    #[serde(default = "default_root_name")]
+    pub name: RootName,
+    #[json_schema(min_items = 1)]
+    #[serde(default, deserialize_with = "deserialize_root_tags")]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i64")]
+pub struct RootCode(i64);
+
+impl RootCode {
+    /// The validated value.
+    pub fn get(&self) -> &i64 {
+        &self.0
     }
 
-    #[test]
-    fn default_settings_use_full_dedupe() {
-        let settings: CodeGenSettings = CodeGenSettings::builder().build();
-        let expected = DedupeMode::Full;
-        let actual = settings.dedupe_mode;
+    /// Unwraps the validated value.
+    pub fn into_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl TryFrom<i64> for RootCode {
+    type Error = ::json_schema_rs::constraints::ConstraintError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        ::json_schema_rs::constraints::check(&value, &<Self as ::json_schema_rs::ToJsonSchema>::json_schema())?;
+        Ok(Self(value))
+    }
+}
+
+impl ::json_schema_rs::ToJsonSchema for RootCode {
+    fn json_schema() -> ::json_schema_rs::JsonSchema {
+        ::json_schema_rs::JsonSchema {
+            type_: Some("integer".to_string()),
+            minimum: None,
+            maximum: Some(9.0),
+            ..<i64 as ::json_schema_rs::ToJsonSchema>::json_schema()
+        }
+    }
+}
+
+impl ::json_schema_rs::Validate for RootCode {
+    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RootName(String);
+
+impl RootName {
+    /// The validated value.
+    pub fn get(&self) -> &String {
+        &self.0
+    }
+
+    /// Unwraps the validated value.
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for RootName {
+    type Error = ::json_schema_rs::constraints::ConstraintError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        ::json_schema_rs::constraints::check(&value, &<Self as ::json_schema_rs::ToJsonSchema>::json_schema())?;
+        Ok(Self(value))
+    }
+}
+
+impl ::json_schema_rs::ToJsonSchema for RootName {
+    fn json_schema() -> ::json_schema_rs::JsonSchema {
+        ::json_schema_rs::JsonSchema {
+            type_: Some("string".to_string()),
+            min_length: Some(2),
+            max_length: None,
+            ..<String as ::json_schema_rs::ToJsonSchema>::json_schema()
+        }
+    }
+}
+
+impl ::json_schema_rs::Validate for RootName {
+    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {
+        Ok(())
+    }
+}
+
+fn deserialize_root_tags<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<String>>, D::Error> {
+    let value: Option<Vec<String>> = Deserialize::deserialize(deserializer)?;
+    if let Some(items) = &value {
+        ::json_schema_rs::constraints::check_items(items.len(), Some(1), None)
+            .map_err(|e| serde::de::Error::custom(e.within("tags")))?;
+    }
+    Ok(value)
+}
+
+impl ::json_schema_rs::Validate for Root {
+    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {
+        if let Some(items) = &self.tags {
+            ::json_schema_rs::constraints::check_items(items.len(), Some(1), None).map_err(|e| e.within("tags"))?;
+        }
+        Ok(())
+    }
+}
+
+"#;
         assert_eq!(expected, actual);
     }
 
-    #[cfg(feature = "uuid")]
     #[test]
-    fn uuid_required_property() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string","format":"uuid"}},"required":["id"]}"#;
+    fn enforce_constraints_validates_unions_and_tagged_variants() {
+        let json = r#"{"type":"object","properties":{"contact":{"anyOf":[{"type":"object","title":"Email","properties":{"address":{"type":"string","minLength":3}}},{"type":"integer"}]},"shape":{"oneOf":[{"type":"object","properties":{"kind":{"const":"circle"},"radius":{"type":"number","minimum":0},"tags":{"type":"array","items":{"type":"string"},"maxItems":2}},"required":["kind","radius"]},{"type":"object","properties":{"kind":{"const":"dot"}},"required":["kind"]}]}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+        let settings: CodeGenSettings =
+            CodeGenSettings::builder().enforce_constraints(true).build();
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
+        let source: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let impl_of = |name: &str| -> String {
+            let start: usize = source
+                .find(&format!("impl ::json_schema_rs::Validate for {name} {{"))
+                .unwrap();
+            let end: usize = start + source[start..].find("\n}\n").unwrap() + 3;
+            source[start..end].to_string()
+        };
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub id: Uuid,
+        let expected = r"impl ::json_schema_rs::Validate for ContactAnyOf {
+    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {
+        match self {
+            Self::Variant0(value) => ::json_schema_rs::Validate::validate(value),
+            Self::Variant1(_) => Ok(()),
+        }
+    }
 }
-
 ";
-        assert_eq!(expected, actual);
+        assert_eq!(expected, impl_of("ContactAnyOf"));
+
+        let expected = r#"impl ::json_schema_rs::Validate for ShapeOneOf {
+    fn validate(&self) -> Result<(), ::json_schema_rs::ValidationError> {
+        match self {
+            Self::Circle { tags, .. } => {
+                if let Some(items) = tags {
+                    ::json_schema_rs::constraints::check_items(items.len(), None, Some(2)).map_err(|e| e.within("tags"))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+"#;
+        assert!(source.contains("        radius: ShapeOneOfCircleRadius,\n"));
+        assert!(source.contains("pub struct ShapeOneOfCircleRadius(f64);\n"));
+        assert_eq!(expected, impl_of("ShapeOneOf"));
     }
 
-    #[cfg(feature = "uuid")]
     #[test]
-    fn uuid_optional_property() {
-        let json = r#"{"type":"object","properties":{"id":{"type":"string","format":"uuid"}}}"#;
+    fn type_mappings_use_existing_types_instead_of_generating() {
+        let json = r##"{"type":"object","properties":{"history":{"type":"array","items":{"$ref":"#/$defs/Money"}},"note":{"type":"object","x-rust-type":"crate::Note","properties":{"text":{"type":"string"}}},"placed_at":{"$ref":"https://example.com/timestamp.json"},"total":{"$ref":"#/$defs/Money"}},"required":["total"],"$defs":{"Money":{"type":"object","properties":{"cents":{"type":"integer"}}}}}"##;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .type_mapping("#/$defs/Money", "crate::Money")
+            .type_mapping("https://example.com/timestamp.json", "crate::Timestamp")
+            .emit_all_defs(true)
+            .build();
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
         let expected = r"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
 pub struct Root {
-    pub id: Option<Uuid>,
+    pub history: Option<Vec<crate::Money>>,
+    pub note: Option<crate::Note>,
+    pub placed_at: Option<crate::Timestamp>,
+    pub total: crate::Money,
 }
 
 ";
         assert_eq!(expected, actual);
     }
 
-    #[cfg(feature = "uuid")]
     #[test]
-    fn uuid_array_items() {
-        let json = r#"{"type":"object","properties":{"ids":{"type":"array","items":{"type":"string","format":"uuid"}}},"required":["ids"]}"#;
+    fn type_mapping_by_title_keeps_union_branch_order() {
+        let json = r#"{"type":"object","properties":{"amount":{"anyOf":[{"type":"string"},{"type":"object","title":"Money","properties":{"cents":{"type":"integer"}}}]}}}"#;
         let schema: JsonSchema = serde_json::from_str(json).unwrap();
-        let settings: CodeGenSettings = default_settings();
-        let output: super::GenerateRustOutput = generate_rust(&[schema], &settings).unwrap();
-        let actual = String::from_utf8(output.per_schema[0].clone()).unwrap();
-        let expected = "//! Generated by json-schema-rs. Do not edit manually.\n\nuse serde::{\"Deserialize\", \"Serialize\"};\nuse uuid::Uuid;\n\n#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\npub struct Root {\n    pub ids: Vec<Uuid>,\n}\n\n";
-        assert_eq!(expected, actual);
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .type_mapping("Money", "money::Money")
+            .dedupe_mode(DedupeMode::Disabled)
+            .build();
+        let output: super::GenerateRustOutput =
+            generate_rust(std::slice::from_ref(&schema), &settings).unwrap();
+        let actual: String = String::from_utf8(output.per_schema[0].clone()).unwrap();
+        assert!(!actual.contains("pub struct Money"));
+        assert!(actual.contains(
+            "pub enum AmountAnyOf {\n    Variant1(money::Money),\n    Variant0(String),\n}\n"
+        ));
     }
 }
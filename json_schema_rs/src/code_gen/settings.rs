@@ -1,5 +1,7 @@
 //! Code generation settings (model naming, dedupe mode, etc.).
 
+use std::collections::BTreeMap;
+
 /// How to choose the generated struct/type name when both `title` and property key are available.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ModelNameSource {
@@ -23,6 +25,37 @@ pub enum DedupeMode {
     Full,
 }
 
+/// How to rename a generated type whose name is already taken by a different type in the same
+/// module (e.g. two `status` enums with different values landing in `shared.rs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameCollisionStrategy {
+    /// Append the lowest free number, starting at 2 (`Status`, `Status2`).
+    #[default]
+    NumericSuffix,
+    /// Prefix the name of the enclosing struct (`Status` inside `Order` becomes `OrderStatus`).
+    /// Falls back to a numeric suffix for roots and when the prefixed name is taken too.
+    ParentPrefix,
+}
+
+/// Extra derives and raw attributes to put on generated types.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TypeAttributes {
+    /// Trait names added to the `#[derive(...)]` list (e.g. `"PartialEq"`, `"Hash"`). Requested
+    /// `PartialEq`, `Eq`, `PartialOrd`, `Ord`, `Hash`, `Default`, and `Copy` derives are skipped,
+    /// with a diagnostic, on types whose fields cannot support them.
+    pub derives: Vec<String>,
+    /// Attributes written verbatim after the derive line (e.g. `"#[non_exhaustive]"`).
+    pub attributes: Vec<String>,
+}
+
+impl TypeAttributes {
+    /// True when neither derives nor attributes are requested.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.derives.is_empty() && self.attributes.is_empty()
+    }
+}
+
 /// Language-agnostic code generation settings.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CodeGenSettings {
@@ -30,6 +63,29 @@ pub struct CodeGenSettings {
     pub model_name_source: ModelNameSource,
     /// Whether and how to deduplicate identical object schemas (Disabled, Functional, Full).
     pub dedupe_mode: DedupeMode,
+    /// How to rename colliding type names. Names taken from `title` are never renamed.
+    pub name_collision: NameCollisionStrategy,
+    /// Generate the types of every `$defs`/`definitions` entry, even when nothing references it
+    /// (for using a schema as a library of types). Only objects with properties, string enums and
+    /// anyOf/oneOf generate a type of their own; array, map and scalar entries are listed in
+    /// [`GenerateRustOutput::skipped_defs`](crate::GenerateRustOutput::skipped_defs) instead.
+    pub emit_all_defs: bool,
+    /// Extra derives and attributes for every generated struct and enum.
+    pub type_attributes: TypeAttributes,
+    /// Extra derives and attributes for single types, keyed by generated type name (`"Address"`)
+    /// or by JSON Pointer to the schema the type is generated from (`"#/$defs/address"`).
+    pub type_attributes_by_type: BTreeMap<String, TypeAttributes>,
+    /// Generate types that enforce `minLength`, `maxLength`, `pattern`, `minimum`, `maximum`,
+    /// `minItems`, `maxItems`, `enum`, and `const` during deserialization: constrained scalars
+    /// become validated newtypes and every generated type implements [`Validate`].
+    ///
+    /// [`Validate`]: crate::constraints::Validate
+    pub enforce_constraints: bool,
+    /// Existing Rust types to use instead of generating one, keyed by JSON Pointer to the schema
+    /// (`"#/$defs/money"`), a `$ref` or `$id` value, or a `title`; the value is a type path
+    /// (`"crate::money::Money"`). A schema can name its type itself with the `x-rust-type`
+    /// keyword. Mapped schemas generate nothing, and fields holding them use the path.
+    pub type_mappings: BTreeMap<String, String>,
 }
 
 impl Default for CodeGenSettings {
@@ -37,6 +93,12 @@ impl Default for CodeGenSettings {
         Self {
             model_name_source: ModelNameSource::TitleFirst,
             dedupe_mode: DedupeMode::Full,
+            name_collision: NameCollisionStrategy::NumericSuffix,
+            emit_all_defs: false,
+            type_attributes: TypeAttributes::default(),
+            type_attributes_by_type: BTreeMap::new(),
+            enforce_constraints: false,
+            type_mappings: BTreeMap::new(),
         }
     }
 }
@@ -46,6 +108,12 @@ impl Default for CodeGenSettings {
 pub struct CodeGenSettingsBuilder {
     model_name_source: Option<ModelNameSource>,
     dedupe_mode: Option<DedupeMode>,
+    name_collision: Option<NameCollisionStrategy>,
+    emit_all_defs: Option<bool>,
+    type_attributes: TypeAttributes,
+    type_attributes_by_type: BTreeMap<String, TypeAttributes>,
+    enforce_constraints: Option<bool>,
+    type_mappings: BTreeMap<String, String>,
 }
 
 impl CodeGenSettingsBuilder {
@@ -63,12 +131,83 @@ impl CodeGenSettingsBuilder {
         self
     }
 
+    /// Set how colliding type names are renamed (numeric suffix or parent prefix).
+    #[must_use]
+    pub fn name_collision(mut self, value: NameCollisionStrategy) -> Self {
+        self.name_collision = Some(value);
+        self
+    }
+
+    /// Set whether every `$defs`/`definitions` entry gets a type, referenced or not.
+    #[must_use]
+    pub fn emit_all_defs(mut self, value: bool) -> Self {
+        self.emit_all_defs = Some(value);
+        self
+    }
+
+    /// Set whether generated types enforce schema constraints during deserialization.
+    #[must_use]
+    pub fn enforce_constraints(mut self, value: bool) -> Self {
+        self.enforce_constraints = Some(value);
+        self
+    }
+
+    /// Add a derive to every generated struct and enum (e.g. `"Hash"`).
+    #[must_use]
+    pub fn derive(mut self, value: impl Into<String>) -> Self {
+        self.type_attributes.derives.push(value.into());
+        self
+    }
+
+    /// Add a raw attribute to every generated struct and enum (e.g. `"#[non_exhaustive]"`).
+    #[must_use]
+    pub fn attribute(mut self, value: impl Into<String>) -> Self {
+        self.type_attributes.attributes.push(value.into());
+        self
+    }
+
+    /// Add a derive to one type, selected by type name or JSON Pointer.
+    #[must_use]
+    pub fn type_derive(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.type_attributes_by_type
+            .entry(key.into())
+            .or_default()
+            .derives
+            .push(value.into());
+        self
+    }
+
+    /// Add a raw attribute to one type, selected by type name or JSON Pointer.
+    #[must_use]
+    pub fn type_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.type_attributes_by_type
+            .entry(key.into())
+            .or_default()
+            .attributes
+            .push(value.into());
+        self
+    }
+
+    /// Map the schemas matching `key` (JSON Pointer, `$ref`, `$id`, or `title`) to the existing
+    /// Rust type `rust_type`.
+    #[must_use]
+    pub fn type_mapping(mut self, key: impl Into<String>, rust_type: impl Into<String>) -> Self {
+        self.type_mappings.insert(key.into(), rust_type.into());
+        self
+    }
+
     /// Build the settings. Any option not set uses its per-option default.
     #[must_use]
     pub fn build(self) -> CodeGenSettings {
         CodeGenSettings {
             model_name_source: self.model_name_source.unwrap_or_default(),
             dedupe_mode: self.dedupe_mode.unwrap_or_default(),
+            name_collision: self.name_collision.unwrap_or_default(),
+            emit_all_defs: self.emit_all_defs.unwrap_or_default(),
+            type_attributes: self.type_attributes,
+            type_attributes_by_type: self.type_attributes_by_type,
+            enforce_constraints: self.enforce_constraints.unwrap_or_default(),
+            type_mappings: self.type_mappings,
         }
     }
 }
@@ -0,0 +1,276 @@
+//! Schemas mapped to existing Rust types instead of generated ones.
+//!
+//! A schema is mapped when it carries the `x-rust-type` keyword or matches a key of
+//! [`CodeGenSettings::type_mappings`]. [`apply_type_mappings`] resolves both into the keyword on
+//! a copy of the schema before codegen, and copies it onto every `$ref` that leads to a mapped
+//! schema, so the backend only has to look at [`mapped_type`] of the schema in hand.
+
+use super::settings::CodeGenSettings;
+use super::{CodeGenError, CodeGenResult};
+use crate::json_schema::JsonSchema;
+use crate::json_schema::ref_resolver;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Vendor keyword naming the Rust type a schema maps to (e.g. `"crate::money::Money"`).
+pub(crate) const RUST_TYPE_KEYWORD: &str = "x-rust-type";
+
+/// The Rust type path `schema` maps to, if any.
+pub(crate) fn mapped_type(schema: &JsonSchema) -> Option<&str> {
+    schema
+        .extensions
+        .get(RUST_TYPE_KEYWORD)
+        .and_then(serde_json::Value::as_str)
+}
+
+/// Returns `root` with [`RUST_TYPE_KEYWORD`] set on every mapped subschema. A settings key
+/// matches a subschema by JSON Pointer (`#/$defs/money`), by its `$ref`, `$id`, or `title`, in
+/// that order, and overrides a keyword already in the schema. A `$ref` whose target (followed
+/// transitively) is mapped maps to the same type.
+///
+/// Every mapped type is pasted into the generated code, so each one must be a Rust type path
+/// ([`is_rust_type_path`]); anything else is [`CodeGenError::InvalidTypeMapping`].
+pub(crate) fn apply_type_mappings(
+    root: &JsonSchema,
+    settings: &CodeGenSettings,
+) -> CodeGenResult<JsonSchema> {
+    if let Some((key, ty)) = settings
+        .type_mappings
+        .iter()
+        .find(|(_, ty)| !is_rust_type_path(ty))
+    {
+        return Err(CodeGenError::InvalidTypeMapping {
+            key: key.clone(),
+            rust_type: ty.clone(),
+        });
+    }
+    if let Some((pointer, value)) = root.walk().find_map(|(pointer, _, schema)| {
+        schema
+            .extensions
+            .get(RUST_TYPE_KEYWORD)
+            .filter(|value| !value.as_str().is_some_and(is_rust_type_path))
+            .map(|value| (pointer, value))
+    }) {
+        return Err(CodeGenError::InvalidTypeMapping {
+            key: format!("{RUST_TYPE_KEYWORD} at #{}", pointer.as_str()),
+            rust_type: value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string),
+        });
+    }
+    let mut out: JsonSchema = root.clone();
+    if !settings.type_mappings.is_empty() {
+        let by_pointer: BTreeMap<&str, &String> = settings
+            .type_mappings
+            .iter()
+            .filter(|(key, _)| key.starts_with('#') || key.starts_with('/'))
+            .map(|(key, ty)| (key.trim_start_matches('#'), ty))
+            .collect();
+        out.walk_mut(|pointer, _, schema| {
+            let ty: Option<&String> = by_pointer.get(pointer.as_str()).copied().or_else(|| {
+                [&schema.ref_, &schema.id, &schema.title]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|key| settings.type_mappings.get(key))
+            });
+            if let Some(ty) = ty {
+                set_mapped_type(schema, ty);
+            }
+        });
+    }
+    let inherited: BTreeMap<String, String> = out
+        .walk()
+        .filter(|(_, _, schema)| mapped_type(schema).is_none())
+        .filter_map(|(pointer, _, schema)| {
+            ref_target_mapping(&out, schema)
+                .map(|ty| (pointer.as_str().to_string(), ty.to_string()))
+        })
+        .collect();
+    if !inherited.is_empty() {
+        out.walk_mut(|pointer, _, schema| {
+            if let Some(ty) = inherited.get(pointer.as_str()) {
+                set_mapped_type(schema, ty);
+            }
+        });
+    }
+    Ok(out)
+}
+
+/// True when `ty` is a Rust type path: `::`-separated identifiers, optionally with a leading `::`,
+/// where each segment may take generic arguments that are type paths themselves
+/// (`crate::money::Money`, `Vec<crate::Id>`, `std::collections::BTreeMap<String, u8>`).
+pub(crate) fn is_rust_type_path(ty: &str) -> bool {
+    fn path(s: &str) -> Option<&str> {
+        let mut rest: &str = s.trim_start();
+        rest = rest.strip_prefix("::").unwrap_or(rest);
+        loop {
+            let len: usize = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let ident: &str = &rest[..len];
+            if ident.is_empty() || ident == "_" || ident.starts_with(|c: char| c.is_ascii_digit()) {
+                return None;
+            }
+            rest = rest[len..].trim_start();
+            if let Some(args) = rest.strip_prefix('<') {
+                rest = path(args)?;
+                while let Some(next) = rest.strip_prefix(',') {
+                    rest = path(next)?;
+                }
+                rest = rest.strip_prefix('>')?.trim_start();
+            }
+            match rest.strip_prefix("::") {
+                Some(next) => rest = next.trim_start(),
+                None => return Some(rest),
+            }
+        }
+    }
+    path(ty).is_some_and(str::is_empty)
+}
+
+/// Every Rust type the (already mapped) `schemas` map to.
+pub(crate) fn mapped_types(schemas: &[JsonSchema]) -> BTreeSet<String> {
+    schemas
+        .iter()
+        .flat_map(JsonSchema::walk)
+        .filter_map(|(_, _, schema)| mapped_type(schema).map(str::to_string))
+        .collect()
+}
+
+/// Marks `schema` as mapped to `ty`.
+pub(crate) fn set_mapped_type(schema: &mut JsonSchema, ty: &str) {
+    schema.extensions.insert(
+        RUST_TYPE_KEYWORD.to_string(),
+        serde_json::Value::String(ty.to_string()),
+    );
+}
+
+/// The mapping of the first mapped schema along the `$ref` chain from `schema`. Refs that do not
+/// resolve end the chain; codegen reports them where it needs the target.
+fn ref_target_mapping<'a>(root: &'a JsonSchema, schema: &'a JsonSchema) -> Option<&'a str> {
+    let mut current: &JsonSchema = schema;
+    let mut visited: BTreeSet<&str> = BTreeSet::new();
+    while let Some(ref_str) = current.ref_.as_deref() {
+        if !visited.insert(ref_str) {
+            return None;
+        }
+        current = ref_resolver::resolve_ref(root, ref_str).ok()?;
+        if let Some(ty) = mapped_type(current) {
+            return Some(ty);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_type_mappings, is_rust_type_path, mapped_type};
+    use crate::code_gen::CodeGenError;
+    use crate::code_gen::settings::CodeGenSettings;
+    use crate::json_schema::JsonSchema;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn settings_keys_match_pointer_ref_id_and_title() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"type":"object","properties":{
+                "price":{"$ref":"#/$defs/money"},
+                "at":{"$ref":"https://example.com/timestamp.json"},
+                "geo":{"$id":"https://example.com/geo.json","type":"object","properties":{"lat":{"type":"number"}}},
+                "tag":{"title":"Tag","type":"string"},
+                "note":{"type":"string"}
+            },"$defs":{"money":{"type":"object","properties":{"cents":{"type":"integer"}}}}}"##,
+        ).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .type_mapping("#/$defs/money", "crate::Money")
+            .type_mapping("https://example.com/timestamp.json", "crate::Timestamp")
+            .type_mapping("https://example.com/geo.json", "crate::Geo")
+            .type_mapping("Tag", "crate::Tag")
+            .build();
+        let mapped: JsonSchema = apply_type_mappings(&schema, &settings).unwrap();
+        let actual: BTreeMap<&str, Option<&str>> = mapped
+            .properties
+            .iter()
+            .map(|(key, prop)| (key.as_str(), mapped_type(prop)))
+            .collect();
+        let expected: BTreeMap<&str, Option<&str>> = BTreeMap::from([
+            ("at", Some("crate::Timestamp")),
+            ("geo", Some("crate::Geo")),
+            ("note", None),
+            ("price", Some("crate::Money")),
+            ("tag", Some("crate::Tag")),
+        ]);
+        assert_eq!(expected, actual);
+        assert_eq!(
+            Some("crate::Money"),
+            mapped_type(&mapped.defs.as_ref().unwrap()["money"])
+        );
+    }
+
+    #[test]
+    fn in_schema_keyword_is_inherited_through_refs_and_overridden_by_settings() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"type":"object","properties":{
+                "a":{"$ref":"#/$defs/alias"},
+                "b":{"type":"string","x-rust-type":"crate::B"}
+            },"$defs":{
+                "alias":{"$ref":"#/$defs/target"},
+                "target":{"type":"string","x-rust-type":"crate::Target"}
+            }}"##,
+        )
+        .unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .type_mapping("/properties/b", "crate::Other")
+            .build();
+        let mapped: JsonSchema = apply_type_mappings(&schema, &settings).unwrap();
+        assert_eq!(Some("crate::Target"), mapped_type(&mapped.properties["a"]));
+        assert_eq!(Some("crate::Other"), mapped_type(&mapped.properties["b"]));
+    }
+
+    #[test]
+    fn rust_type_paths() {
+        for ty in [
+            "Money",
+            "crate::money::Money",
+            "::std::net::Ipv4Addr",
+            "Vec<crate::Id>",
+            "std::collections::BTreeMap<String, Vec<u8>>",
+        ] {
+            assert!(is_rust_type_path(ty), "{ty}");
+        }
+        for ty in [
+            "",
+            "crate::",
+            "Vec<u8",
+            "Vec<>",
+            "1Money",
+            "&str",
+            "String, pub b: i32 } fn evil() {} struct Z {",
+        ] {
+            assert!(!is_rust_type_path(ty), "{ty}");
+        }
+    }
+
+    #[test]
+    fn mappings_that_are_not_type_paths_are_rejected() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"type":"string","x-rust-type":"String, pub b: i32 } fn evil() {} struct Z {"}}}"#,
+        )
+        .unwrap();
+        let err: CodeGenError =
+            apply_type_mappings(&schema, &CodeGenSettings::builder().build()).unwrap_err();
+        assert_eq!(
+            "type mapping \"x-rust-type at #/properties/a\" is not a Rust type path (like crate::money::Money or Vec<crate::Id>): String, pub b: i32 } fn evil() {} struct Z {",
+            err.to_string()
+        );
+
+        let schema: JsonSchema = serde_json::from_str(r#"{"type":"object"}"#).unwrap();
+        let settings: CodeGenSettings = CodeGenSettings::builder()
+            .type_mapping("Tag", "crate::Tag; fn evil() {}")
+            .build();
+        let err: CodeGenError = apply_type_mappings(&schema, &settings).unwrap_err();
+        assert!(
+            matches!(&err, CodeGenError::InvalidTypeMapping { key, .. } if key == "Tag"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Runtime support for generated types that enforce schema constraints.
+//!
+//! With [`CodeGenSettings::enforce_constraints`] the Rust backend emits validated newtypes for
+//! constrained scalars, whose `TryFrom` runs [`check`], and deserializers for arrays with
+//! `minItems`/`maxItems`, which run [`check_items`]. Every generated type implements
+//! [`Validate`], which re-checks a value built or changed in code.
+//!
+//! [`CodeGenSettings::enforce_constraints`]: crate::code_gen::CodeGenSettings::enforce_constraints
+
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::JsonSchema;
+use crate::validator::{ValidationError, validate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// Checks a value against the schema constraints it was generated from.
+///
+/// Generated structs and enums implement it by validating every field, with errors located by
+/// property key or array index; validated newtypes and types without constraints always pass.
+pub trait Validate {
+    /// Returns the first constraint the value violates.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ValidationError`] of the first violated constraint, located relative to
+    /// this value.
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+macro_rules! always_valid {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Validate for $ty {
+                fn validate(&self) -> Result<(), ValidationError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+always_valid!(
+    String,
+    bool,
+    i8,
+    u8,
+    i16,
+    u16,
+    i32,
+    u32,
+    i64,
+    u64,
+    f32,
+    f64,
+    serde_json::Value,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+);
+
+#[cfg(feature = "uuid")]
+always_valid!(uuid::Uuid);
+
+#[cfg(feature = "chrono")]
+always_valid!(chrono::NaiveDate);
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> Validate for chrono::DateTime<Tz> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.as_ref().map_or(Ok(()), Validate::validate)
+    }
+}
+
+impl<T: Validate> Validate for Box<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.as_ref().validate()
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (i, item) in self.iter().enumerate() {
+            item.validate().map_err(|e| e.within(&i.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Set items have no stable index, so errors are located at the set itself.
+impl<T: Validate, S> Validate for HashSet<T, S> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.iter().try_for_each(Validate::validate)
+    }
+}
+
+impl<V: Validate> Validate for BTreeMap<String, V> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (key, value) in self {
+            value.validate().map_err(|e| e.within(key))?;
+        }
+        Ok(())
+    }
+}
+
+/// The error of a validated newtype's `TryFrom`: the violated constraint, without a location.
+///
+/// A newtype does not know where its value sits in the document, so the wrapped
+/// [`ValidationError`] is located at the newtype's own value and the message leaves the location
+/// out; serde adds the line and column when it fails a deserialize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintError(ValidationError);
+
+impl ConstraintError {
+    /// The violated constraint, located relative to the newtype's value.
+    #[must_use]
+    pub fn into_inner(self) -> ValidationError {
+        self.0
+    }
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message: String = self.0.to_string();
+        let location: String = format!("{}: ", self.0.instance_path().display_root_or_path());
+        f.write_str(message.strip_prefix(&location).unwrap_or(&message))
+    }
+}
+
+impl std::error::Error for ConstraintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Checks `value`, as serialized to JSON, against `schema` with [`validate`]. Validated newtypes
+/// call it from `TryFrom` with their own [`ToJsonSchema`](crate::ToJsonSchema) schema.
+///
+/// # Errors
+///
+/// Returns the first [`ValidationError`] the value produces, as a [`ConstraintError`].
+pub fn check<T: Serialize + ?Sized>(value: &T, schema: &JsonSchema) -> Result<(), ConstraintError> {
+    let instance: serde_json::Value = serde_json::to_value(value).map_err(|e| {
+        ConstraintError(ValidationError::Custom {
+            instance_path: JsonPointer::root(),
+            keyword: "type".to_string(),
+            message: format!("value is not representable as JSON: {e}"),
+        })
+    })?;
+    match validate(schema, &instance) {
+        Ok(()) => Ok(()),
+        Err(mut errors) => Err(ConstraintError(errors.swap_remove(0))),
+    }
+}
+
+/// Checks an array length against `minItems` and `maxItems`.
+///
+/// # Errors
+///
+/// Returns [`ValidationError::TooFewItems`] or [`ValidationError::TooManyItems`], located at the
+/// root.
+pub fn check_items(
+    len: usize,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+) -> Result<(), ValidationError> {
+    let actual_count: u64 = len as u64;
+    if let Some(min_items) = min_items
+        && actual_count < min_items
+    {
+        return Err(ValidationError::TooFewItems {
+            instance_path: JsonPointer::root(),
+            min_items,
+            actual_count,
+        });
+    }
+    if let Some(max_items) = max_items
+        && actual_count > max_items
+    {
+        return Err(ValidationError::TooManyItems {
+            instance_path: JsonPointer::root(),
+            max_items,
+            actual_count,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Validate, check, check_items};
+    use crate::json_schema::JsonSchema;
+    use crate::validator::ValidationError;
+    use std::collections::BTreeMap;
+
+    /// Fails every check, to see where container impls locate errors.
+    struct Invalid;
+
+    impl Validate for Invalid {
+        fn validate(&self) -> Result<(), ValidationError> {
+            check_items(0, Some(1), None)
+        }
+    }
+
+    #[test]
+    fn check_uses_validator_messages() {
+        let schema: JsonSchema = JsonSchema {
+            type_: Some("string".to_string()),
+            min_length: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), check("abc", &schema));
+        let actual: String = check("ab", &schema).unwrap_err().to_string();
+        let expected = "string has 2 code points, minLength is 3";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn check_items_bounds() {
+        assert_eq!(Ok(()), check_items(2, Some(1), Some(2)));
+        let actual: String = check_items(3, Some(1), Some(2)).unwrap_err().to_string();
+        let expected = "root: array has 3 item(s), maximum is 2";
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn containers_locate_errors_by_index_and_key() {
+        let items: Vec<Option<Invalid>> = vec![None, Some(Invalid)];
+        let actual: String = items.validate().unwrap_err().to_string();
+        let expected = "/1: array has 0 item(s), minimum is 1";
+        assert_eq!(expected, actual);
+
+        let map: BTreeMap<String, Box<Invalid>> =
+            BTreeMap::from([("a/b".to_string(), Box::new(Invalid))]);
+        let actual: String = map.validate().unwrap_err().within("m").to_string();
+        let expected = "/m/a~1b: array has 0 item(s), minimum is 1";
+        assert_eq!(expected, actual);
+    }
+}
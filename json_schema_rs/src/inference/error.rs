@@ -0,0 +1,45 @@
+//! Errors when inferring a schema from samples.
+
+use std::fmt;
+
+/// Result type for schema inference from readers.
+pub type InferResult<T> = Result<T, InferError>;
+
+/// Error when reading samples for schema inference.
+#[derive(Debug)]
+pub enum InferError {
+    /// I/O error when reading from a reader.
+    Io(std::io::Error),
+    /// A non-blank NDJSON line is not valid JSON.
+    InvalidLine {
+        /// 1-based line number.
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for InferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InferError::Io(e) => write!(f, "io error: {e}"),
+            InferError::InvalidLine { line, source } => {
+                write!(f, "invalid JSON on line {line}: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InferError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InferError::Io(e) => Some(e),
+            InferError::InvalidLine { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<std::io::Error> for InferError {
+    fn from(e: std::io::Error) -> Self {
+        InferError::Io(e)
+    }
+}
@@ -0,0 +1,504 @@
+//! Schema inference: derive a [`JsonSchema`] from example JSON instances.
+//!
+//! Samples are merged position by position (root, object property, array item), so one sample
+//! with a property and one without yield an optional property, and an array of integers and
+//! floats yields `number` items. The result parses like any other schema and can be passed
+//! straight to codegen or the validator.
+
+mod error;
+mod settings;
+
+pub use error::{InferError, InferResult};
+pub use settings::{InferSettings, InferSettingsBuilder};
+
+use crate::json_schema::{JsonSchema, PropertyMap};
+use crate::validator::formats;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+
+/// Everything observed at one position across all samples. Positions live in an arena and refer
+/// to their children by index, so one item position can absorb every element of every array.
+#[derive(Debug, Clone, Default)]
+struct Shape {
+    /// Values seen here, including `null`; for a property, the number of objects that had it.
+    seen: usize,
+    nulls: usize,
+    booleans: usize,
+    integers: usize,
+    /// Numbers without an exact integer representation.
+    numbers: usize,
+    strings: usize,
+    /// Distinct strings, while there are at most [`InferSettings::max_enum_values`] of them.
+    string_values: BTreeSet<String>,
+    too_many_string_values: bool,
+    uuids: usize,
+    date_times: usize,
+    dates: usize,
+    objects: usize,
+    properties: BTreeMap<String, usize>,
+    arrays: usize,
+    items: Option<usize>,
+}
+
+/// Incremental schema inference: add samples one at a time (e.g. while streaming NDJSON), then
+/// [`finish`](Self::finish).
+///
+/// # Example
+///
+/// ```
+/// use json_schema_rs::{InferSettings, JsonSchema, SchemaInferrer};
+///
+/// let mut inferrer = SchemaInferrer::new(InferSettings::default());
+/// inferrer.add_sample(&serde_json::json!({"id": 1, "name": "a"}));
+/// inferrer.add_sample(&serde_json::json!({"id": 2}));
+/// let schema: JsonSchema = inferrer.finish();
+/// assert_eq!(Some(vec!["id".to_string()]), schema.required);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaInferrer {
+    settings: InferSettings,
+    /// Index 0 is the root; children are always allocated after their parent.
+    shapes: Vec<Shape>,
+}
+
+impl SchemaInferrer {
+    #[must_use]
+    pub fn new(settings: InferSettings) -> Self {
+        SchemaInferrer {
+            settings,
+            shapes: vec![Shape::default()],
+        }
+    }
+
+    /// Merges one sample into the inferred schema.
+    pub fn add_sample(&mut self, sample: &Value) {
+        let mut stack: Vec<(&Value, usize)> = vec![(sample, 0)];
+        while let Some((value, index)) = stack.pop() {
+            self.shapes[index].seen += 1;
+            match value {
+                Value::Null => self.shapes[index].nulls += 1,
+                Value::Bool(_) => self.shapes[index].booleans += 1,
+                Value::Number(n) => {
+                    if n.is_i64() || n.is_u64() {
+                        self.shapes[index].integers += 1;
+                    } else {
+                        self.shapes[index].numbers += 1;
+                    }
+                }
+                Value::String(s) => self.observe_string(index, s),
+                Value::Array(elements) => {
+                    self.shapes[index].arrays += 1;
+                    let items: usize = if let Some(items) = self.shapes[index].items {
+                        items
+                    } else {
+                        let items: usize = self.allocate();
+                        self.shapes[index].items = Some(items);
+                        items
+                    };
+                    stack.extend(elements.iter().rev().map(|element| (element, items)));
+                }
+                Value::Object(map) => {
+                    self.shapes[index].objects += 1;
+                    for (key, property_value) in map.iter().rev() {
+                        let property: usize =
+                            if let Some(&property) = self.shapes[index].properties.get(key) {
+                                property
+                            } else {
+                                let property: usize = self.allocate();
+                                self.shapes[index].properties.insert(key.clone(), property);
+                                property
+                            };
+                        stack.push((property_value, property));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Merges every non-blank line of `reader` as one sample (newline-delimited JSON).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InferError::Io`] when reading fails and [`InferError::InvalidLine`] for the
+    /// first line that is not valid JSON; samples before it have already been merged.
+    pub fn add_ndjson<R: BufRead>(&mut self, reader: R) -> InferResult<()> {
+        for (i, line) in reader.lines().enumerate() {
+            let line: String = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let sample: Value =
+                serde_json::from_str(&line).map_err(|source| InferError::InvalidLine {
+                    line: i + 1,
+                    source,
+                })?;
+            self.add_sample(&sample);
+        }
+        Ok(())
+    }
+
+    /// Builds the schema for everything added so far; `{}` when nothing was added.
+    #[must_use]
+    pub fn finish(self) -> JsonSchema {
+        let mut built: Vec<Option<JsonSchema>> = vec![None; self.shapes.len()];
+        for index in (0..self.shapes.len()).rev() {
+            let schema: JsonSchema = self.build(index, &mut built);
+            built[index] = Some(schema);
+        }
+        built[0].take().unwrap_or_default()
+    }
+
+    fn allocate(&mut self) -> usize {
+        self.shapes.push(Shape::default());
+        self.shapes.len() - 1
+    }
+
+    fn observe_string(&mut self, index: usize, s: &str) {
+        let detect_formats: bool = self.settings.detect_formats;
+        let max_enum_values: Option<usize> = self.settings.max_enum_values;
+        let shape: &mut Shape = &mut self.shapes[index];
+        shape.strings += 1;
+        if detect_formats {
+            shape.uuids += usize::from(is_uuid(s));
+            shape.date_times += usize::from(formats::is_valid_date_time(s));
+            shape.dates += usize::from(formats::is_valid_date(s));
+        }
+        if let Some(max) = max_enum_values
+            && !shape.too_many_string_values
+        {
+            shape.string_values.insert(s.to_string());
+            if shape.string_values.len() > max {
+                shape.too_many_string_values = true;
+                shape.string_values.clear();
+            }
+        }
+    }
+
+    /// Builds the schema for `shapes[index]`, taking its children's schemas out of `built`.
+    fn build(&self, index: usize, built: &mut [Option<JsonSchema>]) -> JsonSchema {
+        let shape: &Shape = &self.shapes[index];
+        let mut variants: Vec<JsonSchema> = Vec::new();
+        if shape.objects > 0 {
+            let properties: PropertyMap = shape
+                .properties
+                .iter()
+                .map(|(name, &child)| (name.clone(), built[child].take().unwrap_or_default()))
+                .collect();
+            let required: Vec<String> = shape
+                .properties
+                .iter()
+                .filter(|&(_, &child)| self.shapes[child].seen == shape.objects)
+                .map(|(name, _)| name.clone())
+                .collect();
+            variants.push(JsonSchema {
+                type_: Some("object".to_string()),
+                properties,
+                required: (!required.is_empty()).then_some(required),
+                ..Default::default()
+            });
+        }
+        if shape.arrays > 0 {
+            let items: Option<Box<JsonSchema>> = shape
+                .items
+                .filter(|&child| self.shapes[child].seen > 0)
+                .and_then(|child| built[child].take())
+                .map(Box::new);
+            variants.push(JsonSchema {
+                type_: Some("array".to_string()),
+                items,
+                ..Default::default()
+            });
+        }
+        if shape.strings > 0 {
+            variants.push(self.string_schema(shape));
+        }
+        if shape.integers > 0 || shape.numbers > 0 {
+            let type_: &str = if shape.numbers > 0 {
+                "number"
+            } else {
+                "integer"
+            };
+            variants.push(schema_of_type(type_));
+        }
+        if shape.booleans > 0 {
+            variants.push(schema_of_type("boolean"));
+        }
+        match (variants.len(), shape.nulls > 0) {
+            (0, false) => JsonSchema::default(),
+            (0, true) => schema_of_type("null"),
+            (1, false) => variants.pop().unwrap_or_default(),
+            (_, nullable) => {
+                if nullable {
+                    variants.push(schema_of_type("null"));
+                }
+                JsonSchema {
+                    any_of: Some(variants),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    fn string_schema(&self, shape: &Shape) -> JsonSchema {
+        let format: Option<&str> = if !self.settings.detect_formats {
+            None
+        } else if shape.uuids == shape.strings {
+            Some("uuid")
+        } else if shape.date_times == shape.strings {
+            Some("date-time")
+        } else if shape.dates == shape.strings {
+            Some("date")
+        } else {
+            None
+        };
+        let enum_values: Option<Vec<Value>> = (self.settings.max_enum_values.is_some()
+            && !shape.too_many_string_values
+            && shape.strings > shape.string_values.len())
+        .then(|| {
+            shape
+                .string_values
+                .iter()
+                .map(|s| Value::String(s.clone()))
+                .collect()
+        });
+        JsonSchema {
+            type_: Some("string".to_string()),
+            format: format.map(str::to_string),
+            enum_values,
+            ..Default::default()
+        }
+    }
+}
+
+fn schema_of_type(type_: &str) -> JsonSchema {
+    JsonSchema {
+        type_: Some(type_.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Hyphenated UUID text (`8-4-4-4-12` hex digits), any version.
+fn is_uuid(s: &str) -> bool {
+    let bytes: &[u8] = s.as_bytes();
+    bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// Infers one schema that accepts every sample.
+///
+/// - Objects are merged across samples; a property is `required` only if every object sample at
+///   that position has it.
+/// - A position that is sometimes `null` becomes `anyOf: [<schema>, {"type": "null"}]`.
+/// - Array elements are unified into one `items` schema; integers and floats unify to `number`,
+///   other mixed types become an `anyOf` of one schema per JSON type.
+/// - Formats and small string enums are only inferred when enabled in `settings`.
+///
+/// # Example
+///
+/// ```
+/// use json_schema_rs::{InferSettings, JsonSchema, infer_schema};
+///
+/// let samples = [serde_json::json!({"tags": ["a", 1]}), serde_json::json!({"tags": [2.5]})];
+/// let schema: JsonSchema = infer_schema(&samples, &InferSettings::default());
+/// let expected: JsonSchema = serde_json::from_str(
+///     r#"{"type":"object","properties":{"tags":{"type":"array","items":{"anyOf":[{"type":"string"},{"type":"number"}]}}},"required":["tags"]}"#,
+/// ).unwrap();
+/// assert_eq!(expected, schema);
+/// ```
+#[must_use]
+pub fn infer_schema(samples: &[Value], settings: &InferSettings) -> JsonSchema {
+    let mut inferrer: SchemaInferrer = SchemaInferrer::new(settings.clone());
+    for sample in samples {
+        inferrer.add_sample(sample);
+    }
+    inferrer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InferError, InferSettings, SchemaInferrer, infer_schema};
+    use crate::json_schema::JsonSchema;
+    use crate::validator::validate;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn no_samples_infer_empty_schema() {
+        let actual: JsonSchema = infer_schema(&[], &InferSettings::default());
+        let expected: JsonSchema = JsonSchema::default();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn objects_merge_and_required_only_when_always_present() {
+        let samples: [Value; 2] = [
+            json!({"id": 1, "name": "a", "flag": true}),
+            json!({"id": 2, "extra": {"x": 1}}),
+        ];
+        let actual: JsonSchema = infer_schema(&samples, &InferSettings::default());
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "extra": {"type": "object", "properties": {"x": {"type": "integer"}}, "required": ["x"]},
+                    "flag": {"type": "boolean"},
+                    "id": {"type": "integer"},
+                    "name": {"type": "string"}
+                },
+                "required": ["id"]
+            }"#,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn null_values_make_positions_nullable() {
+        let samples: [Value; 3] = [
+            json!({"a": "x", "b": null}),
+            json!({"a": null, "b": null}),
+            json!({"a": "y", "b": null}),
+        ];
+        let actual: JsonSchema = infer_schema(&samples, &InferSettings::default());
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "a": {"anyOf": [{"type": "string"}, {"type": "null"}]},
+                    "b": {"type": "null"}
+                },
+                "required": ["a", "b"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+        for sample in &samples {
+            assert_eq!(Ok(()), validate(&actual, sample));
+        }
+        assert!(validate(&actual, &json!({"a": 1, "b": null})).is_err());
+    }
+
+    #[test]
+    fn array_items_are_unified_across_arrays() {
+        let samples: [Value; 2] = [json!([1, 2]), json!([[], [3.5]])];
+        let actual: JsonSchema = infer_schema(&samples, &InferSettings::default());
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"array","items":{"anyOf":[{"type":"array","items":{"type":"number"}},{"type":"integer"}]}}"#,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn integers_and_floats_unify_to_number() {
+        let samples: [Value; 2] = [json!(1), json!(1.5)];
+        let actual: JsonSchema = infer_schema(&samples, &InferSettings::default());
+        let expected: JsonSchema = serde_json::from_str(r#"{"type":"number"}"#).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn empty_arrays_have_no_items() {
+        let actual: JsonSchema = infer_schema(&[json!([])], &InferSettings::default());
+        let expected: JsonSchema = serde_json::from_str(r#"{"type":"array"}"#).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn formats_are_detected_only_when_enabled_and_unanimous() {
+        let samples: [Value; 2] = [
+            json!({"id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "at": "2024-01-02T03:04:05Z", "day": "2024-01-02", "mixed": "2024-01-02"}),
+            json!({"id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8", "at": "2024-02-03T00:00:00+01:00", "day": "2024-12-31", "mixed": "soon"}),
+        ];
+        let settings: InferSettings = InferSettings::builder().detect_formats(true).build();
+        let actual: JsonSchema = infer_schema(&samples, &settings);
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "at": {"type": "string", "format": "date-time"},
+                    "day": {"type": "string", "format": "date"},
+                    "id": {"type": "string", "format": "uuid"},
+                    "mixed": {"type": "string"}
+                },
+                "required": ["at", "day", "id", "mixed"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+        let without: JsonSchema = infer_schema(&samples, &InferSettings::default());
+        assert_eq!(None, without.properties["id"].format);
+    }
+
+    #[test]
+    fn small_repeated_string_sets_become_enums() {
+        let samples: Vec<Value> = ["red", "green", "red", "green", "red"]
+            .iter()
+            .map(|s| json!({"color": s, "name": format!("n{s}")}))
+            .collect();
+        let settings: InferSettings = InferSettings::builder().max_enum_values(2).build();
+        let actual: JsonSchema = infer_schema(&samples, &settings);
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "color": {"type": "string", "enum": ["green", "red"]},
+                    "name": {"type": "string", "enum": ["ngreen", "nred"]}
+                },
+                "required": ["color", "name"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+        let settings: InferSettings = InferSettings::builder().max_enum_values(1).build();
+        let actual: JsonSchema = infer_schema(&samples, &settings);
+        assert_eq!(None, actual.properties["color"].enum_values);
+    }
+
+    #[test]
+    fn unique_strings_do_not_become_enums() {
+        let samples: [Value; 2] = [json!("a"), json!("b")];
+        let settings: InferSettings = InferSettings::builder().max_enum_values(5).build();
+        let actual: JsonSchema = infer_schema(&samples, &settings);
+        let expected: JsonSchema = serde_json::from_str(r#"{"type":"string"}"#).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn ndjson_lines_are_samples_and_bad_lines_are_reported() {
+        let mut inferrer: SchemaInferrer = SchemaInferrer::new(InferSettings::default());
+        inferrer
+            .add_ndjson("{\"a\":1}\n\n{\"a\":2,\"b\":true}\n".as_bytes())
+            .expect("valid ndjson");
+        let actual: JsonSchema = inferrer.finish();
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"type":"integer"},"b":{"type":"boolean"}},"required":["a"]}"#,
+        ).unwrap();
+        assert_eq!(expected, actual);
+
+        let mut inferrer: SchemaInferrer = SchemaInferrer::new(InferSettings::default());
+        let actual: Option<usize> = match inferrer.add_ndjson("{}\n{oops\n".as_bytes()) {
+            Err(InferError::InvalidLine { line, .. }) => Some(line),
+            _ => None,
+        };
+        assert_eq!(Some(2), actual);
+    }
+
+    #[test]
+    fn deep_nesting_does_not_recurse() {
+        let mut sample: Value = json!(1);
+        for _ in 0..2_000 {
+            sample = Value::Array(vec![sample]);
+        }
+        let actual: JsonSchema = infer_schema(&[sample], &InferSettings::default());
+        let mut depth: usize = 0;
+        let mut node: &JsonSchema = &actual;
+        while let Some(items) = node.items.as_deref() {
+            depth += 1;
+            node = items;
+        }
+        assert_eq!(2_000, depth);
+        assert_eq!(Some("integer"), node.type_.as_deref());
+    }
+}
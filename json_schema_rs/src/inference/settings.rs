@@ -0,0 +1,55 @@
+//! Schema inference settings.
+
+/// Settings for [`infer_schema`](super::infer_schema).
+///
+/// [`Default`] infers types and structure only: no `format` and no `enum`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InferSettings {
+    /// When `true`, a string position whose every sample is a UUID, an RFC 3339 date-time, or an
+    /// RFC 3339 full-date gets `format: "uuid"`, `"date-time"`, or `"date"`.
+    pub detect_formats: bool,
+
+    /// When `Some(n)`, a string position with at most `n` distinct values, each seen more than
+    /// once on average, gets an `enum` of those values. `None` never infers `enum`.
+    pub max_enum_values: Option<usize>,
+}
+
+/// Builder for [`InferSettings`].
+#[derive(Debug, Clone, Default)]
+pub struct InferSettingsBuilder {
+    detect_formats: Option<bool>,
+    max_enum_values: Option<usize>,
+}
+
+impl InferSettingsBuilder {
+    /// Set whether string formats (`uuid`, `date-time`, `date`) are detected.
+    #[must_use]
+    pub fn detect_formats(mut self, value: bool) -> Self {
+        self.detect_formats = Some(value);
+        self
+    }
+
+    /// Infer string `enum`s with at most `value` distinct values.
+    #[must_use]
+    pub fn max_enum_values(mut self, value: usize) -> Self {
+        self.max_enum_values = Some(value);
+        self
+    }
+
+    /// Build the settings. Any option not set uses its per-option default.
+    #[must_use]
+    pub fn build(self) -> InferSettings {
+        InferSettings {
+            detect_formats: self.detect_formats.unwrap_or(false),
+            max_enum_values: self.max_enum_values,
+        }
+    }
+}
+
+impl InferSettings {
+    /// Start a builder with all options unset (per-option defaults will be used on [`build`](InferSettingsBuilder::build)).
+    #[must_use]
+    pub fn builder() -> InferSettingsBuilder {
+        InferSettingsBuilder::default()
+    }
+}
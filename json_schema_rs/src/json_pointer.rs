@@ -107,6 +107,16 @@ impl JsonPointer {
         }
     }
 
+    /// Returns a new pointer with `segment` inserted before the first segment, for a path found
+    /// inside a value that is itself nested under `segment`.
+    #[must_use]
+    pub fn prepend(&self, segment: &str) -> Self {
+        let mut new_segments: Vec<String> = Vec::with_capacity(self.segments.len() + 1);
+        new_segments.push(segment.to_string());
+        new_segments.extend(self.segments.iter().cloned());
+        Self::from(new_segments)
+    }
+
     /// Returns a new pointer with the last segment removed. Root unchanged.
     #[must_use]
     pub fn pop(&self) -> Self {
@@ -0,0 +1,574 @@
+//! Bundling (external `$ref` targets pulled into root `$defs`) and dereferencing (`$ref`s inlined).
+//!
+//! [`bundle`] produces a single compound document: every schema reached through a `$ref` to
+//! another file is copied into the root's `$defs` and the ref is rewritten to
+//! `#/$defs/<name>`, so the result resolves with fragment-only [`ref_resolver`] lookups. Embedded
+//! schemas drop their `$id` and `$schema` so those rewritten refs stay relative to the root.
+//!
+//! [`deref`] replaces every local `$ref` with a copy of its target. A ref that would re-enter a
+//! definition already being inlined (a cycle) is kept, and its target is kept under `$defs` (or
+//! `definitions`); definitions nothing refers to anymore are dropped.
+
+use super::error::{BundleError, BundleResult, JsonSchemaParseError};
+use super::json_schema::JsonSchema;
+use super::ref_resolver::{self, ParsedRef, RefResolutionError};
+use super::settings::JsonSchemaSettings;
+use super::walk;
+use crate::json_pointer::JsonPointer;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A `$ref` target: the whole document or one of its root definitions, in a loaded file.
+type Target = (PathBuf, ParsedRef);
+
+/// Returns the fragment-only `$ref` string for `parsed` (e.g. `#/$defs/Name`).
+fn fragment_of(parsed: &ParsedRef) -> String {
+    let pointer: JsonPointer = match parsed {
+        ParsedRef::Root => JsonPointer::root(),
+        ParsedRef::Defs(name) => JsonPointer::root().push("$defs").push(name),
+        ParsedRef::Definitions(name) => JsonPointer::root().push("definitions").push(name),
+    };
+    format!("#{pointer}")
+}
+
+/// Splits a `$ref` into its document part and fragment (`#` when absent).
+fn split_ref(ref_str: &str) -> (&str, &str) {
+    match ref_str.find('#') {
+        Some(index) => (&ref_str[..index], &ref_str[index..]),
+        None => (ref_str, "#"),
+    }
+}
+
+fn unresolved(ref_str: &str, e: &RefResolutionError) -> BundleError {
+    BundleError::UnresolvedRef {
+        ref_str: ref_str.to_string(),
+        reason: format!("{e:?}"),
+    }
+}
+
+/// State for one [`bundle`] run.
+struct Bundler<'s> {
+    settings: &'s JsonSchemaSettings,
+    /// Canonical path of the root document, when known; refs back into it stay local.
+    root_path: Option<PathBuf>,
+    /// Loaded documents keyed by canonical path.
+    documents: HashMap<PathBuf, JsonSchema>,
+    /// Root `$defs` name assigned to each target.
+    names: HashMap<Target, String>,
+    /// Root `$defs` names in use.
+    taken: BTreeSet<String>,
+    /// Targets named but not yet copied into the root.
+    pending: Vec<(Target, String)>,
+}
+
+impl Bundler<'_> {
+    /// Returns `base`, or `base_2`, `base_3`, ... when `base` is already a root `$defs` name.
+    fn unique_name(&mut self, base: &str) -> String {
+        let mut name: String = base.to_string();
+        let mut suffix: usize = 2;
+        while self.taken.contains(&name) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        self.taken.insert(name.clone());
+        name
+    }
+
+    /// Resolves the file part of `ref_str` against `dir` to a canonical path.
+    fn resolve_file(ref_str: &str, file_part: &str, dir: &Path) -> BundleResult<PathBuf> {
+        let file_part: &str = file_part.strip_prefix("file://").unwrap_or(file_part);
+        if file_part.contains("://") || file_part.starts_with("urn:") {
+            return Err(BundleError::UnsupportedRef {
+                ref_str: ref_str.to_string(),
+            });
+        }
+        let path: PathBuf = dir.join(file_part);
+        path.canonicalize().map_err(|e| BundleError::Load {
+            path,
+            source: JsonSchemaParseError::Io(e),
+        })
+    }
+
+    /// Returns the rewritten form of `ref_str`, found in the document at `document` (`None` for
+    /// the root) whose directory is `dir`, naming and queueing its target when first seen.
+    fn rewrite(
+        &mut self,
+        ref_str: &str,
+        document: Option<&Path>,
+        dir: &Path,
+    ) -> BundleResult<String> {
+        let (file_part, fragment) = split_ref(ref_str);
+        let parsed: ParsedRef =
+            ref_resolver::parse_ref(fragment).map_err(|e| unresolved(ref_str, &e))?;
+        let path: PathBuf = if file_part.is_empty() {
+            match document {
+                None => return Ok(ref_str.to_string()),
+                Some(path) => path.to_path_buf(),
+            }
+        } else {
+            Self::resolve_file(ref_str, file_part, dir)?
+        };
+        if self.root_path.as_ref() == Some(&path) {
+            return Ok(fragment_of(&parsed));
+        }
+        let target: Target = (path, parsed);
+        let name: String = if let Some(name) = self.names.get(&target) {
+            name.clone()
+        } else {
+            let base: String = match &target.1 {
+                ParsedRef::Root => target.0.file_stem().map_or_else(
+                    || "schema".to_string(),
+                    |s| s.to_string_lossy().into_owned(),
+                ),
+                ParsedRef::Defs(name) | ParsedRef::Definitions(name) => name.clone(),
+            };
+            let name: String = self.unique_name(&base);
+            self.names.insert(target.clone(), name.clone());
+            self.pending.push((target, name.clone()));
+            name
+        };
+        Ok(fragment_of(&ParsedRef::Defs(name)))
+    }
+
+    /// Rewrites every `$ref` in `schema`, which belongs to `document` (`None` for the root).
+    fn rewrite_refs(
+        &mut self,
+        schema: &mut JsonSchema,
+        document: Option<&Path>,
+        dir: &Path,
+    ) -> BundleResult<()> {
+        let mut error: Option<BundleError> = None;
+        schema.walk_mut(|_, _, node| {
+            if error.is_some() {
+                return;
+            }
+            if let Some(ref_str) = node.ref_.as_deref() {
+                match self.rewrite(ref_str, document, dir) {
+                    Ok(rewritten) => node.ref_ = Some(rewritten),
+                    Err(e) => error = Some(e),
+                }
+            }
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    fn run(mut self, schema: &JsonSchema, dir: &Path) -> BundleResult<JsonSchema> {
+        let mut out: JsonSchema = schema.clone();
+        self.taken
+            .extend(out.defs.iter().flat_map(BTreeMap::keys).cloned());
+        self.rewrite_refs(&mut out, None, dir)?;
+        while let Some(((path, parsed), name)) = self.pending.pop() {
+            if !self.documents.contains_key(&path) {
+                let document: JsonSchema = JsonSchema::new_from_path(&path, self.settings)
+                    .map_err(|source| BundleError::Load {
+                        path: path.clone(),
+                        source,
+                    })?;
+                self.documents.insert(path.clone(), document);
+            }
+            let fragment: String = fragment_of(&parsed);
+            let mut embedded: JsonSchema =
+                ref_resolver::resolve_ref(&self.documents[&path], &fragment)
+                    .map_err(|e| unresolved(&format!("{}{fragment}", path.display()), &e))?
+                    .clone();
+            embedded.id = None;
+            embedded.schema = None;
+            if parsed == ParsedRef::Root {
+                // Its definitions are pulled in individually as refs reach them.
+                embedded.defs = None;
+                embedded.definitions = None;
+            }
+            let dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            self.rewrite_refs(&mut embedded, Some(&path), &dir)?;
+            out.defs
+                .get_or_insert_with(BTreeMap::new)
+                .insert(name, embedded);
+        }
+        Ok(out)
+    }
+}
+
+/// Bundles `schema` into a single document: every schema reached through a `$ref` to another
+/// file (relative paths resolve against `base_dir`, then against the referring file's directory)
+/// is copied into the root `$defs` and the ref is rewritten to `#/$defs/<name>`. Names come from
+/// the definition name, or the file stem for whole-document refs, with `_2`, `_3`, ... appended
+/// on collision. Local refs in `schema` are left as they are.
+///
+/// # Errors
+///
+/// Returns [`BundleError::Load`] when a referenced file cannot be read or parsed,
+/// [`BundleError::UnsupportedRef`] for non-file URIs (e.g. `https://`), and
+/// [`BundleError::UnresolvedRef`] when a fragment is unsupported or names a missing definition.
+pub fn bundle(
+    schema: &JsonSchema,
+    base_dir: &Path,
+    settings: &JsonSchemaSettings,
+) -> BundleResult<JsonSchema> {
+    Bundler {
+        settings,
+        root_path: None,
+        documents: HashMap::new(),
+        names: HashMap::new(),
+        taken: BTreeSet::new(),
+        pending: Vec::new(),
+    }
+    .run(schema, base_dir)
+}
+
+/// Reads the schema at `path` and [`bundle`]s it relative to its directory. Refs from other
+/// files back into this one become local refs.
+///
+/// # Errors
+///
+/// Returns [`BundleError::Load`] when `path` cannot be read or parsed; otherwise same as
+/// [`bundle`].
+pub fn bundle_from_path<P: AsRef<Path>>(
+    path: P,
+    settings: &JsonSchemaSettings,
+) -> BundleResult<JsonSchema> {
+    let path: PathBuf = path
+        .as_ref()
+        .canonicalize()
+        .map_err(|e| BundleError::Load {
+            path: path.as_ref().to_path_buf(),
+            source: JsonSchemaParseError::Io(e),
+        })?;
+    let schema: JsonSchema =
+        JsonSchema::new_from_path(&path, settings).map_err(|source| BundleError::Load {
+            path: path.clone(),
+            source,
+        })?;
+    let dir: PathBuf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    Bundler {
+        settings,
+        root_path: Some(path),
+        documents: HashMap::new(),
+        names: HashMap::new(),
+        taken: BTreeSet::new(),
+        pending: Vec::new(),
+    }
+    .run(&schema, &dir)
+}
+
+/// Replaces `node`'s `$ref` with `target`. Annotation-only siblings (`title`, `description`,
+/// `$comment`, `deprecated`, `examples`, `default`) override the target's; any other sibling
+/// keywords still apply, so the target is added to `allOf` instead.
+fn inline(node: &mut JsonSchema, target: &JsonSchema) {
+    let mut siblings: JsonSchema = std::mem::take(node);
+    siblings.ref_ = None;
+    let annotations_only: bool = JsonSchema {
+        title: None,
+        description: None,
+        comment: None,
+        deprecated: None,
+        examples: None,
+        default_value: None,
+        ..siblings.clone()
+    } == JsonSchema::default();
+    if annotations_only {
+        *node = target.clone();
+        if siblings.title.is_some() {
+            node.title = siblings.title;
+        }
+        if siblings.description.is_some() {
+            node.description = siblings.description;
+        }
+        if siblings.comment.is_some() {
+            node.comment = siblings.comment;
+        }
+        if siblings.deprecated.is_some() {
+            node.deprecated = siblings.deprecated;
+        }
+        if siblings.examples.is_some() {
+            node.examples = siblings.examples;
+        }
+        if siblings.default_value.is_some() {
+            node.default_value = siblings.default_value;
+        }
+    } else {
+        siblings
+            .all_of
+            .get_or_insert_with(Vec::new)
+            .push(target.clone());
+        *node = siblings;
+    }
+}
+
+/// Inlines every `$ref` under `schema` against `source`. `chain` holds the targets already being
+/// inlined above `schema`; refs to them are cycles, are kept, and their targets are queued on
+/// `kept`.
+fn inline_refs(
+    source: &JsonSchema,
+    schema: &mut JsonSchema,
+    chain: Vec<ParsedRef>,
+    kept: &mut Vec<ParsedRef>,
+    seen: &mut HashSet<ParsedRef>,
+) -> BundleResult<()> {
+    let mut stack: Vec<(&mut JsonSchema, Vec<ParsedRef>)> = vec![(schema, chain)];
+    while let Some((node, mut chain)) = stack.pop() {
+        while let Some(ref_str) = node.ref_.clone() {
+            let parsed: ParsedRef = ref_resolver::parse_ref(&ref_str).map_err(|e| {
+                if ref_str.starts_with('#') {
+                    unresolved(&ref_str, &e)
+                } else {
+                    BundleError::ExternalRef {
+                        ref_str: ref_str.clone(),
+                    }
+                }
+            })?;
+            if chain.contains(&parsed) {
+                if parsed != ParsedRef::Root && seen.insert(parsed.clone()) {
+                    kept.push(parsed);
+                }
+                break;
+            }
+            let target: &JsonSchema = ref_resolver::resolve_ref(source, &ref_str)
+                .map_err(|e| unresolved(&ref_str, &e))?;
+            inline(node, target);
+            chain.push(parsed);
+        }
+        for (_, _, child) in walk::children_mut(node, &JsonPointer::root()) {
+            stack.push((child, chain.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns a copy of `schema` with every `$ref` replaced by a copy of its target. Refs that
+/// would recurse forever (e.g. a tree node referring to itself) are kept as local refs, with
+/// their targets kept in `$defs`/`definitions`; all other definitions are dropped. Only local
+/// refs are supported; [`bundle`] external refs first.
+///
+/// # Errors
+///
+/// Returns [`BundleError::ExternalRef`] for a non-fragment `$ref` and
+/// [`BundleError::UnresolvedRef`] when a fragment is unsupported or names a missing definition.
+pub fn deref(schema: &JsonSchema) -> BundleResult<JsonSchema> {
+    let mut out: JsonSchema = schema.clone();
+    out.defs = None;
+    out.definitions = None;
+    let mut kept: Vec<ParsedRef> = Vec::new();
+    let mut seen: HashSet<ParsedRef> = HashSet::new();
+    inline_refs(
+        schema,
+        &mut out,
+        vec![ParsedRef::Root],
+        &mut kept,
+        &mut seen,
+    )?;
+    while let Some(parsed) = kept.pop() {
+        let fragment: String = fragment_of(&parsed);
+        let mut body: JsonSchema = ref_resolver::resolve_ref(schema, &fragment)
+            .map_err(|e| unresolved(&fragment, &e))?
+            .clone();
+        inline_refs(
+            schema,
+            &mut body,
+            vec![ParsedRef::Root, parsed.clone()],
+            &mut kept,
+            &mut seen,
+        )?;
+        match parsed {
+            ParsedRef::Defs(name) => {
+                out.defs
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert(name, body);
+            }
+            ParsedRef::Definitions(name) => {
+                out.definitions
+                    .get_or_insert_with(BTreeMap::new)
+                    .insert(name, body);
+            }
+            ParsedRef::Root => {}
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bundle, bundle_from_path, deref};
+    use crate::json_schema::{BundleError, JsonSchema, JsonSchemaSettings};
+    use std::path::Path;
+
+    fn write(dir: &Path, name: &str, json: &str) {
+        std::fs::write(dir.join(name), json).expect("write schema file");
+    }
+
+    #[test]
+    fn bundle_pulls_external_targets_into_root_defs() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        write(
+            temp_dir.path(),
+            "root.json",
+            r#"{"type":"object","properties":{"address":{"$ref":"common/address.json"},"id":{"$ref":"common/types.json#/$defs/Id"}}}"#,
+        );
+        std::fs::create_dir(temp_dir.path().join("common")).expect("mkdir");
+        write(
+            temp_dir.path(),
+            "common/address.json",
+            r##"{"$id":"https://example.com/address.json","type":"object","properties":{"zip":{"$ref":"#/$defs/Zip"},"owner":{"$ref":"types.json#/$defs/Id"}},"$defs":{"Zip":{"type":"string"},"Unused":{}}}"##,
+        );
+        write(
+            temp_dir.path(),
+            "common/types.json",
+            r#"{"$defs":{"Id":{"type":"string","format":"uuid"}}}"#,
+        );
+        let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+        let actual: JsonSchema =
+            bundle_from_path(temp_dir.path().join("root.json"), &settings).expect("bundle");
+        let expected: JsonSchema = serde_json::from_str(
+            r##"{
+                "type": "object",
+                "properties": {
+                    "address": {"$ref": "#/$defs/address"},
+                    "id": {"$ref": "#/$defs/Id"}
+                },
+                "$defs": {
+                    "address": {"type": "object", "properties": {"zip": {"$ref": "#/$defs/Zip"}, "owner": {"$ref": "#/$defs/Id"}}},
+                    "Id": {"type": "string", "format": "uuid"},
+                    "Zip": {"type": "string"}
+                }
+            }"##,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bundle_renames_colliding_definitions_and_keeps_local_refs() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        write(
+            temp_dir.path(),
+            "other.json",
+            r#"{"$defs":{"Name":{"type":"integer"}}}"#,
+        );
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"properties":{"a":{"$ref":"#/$defs/Name"},"b":{"$ref":"other.json#/$defs/Name"}},"$defs":{"Name":{"type":"string"}}}"##,
+        ).unwrap();
+        let actual: JsonSchema =
+            bundle(&schema, temp_dir.path(), &JsonSchemaSettings::default()).expect("bundle");
+        let expected: JsonSchema = serde_json::from_str(
+            r##"{"properties":{"a":{"$ref":"#/$defs/Name"},"b":{"$ref":"#/$defs/Name_2"}},"$defs":{"Name":{"type":"string"},"Name_2":{"type":"integer"}}}"##,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bundle_handles_cycles_between_files() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        write(
+            temp_dir.path(),
+            "root.json",
+            r#"{"properties":{"a":{"$ref":"a.json"}},"$defs":{"Local":{"type":"string"}}}"#,
+        );
+        write(
+            temp_dir.path(),
+            "a.json",
+            r#"{"properties":{"b":{"$ref":"b.json"},"local":{"$ref":"root.json#/$defs/Local"}}}"#,
+        );
+        write(
+            temp_dir.path(),
+            "b.json",
+            r#"{"properties":{"a":{"$ref":"a.json"}}}"#,
+        );
+        let actual: JsonSchema = bundle_from_path(
+            temp_dir.path().join("root.json"),
+            &JsonSchemaSettings::default(),
+        )
+        .expect("bundle");
+        let expected: JsonSchema = serde_json::from_str(
+            r##"{
+                "properties": {"a": {"$ref": "#/$defs/a"}},
+                "$defs": {
+                    "Local": {"type": "string"},
+                    "a": {"properties": {"b": {"$ref": "#/$defs/b"}, "local": {"$ref": "#/$defs/Local"}}},
+                    "b": {"properties": {"a": {"$ref": "#/$defs/a"}}}
+                }
+            }"##,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bundle_rejects_remote_refs() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"$ref":"https://example.com/schema.json"}"#).unwrap();
+        let actual = bundle(&schema, Path::new("."), &JsonSchemaSettings::default());
+        assert!(
+            matches!(actual, Err(BundleError::UnsupportedRef { ref ref_str }) if ref_str == "https://example.com/schema.json"),
+            "got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn bundle_missing_file_is_load_error() {
+        let temp_dir = tempfile::tempdir().expect("temp dir");
+        let schema: JsonSchema = serde_json::from_str(r#"{"$ref":"missing.json"}"#).unwrap();
+        let actual = bundle(&schema, temp_dir.path(), &JsonSchemaSettings::default());
+        assert!(
+            matches!(actual, Err(BundleError::Load { .. })),
+            "got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn deref_inlines_refs_and_drops_definitions() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"type":"object","properties":{"a":{"$ref":"#/$defs/A","description":"the a"},"b":{"$ref":"#/definitions/B"}},"$defs":{"A":{"type":"string","description":"an A"},"Unused":{}},"definitions":{"B":{"$ref":"#/$defs/A"}}}"##,
+        ).unwrap();
+        let actual: JsonSchema = deref(&schema).expect("deref");
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"type":"string","description":"the a"},"b":{"type":"string","description":"an A"}}}"#,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deref_keeps_non_annotation_siblings_via_all_of() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"$ref":"#/$defs/A","minLength":1,"$defs":{"A":{"type":"string"}}}"##,
+        )
+        .unwrap();
+        let actual: JsonSchema = deref(&schema).expect("deref");
+        let expected: JsonSchema =
+            serde_json::from_str(r#"{"minLength":1,"allOf":[{"type":"string"}]}"#).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deref_keeps_cyclic_refs_as_local_refs() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"properties":{"root":{"$ref":"#/$defs/Node"},"self":{"$ref":"#"}},"$defs":{"Node":{"type":"object","properties":{"child":{"$ref":"#/$defs/Node"},"leaf":{"$ref":"#/$defs/Leaf"}}},"Leaf":{"type":"string"}}}"##,
+        ).unwrap();
+        let actual: JsonSchema = deref(&schema).expect("deref");
+        let expected: JsonSchema = serde_json::from_str(
+            r##"{
+                "properties": {
+                    "root": {"type": "object", "properties": {"child": {"$ref": "#/$defs/Node"}, "leaf": {"type": "string"}}},
+                    "self": {"$ref": "#"}
+                },
+                "$defs": {
+                    "Node": {"type": "object", "properties": {"child": {"$ref": "#/$defs/Node"}, "leaf": {"type": "string"}}}
+                }
+            }"##,
+        ).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deref_rejects_external_and_missing_refs() {
+        let external: JsonSchema = serde_json::from_str(r#"{"$ref":"other.json"}"#).unwrap();
+        let actual = deref(&external);
+        assert!(
+            matches!(actual, Err(BundleError::ExternalRef { .. })),
+            "got {actual:?}"
+        );
+        let missing: JsonSchema =
+            serde_json::from_str(r##"{"$ref":"#/$defs/Missing","$defs":{}}"##).unwrap();
+        let actual = deref(&missing);
+        assert!(
+            matches!(actual, Err(BundleError::UnresolvedRef { .. })),
+            "got {actual:?}"
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! Canonical form and stable content fingerprints of a [`JsonSchema`].
+//!
+//! [`JsonSchema::canonicalize`] rewrites a schema into a normal form so that schemas that only
+//! differ in spelling (keyword order, `required` order, no-op keywords, single-branch
+//! combinators) compare equal. [`JsonSchema::fingerprint`] hashes that form into a `u64` that is
+//! stable across runs, platforms, and crate versions with the same canonical form.
+
+use super::json_schema::{AdditionalProperties, JsonSchema};
+use std::collections::BTreeMap;
+use std::io;
+
+/// Which keywords a [`JsonSchema::fingerprint`] covers; mirrors
+/// [`DedupeMode`](crate::DedupeMode) without its `Disabled` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FingerprintMode {
+    /// Only functional data (`type`, `properties`, `required`, `title`, `default`, constraints).
+    /// Excludes `$id`, `description`, `$comment`, `deprecated`, `examples`, and extension keywords.
+    Functional,
+    /// Every keyword, including non-functional ones like `description`.
+    #[default]
+    Full,
+}
+
+/// Keywords that stop a single-branch combinator from being merged into a parent that has other
+/// keywords: `$ref` ignores its siblings in older drafts and in this crate's validator, and
+/// `$id`/`$schema`/`$defs`/`definitions` change how refs inside the branch resolve.
+const UNMERGEABLE_BRANCH_KEYWORDS: [&str; 5] = ["$ref", "$id", "$schema", "$defs", "definitions"];
+
+/// 64-bit FNV-1a, fed by `serde_json::to_writer` so the canonical JSON is never buffered.
+struct Fnv1a(u64);
+
+impl io::Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl JsonSchema {
+    /// Returns the canonical form of this schema.
+    ///
+    /// Applied to every subschema until nothing changes:
+    /// - `properties` and the object keys inside `const`, `default`, `enum`, `examples`, and
+    ///   extension values are sorted by name (a no-op unless the `preserve_order` feature keeps
+    ///   declaration order).
+    /// - `required` is sorted and deduplicated; an empty `required` is dropped.
+    /// - `enum` values are sorted by their JSON text and deduplicated.
+    /// - No-op keywords are dropped: `additionalProperties: true` or `{}`, `items: {}`,
+    ///   `uniqueItems: false`, `minItems: 0`, `minLength: 0`, `deprecated: false`, empty
+    ///   `examples`/`$defs`/`definitions`/`allOf`, and `{}` branches of `allOf`.
+    /// - Single-branch `allOf`/`anyOf`/`oneOf` are collapsed: the branch replaces a parent with no
+    ///   other keywords, or is merged into it when their keywords are disjoint and neither side
+    ///   brings a `$ref` (nor the branch `$id`, `$schema`, `$defs`, or `definitions`).
+    ///
+    /// `type` arrays are already reduced to their first entry when parsing, so `type` is left as
+    /// is. The result validates exactly the same instances as the input.
+    #[must_use]
+    pub fn canonicalize(&self) -> JsonSchema {
+        let mut canonical: JsonSchema = self.clone();
+        canonical.canonicalize_in_place();
+        canonical
+    }
+
+    /// Stable hash of this schema's canonical form, restricted to the keywords `mode` covers.
+    ///
+    /// Two schemas with the same canonical form always have the same fingerprint, so it can be
+    /// used as a cache key (e.g. for compiled validators) or to tell real schema changes from
+    /// reformatting. The hash is 64-bit FNV-1a over the canonical JSON text, which has sorted
+    /// keys; it is not cryptographic.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic: serializing a [`JsonSchema`] to JSON cannot fail.
+    ///
+    /// ```
+    /// use json_schema_rs::{FingerprintMode, JsonSchema};
+    ///
+    /// let a: JsonSchema =
+    ///     JsonSchema::try_from(r#"{"required":["b","a"],"description":"old"}"#).unwrap();
+    /// let b: JsonSchema =
+    ///     JsonSchema::try_from(r#"{"description":"new","required":["a","b"]}"#).unwrap();
+    /// assert_eq!(a.fingerprint(FingerprintMode::Functional), b.fingerprint(FingerprintMode::Functional));
+    /// assert_ne!(a.fingerprint(FingerprintMode::Full), b.fingerprint(FingerprintMode::Full));
+    /// ```
+    #[must_use]
+    pub fn fingerprint(&self, mode: FingerprintMode) -> u64 {
+        let mut canonical: JsonSchema = self.clone();
+        if mode == FingerprintMode::Functional {
+            canonical.walk_mut(|_, _, node| strip_non_functional(node));
+        }
+        canonical.canonicalize_in_place();
+        let mut hasher: Fnv1a = Fnv1a(0xcbf2_9ce4_8422_2325);
+        serde_json::to_writer(&mut hasher, &canonical)
+            .expect("JsonSchema serializes to JSON and Fnv1a never fails to write");
+        hasher.0
+    }
+
+    /// Canonicalizes every node, repeating passes until one changes nothing (a collapse can
+    /// expose a subschema that is itself a no-op to its parent).
+    fn canonicalize_in_place(&mut self) {
+        loop {
+            let mut changed: bool = false;
+            self.walk_mut(|_, _, node| changed |= canonicalize_node(node));
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+/// Clears the keywords [`FingerprintMode::Functional`] ignores.
+fn strip_non_functional(schema: &mut JsonSchema) {
+    schema.id = None;
+    schema.description = None;
+    schema.comment = None;
+    schema.deprecated = None;
+    schema.examples = None;
+    schema.extensions.clear();
+}
+
+/// Canonicalizes one node's own keywords; returns whether anything changed.
+fn canonicalize_node(schema: &mut JsonSchema) -> bool {
+    let mut changed: bool = false;
+    changed |= canonicalize_key_order(schema);
+    changed |= canonicalize_required(schema);
+    changed |= canonicalize_enum(schema);
+    changed |= drop_no_op_keywords(schema);
+    changed |= collapse_single_branch(schema, |s| &mut s.all_of);
+    changed |= collapse_single_branch(schema, |s| &mut s.any_of);
+    changed |= collapse_single_branch(schema, |s| &mut s.one_of);
+    changed
+}
+
+/// Sorts `properties` and the objects inside value keywords by key; only reordering `properties`
+/// counts as a change, since sorted values never expose further canonicalization.
+fn canonicalize_key_order(schema: &mut JsonSchema) -> bool {
+    let values: Vec<&mut serde_json::Value> = schema
+        .const_value
+        .iter_mut()
+        .chain(schema.default_value.iter_mut())
+        .chain(schema.enum_values.iter_mut().flatten())
+        .chain(schema.examples.iter_mut().flatten())
+        .chain(schema.extensions.values_mut())
+        .collect();
+    for value in values {
+        value.sort_all_objects();
+    }
+    if schema.properties.keys().is_sorted() {
+        return false;
+    }
+    let sorted: BTreeMap<String, JsonSchema> =
+        std::mem::take(&mut schema.properties).into_iter().collect();
+    schema.properties = sorted.into_iter().collect();
+    true
+}
+
+fn canonicalize_required(schema: &mut JsonSchema) -> bool {
+    let Some(required) = schema.required.as_mut() else {
+        return false;
+    };
+    let before: Vec<String> = required.clone();
+    required.sort();
+    required.dedup();
+    if required.is_empty() {
+        schema.required = None;
+        return true;
+    }
+    *required != before
+}
+
+fn canonicalize_enum(schema: &mut JsonSchema) -> bool {
+    let Some(values) = schema.enum_values.as_mut() else {
+        return false;
+    };
+    let mut keyed: Vec<(String, serde_json::Value)> =
+        values.drain(..).map(|v| (v.to_string(), v)).collect();
+    let before: Vec<String> = keyed.iter().map(|(k, _)| k.clone()).collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    keyed.dedup_by(|a, b| a.0 == b.0);
+    let changed: bool =
+        keyed.len() != before.len() || keyed.iter().zip(&before).any(|((k, _), b)| k != b);
+    values.extend(keyed.into_iter().map(|(_, v)| v));
+    changed
+}
+
+fn drop_no_op_keywords(schema: &mut JsonSchema) -> bool {
+    let empty: JsonSchema = JsonSchema::default();
+    let mut changed: bool = false;
+    let additional_properties_no_op: bool = match schema.additional_properties.as_ref() {
+        Some(AdditionalProperties::Allow) => true,
+        Some(AdditionalProperties::Schema(s)) => **s == empty,
+        None | Some(AdditionalProperties::Forbid) => false,
+    };
+    if additional_properties_no_op {
+        schema.additional_properties = None;
+        changed = true;
+    }
+    if schema.items.as_deref() == Some(&empty) {
+        schema.items = None;
+        changed = true;
+    }
+    if schema.unique_items == Some(false) {
+        schema.unique_items = None;
+        changed = true;
+    }
+    if schema.min_items == Some(0) {
+        schema.min_items = None;
+        changed = true;
+    }
+    if schema.min_length == Some(0) {
+        schema.min_length = None;
+        changed = true;
+    }
+    if schema.deprecated == Some(false) {
+        schema.deprecated = None;
+        changed = true;
+    }
+    if schema.examples.as_ref().is_some_and(Vec::is_empty) {
+        schema.examples = None;
+        changed = true;
+    }
+    if schema.defs.as_ref().is_some_and(BTreeMap::is_empty) {
+        schema.defs = None;
+        changed = true;
+    }
+    if schema.definitions.as_ref().is_some_and(BTreeMap::is_empty) {
+        schema.definitions = None;
+        changed = true;
+    }
+    if let Some(all_of) = schema.all_of.as_mut() {
+        let before: usize = all_of.len();
+        all_of.retain(|branch| *branch != empty);
+        changed |= all_of.len() != before;
+        if all_of.is_empty() {
+            schema.all_of = None;
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Collapses the combinator selected by `field` when it has exactly one branch; see
+/// [`JsonSchema::canonicalize`] for when that is possible.
+fn collapse_single_branch(
+    schema: &mut JsonSchema,
+    field: fn(&mut JsonSchema) -> &mut Option<Vec<JsonSchema>>,
+) -> bool {
+    if field(schema)
+        .as_ref()
+        .is_none_or(|branches| branches.len() != 1)
+    {
+        return false;
+    }
+    let branch: JsonSchema = field(schema)
+        .take()
+        .and_then(|mut branches| branches.pop())
+        .expect("exactly one branch");
+    if *schema == JsonSchema::default() {
+        *schema = branch;
+        return true;
+    }
+    if let Some(merged) = merge_disjoint(schema, &branch) {
+        *schema = merged;
+        true
+    } else {
+        *field(schema) = Some(vec![branch]);
+        false
+    }
+}
+
+/// Unions the keywords of `parent` and `branch` when they share none and merging cannot change
+/// how `$ref`s resolve; `None` otherwise.
+fn merge_disjoint(parent: &JsonSchema, branch: &JsonSchema) -> Option<JsonSchema> {
+    let serde_json::Value::Object(mut merged) = serde_json::to_value(parent).ok()? else {
+        return None;
+    };
+    let serde_json::Value::Object(branch_map) = serde_json::to_value(branch).ok()? else {
+        return None;
+    };
+    if merged.contains_key("$ref")
+        || UNMERGEABLE_BRANCH_KEYWORDS
+            .iter()
+            .any(|k| branch_map.contains_key(*k))
+        || branch_map.keys().any(|k| merged.contains_key(k))
+    {
+        return None;
+    }
+    merged.extend(branch_map);
+    serde_json::from_value(serde_json::Value::Object(merged)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FingerprintMode;
+    use crate::json_schema::JsonSchema;
+
+    #[test]
+    fn canonicalize_sorts_and_dedupes_required_and_enum() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","required":["b","a","b"],"properties":{"k":{"enum":["z",1,"a","z"]}}}"#,
+        ).unwrap();
+        let actual: JsonSchema = schema.canonicalize();
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","required":["a","b"],"properties":{"k":{"enum":["a","z",1]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn canonicalize_drops_no_op_keywords() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{
+                "type": "array",
+                "required": [],
+                "additionalProperties": true,
+                "uniqueItems": false,
+                "minItems": 0,
+                "deprecated": false,
+                "examples": [],
+                "$defs": {},
+                "allOf": [{}],
+                "items": {"type": "string", "minLength": 0, "additionalProperties": {}}
+            }"#,
+        )
+        .unwrap();
+        let actual: JsonSchema = schema.canonicalize();
+        let expected: JsonSchema =
+            serde_json::from_str(r#"{"type":"array","items":{"type":"string"}}"#).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn canonicalize_keeps_empty_enum_and_forbidden_additional_properties() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"enum":[],"additionalProperties":false}"#).unwrap();
+        let actual: JsonSchema = schema.canonicalize();
+        let expected: JsonSchema = schema.clone();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn canonicalize_collapses_single_branch_combinators() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"properties":{
+                "a": {"anyOf": [{"type": "string"}]},
+                "b": {"description": "B", "oneOf": [{"type": "integer", "minimum": 1}]},
+                "c": {"allOf": [{"allOf": [{"items": {}}]}]}
+            }}"#,
+        )
+        .unwrap();
+        let actual: JsonSchema = schema.canonicalize();
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"properties":{
+                "a": {"type": "string"},
+                "b": {"description": "B", "type": "integer", "minimum": 1},
+                "c": {}
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn canonicalize_keeps_single_branch_when_merge_would_change_meaning() {
+        let schema: JsonSchema = serde_json::from_str(
+            r##"{"properties":{
+                "overlap": {"type": "integer", "allOf": [{"type": "number"}]},
+                "ref": {"description": "R", "allOf": [{"$ref": "#/$defs/X"}]}
+            },"$defs":{"X":{"type":"string"}}}"##,
+        )
+        .unwrap();
+        let actual: JsonSchema = schema.canonicalize();
+        let expected: JsonSchema = schema.clone();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fingerprint_is_equal_for_equivalent_schemas() {
+        let a: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","required":["x","y"],"properties":{"x":{"allOf":[{"type":"string"}]}}}"#,
+        ).unwrap();
+        let b: JsonSchema = serde_json::from_str(
+            r#"{"properties":{"x":{"type":"string"}},"required":["y","x"],"type":"object","additionalProperties":true}"#,
+        ).unwrap();
+        assert_eq!(
+            a.fingerprint(FingerprintMode::Full),
+            b.fingerprint(FingerprintMode::Full)
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_property_and_object_key_order() {
+        let a: JsonSchema = serde_json::from_str(
+            r#"{"properties":{"b":{"type":"string"},"a":{"default":{"y":1,"x":2}}}}"#,
+        )
+        .unwrap();
+        let b: JsonSchema = serde_json::from_str(
+            r#"{"properties":{"a":{"default":{"x":2,"y":1}},"b":{"type":"string"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            a.fingerprint(FingerprintMode::Full),
+            b.fingerprint(FingerprintMode::Full)
+        );
+    }
+
+    #[test]
+    fn fingerprint_functional_ignores_annotations_but_full_does_not() {
+        let a: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","description":"one","x-owner":"a"}"#).unwrap();
+        let b: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","description":"two","$comment":"c"}"#)
+                .unwrap();
+        assert_eq!(
+            a.fingerprint(FingerprintMode::Functional),
+            b.fingerprint(FingerprintMode::Functional)
+        );
+        assert_ne!(
+            a.fingerprint(FingerprintMode::Full),
+            b.fingerprint(FingerprintMode::Full)
+        );
+    }
+
+    #[test]
+    fn fingerprint_changes_with_functional_keywords() {
+        let a: JsonSchema = serde_json::from_str(r#"{"type":"string","maxLength":3}"#).unwrap();
+        let b: JsonSchema = serde_json::from_str(r#"{"type":"string","maxLength":4}"#).unwrap();
+        assert_ne!(
+            a.fingerprint(FingerprintMode::Functional),
+            b.fingerprint(FingerprintMode::Functional)
+        );
+    }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"type":"string"}"#).unwrap();
+        let actual: u64 = schema.fingerprint(FingerprintMode::Full);
+        let expected: u64 = {
+            let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+            for byte in br#"{"type":"string"}"# {
+                hash ^= u64::from(*byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+            hash
+        };
+        assert_eq!(expected, actual);
+    }
+}
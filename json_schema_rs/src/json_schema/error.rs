@@ -1,6 +1,8 @@
 //! Errors when parsing JSON Schema.
 
+use crate::validator::ValidationError;
 use std::fmt;
+use std::path::PathBuf;
 
 /// Result type for JSON Schema parsing operations.
 pub type JsonSchemaParseResult<T> = Result<T, JsonSchemaParseError>;
@@ -19,6 +21,12 @@ pub enum JsonSchemaParseError {
     },
     /// I/O error when reading from a reader or file.
     Io(std::io::Error),
+    /// Meta-schema validation was enabled and the document is not a valid schema for its draft.
+    /// Each error's path points into the schema document.
+    MetaSchema(Vec<ValidationError>),
+    /// The YAML document is malformed or uses a construct JSON cannot represent.
+    #[cfg(feature = "yaml")]
+    Yaml(crate::yaml::YamlError),
 }
 
 impl fmt::Display for JsonSchemaParseError {
@@ -29,6 +37,15 @@ impl fmt::Display for JsonSchemaParseError {
                 write!(f, "unknown schema key \"{key}\" at {path}")
             }
             JsonSchemaParseError::Io(e) => write!(f, "io error: {e}"),
+            JsonSchemaParseError::MetaSchema(errors) => {
+                write!(f, "schema does not conform to its meta-schema:")?;
+                for e in errors {
+                    write!(f, "\n  {e}")?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "yaml")]
+            JsonSchemaParseError::Yaml(e) => write!(f, "invalid YAML: {e}"),
         }
     }
 }
@@ -37,8 +54,10 @@ impl std::error::Error for JsonSchemaParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             JsonSchemaParseError::Serde(e) => Some(e),
-            JsonSchemaParseError::UnknownField { .. } => None,
+            JsonSchemaParseError::UnknownField { .. } | JsonSchemaParseError::MetaSchema(_) => None,
             JsonSchemaParseError::Io(e) => Some(e),
+            #[cfg(feature = "yaml")]
+            JsonSchemaParseError::Yaml(e) => Some(e),
         }
     }
 }
@@ -54,3 +73,67 @@ impl From<std::io::Error> for JsonSchemaParseError {
         JsonSchemaParseError::Io(e)
     }
 }
+
+#[cfg(feature = "yaml")]
+impl From<crate::yaml::YamlError> for JsonSchemaParseError {
+    fn from(e: crate::yaml::YamlError) -> Self {
+        JsonSchemaParseError::Yaml(e)
+    }
+}
+
+/// Result type for bundling and dereferencing.
+pub type BundleResult<T> = Result<T, BundleError>;
+
+/// Error when bundling external `$ref`s into one document or inlining `$ref`s.
+#[derive(Debug)]
+pub enum BundleError {
+    /// A referenced schema file could not be read or parsed.
+    Load {
+        /// Path of the referenced file.
+        path: PathBuf,
+        source: JsonSchemaParseError,
+    },
+    /// `$ref` uses a form this crate cannot load (e.g. an `http(s)://` URI).
+    UnsupportedRef { ref_str: String },
+    /// `$ref` points outside the document; bundle the schema before dereferencing it.
+    ExternalRef { ref_str: String },
+    /// `$ref` fragment is unsupported or names a definition that does not exist.
+    UnresolvedRef {
+        ref_str: String,
+        /// Human-readable reason (for user-facing context).
+        reason: String,
+    },
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Load { path, source } => {
+                write!(f, "failed to load {}: {source}", path.display())
+            }
+            BundleError::UnsupportedRef { ref_str } => {
+                write!(
+                    f,
+                    "unsupported $ref \"{ref_str}\": only local files can be loaded"
+                )
+            }
+            BundleError::ExternalRef { ref_str } => {
+                write!(f, "$ref \"{ref_str}\" is external; bundle the schema first")
+            }
+            BundleError::UnresolvedRef { ref_str, reason } => {
+                write!(f, "could not resolve $ref \"{ref_str}\": {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BundleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BundleError::Load { source, .. } => Some(source),
+            BundleError::UnsupportedRef { .. }
+            | BundleError::ExternalRef { .. }
+            | BundleError::UnresolvedRef { .. } => None,
+        }
+    }
+}
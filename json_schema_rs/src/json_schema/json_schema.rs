@@ -1,11 +1,24 @@
 //! In-memory representation of JSON Schema for codegen.
 
 use super::error::{JsonSchemaParseError, JsonSchemaParseResult};
+use super::property_map::PropertyMap;
 use super::settings::JsonSchemaSettings;
+use super::spec_version::SpecVersion;
+use crate::source_map::SourceMap;
+use crate::validator::ValidationResult;
+use crate::validator::meta_schema;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::io::Read;
 
+/// [`PropertyMap`] with a different value type, for the strict parsing helper.
+#[cfg(not(feature = "preserve_order"))]
+pub(crate) type PropertyMapOf<V> = BTreeMap<String, V>;
+
+/// [`PropertyMap`] with a different value type, for the strict parsing helper.
+#[cfg(feature = "preserve_order")]
+pub(crate) type PropertyMapOf<V> = indexmap::IndexMap<String, V>;
+
 /// Returns true when `required` should be omitted from serialized output (None or empty).
 #[expect(clippy::ref_option)]
 fn skip_required(v: &Option<Vec<String>>) -> bool {
@@ -155,7 +168,7 @@ pub(crate) struct DenyUnknownFieldsJsonSchema {
     #[serde(default)]
     pub(crate) definitions: Option<BTreeMap<String, DenyUnknownFieldsJsonSchema>>,
     #[serde(default)]
-    pub(crate) properties: Option<BTreeMap<String, DenyUnknownFieldsJsonSchema>>,
+    pub(crate) properties: Option<PropertyMapOf<DenyUnknownFieldsJsonSchema>>,
     #[serde(default, rename = "additionalProperties")]
     pub(crate) additional_properties: Option<AdditionalProperties>,
     #[serde(default)]
@@ -216,7 +229,7 @@ pub(crate) fn deny_unknown_fields_helper_to_schema(h: DenyUnknownFieldsJsonSchem
             .map(|(k, v)| (k, deny_unknown_fields_helper_to_schema(v)))
             .collect()
     });
-    let properties: BTreeMap<String, JsonSchema> = h
+    let properties: PropertyMap = h
         .properties
         .unwrap_or_default()
         .into_iter()
@@ -271,6 +284,7 @@ pub(crate) fn deny_unknown_fields_helper_to_schema(h: DenyUnknownFieldsJsonSchem
         all_of,
         any_of,
         one_of,
+        extensions: BTreeMap::new(),
     }
 }
 
@@ -301,9 +315,9 @@ pub struct JsonSchema {
     #[serde(skip_serializing_if = "skip_defs_map")]
     pub definitions: Option<BTreeMap<String, JsonSchema>>,
 
-    /// Object properties (only when type is "object"). Default empty; use `BTreeMap` for stable ordering.
-    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
-    pub properties: BTreeMap<String, JsonSchema>,
+    /// Object properties (only when type is "object"). Default empty; sorted by name unless the `preserve_order` feature keeps declaration order (see [`PropertyMap`]).
+    #[serde(skip_serializing_if = "PropertyMap::is_empty")]
+    pub properties: PropertyMap,
 
     /// When set, controls whether properties not in `properties` are allowed (Allow/absent), forbidden (Forbid), or must validate against a schema (Schema). We do not implement `patternProperties` yet, so "additional" means any key not in `properties`.
     #[serde(
@@ -400,6 +414,10 @@ pub struct JsonSchema {
     /// oneOf: instance must validate against exactly one subschema. Stored as-is at ingestion; validator and codegen (union enum) use it. Not emitted by reverse codegen.
     #[serde(rename = "oneOf", skip_serializing_if = "skip_one_of")]
     pub one_of: Option<Vec<JsonSchema>>,
+
+    /// Keywords the model does not recognize (e.g. `x-*` vendor keywords, `OpenAPI` extensions, newer-draft keywords, or keywords handled by a registered [`Keyword`](crate::Keyword)), captured with their raw values by lenient parsing and serialized back alongside the modeled keywords. Always empty after strict parsing. Keys must not collide with modeled keywords.
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, serde_json::Value>,
 }
 
 impl<'de> Deserialize<'de> for JsonSchema {
@@ -423,7 +441,7 @@ impl<'de> Deserialize<'de> for JsonSchema {
             #[serde(default)]
             definitions: Option<BTreeMap<String, JsonSchema>>,
             #[serde(default)]
-            properties: Option<BTreeMap<String, JsonSchema>>,
+            properties: Option<PropertyMap>,
             #[serde(default, rename = "additionalProperties")]
             additional_properties: Option<AdditionalProperties>,
             #[serde(default)]
@@ -470,6 +488,8 @@ impl<'de> Deserialize<'de> for JsonSchema {
             any_of: Option<Vec<JsonSchema>>,
             #[serde(default, rename = "oneOf")]
             one_of: Option<Vec<JsonSchema>>,
+            #[serde(flatten)]
+            extensions: BTreeMap<String, serde_json::Value>,
         }
         let h: JsonSchemaHelper = JsonSchemaHelper::deserialize(deserializer)?;
         Ok(JsonSchema {
@@ -503,6 +523,7 @@ impl<'de> Deserialize<'de> for JsonSchema {
             all_of: h.all_of,
             any_of: h.any_of,
             one_of: h.one_of,
+            extensions: h.extensions,
         })
     }
 }
@@ -591,6 +612,31 @@ impl JsonSchema {
             .is_some_and(serde_json::Value::is_string)
     }
 
+    /// Validates a raw schema document against the bundled official meta-schema of its spec version.
+    ///
+    /// The version is [`JsonSchemaSettings::spec_version`] when set, otherwise inferred from the
+    /// document's `$schema` (Draft 2020-12 when absent or unrecognized). Use this to catch schemas
+    /// that parse but are invalid (e.g. `minLength: -1`, `type: "strng"`) with precise locations.
+    ///
+    /// # Errors
+    ///
+    /// Returns every [`ValidationError`](crate::ValidationError) found; each error's path points
+    /// into `document`. Drafts before draft-04 have no bundled meta-schema and yield
+    /// [`ValidationError::UnsupportedMetaSchema`](crate::ValidationError::UnsupportedMetaSchema).
+    pub fn validate_against_metaschema(
+        document: &serde_json::Value,
+        settings: &JsonSchemaSettings,
+    ) -> ValidationResult {
+        let spec_version: SpecVersion = settings.spec_version.unwrap_or_else(|| {
+            document
+                .get("$schema")
+                .and_then(serde_json::Value::as_str)
+                .and_then(SpecVersion::from_schema_uri)
+                .unwrap_or(SpecVersion::Draft202012)
+        });
+        meta_schema::validate_against_meta_schema(document, spec_version)
+    }
+
     /// Parse a JSON Schema from a string with the given settings.
     ///
     /// When [`JsonSchemaSettings::disallow_unknown_fields`] is `false`, unknown keys
@@ -600,8 +646,13 @@ impl JsonSchema {
     ///
     /// Returns [`JsonSchemaParseError::Serde`] on invalid JSON or type mismatch.
     /// Returns [`JsonSchemaParseError::UnknownField`] when strict and an unknown key is present.
+    /// Returns [`JsonSchemaParseError::MetaSchema`] when [`JsonSchemaSettings::validate_metaschema`]
+    /// is set and the document does not conform to its meta-schema.
     pub fn new_from_str(json: &str, settings: &JsonSchemaSettings) -> JsonSchemaParseResult<Self> {
-        if settings.disallow_unknown_fields {
+        if settings.validate_metaschema {
+            let value: serde_json::Value = serde_json::from_str(json)?;
+            Self::new_from_serde_value(&value, settings)
+        } else if settings.disallow_unknown_fields {
             parse_strict_str(json)
         } else {
             let schema: JsonSchema = serde_json::from_str(json)?;
@@ -609,6 +660,20 @@ impl JsonSchema {
         }
     }
 
+    /// Parse a JSON Schema from a string with the given settings, also recording where every
+    /// value sits in `json` so errors can be reported by line and column.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new_from_str`](Self::new_from_str).
+    pub fn new_from_str_with_source_map(
+        json: &str,
+        settings: &JsonSchemaSettings,
+    ) -> JsonSchemaParseResult<(Self, SourceMap)> {
+        let schema: JsonSchema = Self::new_from_str(json, settings)?;
+        Ok((schema, SourceMap::from_json(json)))
+    }
+
     /// Parse a JSON Schema from a byte slice with the given settings.
     ///
     /// Same as [`new_from_str`](Self::new_from_str) but takes bytes (e.g. from a file).
@@ -620,7 +685,10 @@ impl JsonSchema {
         slice: &[u8],
         settings: &JsonSchemaSettings,
     ) -> JsonSchemaParseResult<Self> {
-        if settings.disallow_unknown_fields {
+        if settings.validate_metaschema {
+            let value: serde_json::Value = serde_json::from_slice(slice)?;
+            Self::new_from_serde_value(&value, settings)
+        } else if settings.disallow_unknown_fields {
             parse_strict_slice(slice)
         } else {
             let schema: JsonSchema = serde_json::from_slice(slice)?;
@@ -640,6 +708,10 @@ impl JsonSchema {
         value: &serde_json::Value,
         settings: &JsonSchemaSettings,
     ) -> JsonSchemaParseResult<Self> {
+        if settings.validate_metaschema {
+            Self::validate_against_metaschema(value, settings)
+                .map_err(JsonSchemaParseError::MetaSchema)?;
+        }
         if settings.disallow_unknown_fields {
             parse_strict_value(value)
         } else {
@@ -672,7 +744,8 @@ impl JsonSchema {
     /// Parse a JSON Schema from a file path with the given settings.
     ///
     /// Same as [`new_from_str`](Self::new_from_str) but reads from a file. I/O errors (e.g. file not found)
-    /// are returned as [`JsonSchemaParseError::Io`].
+    /// are returned as [`JsonSchemaParseError::Io`]. With the `yaml` feature, files ending in
+    /// `.yaml` or `.yml` are read with [`new_from_yaml_reader`](Self::new_from_yaml_reader).
     ///
     /// # Errors
     ///
@@ -683,8 +756,60 @@ impl JsonSchema {
         settings: &JsonSchemaSettings,
     ) -> JsonSchemaParseResult<Self> {
         let f: std::fs::File = std::fs::File::open(path.as_ref())?;
+        #[cfg(feature = "yaml")]
+        if crate::yaml::is_yaml_path(path.as_ref()) {
+            return Self::new_from_yaml_reader(f, settings);
+        }
         Self::new_from_reader(f, settings)
     }
+
+    /// Parse a JSON Schema written as YAML with the given settings (requires the `yaml` feature).
+    ///
+    /// The document is converted to JSON by [`yaml::from_str`](crate::yaml::from_str), then parsed
+    /// as by [`new_from_serde_value`](Self::new_from_serde_value).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JsonSchemaParseError::Yaml`] when `yaml` is malformed or has no JSON equivalent.
+    /// Otherwise same as [`new_from_str`](Self::new_from_str).
+    #[cfg(feature = "yaml")]
+    pub fn new_from_yaml_str(
+        yaml: &str,
+        settings: &JsonSchemaSettings,
+    ) -> JsonSchemaParseResult<Self> {
+        let value: serde_json::Value = crate::yaml::from_str(yaml)?;
+        Self::new_from_serde_value(&value, settings)
+    }
+
+    /// Parse a JSON Schema written as YAML from a byte slice with the given settings.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`new_from_yaml_str`](Self::new_from_yaml_str).
+    #[cfg(feature = "yaml")]
+    pub fn new_from_yaml_slice(
+        slice: &[u8],
+        settings: &JsonSchemaSettings,
+    ) -> JsonSchemaParseResult<Self> {
+        let value: serde_json::Value = crate::yaml::from_slice(slice)?;
+        Self::new_from_serde_value(&value, settings)
+    }
+
+    /// Parse a JSON Schema written as YAML from a reader with the given settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JsonSchemaParseError::Io`] on read failure.
+    /// Otherwise same as [`new_from_yaml_str`](Self::new_from_yaml_str).
+    #[cfg(feature = "yaml")]
+    pub fn new_from_yaml_reader<R: Read>(
+        mut reader: R,
+        settings: &JsonSchemaSettings,
+    ) -> JsonSchemaParseResult<Self> {
+        let mut buf: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        Self::new_from_yaml_slice(&buf, settings)
+    }
 }
 
 // TryFrom: parse into JsonSchema with default settings
@@ -800,7 +925,7 @@ impl TryFrom<JsonSchema> for Vec<u8> {
 
 #[cfg(test)]
 mod tests {
-    use super::JsonSchema;
+    use super::{JsonSchema, PropertyMap};
     use crate::json_schema::{
         JsonSchemaParseError, JsonSchemaSettings, SpecVersion, resolved_spec_version,
     };
@@ -819,6 +944,38 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[cfg(not(feature = "preserve_order"))]
+    #[test]
+    fn properties_are_sorted_by_name_when_parsed_and_serialized() {
+        let json =
+            r#"{"type":"object","properties":{"name":{"type":"string"},"id":{"type":"integer"}}}"#;
+        let schema: JsonSchema = JsonSchema::try_from(json).expect("parse");
+        let actual: String = (&schema).try_into().expect("serialize");
+        let expected =
+            r#"{"type":"object","properties":{"id":{"type":"integer"},"name":{"type":"string"}}}"#;
+        assert_eq!(expected, actual);
+    }
+
+    #[cfg(feature = "preserve_order")]
+    #[test]
+    fn properties_keep_declaration_order_when_parsed_and_serialized() {
+        let json =
+            r#"{"type":"object","properties":{"name":{"type":"string"},"id":{"type":"integer"}}}"#;
+        for settings in [
+            JsonSchemaSettings::default(),
+            JsonSchemaSettings::builder()
+                .disallow_unknown_fields(true)
+                .build(),
+            JsonSchemaSettings::builder()
+                .validate_metaschema(true)
+                .build(),
+        ] {
+            let schema: JsonSchema = JsonSchema::new_from_str(json, &settings).expect("parse");
+            let actual: String = (&schema).try_into().expect("serialize");
+            assert_eq!(json, actual);
+        }
+    }
+
     #[test]
     fn try_from_schema_with_comment_serializes_dollar_comment() {
         let schema: JsonSchema = JsonSchema {
@@ -925,7 +1082,7 @@ mod tests {
             JsonSchema::new_from_str(json, &settings).expect("parse with strict");
         let expected: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             examples: Some(vec![serde_json::json!(1)]),
             ..Default::default()
         };
@@ -988,7 +1145,7 @@ mod tests {
             .build();
         let actual: JsonSchema =
             JsonSchema::new_from_str(json, &settings).expect("parse with strict");
-        let mut properties: BTreeMap<String, JsonSchema> = BTreeMap::new();
+        let mut properties: PropertyMap = PropertyMap::new();
         properties.insert(
             "x".to_string(),
             JsonSchema {
@@ -1441,7 +1598,7 @@ mod tests {
         let expected: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "a".to_string(),
                     JsonSchema {
@@ -1461,7 +1618,7 @@ mod tests {
     fn parse_all_of_present() {
         let json = r#"{"allOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
         let parsed = JsonSchema::try_from(json).expect("parse");
-        let mut props_a = BTreeMap::new();
+        let mut props_a = PropertyMap::new();
         props_a.insert(
             "a".to_string(),
             JsonSchema {
@@ -1469,7 +1626,7 @@ mod tests {
                 ..Default::default()
             },
         );
-        let mut props_b = BTreeMap::new();
+        let mut props_b = PropertyMap::new();
         props_b.insert(
             "b".to_string(),
             JsonSchema {
@@ -1515,7 +1672,7 @@ mod tests {
     fn parse_all_of_single_subschema() {
         let json = r#"{"allOf":[{"type":"object","properties":{"x":{"type":"string"}}}]}"#;
         let parsed = JsonSchema::try_from(json).expect("parse");
-        let mut props = BTreeMap::new();
+        let mut props = PropertyMap::new();
         props.insert(
             "x".to_string(),
             JsonSchema {
@@ -1572,7 +1729,7 @@ mod tests {
     fn parse_any_of_single_subschema() {
         let json = r#"{"anyOf":[{"type":"object","properties":{"x":{"type":"string"}}}]}"#;
         let parsed = JsonSchema::try_from(json).expect("parse");
-        let mut props = BTreeMap::new();
+        let mut props = PropertyMap::new();
         props.insert(
             "x".to_string(),
             JsonSchema {
@@ -1629,7 +1786,7 @@ mod tests {
     fn parse_one_of_single_subschema() {
         let json = r#"{"oneOf":[{"type":"object","properties":{"x":{"type":"string"}}}]}"#;
         let parsed = JsonSchema::try_from(json).expect("parse");
-        let mut props = BTreeMap::new();
+        let mut props = PropertyMap::new();
         props.insert(
             "x".to_string(),
             JsonSchema {
@@ -1652,7 +1809,7 @@ mod tests {
         let expected: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "x".to_string(),
                     JsonSchema {
@@ -1677,18 +1834,30 @@ mod tests {
     }
 
     #[test]
-    fn deserialize_ignores_unknown_keys() {
+    fn deserialize_captures_unknown_keys_as_extensions() {
         let json =
             r#"{"type":"object","properties":{},"$schema":"https://example.com","unknown":42}"#;
         let expected: JsonSchema = JsonSchema {
             schema: Some("https://example.com".to_string()),
             type_: Some("object".to_string()),
+            extensions: BTreeMap::from([("unknown".to_string(), serde_json::json!(42))]),
             ..Default::default()
         };
         let actual: JsonSchema = serde_json::from_str(json).expect("parse");
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn serialize_round_trips_extensions_in_place() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string","x-pii":true}},"x-owner":"team-a","x-meta":{"tags":["a","b"],"since":3}}"#;
+        let schema: JsonSchema = serde_json::from_str(json).expect("parse");
+        let name: &JsonSchema = schema.properties.get("name").expect("name property");
+        assert_eq!(name.extensions.get("x-pii"), Some(&serde_json::json!(true)));
+        let actual: serde_json::Value = serde_json::to_value(&schema).expect("serialize");
+        let expected: serde_json::Value = serde_json::from_str(json).expect("expected");
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn deserialize_type_array_takes_first() {
         let json = r#"{"type":["string", "null"],"properties":{}}"#;
@@ -2273,6 +2442,98 @@ mod tests {
         assert!(matches!(result, Err(JsonSchemaParseError::Io(_))));
     }
 
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_schema_from_yaml_str_matches_json() {
+        let yaml =
+            "type: object\nproperties:\n  name: {type: string, minLength: 1}\nrequired: [name]\n";
+        let json = r#"{"type":"object","properties":{"name":{"type":"string","minLength":1}},"required":["name"]}"#;
+        let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+        let expected: JsonSchema = JsonSchema::new_from_str(json, &settings).expect("parse json");
+        let actual: JsonSchema =
+            JsonSchema::new_from_yaml_str(yaml, &settings).expect("parse yaml");
+        assert_eq!(expected, actual);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_schema_from_yaml_unsupported_value_has_pointer() {
+        let yaml = "properties:\n  id: !Ref Id\n";
+        let settings: JsonSchemaSettings = JsonSchemaSettings::default();
+        let Err(JsonSchemaParseError::Yaml(crate::yaml::YamlError::Unsupported {
+            pointer, ..
+        })) = JsonSchema::new_from_yaml_str(yaml, &settings)
+        else {
+            panic!("expected unsupported YAML error");
+        };
+        let expected: crate::JsonPointer = crate::JsonPointer::try_from("/properties/id").unwrap();
+        assert_eq!(expected, pointer);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parse_schema_from_yaml_path_by_extension() {
+        let dir: tempfile::TempDir = tempfile::tempdir().expect("tempdir");
+        let path: std::path::PathBuf = dir.path().join("schema.yml");
+        std::fs::write(&path, "type: string\nformat: email\n").expect("write");
+        let actual: JsonSchema =
+            JsonSchema::new_from_path(&path, &JsonSchemaSettings::default()).expect("parse");
+        let expected: (Option<&str>, Option<&str>) = (Some("string"), Some("email"));
+        assert_eq!(
+            expected,
+            (actual.type_.as_deref(), actual.format.as_deref())
+        );
+    }
+
+    #[test]
+    fn validate_metaschema_setting_reports_pointer_paths() {
+        let json = r#"{"type":"object","properties":{"name":{"type":"string","minLength":-1}}}"#;
+        let settings: JsonSchemaSettings = JsonSchemaSettings::builder()
+            .validate_metaschema(true)
+            .build();
+        let Err(JsonSchemaParseError::MetaSchema(errors)) =
+            JsonSchema::new_from_str(json, &settings)
+        else {
+            panic!("expected meta-schema error");
+        };
+        let expected: Vec<crate::ValidationError> = vec![crate::ValidationError::BelowMinimum {
+            instance_path: crate::JsonPointer::try_from("/properties/name/minLength").unwrap(),
+            minimum: crate::OrderedF64(0.0),
+            actual: crate::OrderedF64(-1.0),
+        }];
+        assert_eq!(expected, errors);
+        let without: Result<JsonSchema, JsonSchemaParseError> =
+            JsonSchema::new_from_str(json, &JsonSchemaSettings::default());
+        assert!(matches!(without, Err(JsonSchemaParseError::Serde(_))));
+    }
+
+    #[test]
+    fn validate_metaschema_setting_accepts_valid_schema() {
+        let json = r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"object","properties":{"a":{"type":"integer","minimum":0}}}"#;
+        let settings: JsonSchemaSettings = JsonSchemaSettings::builder()
+            .validate_metaschema(true)
+            .disallow_unknown_fields(true)
+            .build();
+        let actual: JsonSchema =
+            JsonSchema::new_from_slice(json.as_bytes(), &settings).expect("parse");
+        let expected: JsonSchema = JsonSchema::try_from(json).expect("parse");
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_against_metaschema_uses_explicit_spec_version() {
+        let document: serde_json::Value = serde_json::json!({"properties": {"a": true}});
+        let draft_04: JsonSchemaSettings = JsonSchemaSettings::builder()
+            .spec_version(SpecVersion::Draft04)
+            .build();
+        assert!(JsonSchema::validate_against_metaschema(&document, &draft_04).is_err());
+        let inferred: JsonSchemaSettings = JsonSchemaSettings::default();
+        assert_eq!(
+            Ok(()),
+            JsonSchema::validate_against_metaschema(&document, &inferred)
+        );
+    }
+
     #[test]
     fn parse_schema_from_path_success() {
         let temp_dir = tempfile::tempdir().expect("temp dir");
@@ -2339,7 +2600,7 @@ mod tests {
         let actual: JsonSchema = JsonSchema::try_from(json).expect("parse");
         let expected: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             ..Default::default()
         };
         assert_eq!(expected, actual);
@@ -2381,7 +2642,7 @@ mod tests {
         let actual: JsonSchema = JsonSchema::try_from(json).expect("parse");
         let expected: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             ..Default::default()
         };
         assert_eq!(expected, actual);
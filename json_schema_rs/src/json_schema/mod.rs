@@ -1,13 +1,21 @@
 //! JSON Schema parsing, model, and settings.
 
+pub mod bundle;
+pub mod canonical;
 pub mod error;
 #[expect(clippy::module_inception)]
 pub mod json_schema;
+pub mod property_map;
 pub mod ref_resolver;
 pub mod settings;
 pub mod spec_version;
+pub mod walk;
 
-pub use error::{JsonSchemaParseError, JsonSchemaParseResult};
+pub use bundle::{bundle, bundle_from_path, deref};
+pub use canonical::FingerprintMode;
+pub use error::{BundleError, BundleResult, JsonSchemaParseError, JsonSchemaParseResult};
 pub use json_schema::JsonSchema;
+pub use property_map::PropertyMap;
 pub use settings::{JsonSchemaSettings, JsonSchemaSettingsBuilder, resolved_spec_version};
 pub use spec_version::SpecVersion;
+pub use walk::{SchemaKeyword, Walk};
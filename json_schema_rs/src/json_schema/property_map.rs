@@ -0,0 +1,310 @@
+//! [`PropertyMap`]: the map type of [`JsonSchema::properties`].
+
+use super::json_schema::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::Index;
+
+#[cfg(not(feature = "preserve_order"))]
+use std::collections::btree_map as map_impl;
+
+#[cfg(feature = "preserve_order")]
+use indexmap::map as map_impl;
+
+#[cfg(not(feature = "preserve_order"))]
+type MapImpl = map_impl::BTreeMap<String, JsonSchema>;
+
+#[cfg(feature = "preserve_order")]
+type MapImpl = map_impl::IndexMap<String, JsonSchema>;
+
+/// Property names to their schemas: the type of [`JsonSchema::properties`].
+///
+/// Iterates in name order by default. With the `preserve_order` feature it iterates in insertion
+/// order instead, so parsed schemas, generated structs, and serialized output list properties in
+/// the order the author declared them. The API is the same with or without the feature, and
+/// equality ignores order, so turning it on does not break code that uses the map.
+#[derive(Clone, Default, PartialEq)]
+pub struct PropertyMap {
+    map: MapImpl,
+}
+
+impl PropertyMap {
+    /// An empty map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of properties.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// True when there are no properties.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// The schema of property `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&JsonSchema> {
+        self.map.get(key)
+    }
+
+    /// The schema of property `key`, mutably.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JsonSchema> {
+        self.map.get_mut(key)
+    }
+
+    /// True when there is a property `key`.
+    #[must_use]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Sets the schema of property `key`, returning the previous one. A new key goes last in
+    /// insertion order; an existing key keeps its position.
+    pub fn insert(&mut self, key: String, schema: JsonSchema) -> Option<JsonSchema> {
+        self.map.insert(key, schema)
+    }
+
+    /// Removes property `key`, returning its schema. The other properties keep their order.
+    pub fn remove(&mut self, key: &str) -> Option<JsonSchema> {
+        #[cfg(not(feature = "preserve_order"))]
+        return self.map.remove(key);
+        #[cfg(feature = "preserve_order")]
+        return self.map.shift_remove(key);
+    }
+
+    /// Keeps only the properties for which `keep` returns true, in their order.
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: FnMut(&String, &mut JsonSchema) -> bool,
+    {
+        self.map.retain(keep);
+    }
+
+    /// The properties in map order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    /// The properties in map order, with mutable schemas.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            iter: self.map.iter_mut(),
+        }
+    }
+
+    /// The property names in map order.
+    #[must_use]
+    pub fn keys(&self) -> Keys<'_> {
+        Keys {
+            iter: self.map.keys(),
+        }
+    }
+
+    /// The property schemas in map order.
+    #[must_use]
+    pub fn values(&self) -> Values<'_> {
+        Values {
+            iter: self.map.values(),
+        }
+    }
+
+    /// The property schemas in map order, mutably.
+    pub fn values_mut(&mut self) -> ValuesMut<'_> {
+        ValuesMut {
+            iter: self.map.values_mut(),
+        }
+    }
+}
+
+impl fmt::Debug for PropertyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl Index<&str> for PropertyMap {
+    type Output = JsonSchema;
+
+    /// # Panics
+    ///
+    /// Panics when there is no property `key`.
+    fn index(&self, key: &str) -> &JsonSchema {
+        self.map
+            .get(key)
+            .unwrap_or_else(|| panic!("no property named {key:?}"))
+    }
+}
+
+impl Serialize for PropertyMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        MapImpl::deserialize(deserializer).map(|map| PropertyMap { map })
+    }
+}
+
+impl FromIterator<(String, JsonSchema)> for PropertyMap {
+    fn from_iter<I: IntoIterator<Item = (String, JsonSchema)>>(iter: I) -> Self {
+        PropertyMap {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(String, JsonSchema)> for PropertyMap {
+    fn extend<I: IntoIterator<Item = (String, JsonSchema)>>(&mut self, iter: I) {
+        self.map.extend(iter);
+    }
+}
+
+impl<const N: usize> From<[(String, JsonSchema); N]> for PropertyMap {
+    fn from(entries: [(String, JsonSchema); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl IntoIterator for PropertyMap {
+    type Item = (String, JsonSchema);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a PropertyMap {
+    type Item = (&'a String, &'a JsonSchema);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut PropertyMap {
+    type Item = (&'a String, &'a mut JsonSchema);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+/// Defines an iterator over a [`PropertyMap`] that hides the map behind it.
+macro_rules! delegate_iterator {
+    ($(#[$attr:meta])* $name:ident $(<$lt:lifetime>)? => $item:ty, $inner:ty) => {
+        $(#[$attr])*
+        pub struct $name$(<$lt>)? {
+            iter: $inner,
+        }
+
+        impl$(<$lt>)? Iterator for $name$(<$lt>)? {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<$item> {
+                self.iter.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+
+        impl$(<$lt>)? DoubleEndedIterator for $name$(<$lt>)? {
+            fn next_back(&mut self) -> Option<$item> {
+                self.iter.next_back()
+            }
+        }
+
+        impl$(<$lt>)? ExactSizeIterator for $name$(<$lt>)? {
+            fn len(&self) -> usize {
+                self.iter.len()
+            }
+        }
+
+        impl$(<$lt>)? FusedIterator for $name$(<$lt>)? {}
+    };
+}
+
+delegate_iterator!(
+    /// Iterator over the properties of a [`PropertyMap`], from [`PropertyMap::iter`].
+    Iter<'a> => (&'a String, &'a JsonSchema), map_impl::Iter<'a, String, JsonSchema>
+);
+delegate_iterator!(
+    /// Iterator over the properties of a [`PropertyMap`] with mutable schemas, from
+    /// [`PropertyMap::iter_mut`].
+    IterMut<'a> => (&'a String, &'a mut JsonSchema), map_impl::IterMut<'a, String, JsonSchema>
+);
+delegate_iterator!(
+    /// Owning iterator over the properties of a [`PropertyMap`].
+    IntoIter => (String, JsonSchema), map_impl::IntoIter<String, JsonSchema>
+);
+delegate_iterator!(
+    /// Iterator over the property names of a [`PropertyMap`], from [`PropertyMap::keys`].
+    Keys<'a> => &'a String, map_impl::Keys<'a, String, JsonSchema>
+);
+delegate_iterator!(
+    /// Iterator over the property schemas of a [`PropertyMap`], from [`PropertyMap::values`].
+    Values<'a> => &'a JsonSchema, map_impl::Values<'a, String, JsonSchema>
+);
+delegate_iterator!(
+    /// Iterator over the property schemas of a [`PropertyMap`], mutably, from
+    /// [`PropertyMap::values_mut`].
+    ValuesMut<'a> => &'a mut JsonSchema, map_impl::ValuesMut<'a, String, JsonSchema>
+);
+
+#[cfg(test)]
+mod tests {
+    use super::PropertyMap;
+    use crate::json_schema::JsonSchema;
+
+    fn titled(title: &str) -> JsonSchema {
+        JsonSchema {
+            title: Some(title.to_string()),
+            ..JsonSchema::default()
+        }
+    }
+
+    #[test]
+    fn remove_keeps_the_other_properties_in_order() {
+        let mut map: PropertyMap = ["b", "a", "c"]
+            .into_iter()
+            .map(|key| (key.to_string(), titled(key)))
+            .collect();
+        assert_eq!(Some(titled("a")), map.remove("a"));
+        let actual: Vec<&str> = map.keys().map(String::as_str).collect();
+        let expected: Vec<&str> = vec!["b", "c"];
+        assert_eq!(expected, actual);
+        assert_eq!(titled("c"), map["c"]);
+        assert_eq!(None, map.remove("a"));
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let a: PropertyMap = PropertyMap::from([
+            ("a".to_string(), titled("a")),
+            ("b".to_string(), titled("b")),
+        ]);
+        let b: PropertyMap = PropertyMap::from([
+            ("b".to_string(), titled("b")),
+            ("a".to_string(), titled("a")),
+        ]);
+        assert_eq!(a, b);
+    }
+}
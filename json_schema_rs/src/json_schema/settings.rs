@@ -18,6 +18,11 @@ pub struct JsonSchemaSettings {
     /// Explicit spec version to use. When `Some(v)`, that version is used and `$schema` is not used for draft selection.
     /// When `None`, the spec version is inferred from the root schema's `$schema` keyword; if absent or unrecognized, **Draft 2020-12** is used.
     pub spec_version: Option<SpecVersion>,
+
+    /// When `true`, parsing first validates the raw document against the official meta-schema of
+    /// its spec version (see [`JsonSchema::validate_against_metaschema`]) and fails with
+    /// [`JsonSchemaParseError::MetaSchema`](super::JsonSchemaParseError::MetaSchema) on any error.
+    pub validate_metaschema: bool,
 }
 
 /// Builder for [`JsonSchemaSettings`].
@@ -25,6 +30,7 @@ pub struct JsonSchemaSettings {
 pub struct JsonSchemaSettingsBuilder {
     disallow_unknown_fields: Option<bool>,
     spec_version: Option<SpecVersion>,
+    validate_metaschema: Option<bool>,
 }
 
 impl JsonSchemaSettingsBuilder {
@@ -43,12 +49,20 @@ impl JsonSchemaSettingsBuilder {
         self
     }
 
+    /// Set whether schema documents are validated against their draft's meta-schema before parsing.
+    #[must_use]
+    pub fn validate_metaschema(mut self, value: bool) -> Self {
+        self.validate_metaschema = Some(value);
+        self
+    }
+
     /// Build the settings. Any option not set uses its per-option default.
     #[must_use]
     pub fn build(self) -> JsonSchemaSettings {
         JsonSchemaSettings {
             disallow_unknown_fields: self.disallow_unknown_fields.unwrap_or(false),
             spec_version: self.spec_version,
+            validate_metaschema: self.validate_metaschema.unwrap_or(false),
         }
     }
 }
@@ -98,6 +112,15 @@ mod tests {
         assert!(settings.disallow_unknown_fields);
     }
 
+    #[test]
+    fn builder_validate_metaschema() {
+        let settings: JsonSchemaSettings = JsonSchemaSettings::builder()
+            .validate_metaschema(true)
+            .build();
+        assert!(settings.validate_metaschema);
+        assert!(!JsonSchemaSettings::default().validate_metaschema);
+    }
+
     #[test]
     fn builder_spec_version_explicit() {
         let settings: JsonSchemaSettings = JsonSchemaSettings::builder()
@@ -70,6 +70,15 @@ impl SpecVersion {
         }
     }
 
+    /// Returns the bundled official meta-schema document for this draft, for offline use.
+    ///
+    /// Available for draft-04 through 2020-12; draft-05 published no meta-schema of its own and
+    /// returns draft-04's. Returns `None` for drafts 00–03.
+    #[must_use]
+    pub fn meta_schema(self) -> Option<&'static serde_json::Value> {
+        crate::validator::meta_schema::meta_schema(self)
+    }
+
     /// Returns [`JsonSchemaSettings`] tuned for this spec version.
     /// Callers can use the builder to override individual options.
     ///
@@ -81,6 +90,7 @@ impl SpecVersion {
         JsonSchemaSettings {
             disallow_unknown_fields: false,
             spec_version: None,
+            validate_metaschema: false,
         }
     }
 }
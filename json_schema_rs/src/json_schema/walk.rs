@@ -0,0 +1,318 @@
+//! Non-recursive traversal over every subschema of a [`JsonSchema`].
+//!
+//! [`JsonSchema::walk`] yields each schema with its JSON Pointer and the [`SchemaKeyword`] it sits
+//! under, in pre-order and document order. [`JsonSchema::walk_mut`] visits the same nodes mutably.
+//! Both cover `$defs`, `definitions`, `properties`, `additionalProperties` (schema form), `items`,
+//! `allOf`, `anyOf`, and `oneOf`. `$ref`s are not followed.
+
+use super::json_schema::{AdditionalProperties, JsonSchema};
+use crate::json_pointer::JsonPointer;
+
+/// The keyword under which a subschema appears in its parent. The property name, definition
+/// name, or branch index is the last segment of the subschema's pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaKeyword {
+    /// The schema the walk started from.
+    Root,
+    /// A `$defs` entry.
+    Defs,
+    /// A `definitions` entry.
+    Definitions,
+    /// A `properties` entry.
+    Properties,
+    /// The `additionalProperties` schema.
+    AdditionalProperties,
+    /// The `items` schema.
+    Items,
+    /// An `allOf` branch.
+    AllOf,
+    /// An `anyOf` branch.
+    AnyOf,
+    /// A `oneOf` branch.
+    OneOf,
+}
+
+impl SchemaKeyword {
+    /// The JSON keyword (e.g. `"$defs"`); empty for [`SchemaKeyword::Root`].
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SchemaKeyword::Root => "",
+            SchemaKeyword::Defs => "$defs",
+            SchemaKeyword::Definitions => "definitions",
+            SchemaKeyword::Properties => "properties",
+            SchemaKeyword::AdditionalProperties => "additionalProperties",
+            SchemaKeyword::Items => "items",
+            SchemaKeyword::AllOf => "allOf",
+            SchemaKeyword::AnyOf => "anyOf",
+            SchemaKeyword::OneOf => "oneOf",
+        }
+    }
+}
+
+/// Direct subschemas of `schema` (located at `path`) in document order.
+pub(crate) fn children<'a>(
+    schema: &'a JsonSchema,
+    path: &JsonPointer,
+) -> Vec<(JsonPointer, SchemaKeyword, &'a JsonSchema)> {
+    let mut out: Vec<(JsonPointer, SchemaKeyword, &'a JsonSchema)> = Vec::new();
+    for (keyword, map) in [
+        (SchemaKeyword::Defs, &schema.defs),
+        (SchemaKeyword::Definitions, &schema.definitions),
+    ] {
+        for (name, def) in map.iter().flatten() {
+            out.push((path.push(keyword.as_str()).push(name), keyword, def));
+        }
+    }
+    for (name, property) in &schema.properties {
+        out.push((
+            path.push(SchemaKeyword::Properties.as_str()).push(name),
+            SchemaKeyword::Properties,
+            property,
+        ));
+    }
+    if let Some(AdditionalProperties::Schema(additional)) = &schema.additional_properties {
+        let keyword: SchemaKeyword = SchemaKeyword::AdditionalProperties;
+        out.push((path.push(keyword.as_str()), keyword, additional));
+    }
+    if let Some(items) = &schema.items {
+        let keyword: SchemaKeyword = SchemaKeyword::Items;
+        out.push((path.push(keyword.as_str()), keyword, items));
+    }
+    for (keyword, branches) in [
+        (SchemaKeyword::AllOf, &schema.all_of),
+        (SchemaKeyword::AnyOf, &schema.any_of),
+        (SchemaKeyword::OneOf, &schema.one_of),
+    ] {
+        for (index, branch) in branches.iter().flatten().enumerate() {
+            out.push((
+                path.push(keyword.as_str()).push(&index.to_string()),
+                keyword,
+                branch,
+            ));
+        }
+    }
+    out
+}
+
+/// Mutable counterpart of [`children`]; same order.
+pub(crate) fn children_mut<'a>(
+    schema: &'a mut JsonSchema,
+    path: &JsonPointer,
+) -> Vec<(JsonPointer, SchemaKeyword, &'a mut JsonSchema)> {
+    let mut out: Vec<(JsonPointer, SchemaKeyword, &'a mut JsonSchema)> = Vec::new();
+    for (keyword, map) in [
+        (SchemaKeyword::Defs, &mut schema.defs),
+        (SchemaKeyword::Definitions, &mut schema.definitions),
+    ] {
+        for (name, def) in map.iter_mut().flatten() {
+            out.push((path.push(keyword.as_str()).push(name), keyword, def));
+        }
+    }
+    for (name, property) in &mut schema.properties {
+        out.push((
+            path.push(SchemaKeyword::Properties.as_str()).push(name),
+            SchemaKeyword::Properties,
+            property,
+        ));
+    }
+    if let Some(AdditionalProperties::Schema(additional)) = &mut schema.additional_properties {
+        let keyword: SchemaKeyword = SchemaKeyword::AdditionalProperties;
+        out.push((path.push(keyword.as_str()), keyword, additional));
+    }
+    if let Some(items) = &mut schema.items {
+        let keyword: SchemaKeyword = SchemaKeyword::Items;
+        out.push((path.push(keyword.as_str()), keyword, items));
+    }
+    for (keyword, branches) in [
+        (SchemaKeyword::AllOf, &mut schema.all_of),
+        (SchemaKeyword::AnyOf, &mut schema.any_of),
+        (SchemaKeyword::OneOf, &mut schema.one_of),
+    ] {
+        for (index, branch) in branches.iter_mut().flatten().enumerate() {
+            out.push((
+                path.push(keyword.as_str()).push(&index.to_string()),
+                keyword,
+                branch,
+            ));
+        }
+    }
+    out
+}
+
+/// Pre-order iterator over a schema and all its subschemas. Created by [`JsonSchema::walk`].
+#[derive(Debug, Clone)]
+pub struct Walk<'a> {
+    stack: Vec<(JsonPointer, SchemaKeyword, &'a JsonSchema)>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = (JsonPointer, SchemaKeyword, &'a JsonSchema);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, keyword, schema) = self.stack.pop()?;
+        let mut next: Vec<(JsonPointer, SchemaKeyword, &'a JsonSchema)> = children(schema, &path);
+        next.reverse();
+        self.stack.extend(next);
+        Some((path, keyword, schema))
+    }
+}
+
+impl JsonSchema {
+    /// Iterates over this schema and every subschema in pre-order (parents before children,
+    /// siblings in document order), yielding each with its JSON Pointer relative to `self` and
+    /// the keyword it appears under. `self` is yielded first with [`SchemaKeyword::Root`] and the
+    /// root pointer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use json_schema_rs::{JsonSchema, SchemaKeyword};
+    ///
+    /// let schema: JsonSchema = serde_json::from_str(
+    ///     r#"{"type":"object","properties":{"tags":{"type":"array","items":{"type":"string"}}}}"#,
+    /// )
+    /// .unwrap();
+    /// let pointers: Vec<String> = schema.walk().map(|(p, _, _)| p.to_string()).collect();
+    /// assert_eq!(vec!["", "/properties/tags", "/properties/tags/items"], pointers);
+    /// let (_, keyword, _) = schema.walk().last().unwrap();
+    /// assert_eq!(SchemaKeyword::Items, keyword);
+    /// ```
+    #[must_use]
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            stack: vec![(JsonPointer::root(), SchemaKeyword::Root, self)],
+        }
+    }
+
+    /// Calls `f` on this schema and every subschema in the same order as [`walk`](Self::walk).
+    /// Each node's children are collected after `f` returns, so subschemas added or replaced by
+    /// `f` are visited too.
+    pub fn walk_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&JsonPointer, SchemaKeyword, &mut JsonSchema),
+    {
+        let mut stack: Vec<(JsonPointer, SchemaKeyword, &mut JsonSchema)> =
+            vec![(JsonPointer::root(), SchemaKeyword::Root, self)];
+        while let Some((path, keyword, schema)) = stack.pop() {
+            f(&path, keyword, schema);
+            let mut next: Vec<(JsonPointer, SchemaKeyword, &mut JsonSchema)> =
+                children_mut(schema, &path);
+            next.reverse();
+            stack.extend(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SchemaKeyword;
+    use crate::json_schema::JsonSchema;
+
+    #[test]
+    fn walk_visits_every_subschema_in_document_order() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{
+                "$defs": {"D": {"type": "string"}},
+                "definitions": {"L": {}},
+                "properties": {
+                    "a": {"type": "array", "items": {"type": "integer"}},
+                    "b": {"additionalProperties": {"type": "boolean"}}
+                },
+                "allOf": [{}],
+                "anyOf": [{}, {}],
+                "oneOf": [{}]
+            }"#,
+        )
+        .unwrap();
+        let actual: Vec<(String, SchemaKeyword)> =
+            schema.walk().map(|(p, k, _)| (p.to_string(), k)).collect();
+        let expected: Vec<(String, SchemaKeyword)> = vec![
+            (String::new(), SchemaKeyword::Root),
+            ("/$defs/D".to_string(), SchemaKeyword::Defs),
+            ("/definitions/L".to_string(), SchemaKeyword::Definitions),
+            ("/properties/a".to_string(), SchemaKeyword::Properties),
+            ("/properties/a/items".to_string(), SchemaKeyword::Items),
+            ("/properties/b".to_string(), SchemaKeyword::Properties),
+            (
+                "/properties/b/additionalProperties".to_string(),
+                SchemaKeyword::AdditionalProperties,
+            ),
+            ("/allOf/0".to_string(), SchemaKeyword::AllOf),
+            ("/anyOf/0".to_string(), SchemaKeyword::AnyOf),
+            ("/anyOf/1".to_string(), SchemaKeyword::AnyOf),
+            ("/oneOf/0".to_string(), SchemaKeyword::OneOf),
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn walk_escapes_pointer_segments() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"properties":{"a/b~c":{}}}"#).unwrap();
+        let actual: Vec<String> = schema.walk().map(|(p, _, _)| p.to_string()).collect();
+        let expected: Vec<String> = vec![String::new(), "/properties/a~1b~0c".to_string()];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn walk_does_not_follow_refs() {
+        let schema: JsonSchema =
+            serde_json::from_str(r##"{"properties":{"self":{"$ref":"#"}}}"##).unwrap();
+        let actual: usize = schema.walk().count();
+        let expected: usize = 2;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn walk_handles_deep_nesting_without_recursion() {
+        let mut schema: JsonSchema = JsonSchema::default();
+        for _ in 0..2_000 {
+            schema = JsonSchema {
+                items: Some(Box::new(schema)),
+                ..Default::default()
+            };
+        }
+        let actual: usize = schema.walk().count();
+        let expected: usize = 2_001;
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn walk_mut_transforms_every_node_with_pointer() {
+        let mut schema: JsonSchema = serde_json::from_str(
+            r#"{"properties":{"a":{"type":"string"}},"items":{"anyOf":[{}]}}"#,
+        )
+        .unwrap();
+        let mut visited: Vec<String> = Vec::new();
+        schema.walk_mut(|path, keyword, node| {
+            visited.push(path.to_string());
+            if keyword != SchemaKeyword::Root {
+                node.description = Some(path.to_string());
+            }
+        });
+        let expected_visited: Vec<String> = vec![
+            String::new(),
+            "/properties/a".to_string(),
+            "/items".to_string(),
+            "/items/anyOf/0".to_string(),
+        ];
+        assert_eq!(expected_visited, visited);
+        let expected: JsonSchema = serde_json::from_str(
+            r#"{"properties":{"a":{"type":"string","description":"/properties/a"}},"items":{"description":"/items","anyOf":[{"description":"/items/anyOf/0"}]}}"#,
+        ).unwrap();
+        assert_eq!(expected, schema);
+    }
+
+    #[test]
+    fn walk_mut_visits_subschemas_added_by_callback() {
+        let mut schema: JsonSchema = serde_json::from_str(r#"{"type":"array"}"#).unwrap();
+        let mut count: usize = 0;
+        schema.walk_mut(|_, keyword, node| {
+            count += 1;
+            if keyword == SchemaKeyword::Root {
+                node.items = Some(Box::new(JsonSchema::default()));
+            }
+        });
+        assert_eq!(2, count);
+    }
+}
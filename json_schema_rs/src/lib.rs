@@ -4,20 +4,42 @@
 //! to a writer. Supported keywords and types are documented in the README.
 
 pub mod code_gen;
+pub mod constraints;
+pub mod inference;
 pub mod json_pointer;
 pub mod json_schema;
+pub mod linter;
 pub mod reverse_code_gen;
 pub mod sanitizers;
+pub mod schema_diff;
+pub mod source_map;
 pub mod validator;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
 pub use code_gen::{
     CodeGenBackend, CodeGenError, CodeGenResult, CodeGenSettings, CodeGenSettingsBuilder,
-    DedupeMode, GenerateRustOutput, ModelNameSource, RustBackend, generate_rust,
+    DedupeMode, GenerateRustOutput, ModelNameSource, NameCollisionStrategy, RustBackend,
+    SkippedDef, SkippedDerive, TypeAttributes, generate_rust,
+};
+pub use constraints::Validate;
+pub use inference::{
+    InferError, InferResult, InferSettings, InferSettingsBuilder, SchemaInferrer, infer_schema,
 };
 pub use json_pointer::{JsonPointer, JsonPointerError};
 pub use json_schema::{
-    JsonSchema, JsonSchemaParseError, JsonSchemaParseResult, JsonSchemaSettings,
-    JsonSchemaSettingsBuilder, SpecVersion, resolved_spec_version,
+    BundleError, BundleResult, FingerprintMode, JsonSchema, JsonSchemaParseError,
+    JsonSchemaParseResult, JsonSchemaSettings, JsonSchemaSettingsBuilder, PropertyMap,
+    SchemaKeyword, SpecVersion, Walk, bundle, bundle_from_path, deref, resolved_spec_version,
 };
+pub use linter::{LintDiagnostic, LintSeverity, lint};
 pub use reverse_code_gen::ToJsonSchema;
-pub use validator::{OrderedF64, ValidationError, ValidationResult, validate};
+pub use schema_diff::{AdditionalPropertiesState, BoundKeyword, Compatibility, SchemaChange, diff};
+pub use source_map::{SourceMap, Span};
+pub use validator::{
+    FormatChecker, FormatMode, Keyword, KeywordRegistration, OrderedF64, UnknownFormatMode,
+    ValidationError, ValidationResult, ValidatorSettings, ValidatorSettingsBuilder, validate,
+    validate_with_settings,
+};
+#[cfg(feature = "yaml")]
+pub use yaml::YamlError;
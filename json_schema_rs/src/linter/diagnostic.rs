@@ -0,0 +1,191 @@
+use crate::json_pointer::JsonPointer;
+use crate::validator::ValidationError;
+use std::fmt;
+
+/// How serious a [`LintDiagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintSeverity {
+    /// The schema can never be satisfied (or part of it can't), or it is broken.
+    Error,
+    /// The schema works but is likely not what the author intended.
+    Warning,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Error => write!(f, "error"),
+            LintSeverity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A single lint finding: kind and schema location.
+///
+/// `schema_path` is a JSON Pointer into the schema document pointing at the offending keyword
+/// (e.g. `/properties/age/minimum`), so it can be mapped to a line and column with a
+/// [`SourceMap`](crate::SourceMap).
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintDiagnostic {
+    /// `minimum` is greater than `maximum`; no number satisfies the schema.
+    MinimumAboveMaximum {
+        /// JSON Pointer to the `minimum` keyword.
+        schema_path: JsonPointer,
+        minimum: f64,
+        maximum: f64,
+    },
+    /// `minLength` is greater than `maxLength`; no string satisfies the schema.
+    MinLengthAboveMaxLength {
+        /// JSON Pointer to the `minLength` keyword.
+        schema_path: JsonPointer,
+        min_length: u64,
+        max_length: u64,
+    },
+    /// `minItems` is greater than `maxItems`; no array satisfies the schema.
+    MinItemsAboveMaxItems {
+        /// JSON Pointer to the `minItems` keyword.
+        schema_path: JsonPointer,
+        min_items: u64,
+        max_items: u64,
+    },
+    /// `enum` is an empty array; no value satisfies the schema.
+    EmptyEnum {
+        /// JSON Pointer to the `enum` keyword.
+        schema_path: JsonPointer,
+    },
+    /// A `required` property is not in `properties` while `additionalProperties` is `false`,
+    /// so no object satisfies the schema.
+    RequiredPropertyForbidden {
+        /// JSON Pointer to the entry in the `required` array.
+        schema_path: JsonPointer,
+        /// The required property name.
+        property: String,
+    },
+    /// `pattern` is not a valid ECMA 262 regex.
+    InvalidPattern {
+        /// JSON Pointer to the `pattern` keyword.
+        schema_path: JsonPointer,
+        pattern: String,
+        /// Regex compiler error message.
+        reason: String,
+    },
+    /// `default` does not validate against the schema it is declared in.
+    InvalidDefault {
+        /// JSON Pointer to the `default` keyword.
+        schema_path: JsonPointer,
+        /// Validation errors for the default value; instance paths are relative to the default.
+        errors: Vec<ValidationError>,
+    },
+    /// `allOf` subschemas contradict each other (e.g. disjoint numeric bounds or different
+    /// `const` values for the same property).
+    AllOfContradiction {
+        /// JSON Pointer to the `allOf` keyword.
+        schema_path: JsonPointer,
+        /// Human-readable description of the contradiction.
+        reason: String,
+    },
+    /// A fragment `$ref` points at a `$defs`/`definitions` entry that does not exist.
+    UnresolvableRef {
+        /// JSON Pointer to the `$ref` keyword.
+        schema_path: JsonPointer,
+        ref_str: String,
+    },
+    /// A root `$defs`/`definitions` entry is never referenced from the root schema.
+    UnusedDefinition {
+        /// JSON Pointer to the definition (e.g. `/$defs/Unused`).
+        schema_path: JsonPointer,
+        name: String,
+    },
+}
+
+impl LintDiagnostic {
+    #[must_use]
+    pub fn schema_path(&self) -> &JsonPointer {
+        match self {
+            LintDiagnostic::MinimumAboveMaximum { schema_path, .. }
+            | LintDiagnostic::MinLengthAboveMaxLength { schema_path, .. }
+            | LintDiagnostic::MinItemsAboveMaxItems { schema_path, .. }
+            | LintDiagnostic::EmptyEnum { schema_path }
+            | LintDiagnostic::RequiredPropertyForbidden { schema_path, .. }
+            | LintDiagnostic::InvalidPattern { schema_path, .. }
+            | LintDiagnostic::InvalidDefault { schema_path, .. }
+            | LintDiagnostic::AllOfContradiction { schema_path, .. }
+            | LintDiagnostic::UnresolvableRef { schema_path, .. }
+            | LintDiagnostic::UnusedDefinition { schema_path, .. } => schema_path,
+        }
+    }
+
+    /// Severity of this kind of finding: unsatisfiable or broken schemas are errors; unused
+    /// definitions are warnings.
+    #[must_use]
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            LintDiagnostic::UnusedDefinition { .. } => LintSeverity::Warning,
+            LintDiagnostic::MinimumAboveMaximum { .. }
+            | LintDiagnostic::MinLengthAboveMaxLength { .. }
+            | LintDiagnostic::MinItemsAboveMaxItems { .. }
+            | LintDiagnostic::EmptyEnum { .. }
+            | LintDiagnostic::RequiredPropertyForbidden { .. }
+            | LintDiagnostic::InvalidPattern { .. }
+            | LintDiagnostic::InvalidDefault { .. }
+            | LintDiagnostic::AllOfContradiction { .. }
+            | LintDiagnostic::UnresolvableRef { .. } => LintSeverity::Error,
+        }
+    }
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.schema_path().display_root_or_path();
+        match self {
+            LintDiagnostic::MinimumAboveMaximum {
+                minimum, maximum, ..
+            } => write!(
+                f,
+                "{location}: minimum {minimum} is greater than maximum {maximum}; no number can match"
+            ),
+            LintDiagnostic::MinLengthAboveMaxLength {
+                min_length,
+                max_length,
+                ..
+            } => write!(
+                f,
+                "{location}: minLength {min_length} is greater than maxLength {max_length}; no string can match"
+            ),
+            LintDiagnostic::MinItemsAboveMaxItems {
+                min_items,
+                max_items,
+                ..
+            } => write!(
+                f,
+                "{location}: minItems {min_items} is greater than maxItems {max_items}; no array can match"
+            ),
+            LintDiagnostic::EmptyEnum { .. } => {
+                write!(f, "{location}: enum is empty; no value can match")
+            }
+            LintDiagnostic::RequiredPropertyForbidden { property, .. } => write!(
+                f,
+                "{location}: required property \"{property}\" is not in properties and additionalProperties is false; no object can match"
+            ),
+            LintDiagnostic::InvalidPattern {
+                pattern, reason, ..
+            } => write!(f, "{location}: invalid pattern \"{pattern}\": {reason}"),
+            LintDiagnostic::InvalidDefault { errors, .. } => {
+                write!(f, "{location}: default does not match its schema")?;
+                for e in errors {
+                    write!(f, "\n  {e}")?;
+                }
+                Ok(())
+            }
+            LintDiagnostic::AllOfContradiction { reason, .. } => {
+                write!(f, "{location}: allOf subschemas contradict: {reason}")
+            }
+            LintDiagnostic::UnresolvableRef { ref_str, .. } => {
+                write!(f, "{location}: $ref \"{ref_str}\" does not resolve")
+            }
+            LintDiagnostic::UnusedDefinition { name, .. } => {
+                write!(f, "{location}: definition \"{name}\" is never referenced")
+            }
+        }
+    }
+}
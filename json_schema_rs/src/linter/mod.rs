@@ -0,0 +1,415 @@
+//! Schema linting: finds unsatisfiable, contradictory, and suspicious parts of a schema.
+//!
+//! Unlike validation, linting looks only at the schema. It collects every finding (no fail-fast)
+//! as a [`LintDiagnostic`] with a [`LintSeverity`] and a JSON Pointer into the schema document.
+
+mod diagnostic;
+pub use diagnostic::{LintDiagnostic, LintSeverity};
+
+use crate::code_gen::{CodeGenError, merge_all_of};
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::json_schema::AdditionalProperties;
+use crate::json_schema::ref_resolver::{self, ParsedRef, RefResolutionError};
+use crate::json_schema::walk;
+use crate::json_schema::{JsonSchema, SchemaKeyword};
+use crate::validator::{self, ValidatorSettings};
+use std::collections::{BTreeMap, HashSet};
+
+/// Lints a schema and returns every finding, in document order, followed by unused definitions.
+///
+/// Checks:
+/// - `minimum > maximum`, `minLength > maxLength`, `minItems > maxItems`, and empty `enum`
+/// - `required` properties missing from `properties` under `additionalProperties: false`
+/// - `pattern` values that are not valid ECMA 262 regexes
+/// - `default` values that do not validate against their own schema
+/// - `allOf` branches whose properties have disjoint bounds or different `const` values
+/// - fragment `$ref`s to missing `$defs`/`definitions` entries
+/// - root `$defs`/`definitions` entries that are never referenced (warning)
+///
+/// # Example
+///
+/// ```
+/// use json_schema_rs::{JsonSchema, LintSeverity, lint};
+///
+/// let schema: JsonSchema = serde_json::from_str(r#"{"type":"integer","minimum":10,"maximum":1}"#).unwrap();
+/// let diagnostics = lint(&schema);
+/// assert_eq!(1, diagnostics.len());
+/// assert_eq!(LintSeverity::Error, diagnostics[0].severity());
+/// assert_eq!("/minimum", diagnostics[0].schema_path().as_str());
+/// ```
+#[must_use]
+pub fn lint(schema: &JsonSchema) -> Vec<LintDiagnostic> {
+    let settings: ValidatorSettings = ValidatorSettings::default();
+    let mut diagnostics: Vec<LintDiagnostic> = Vec::new();
+    for (path, _, node) in schema.walk() {
+        lint_node(schema, node, &path, &settings, &mut diagnostics);
+    }
+    lint_unused_definitions(schema, &mut diagnostics);
+    diagnostics
+}
+
+/// Runs every per-node check on `node`, located at `path` within `root`.
+fn lint_node(
+    root: &JsonSchema,
+    node: &JsonSchema,
+    path: &JsonPointer,
+    settings: &ValidatorSettings,
+    diagnostics: &mut Vec<LintDiagnostic>,
+) {
+    if let (Some(minimum), Some(maximum)) = (node.minimum, node.maximum)
+        && minimum > maximum
+    {
+        diagnostics.push(LintDiagnostic::MinimumAboveMaximum {
+            schema_path: path.push("minimum"),
+            minimum,
+            maximum,
+        });
+    }
+    if let (Some(min_length), Some(max_length)) = (node.min_length, node.max_length)
+        && min_length > max_length
+    {
+        diagnostics.push(LintDiagnostic::MinLengthAboveMaxLength {
+            schema_path: path.push("minLength"),
+            min_length,
+            max_length,
+        });
+    }
+    if let (Some(min_items), Some(max_items)) = (node.min_items, node.max_items)
+        && min_items > max_items
+    {
+        diagnostics.push(LintDiagnostic::MinItemsAboveMaxItems {
+            schema_path: path.push("minItems"),
+            min_items,
+            max_items,
+        });
+    }
+    if node.enum_values.as_ref().is_some_and(Vec::is_empty) {
+        diagnostics.push(LintDiagnostic::EmptyEnum {
+            schema_path: path.push("enum"),
+        });
+    }
+    // patternProperties is not modeled; when present it may admit the required key.
+    if node.additional_properties == Some(AdditionalProperties::Forbid)
+        && !node.extensions.contains_key("patternProperties")
+    {
+        for (index, property) in node.required.iter().flatten().enumerate() {
+            if !node.properties.contains_key(property) {
+                diagnostics.push(LintDiagnostic::RequiredPropertyForbidden {
+                    schema_path: path.push("required").push(&index.to_string()),
+                    property: property.clone(),
+                });
+            }
+        }
+    }
+    if let Some(pattern) = &node.pattern
+        && let Err(e) = regress::Regex::new(pattern)
+    {
+        diagnostics.push(LintDiagnostic::InvalidPattern {
+            schema_path: path.push("pattern"),
+            pattern: pattern.clone(),
+            reason: e.to_string(),
+        });
+    }
+    if let Some(default) = &node.default_value
+        && let Err(errors) = validator::validate_subschema(root, node, default, settings)
+    {
+        diagnostics.push(LintDiagnostic::InvalidDefault {
+            schema_path: path.push("default"),
+            errors,
+        });
+    }
+    if let Some(all_of) = &node.all_of
+        && let Some(reason) = all_of_contradiction(all_of)
+    {
+        diagnostics.push(LintDiagnostic::AllOfContradiction {
+            schema_path: path.push("allOf"),
+            reason,
+        });
+    }
+    if let Some(ref_str) = &node.ref_
+        && let Err(
+            RefResolutionError::DefsMissing { .. }
+            | RefResolutionError::DefinitionsMissing { .. }
+            | RefResolutionError::DefNotFound { .. }
+            | RefResolutionError::DefinitionNotFound { .. },
+        ) = ref_resolver::resolve_ref(root, ref_str)
+    {
+        diagnostics.push(LintDiagnostic::UnresolvableRef {
+            schema_path: path.push("$ref"),
+            ref_str: ref_str.clone(),
+        });
+    }
+}
+
+/// Merges object `allOf` branches as codegen does and describes the first true contradiction.
+/// Merge failures that only mean "codegen can't combine these" (e.g. non-object branches,
+/// different patterns) are not contradictions and are ignored.
+fn all_of_contradiction(all_of: &[JsonSchema]) -> Option<String> {
+    match merge_all_of(all_of) {
+        Err(CodeGenError::AllOfMergeConflictingNumericBounds {
+            property_key,
+            keyword,
+        }) => Some(format!(
+            "property \"{property_key}\" has disjoint {keyword} bounds"
+        )),
+        Err(CodeGenError::AllOfMergeConflictingConst { property_key }) => Some(format!(
+            "property \"{property_key}\" has different const values"
+        )),
+        _ => None,
+    }
+}
+
+/// Reports root `$defs`/`definitions` entries not reachable from the root schema via `$ref`.
+fn lint_unused_definitions(root: &JsonSchema, diagnostics: &mut Vec<LintDiagnostic>) {
+    let mut used: HashSet<ParsedRef> = HashSet::new();
+    let mut stack: Vec<(&JsonSchema, JsonPointer)> = vec![(root, JsonPointer::root())];
+    while let Some((node, path)) = stack.pop() {
+        if let Some(ref_str) = &node.ref_
+            && let Ok(parsed) = ref_resolver::parse_ref(ref_str)
+            && !used.contains(&parsed)
+            && let Ok(target) = ref_resolver::resolve_ref(root, ref_str)
+        {
+            let target_path: JsonPointer = match &parsed {
+                ParsedRef::Root => JsonPointer::root(),
+                ParsedRef::Defs(name) => JsonPointer::root().push("$defs").push(name),
+                ParsedRef::Definitions(name) => JsonPointer::root().push("definitions").push(name),
+            };
+            used.insert(parsed);
+            if !target_path.is_root() {
+                stack.push((target, target_path));
+            }
+        }
+        // Root definitions are only reached through `$ref`.
+        stack.extend(
+            walk::children(node, &path)
+                .into_iter()
+                .filter(|(_, keyword, _)| {
+                    !(path.is_root()
+                        && matches!(keyword, SchemaKeyword::Defs | SchemaKeyword::Definitions))
+                })
+                .map(|(child_path, _, child)| (child, child_path)),
+        );
+    }
+    for (keyword, defs, wrap) in [
+        (
+            "$defs",
+            &root.defs,
+            ParsedRef::Defs as fn(String) -> ParsedRef,
+        ),
+        ("definitions", &root.definitions, ParsedRef::Definitions),
+    ] {
+        let defs: Option<&BTreeMap<String, JsonSchema>> = defs.as_ref();
+        for name in defs.into_iter().flat_map(BTreeMap::keys) {
+            if !used.contains(&wrap(name.clone())) {
+                diagnostics.push(LintDiagnostic::UnusedDefinition {
+                    schema_path: JsonPointer::root().push(keyword).push(name),
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LintDiagnostic, LintSeverity, lint};
+    use crate::json_pointer::JsonPointer;
+    use crate::json_schema::JsonSchema;
+    use crate::validator::{OrderedF64, ValidationError};
+
+    fn lint_json(json: &str) -> Vec<LintDiagnostic> {
+        let schema: JsonSchema = serde_json::from_str(json).expect("parse schema");
+        lint(&schema)
+    }
+
+    fn pointer(s: &str) -> JsonPointer {
+        JsonPointer::try_from(s).expect("valid pointer")
+    }
+
+    #[test]
+    fn clean_schema_has_no_diagnostics() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r##"{"type":"object","properties":{"a":{"$ref":"#/$defs/A"}},"$defs":{"A":{"type":"string","minLength":1,"maxLength":3,"default":"ab"}}}"##,
+        );
+        let expected: Vec<LintDiagnostic> = vec![];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn minimum_above_maximum() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"type":"object","properties":{"age":{"type":"integer","minimum":10,"maximum":1}}}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::MinimumAboveMaximum {
+            schema_path: pointer("/properties/age/minimum"),
+            minimum: 10.0,
+            maximum: 1.0,
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn min_length_and_min_items_above_max() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"type":"array","minItems":3,"maxItems":2,"items":{"type":"string","minLength":5,"maxLength":4}}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![
+            LintDiagnostic::MinItemsAboveMaxItems {
+                schema_path: pointer("/minItems"),
+                min_items: 3,
+                max_items: 2,
+            },
+            LintDiagnostic::MinLengthAboveMaxLength {
+                schema_path: pointer("/items/minLength"),
+                min_length: 5,
+                max_length: 4,
+            },
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn empty_enum() {
+        let actual: Vec<LintDiagnostic> = lint_json(r#"{"enum":[]}"#);
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::EmptyEnum {
+            schema_path: pointer("/enum"),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn required_property_forbidden_by_additional_properties_false() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"type":"object","properties":{"a":{"type":"string"}},"required":["a","b"],"additionalProperties":false}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::RequiredPropertyForbidden {
+            schema_path: pointer("/required/1"),
+            property: "b".to_string(),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn required_property_not_in_properties_allowed_with_additional_properties() {
+        let actual: Vec<LintDiagnostic> =
+            lint_json(r#"{"type":"object","properties":{},"required":["b"]}"#);
+        let expected: Vec<LintDiagnostic> = vec![];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let actual: Vec<LintDiagnostic> = lint_json(r#"{"type":"string","pattern":"(unclosed"}"#);
+        assert_eq!(1, actual.len());
+        assert!(
+            matches!(
+                &actual[0],
+                LintDiagnostic::InvalidPattern { schema_path, pattern, .. }
+                    if schema_path.as_str() == "/pattern" && pattern == "(unclosed"
+            ),
+            "got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn default_that_fails_its_own_schema() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"type":"object","properties":{"n":{"type":"integer","maximum":5,"default":9}}}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::InvalidDefault {
+            schema_path: pointer("/properties/n/default"),
+            errors: vec![ValidationError::AboveMaximum {
+                instance_path: JsonPointer::root(),
+                maximum: OrderedF64(5.0),
+                actual: OrderedF64(9.0),
+            }],
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn default_resolves_refs_against_root() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r##"{"$defs":{"S":{"type":"string"}},"properties":{"s":{"$ref":"#/$defs/S","default":1}}}"##,
+        );
+        assert_eq!(1, actual.len());
+        assert!(
+            matches!(&actual[0], LintDiagnostic::InvalidDefault { schema_path, .. } if schema_path.as_str() == "/properties/s/default"),
+            "got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn all_of_contradicting_bounds() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"allOf":[{"type":"object","properties":{"n":{"type":"integer","minimum":10}}},{"type":"object","properties":{"n":{"type":"integer","maximum":5}}}]}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::AllOfContradiction {
+            schema_path: pointer("/allOf"),
+            reason: "property \"n\" has disjoint minimum/maximum bounds".to_string(),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn all_of_contradicting_const() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r#"{"allOf":[{"type":"object","properties":{"k":{"type":"string","const":"a"}}},{"type":"object","properties":{"k":{"type":"string","const":"b"}}}]}"#,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::AllOfContradiction {
+            schema_path: pointer("/allOf"),
+            reason: "property \"k\" has different const values".to_string(),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn all_of_non_object_branches_are_not_contradictions() {
+        let actual: Vec<LintDiagnostic> =
+            lint_json(r#"{"allOf":[{"type":"string"},{"minLength":1}]}"#);
+        let expected: Vec<LintDiagnostic> = vec![];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unresolvable_ref() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r##"{"$defs":{"A":{"type":"string"}},"properties":{"a":{"$ref":"#/$defs/A"},"b":{"$ref":"#/$defs/Missing"}}}"##,
+        );
+        let expected: Vec<LintDiagnostic> = vec![LintDiagnostic::UnresolvableRef {
+            schema_path: pointer("/properties/b/$ref"),
+            ref_str: "#/$defs/Missing".to_string(),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unused_definitions_are_warnings() {
+        let actual: Vec<LintDiagnostic> = lint_json(
+            r##"{"properties":{"a":{"$ref":"#/$defs/Used"}},"$defs":{"Used":{"items":{"$ref":"#/$defs/Transitive"}},"Transitive":{},"Unused":{"$ref":"#/$defs/OnlyFromUnused"},"OnlyFromUnused":{}},"definitions":{"Legacy":{}}}"##,
+        );
+        let expected: Vec<LintDiagnostic> = vec![
+            LintDiagnostic::UnusedDefinition {
+                schema_path: pointer("/$defs/OnlyFromUnused"),
+                name: "OnlyFromUnused".to_string(),
+            },
+            LintDiagnostic::UnusedDefinition {
+                schema_path: pointer("/$defs/Unused"),
+                name: "Unused".to_string(),
+            },
+            LintDiagnostic::UnusedDefinition {
+                schema_path: pointer("/definitions/Legacy"),
+                name: "Legacy".to_string(),
+            },
+        ];
+        assert_eq!(expected, actual);
+        assert!(actual.iter().all(|d| d.severity() == LintSeverity::Warning));
+    }
+
+    #[test]
+    fn display_includes_location() {
+        let actual: String = lint_json(r#"{"minLength":2,"maxLength":1}"#)[0].to_string();
+        let expected = "/minLength: minLength 2 is greater than maxLength 1; no string can match";
+        assert_eq!(expected, actual);
+    }
+}
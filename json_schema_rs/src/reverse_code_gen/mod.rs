@@ -10,7 +10,7 @@ use std::collections::BTreeMap;
 /// Trait for types that can produce a JSON Schema.
 ///
 /// Implemented for primitive/standard types (e.g. `String`, `Option<T>`) and for
-/// structs via `#[derive(ToJsonSchema)]` with optional container/field attributes.
+/// structs and enums via `#[derive(ToJsonSchema)]` with optional container/field attributes.
 pub trait ToJsonSchema {
     /// Returns the JSON Schema for this type.
     fn json_schema() -> JsonSchema;
@@ -157,6 +157,13 @@ impl<T: ToJsonSchema> ToJsonSchema for Box<T> {
     }
 }
 
+/// Any JSON value: the empty schema, which accepts everything.
+impl ToJsonSchema for serde_json::Value {
+    fn json_schema() -> JsonSchema {
+        JsonSchema::default()
+    }
+}
+
 /// Merges nested `$defs` from `schema` into `root_defs`, returns schema with `defs: None`.
 ///
 /// Recursively flattens so the final result has a single root-level defs map.
@@ -213,6 +220,26 @@ impl ToJsonSchema for HandWrittenExample {
     }
 }
 
+impl ToJsonSchema for std::net::Ipv4Addr {
+    fn json_schema() -> JsonSchema {
+        JsonSchema {
+            type_: Some("string".to_string()),
+            format: Some("ipv4".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl ToJsonSchema for std::net::Ipv6Addr {
+    fn json_schema() -> JsonSchema {
+        JsonSchema {
+            type_: Some("string".to_string()),
+            format: Some("ipv6".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(feature = "uuid")]
 impl ToJsonSchema for uuid::Uuid {
     fn json_schema() -> JsonSchema {
@@ -224,10 +251,32 @@ impl ToJsonSchema for uuid::Uuid {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> ToJsonSchema for chrono::DateTime<Tz> {
+    fn json_schema() -> JsonSchema {
+        JsonSchema {
+            type_: Some("string".to_string()),
+            format: Some("date-time".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToJsonSchema for chrono::NaiveDate {
+    fn json_schema() -> JsonSchema {
+        JsonSchema {
+            type_: Some("string".to_string()),
+            format: Some("date".to_string()),
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ToJsonSchema;
-    use crate::json_schema::JsonSchema;
+    use crate::json_schema::{JsonSchema, PropertyMap};
     use std::collections::BTreeMap;
 
     #[test]
@@ -250,6 +299,13 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn serde_json_value_json_schema_is_empty() {
+        let expected: JsonSchema = JsonSchema::default();
+        let actual: JsonSchema = serde_json::Value::json_schema();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn option_string_json_schema() {
         let expected: JsonSchema = String::json_schema();
@@ -426,6 +482,20 @@ mod tests {
         assert_eq!(expected_unique, actual.unique_items);
     }
 
+    #[test]
+    fn ipv4_addr_json_schema() {
+        let actual: JsonSchema = std::net::Ipv4Addr::json_schema();
+        assert_eq!(actual.type_.as_deref(), Some("string"));
+        assert_eq!(actual.format.as_deref(), Some("ipv4"));
+    }
+
+    #[test]
+    fn option_ipv6_addr_json_schema() {
+        let actual: JsonSchema = Option::<std::net::Ipv6Addr>::json_schema();
+        assert_eq!(actual.type_.as_deref(), Some("string"));
+        assert_eq!(actual.format.as_deref(), Some("ipv6"));
+    }
+
     #[cfg(feature = "uuid")]
     #[test]
     fn uuid_json_schema() {
@@ -465,7 +535,7 @@ mod tests {
         let outer_schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "b".to_string(),
                     JsonSchema {
@@ -483,7 +553,7 @@ mod tests {
         let schema_with_nested: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "a".to_string(),
                     JsonSchema {
@@ -504,7 +574,7 @@ mod tests {
             type_: Some("object".to_string()),
             defs: None,
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "a".to_string(),
                     JsonSchema {
@@ -524,7 +594,7 @@ mod tests {
                 type_: Some("object".to_string()),
                 defs: None,
                 properties: {
-                    let mut m = BTreeMap::new();
+                    let mut m = PropertyMap::new();
                     m.insert(
                         "b".to_string(),
                         JsonSchema {
@@ -540,4 +610,20 @@ mod tests {
 
         assert_eq!((expected_returned, expected_root_defs), (actual, root_defs));
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_time_json_schema() {
+        let actual: JsonSchema = chrono::DateTime::<chrono::FixedOffset>::json_schema();
+        assert_eq!(actual.type_.as_deref(), Some("string"));
+        assert_eq!(actual.format.as_deref(), Some("date-time"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_naive_date_json_schema() {
+        let actual: JsonSchema = chrono::NaiveDate::json_schema();
+        assert_eq!(actual.type_.as_deref(), Some("string"));
+        assert_eq!(actual.format.as_deref(), Some("date"));
+    }
 }
@@ -0,0 +1,494 @@
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::SchemaKeyword;
+use std::fmt;
+
+/// Which side of a schema upgrade a [`SchemaChange`] is safe for.
+///
+/// A schema change is *backward-compatible* when the new schema accepts every instance the old
+/// one accepted (consumers can upgrade first and still read old data), *forward-compatible* when
+/// the old schema accepts every instance the new one accepts (producers can upgrade first), and
+/// *breaking* when neither holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Compatibility {
+    /// The new schema is looser: data written against the old schema stays valid.
+    Backward,
+    /// The new schema is tighter: data written against the new schema is valid under the old one.
+    Forward,
+    /// Neither schema accepts everything the other accepts.
+    Breaking,
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compatibility::Backward => write!(f, "backward-compatible"),
+            Compatibility::Forward => write!(f, "forward-compatible"),
+            Compatibility::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// Numeric and size bound keywords compared by [`SchemaChange::BoundChanged`] and
+/// [`SchemaChange::SizeBoundChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoundKeyword {
+    Minimum,
+    Maximum,
+    MinLength,
+    MaxLength,
+    MinItems,
+    MaxItems,
+}
+
+impl BoundKeyword {
+    /// The JSON Schema keyword, e.g. `"minLength"`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BoundKeyword::Minimum => "minimum",
+            BoundKeyword::Maximum => "maximum",
+            BoundKeyword::MinLength => "minLength",
+            BoundKeyword::MaxLength => "maxLength",
+            BoundKeyword::MinItems => "minItems",
+            BoundKeyword::MaxItems => "maxItems",
+        }
+    }
+
+    /// Whether this is a lower bound (raising it tightens the schema).
+    #[must_use]
+    pub fn is_lower(self) -> bool {
+        matches!(
+            self,
+            BoundKeyword::Minimum | BoundKeyword::MinLength | BoundKeyword::MinItems
+        )
+    }
+}
+
+/// How an object treats properties not listed in `properties`, from loosest to strictest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AdditionalPropertiesState {
+    /// `additionalProperties` absent or `true`.
+    Open,
+    /// `additionalProperties` is a schema.
+    Schema,
+    /// `additionalProperties: false`.
+    Closed,
+}
+
+impl fmt::Display for AdditionalPropertiesState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdditionalPropertiesState::Open => write!(f, "open"),
+            AdditionalPropertiesState::Schema => write!(f, "a schema"),
+            AdditionalPropertiesState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A single difference between an old and a new schema: kind, values, and schema location.
+///
+/// `schema_path` is a JSON Pointer into the new schema (into the old one for things only the old
+/// schema has). Changes inside a `$ref` target are reported once, at the target's location (e.g.
+/// `/$defs/Address/maxLength`). `None` for an old or new keyword value means it is absent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    /// `type` was added, removed, or changed.
+    TypeChanged {
+        /// JSON Pointer to the `type` keyword.
+        schema_path: JsonPointer,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// A property was added to `properties`.
+    PropertyAdded {
+        /// JSON Pointer to the new property's schema.
+        schema_path: JsonPointer,
+        property: String,
+        /// Whether the old schema rejected unknown properties (`additionalProperties: false`).
+        was_closed: bool,
+    },
+    /// A property was removed from `properties`.
+    PropertyRemoved {
+        /// JSON Pointer to the removed property's schema in the old schema.
+        schema_path: JsonPointer,
+        property: String,
+        /// Whether the new schema rejects unknown properties (`additionalProperties: false`).
+        is_closed: bool,
+    },
+    /// A property became required.
+    RequiredAdded {
+        /// JSON Pointer to the object's `required` keyword.
+        schema_path: JsonPointer,
+        property: String,
+    },
+    /// A property is no longer required.
+    RequiredRemoved {
+        /// JSON Pointer to the object's `required` keyword.
+        schema_path: JsonPointer,
+        property: String,
+    },
+    /// `enum` was added, removed, narrowed, or widened.
+    EnumChanged {
+        /// JSON Pointer to the `enum` keyword.
+        schema_path: JsonPointer,
+        old: Option<Vec<serde_json::Value>>,
+        new: Option<Vec<serde_json::Value>>,
+    },
+    /// `const` was added, removed, or changed.
+    ConstChanged {
+        /// JSON Pointer to the `const` keyword.
+        schema_path: JsonPointer,
+        old: Option<serde_json::Value>,
+        new: Option<serde_json::Value>,
+    },
+    /// `minimum` or `maximum` was added, removed, or moved.
+    BoundChanged {
+        /// JSON Pointer to the bound keyword.
+        schema_path: JsonPointer,
+        keyword: BoundKeyword,
+        old: Option<f64>,
+        new: Option<f64>,
+    },
+    /// `minLength`, `maxLength`, `minItems`, or `maxItems` was added, removed, or moved.
+    SizeBoundChanged {
+        /// JSON Pointer to the bound keyword.
+        schema_path: JsonPointer,
+        keyword: BoundKeyword,
+        old: Option<u64>,
+        new: Option<u64>,
+    },
+    /// `pattern` was added, removed, or changed.
+    PatternChanged {
+        /// JSON Pointer to the `pattern` keyword.
+        schema_path: JsonPointer,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// `format` was added, removed, or changed.
+    FormatChanged {
+        /// JSON Pointer to the `format` keyword.
+        schema_path: JsonPointer,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// `additionalProperties` moved between open, a schema, and closed.
+    AdditionalPropertiesChanged {
+        /// JSON Pointer to the `additionalProperties` keyword.
+        schema_path: JsonPointer,
+        old: AdditionalPropertiesState,
+        new: AdditionalPropertiesState,
+    },
+    /// `uniqueItems` was turned on or off.
+    UniqueItemsChanged {
+        /// JSON Pointer to the `uniqueItems` keyword.
+        schema_path: JsonPointer,
+        old: bool,
+        new: bool,
+    },
+    /// `items`, `allOf`, `anyOf`, or `oneOf` was added where the old schema had none.
+    KeywordAdded {
+        /// JSON Pointer to the keyword.
+        schema_path: JsonPointer,
+        keyword: SchemaKeyword,
+    },
+    /// `items`, `allOf`, `anyOf`, or `oneOf` was removed.
+    KeywordRemoved {
+        /// JSON Pointer to the keyword in the old schema.
+        schema_path: JsonPointer,
+        keyword: SchemaKeyword,
+    },
+    /// A branch was appended to an existing `allOf`, `anyOf`, or `oneOf`.
+    BranchAdded {
+        /// JSON Pointer to the new branch.
+        schema_path: JsonPointer,
+        keyword: SchemaKeyword,
+    },
+    /// A trailing branch was removed from an `allOf`, `anyOf`, or `oneOf`.
+    BranchRemoved {
+        /// JSON Pointer to the removed branch in the old schema.
+        schema_path: JsonPointer,
+        keyword: SchemaKeyword,
+    },
+    /// A `$ref` in the new schema does not resolve, so that part cannot be compared.
+    UnresolvableRef {
+        /// JSON Pointer to the `$ref` keyword.
+        schema_path: JsonPointer,
+        ref_str: String,
+    },
+}
+
+impl SchemaChange {
+    #[must_use]
+    pub fn schema_path(&self) -> &JsonPointer {
+        match self {
+            SchemaChange::TypeChanged { schema_path, .. }
+            | SchemaChange::PropertyAdded { schema_path, .. }
+            | SchemaChange::PropertyRemoved { schema_path, .. }
+            | SchemaChange::RequiredAdded { schema_path, .. }
+            | SchemaChange::RequiredRemoved { schema_path, .. }
+            | SchemaChange::EnumChanged { schema_path, .. }
+            | SchemaChange::ConstChanged { schema_path, .. }
+            | SchemaChange::BoundChanged { schema_path, .. }
+            | SchemaChange::SizeBoundChanged { schema_path, .. }
+            | SchemaChange::PatternChanged { schema_path, .. }
+            | SchemaChange::FormatChanged { schema_path, .. }
+            | SchemaChange::AdditionalPropertiesChanged { schema_path, .. }
+            | SchemaChange::UniqueItemsChanged { schema_path, .. }
+            | SchemaChange::KeywordAdded { schema_path, .. }
+            | SchemaChange::KeywordRemoved { schema_path, .. }
+            | SchemaChange::BranchAdded { schema_path, .. }
+            | SchemaChange::BranchRemoved { schema_path, .. }
+            | SchemaChange::UnresolvableRef { schema_path, .. } => schema_path,
+        }
+    }
+
+    /// Stable `snake_case` name of the change kind, for machine-readable output.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SchemaChange::TypeChanged { .. } => "type_changed",
+            SchemaChange::PropertyAdded { .. } => "property_added",
+            SchemaChange::PropertyRemoved { .. } => "property_removed",
+            SchemaChange::RequiredAdded { .. } => "required_added",
+            SchemaChange::RequiredRemoved { .. } => "required_removed",
+            SchemaChange::EnumChanged { .. } => "enum_changed",
+            SchemaChange::ConstChanged { .. } => "const_changed",
+            SchemaChange::BoundChanged { .. } => "bound_changed",
+            SchemaChange::SizeBoundChanged { .. } => "size_bound_changed",
+            SchemaChange::PatternChanged { .. } => "pattern_changed",
+            SchemaChange::FormatChanged { .. } => "format_changed",
+            SchemaChange::AdditionalPropertiesChanged { .. } => "additional_properties_changed",
+            SchemaChange::UniqueItemsChanged { .. } => "unique_items_changed",
+            SchemaChange::KeywordAdded { .. } => "keyword_added",
+            SchemaChange::KeywordRemoved { .. } => "keyword_removed",
+            SchemaChange::BranchAdded { .. } => "branch_added",
+            SchemaChange::BranchRemoved { .. } => "branch_removed",
+            SchemaChange::UnresolvableRef { .. } => "unresolvable_ref",
+        }
+    }
+
+    /// Whether this change is backward-compatible, forward-compatible, or breaking; see
+    /// [`Compatibility`].
+    #[must_use]
+    pub fn compatibility(&self) -> Compatibility {
+        match self {
+            SchemaChange::TypeChanged { old, new, .. } => match (old.as_deref(), new.as_deref()) {
+                (_, None) | (Some("integer"), Some("number")) => Compatibility::Backward,
+                (None, Some(_)) | (Some("number"), Some("integer")) => Compatibility::Forward,
+                (Some(_), Some(_)) => Compatibility::Breaking,
+            },
+            SchemaChange::PropertyAdded { was_closed, .. } => {
+                if *was_closed {
+                    Compatibility::Backward
+                } else {
+                    Compatibility::Forward
+                }
+            }
+            SchemaChange::PropertyRemoved { is_closed, .. } => {
+                if *is_closed {
+                    Compatibility::Forward
+                } else {
+                    Compatibility::Backward
+                }
+            }
+            SchemaChange::RequiredAdded { .. } | SchemaChange::KeywordAdded { .. } => {
+                Compatibility::Forward
+            }
+            SchemaChange::RequiredRemoved { .. } | SchemaChange::KeywordRemoved { .. } => {
+                Compatibility::Backward
+            }
+            SchemaChange::EnumChanged { old, new, .. } => match (old, new) {
+                (_, None) => Compatibility::Backward,
+                (None, Some(_)) => Compatibility::Forward,
+                (Some(old), Some(new)) => {
+                    if new.iter().all(|v| old.contains(v)) {
+                        Compatibility::Forward
+                    } else if old.iter().all(|v| new.contains(v)) {
+                        Compatibility::Backward
+                    } else {
+                        Compatibility::Breaking
+                    }
+                }
+            },
+            SchemaChange::ConstChanged { old, new, .. } => {
+                optional_compatibility(old.as_ref(), new.as_ref())
+            }
+            SchemaChange::PatternChanged { old, new, .. }
+            | SchemaChange::FormatChanged { old, new, .. } => {
+                optional_compatibility(old.as_ref(), new.as_ref())
+            }
+            SchemaChange::BoundChanged {
+                keyword, old, new, ..
+            } => bound_compatibility(*keyword, *old, *new),
+            SchemaChange::SizeBoundChanged {
+                keyword, old, new, ..
+            } => bound_compatibility(*keyword, *old, *new),
+            SchemaChange::AdditionalPropertiesChanged { old, new, .. } => {
+                if new > old {
+                    Compatibility::Forward
+                } else {
+                    Compatibility::Backward
+                }
+            }
+            SchemaChange::UniqueItemsChanged { new, .. } => {
+                if *new {
+                    Compatibility::Forward
+                } else {
+                    Compatibility::Backward
+                }
+            }
+            SchemaChange::BranchAdded { keyword, .. } => {
+                if *keyword == SchemaKeyword::AllOf {
+                    Compatibility::Forward
+                } else {
+                    Compatibility::Backward
+                }
+            }
+            SchemaChange::BranchRemoved { keyword, .. } => {
+                if *keyword == SchemaKeyword::AllOf {
+                    Compatibility::Backward
+                } else {
+                    Compatibility::Forward
+                }
+            }
+            SchemaChange::UnresolvableRef { .. } => Compatibility::Breaking,
+        }
+    }
+}
+
+/// Adding a constraint tightens, removing it loosens, and replacing it with a different one
+/// (e.g. another `pattern`) can do both.
+fn optional_compatibility<T>(old: Option<&T>, new: Option<&T>) -> Compatibility {
+    match (old, new) {
+        (_, None) => Compatibility::Backward,
+        (None, Some(_)) => Compatibility::Forward,
+        (Some(_), Some(_)) => Compatibility::Breaking,
+    }
+}
+
+fn bound_compatibility<T: PartialOrd>(
+    keyword: BoundKeyword,
+    old: Option<T>,
+    new: Option<T>,
+) -> Compatibility {
+    let tightened: bool = match (old, new) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(old), Some(new)) => {
+            if keyword.is_lower() {
+                new > old
+            } else {
+                new < old
+            }
+        }
+    };
+    if tightened {
+        Compatibility::Forward
+    } else {
+        Compatibility::Backward
+    }
+}
+
+fn display_optional<T: fmt::Display>(value: Option<&T>) -> String {
+    value.map_or_else(|| "(none)".to_string(), ToString::to_string)
+}
+
+fn display_values(values: Option<&Vec<serde_json::Value>>) -> String {
+    values.map_or_else(
+        || "(none)".to_string(),
+        |v| serde_json::Value::Array(v.clone()).to_string(),
+    )
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.schema_path().display_root_or_path();
+        match self {
+            SchemaChange::TypeChanged { old, new, .. } => write!(
+                f,
+                "{location}: type changed from {} to {}",
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::PropertyAdded { property, .. } => {
+                write!(f, "{location}: property \"{property}\" added")
+            }
+            SchemaChange::PropertyRemoved { property, .. } => {
+                write!(f, "{location}: property \"{property}\" removed")
+            }
+            SchemaChange::RequiredAdded { property, .. } => {
+                write!(f, "{location}: property \"{property}\" is now required")
+            }
+            SchemaChange::RequiredRemoved { property, .. } => {
+                write!(
+                    f,
+                    "{location}: property \"{property}\" is no longer required"
+                )
+            }
+            SchemaChange::EnumChanged { old, new, .. } => write!(
+                f,
+                "{location}: enum changed from {} to {}",
+                display_values(old.as_ref()),
+                display_values(new.as_ref())
+            ),
+            SchemaChange::ConstChanged { old, new, .. } => write!(
+                f,
+                "{location}: const changed from {} to {}",
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::BoundChanged {
+                keyword, old, new, ..
+            } => write!(
+                f,
+                "{location}: {} changed from {} to {}",
+                keyword.as_str(),
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::SizeBoundChanged {
+                keyword, old, new, ..
+            } => write!(
+                f,
+                "{location}: {} changed from {} to {}",
+                keyword.as_str(),
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::PatternChanged { old, new, .. } => write!(
+                f,
+                "{location}: pattern changed from {} to {}",
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::FormatChanged { old, new, .. } => write!(
+                f,
+                "{location}: format changed from {} to {}",
+                display_optional(old.as_ref()),
+                display_optional(new.as_ref())
+            ),
+            SchemaChange::AdditionalPropertiesChanged { old, new, .. } => write!(
+                f,
+                "{location}: additionalProperties changed from {old} to {new}"
+            ),
+            SchemaChange::UniqueItemsChanged { new, .. } => {
+                write!(f, "{location}: uniqueItems changed to {new}")
+            }
+            SchemaChange::KeywordAdded { keyword, .. } => {
+                write!(f, "{location}: {} added", keyword.as_str())
+            }
+            SchemaChange::KeywordRemoved { keyword, .. } => {
+                write!(f, "{location}: {} removed", keyword.as_str())
+            }
+            SchemaChange::BranchAdded { keyword, .. } => {
+                write!(f, "{location}: {} branch added", keyword.as_str())
+            }
+            SchemaChange::BranchRemoved { keyword, .. } => {
+                write!(f, "{location}: {} branch removed", keyword.as_str())
+            }
+            SchemaChange::UnresolvableRef { ref_str, .. } => {
+                write!(f, "{location}: $ref \"{ref_str}\" does not resolve")
+            }
+        }
+    }
+}
@@ -0,0 +1,605 @@
+//! Structural diff of two schema versions, classifying each change by compatibility.
+//!
+//! [`diff`] walks an old and a new schema side by side and reports every validation-relevant
+//! difference as a [`SchemaChange`] with a JSON Pointer and a [`Compatibility`]. Annotations such
+//! as `description` or `examples` are not compared.
+
+mod change;
+pub use change::{AdditionalPropertiesState, BoundKeyword, Compatibility, SchemaChange};
+
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::json_schema::AdditionalProperties;
+use crate::json_schema::ref_resolver::{self, ParsedRef};
+use crate::json_schema::{JsonSchema, SchemaKeyword};
+use std::collections::{BTreeSet, HashSet};
+
+/// A pair of corresponding old and new subschemas still to compare, at a location in the new
+/// schema.
+type Pending<'a> = (&'a JsonSchema, &'a JsonSchema, JsonPointer);
+
+/// Compares `old` with `new` and returns every change, in document order of the new schema.
+///
+/// Subschemas are matched by position: property name, `items`, `additionalProperties`, and
+/// branch index in `allOf`/`anyOf`/`oneOf`. `$ref`s are followed on both sides (each pair of
+/// targets is compared once), so renaming a definition is not a change but changing its content
+/// is. Parts of the old schema whose `$ref` does not resolve are skipped; in the new schema they
+/// are reported as [`SchemaChange::UnresolvableRef`].
+///
+/// # Example
+///
+/// ```
+/// use json_schema_rs::{Compatibility, JsonSchema, diff};
+///
+/// let old: JsonSchema = serde_json::from_str(r#"{"type":"object","properties":{"id":{"type":"string"}}}"#).unwrap();
+/// let new: JsonSchema = serde_json::from_str(r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#).unwrap();
+/// let changes = diff(&old, &new);
+/// assert_eq!(1, changes.len());
+/// assert_eq!(Compatibility::Forward, changes[0].compatibility());
+/// assert_eq!("/required", changes[0].schema_path().as_str());
+/// ```
+#[must_use]
+pub fn diff(old: &JsonSchema, new: &JsonSchema) -> Vec<SchemaChange> {
+    let mut changes: Vec<SchemaChange> = Vec::new();
+    let mut compared: HashSet<(*const JsonSchema, *const JsonSchema)> = HashSet::new();
+    let mut stack: Vec<Pending<'_>> = vec![(old, new, JsonPointer::root())];
+    while let Some((old_node, new_node, path)) = stack.pop() {
+        let (new_target, path) = match resolve_with_path(new, new_node, path) {
+            Ok(resolved) => resolved,
+            Err((path, ref_str)) => {
+                if old_node.ref_.as_deref() != Some(ref_str.as_str())
+                    || ref_resolver::resolve_schema_ref_transitive(old, old_node).is_ok()
+                {
+                    changes.push(SchemaChange::UnresolvableRef {
+                        schema_path: path.push("$ref"),
+                        ref_str,
+                    });
+                }
+                continue;
+            }
+        };
+        let Ok(old_target) = ref_resolver::resolve_schema_ref_transitive(old, old_node) else {
+            continue;
+        };
+        if !compared.insert((
+            std::ptr::from_ref(old_target),
+            std::ptr::from_ref(new_target),
+        )) {
+            continue;
+        }
+        let mut next: Vec<Pending<'_>> = diff_node(old_target, new_target, &path, &mut changes);
+        next.reverse();
+        stack.extend(next);
+    }
+    changes
+}
+
+/// Follows `schema`'s `$ref` chain within `root`, moving `path` to each target's location.
+/// On failure returns the location of the failing `$ref`'s schema and the ref string.
+fn resolve_with_path<'a>(
+    root: &'a JsonSchema,
+    schema: &'a JsonSchema,
+    path: JsonPointer,
+) -> Result<(&'a JsonSchema, JsonPointer), (JsonPointer, String)> {
+    let mut current: &'a JsonSchema = schema;
+    let mut path: JsonPointer = path;
+    let mut seen: HashSet<&'a str> = HashSet::new();
+    while let Some(ref_str) = current.ref_.as_deref() {
+        let target: Option<(&'a JsonSchema, JsonPointer)> = if seen.insert(ref_str) {
+            ref_resolver::parse_ref(ref_str).ok().and_then(|parsed| {
+                let target: &'a JsonSchema = ref_resolver::resolve_ref(root, ref_str).ok()?;
+                let target_path: JsonPointer = match parsed {
+                    ParsedRef::Root => JsonPointer::root(),
+                    ParsedRef::Defs(name) => JsonPointer::root().push("$defs").push(&name),
+                    ParsedRef::Definitions(name) => {
+                        JsonPointer::root().push("definitions").push(&name)
+                    }
+                };
+                Some((target, target_path))
+            })
+        } else {
+            None
+        };
+        let Some((target, target_path)) = target else {
+            return Err((path, ref_str.to_string()));
+        };
+        current = target;
+        path = target_path;
+    }
+    Ok((current, path))
+}
+
+fn additional_properties_state(schema: &JsonSchema) -> AdditionalPropertiesState {
+    match schema.additional_properties.as_ref() {
+        None | Some(AdditionalProperties::Allow) => AdditionalPropertiesState::Open,
+        Some(AdditionalProperties::Schema(_)) => AdditionalPropertiesState::Schema,
+        Some(AdditionalProperties::Forbid) => AdditionalPropertiesState::Closed,
+    }
+}
+
+/// Whether two `enum` lists allow different values (order and duplicates do not matter).
+fn enum_differs(
+    old: Option<&Vec<serde_json::Value>>,
+    new: Option<&Vec<serde_json::Value>>,
+) -> bool {
+    match (old, new) {
+        (None, None) => false,
+        (Some(old), Some(new)) => {
+            old.iter().any(|v| !new.contains(v)) || new.iter().any(|v| !old.contains(v))
+        }
+        _ => true,
+    }
+}
+
+/// Reports changes in the keywords of one matched pair that hold values rather than subschemas.
+fn diff_value_keywords(
+    old: &JsonSchema,
+    new: &JsonSchema,
+    path: &JsonPointer,
+    changes: &mut Vec<SchemaChange>,
+) {
+    if old.type_ != new.type_ {
+        changes.push(SchemaChange::TypeChanged {
+            schema_path: path.push("type"),
+            old: old.type_.clone(),
+            new: new.type_.clone(),
+        });
+    }
+    if enum_differs(old.enum_values.as_ref(), new.enum_values.as_ref()) {
+        changes.push(SchemaChange::EnumChanged {
+            schema_path: path.push("enum"),
+            old: old.enum_values.clone(),
+            new: new.enum_values.clone(),
+        });
+    }
+    if old.const_value != new.const_value {
+        changes.push(SchemaChange::ConstChanged {
+            schema_path: path.push("const"),
+            old: old.const_value.clone(),
+            new: new.const_value.clone(),
+        });
+    }
+    for (keyword, old_bound, new_bound) in [
+        (BoundKeyword::Minimum, old.minimum, new.minimum),
+        (BoundKeyword::Maximum, old.maximum, new.maximum),
+    ] {
+        if old_bound != new_bound {
+            changes.push(SchemaChange::BoundChanged {
+                schema_path: path.push(keyword.as_str()),
+                keyword,
+                old: old_bound,
+                new: new_bound,
+            });
+        }
+    }
+    for (keyword, old_bound, new_bound) in [
+        (BoundKeyword::MinLength, old.min_length, new.min_length),
+        (BoundKeyword::MaxLength, old.max_length, new.max_length),
+        (BoundKeyword::MinItems, old.min_items, new.min_items),
+        (BoundKeyword::MaxItems, old.max_items, new.max_items),
+    ] {
+        if old_bound != new_bound {
+            changes.push(SchemaChange::SizeBoundChanged {
+                schema_path: path.push(keyword.as_str()),
+                keyword,
+                old: old_bound,
+                new: new_bound,
+            });
+        }
+    }
+    if old.pattern != new.pattern {
+        changes.push(SchemaChange::PatternChanged {
+            schema_path: path.push("pattern"),
+            old: old.pattern.clone(),
+            new: new.pattern.clone(),
+        });
+    }
+    if old.format != new.format {
+        changes.push(SchemaChange::FormatChanged {
+            schema_path: path.push("format"),
+            old: old.format.clone(),
+            new: new.format.clone(),
+        });
+    }
+    let old_unique: bool = old.unique_items.unwrap_or(false);
+    let new_unique: bool = new.unique_items.unwrap_or(false);
+    if old_unique != new_unique {
+        changes.push(SchemaChange::UniqueItemsChanged {
+            schema_path: path.push("uniqueItems"),
+            old: old_unique,
+            new: new_unique,
+        });
+    }
+
+    let old_required: BTreeSet<&str> = old.required.iter().flatten().map(String::as_str).collect();
+    let new_required: BTreeSet<&str> = new.required.iter().flatten().map(String::as_str).collect();
+    for property in new_required.difference(&old_required) {
+        changes.push(SchemaChange::RequiredAdded {
+            schema_path: path.push("required"),
+            property: (*property).to_string(),
+        });
+    }
+    for property in old_required.difference(&new_required) {
+        changes.push(SchemaChange::RequiredRemoved {
+            schema_path: path.push("required"),
+            property: (*property).to_string(),
+        });
+    }
+}
+
+/// Reports the changes in the keywords of one matched pair and returns the subschema pairs to
+/// compare next, in document order.
+fn diff_node<'a>(
+    old: &'a JsonSchema,
+    new: &'a JsonSchema,
+    path: &JsonPointer,
+    changes: &mut Vec<SchemaChange>,
+) -> Vec<Pending<'a>> {
+    diff_value_keywords(old, new, path, changes);
+    let mut next: Vec<Pending<'a>> = Vec::new();
+    let old_state: AdditionalPropertiesState = additional_properties_state(old);
+    let new_state: AdditionalPropertiesState = additional_properties_state(new);
+    let properties_path: JsonPointer = path.push("properties");
+    for (name, new_property) in &new.properties {
+        match old.properties.get(name) {
+            Some(old_property) => {
+                next.push((old_property, new_property, properties_path.push(name)));
+            }
+            None => changes.push(SchemaChange::PropertyAdded {
+                schema_path: properties_path.push(name),
+                property: name.clone(),
+                was_closed: old_state == AdditionalPropertiesState::Closed,
+            }),
+        }
+    }
+    for name in old.properties.keys() {
+        if !new.properties.contains_key(name) {
+            changes.push(SchemaChange::PropertyRemoved {
+                schema_path: properties_path.push(name),
+                property: name.clone(),
+                is_closed: new_state == AdditionalPropertiesState::Closed,
+            });
+        }
+    }
+    if old_state != new_state {
+        changes.push(SchemaChange::AdditionalPropertiesChanged {
+            schema_path: path.push("additionalProperties"),
+            old: old_state,
+            new: new_state,
+        });
+    }
+    if let (
+        Some(AdditionalProperties::Schema(old_additional)),
+        Some(AdditionalProperties::Schema(new_additional)),
+    ) = (
+        old.additional_properties.as_ref(),
+        new.additional_properties.as_ref(),
+    ) {
+        next.push((
+            old_additional,
+            new_additional,
+            path.push("additionalProperties"),
+        ));
+    }
+
+    match (old.items.as_deref(), new.items.as_deref()) {
+        (Some(old_items), Some(new_items)) => next.push((old_items, new_items, path.push("items"))),
+        (None, Some(_)) => changes.push(SchemaChange::KeywordAdded {
+            schema_path: path.push("items"),
+            keyword: SchemaKeyword::Items,
+        }),
+        (Some(_), None) => changes.push(SchemaChange::KeywordRemoved {
+            schema_path: path.push("items"),
+            keyword: SchemaKeyword::Items,
+        }),
+        (None, None) => {}
+    }
+
+    for (keyword, old_branches, new_branches) in [
+        (SchemaKeyword::AllOf, &old.all_of, &new.all_of),
+        (SchemaKeyword::AnyOf, &old.any_of, &new.any_of),
+        (SchemaKeyword::OneOf, &old.one_of, &new.one_of),
+    ] {
+        let old_branches: &[JsonSchema] = old_branches.as_deref().unwrap_or_default();
+        let new_branches: &[JsonSchema] = new_branches.as_deref().unwrap_or_default();
+        let keyword_path: JsonPointer = path.push(keyword.as_str());
+        if old_branches.is_empty() && !new_branches.is_empty() {
+            changes.push(SchemaChange::KeywordAdded {
+                schema_path: keyword_path,
+                keyword,
+            });
+            continue;
+        }
+        if new_branches.is_empty() && !old_branches.is_empty() {
+            changes.push(SchemaChange::KeywordRemoved {
+                schema_path: keyword_path,
+                keyword,
+            });
+            continue;
+        }
+        for (i, (old_branch, new_branch)) in old_branches.iter().zip(new_branches).enumerate() {
+            next.push((old_branch, new_branch, keyword_path.push(&i.to_string())));
+        }
+        for i in old_branches.len()..new_branches.len() {
+            changes.push(SchemaChange::BranchAdded {
+                schema_path: keyword_path.push(&i.to_string()),
+                keyword,
+            });
+        }
+        for i in new_branches.len()..old_branches.len() {
+            changes.push(SchemaChange::BranchRemoved {
+                schema_path: keyword_path.push(&i.to_string()),
+                keyword,
+            });
+        }
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdditionalPropertiesState, BoundKeyword, Compatibility, SchemaChange, diff};
+    use crate::json_pointer::JsonPointer;
+    use crate::json_schema::{JsonSchema, SchemaKeyword};
+
+    fn ptr(s: &str) -> JsonPointer {
+        JsonPointer::try_from(s).expect("valid pointer")
+    }
+
+    fn compatibilities(changes: &[SchemaChange]) -> Vec<Compatibility> {
+        changes.iter().map(SchemaChange::compatibility).collect()
+    }
+
+    #[test]
+    fn identical_schemas_have_no_changes() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"type":"string","enum":["x","y"]}},"required":["a"]}"#,
+        ).unwrap();
+        let actual: Vec<SchemaChange> = diff(&schema, &schema.clone());
+        let expected: Vec<SchemaChange> = vec![];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn annotations_and_enum_order_are_not_changes() {
+        let old: JsonSchema =
+            serde_json::from_str(r#"{"description":"old","enum":["a","b"],"examples":["a"]}"#)
+                .unwrap();
+        let new: JsonSchema =
+            serde_json::from_str(r#"{"description":"new","enum":["b","a"]}"#).unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn required_and_properties_are_classified() {
+        let old: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{},"b":{}},"required":["b"],"additionalProperties":false}"#,
+        ).unwrap();
+        let new: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{},"c":{}},"required":["a"]}"#,
+        )
+        .unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![
+            SchemaChange::RequiredAdded {
+                schema_path: ptr("/required"),
+                property: "a".to_string(),
+            },
+            SchemaChange::RequiredRemoved {
+                schema_path: ptr("/required"),
+                property: "b".to_string(),
+            },
+            SchemaChange::PropertyAdded {
+                schema_path: ptr("/properties/c"),
+                property: "c".to_string(),
+                was_closed: true,
+            },
+            SchemaChange::PropertyRemoved {
+                schema_path: ptr("/properties/b"),
+                property: "b".to_string(),
+                is_closed: false,
+            },
+            SchemaChange::AdditionalPropertiesChanged {
+                schema_path: ptr("/additionalProperties"),
+                old: AdditionalPropertiesState::Closed,
+                new: AdditionalPropertiesState::Open,
+            },
+        ];
+        assert_eq!(expected, actual);
+        assert_eq!(
+            vec![
+                Compatibility::Forward,
+                Compatibility::Backward,
+                Compatibility::Backward,
+                Compatibility::Backward,
+                Compatibility::Backward,
+            ],
+            compatibilities(&actual)
+        );
+    }
+
+    #[test]
+    fn closing_additional_properties_is_forward_compatible() {
+        let old: JsonSchema = serde_json::from_str(r#"{"type":"object"}"#).unwrap();
+        let new: JsonSchema =
+            serde_json::from_str(r#"{"type":"object","additionalProperties":false}"#).unwrap();
+        let actual: Vec<Compatibility> = compatibilities(&diff(&old, &new));
+        let expected: Vec<Compatibility> = vec![Compatibility::Forward];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn enum_narrowed_widened_and_replaced() {
+        let old: JsonSchema = serde_json::from_str(r#"{"enum":["a","b"]}"#).unwrap();
+        let narrowed: JsonSchema = serde_json::from_str(r#"{"enum":["a"]}"#).unwrap();
+        let widened: JsonSchema = serde_json::from_str(r#"{"enum":["a","b","c"]}"#).unwrap();
+        let replaced: JsonSchema = serde_json::from_str(r#"{"enum":["a","c"]}"#).unwrap();
+        let actual: Vec<Vec<Compatibility>> = vec![
+            compatibilities(&diff(&old, &narrowed)),
+            compatibilities(&diff(&old, &widened)),
+            compatibilities(&diff(&old, &replaced)),
+        ];
+        let expected: Vec<Vec<Compatibility>> = vec![
+            vec![Compatibility::Forward],
+            vec![Compatibility::Backward],
+            vec![Compatibility::Breaking],
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn bounds_tightened_and_loosened() {
+        let old: JsonSchema =
+            serde_json::from_str(r#"{"minimum":1,"maximum":10,"maxLength":5}"#).unwrap();
+        let new: JsonSchema =
+            serde_json::from_str(r#"{"minimum":2,"maximum":20,"minLength":1}"#).unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![
+            SchemaChange::BoundChanged {
+                schema_path: ptr("/minimum"),
+                keyword: BoundKeyword::Minimum,
+                old: Some(1.0),
+                new: Some(2.0),
+            },
+            SchemaChange::BoundChanged {
+                schema_path: ptr("/maximum"),
+                keyword: BoundKeyword::Maximum,
+                old: Some(10.0),
+                new: Some(20.0),
+            },
+            SchemaChange::SizeBoundChanged {
+                schema_path: ptr("/minLength"),
+                keyword: BoundKeyword::MinLength,
+                old: None,
+                new: Some(1),
+            },
+            SchemaChange::SizeBoundChanged {
+                schema_path: ptr("/maxLength"),
+                keyword: BoundKeyword::MaxLength,
+                old: Some(5),
+                new: None,
+            },
+        ];
+        assert_eq!(expected, actual);
+        assert_eq!(
+            vec![
+                Compatibility::Forward,
+                Compatibility::Backward,
+                Compatibility::Forward,
+                Compatibility::Backward,
+            ],
+            compatibilities(&actual)
+        );
+    }
+
+    #[test]
+    fn type_changes() {
+        let cases: [(&str, &str, Compatibility); 3] = [
+            (
+                r#"{"type":"string"}"#,
+                r#"{"type":"integer"}"#,
+                Compatibility::Breaking,
+            ),
+            (
+                r#"{"type":"integer"}"#,
+                r#"{"type":"number"}"#,
+                Compatibility::Backward,
+            ),
+            (
+                r#"{"type":"number"}"#,
+                r#"{"type":"integer"}"#,
+                Compatibility::Forward,
+            ),
+        ];
+        for (old, new, expected) in cases {
+            let actual: Vec<Compatibility> = compatibilities(&diff(
+                &serde_json::from_str(old).unwrap(),
+                &serde_json::from_str(new).unwrap(),
+            ));
+            assert_eq!(vec![expected], actual, "{old} -> {new}");
+        }
+    }
+
+    #[test]
+    fn refs_are_followed_and_changes_reported_at_target() {
+        let old: JsonSchema = serde_json::from_str(
+            r##"{"properties":{"a":{"$ref":"#/$defs/A"},"b":{"$ref":"#/$defs/A"}},"$defs":{"A":{"type":"string"}}}"##,
+        ).unwrap();
+        let new: JsonSchema = serde_json::from_str(
+            r##"{"properties":{"a":{"$ref":"#/$defs/Renamed"},"b":{"$ref":"#/$defs/Renamed"}},"$defs":{"Renamed":{"type":"string","maxLength":3}}}"##,
+        ).unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![SchemaChange::SizeBoundChanged {
+            schema_path: ptr("/$defs/Renamed/maxLength"),
+            keyword: BoundKeyword::MaxLength,
+            old: None,
+            new: Some(3),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn recursive_refs_terminate() {
+        let old: JsonSchema = serde_json::from_str(
+            r##"{"$ref":"#/$defs/Node","$defs":{"Node":{"properties":{"next":{"$ref":"#/$defs/Node"}}}}}"##,
+        ).unwrap();
+        let new: JsonSchema = serde_json::from_str(
+            r##"{"$ref":"#/$defs/Node","$defs":{"Node":{"properties":{"next":{"$ref":"#/$defs/Node"}},"required":["next"]}}}"##,
+        ).unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![SchemaChange::RequiredAdded {
+            schema_path: ptr("/$defs/Node/required"),
+            property: "next".to_string(),
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn combinator_branches_added_and_removed() {
+        let old: JsonSchema =
+            serde_json::from_str(r#"{"anyOf":[{"type":"string"}],"allOf":[{},{}]}"#).unwrap();
+        let new: JsonSchema = serde_json::from_str(
+            r##"{"anyOf":[{"type":"string"},{"$ref":"#/$defs/Missing"}],"allOf":[{}],"items":{}}"##,
+        )
+        .unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![
+            SchemaChange::KeywordAdded {
+                schema_path: ptr("/items"),
+                keyword: SchemaKeyword::Items,
+            },
+            SchemaChange::BranchRemoved {
+                schema_path: ptr("/allOf/1"),
+                keyword: SchemaKeyword::AllOf,
+            },
+            SchemaChange::BranchAdded {
+                schema_path: ptr("/anyOf/1"),
+                keyword: SchemaKeyword::AnyOf,
+            },
+        ];
+        assert_eq!(expected, actual);
+        assert_eq!(
+            vec![
+                Compatibility::Forward,
+                Compatibility::Backward,
+                Compatibility::Backward
+            ],
+            compatibilities(&actual)
+        );
+    }
+
+    #[test]
+    fn unresolvable_ref_in_new_schema_is_breaking() {
+        let old: JsonSchema = serde_json::from_str(r#"{"properties":{"a":{}}}"#).unwrap();
+        let new: JsonSchema =
+            serde_json::from_str(r##"{"properties":{"a":{"$ref":"#/$defs/Missing"}}}"##).unwrap();
+        let actual: Vec<SchemaChange> = diff(&old, &new);
+        let expected: Vec<SchemaChange> = vec![SchemaChange::UnresolvableRef {
+            schema_path: ptr("/properties/a/$ref"),
+            ref_str: "#/$defs/Missing".to_string(),
+        }];
+        assert_eq!(expected, actual);
+        assert_eq!(vec![Compatibility::Breaking], compatibilities(&actual));
+    }
+}
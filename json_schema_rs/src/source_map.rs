@@ -0,0 +1,332 @@
+//! Source positions for JSON documents: where each value sits in the original text.
+//!
+//! A [`SourceMap`] records, for every value in a JSON document (schema or instance), its byte
+//! range and the line/column where it starts, keyed by [`JsonPointer`]. Errors that carry a
+//! pointer (e.g. [`ValidationError`](crate::ValidationError)) can then be mapped back to the
+//! file the user is editing. Building a map is optional and separate from parsing, so the normal
+//! parse path pays nothing for it.
+
+use crate::json_pointer::JsonPointer;
+use std::collections::HashMap;
+
+/// Location of one JSON value in its source text.
+///
+/// `start`/`end` are byte offsets (`end` exclusive). `line` and `column` are 1-based and refer to
+/// `start`; the column counts Unicode scalar values, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Spans of every value in a JSON document, keyed by JSON Pointer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Keyed by the RFC 6901 pointer string ("" for the root).
+    spans: HashMap<String, Span>,
+}
+
+impl SourceMap {
+    /// Parses `source` as JSON and records the span of every value in it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`serde_json::Error`] when `source` is not valid JSON; its `line()` and
+    /// `column()` locate the problem.
+    pub fn parse(source: &str) -> Result<(serde_json::Value, SourceMap), serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(source)?;
+        Ok((value, Self::from_json(source)))
+    }
+
+    /// Records the span of every value in `source`, which should be valid JSON. Scanning stops at
+    /// the first malformed token, so a malformed document yields a partial map.
+    #[must_use]
+    pub fn from_json(source: &str) -> SourceMap {
+        let lines: LineIndex = LineIndex::new(source);
+        let mut spans: HashMap<String, Span> = HashMap::new();
+        let mut record = |pointer: &JsonPointer, start: usize, end: usize| {
+            let (line, column) = lines.line_column(source, start);
+            spans.insert(
+                pointer.as_str().to_string(),
+                Span {
+                    start,
+                    end,
+                    line,
+                    column,
+                },
+            );
+        };
+
+        let bytes: &[u8] = source.as_bytes();
+        let mut stack: Vec<Container> = Vec::new();
+        // Pointer of the next value to be read; `None` when the next token must not be a value.
+        let mut next: Option<JsonPointer> = Some(JsonPointer::root());
+        let mut i: usize = 0;
+        loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let Some(&b) = bytes.get(i) else {
+                break;
+            };
+            match b {
+                b'{' | b'[' => {
+                    let Some(pointer) = next.take() else {
+                        break;
+                    };
+                    if b == b'[' {
+                        next = Some(pointer.push("0"));
+                    }
+                    stack.push(Container {
+                        pointer,
+                        start: i,
+                        is_object: b == b'{',
+                        index: 0,
+                        expecting_key: b == b'{',
+                    });
+                    i += 1;
+                }
+                b'}' | b']' => {
+                    let Some(container) = stack.pop() else {
+                        break;
+                    };
+                    record(&container.pointer, container.start, i + 1);
+                    next = None;
+                    i += 1;
+                }
+                b',' => {
+                    let Some(container) = stack.last_mut() else {
+                        break;
+                    };
+                    if container.is_object {
+                        container.expecting_key = true;
+                    } else {
+                        container.index += 1;
+                        next = Some(container.pointer.push(&container.index.to_string()));
+                    }
+                    i += 1;
+                }
+                b'"' if stack.last().is_some_and(|c| c.expecting_key) => {
+                    let Some((key, after_colon)) = member_name(source, i) else {
+                        break;
+                    };
+                    i = after_colon;
+                    let Some(container) = stack.last_mut() else {
+                        break;
+                    };
+                    container.expecting_key = false;
+                    next = Some(container.pointer.push(&key));
+                }
+                _ => {
+                    let Some(pointer) = next.take() else {
+                        break;
+                    };
+                    let Some(end) = scalar_end(bytes, i) else {
+                        break;
+                    };
+                    record(&pointer, i, end);
+                    i = end;
+                }
+            }
+        }
+        SourceMap { spans }
+    }
+
+    /// The span of the value at `pointer`, if the document has one there.
+    #[must_use]
+    pub fn get(&self, pointer: &JsonPointer) -> Option<Span> {
+        self.spans.get(pointer.as_str()).copied()
+    }
+
+    /// The span of the value at `pointer`, or of its closest ancestor that exists (e.g. for a
+    /// pointer to a property that is missing from the document).
+    #[must_use]
+    pub fn get_or_ancestor(&self, pointer: &JsonPointer) -> Option<Span> {
+        let mut current: JsonPointer = pointer.clone();
+        loop {
+            if let Some(span) = self.get(&current) {
+                return Some(span);
+            }
+            if current.is_root() {
+                return None;
+            }
+            current = current.parent();
+        }
+    }
+
+    /// Number of values recorded.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// True when no values were recorded (empty or malformed document).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// An object or array being scanned.
+struct Container {
+    pointer: JsonPointer,
+    start: usize,
+    is_object: bool,
+    /// Index of the current element (arrays only).
+    index: usize,
+    /// True when the next string is a member name (objects only).
+    expecting_key: bool,
+}
+
+/// Reads the member name starting at the quote at `start` and the `:` after it. Returns the
+/// decoded name and the offset just past the colon.
+fn member_name(source: &str, start: usize) -> Option<(String, usize)> {
+    let bytes: &[u8] = source.as_bytes();
+    let end: usize = string_end(bytes, start)?;
+    let name: String = serde_json::from_str(&source[start..end]).ok()?;
+    let mut i: usize = end;
+    while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    (bytes.get(i) == Some(&b':')).then_some((name, i + 1))
+}
+
+/// Byte offset just past the string, number, or literal starting at `start`.
+fn scalar_end(bytes: &[u8], start: usize) -> Option<usize> {
+    if bytes[start] == b'"' {
+        return string_end(bytes, start);
+    }
+    let mut end: usize = start;
+    while end < bytes.len()
+        && !matches!(bytes[end], b',' | b'}' | b']')
+        && !bytes[end].is_ascii_whitespace()
+    {
+        end += 1;
+    }
+    Some(end)
+}
+
+/// Byte offset just past the closing quote of the string starting at `start`.
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i: usize = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Byte offsets where each line starts, for offset → line/column conversion.
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { starts }
+    }
+
+    /// 1-based line and column (in chars) of byte `offset`.
+    fn line_column(&self, source: &str, offset: usize) -> (usize, usize) {
+        let line_index: usize = self.starts.partition_point(|&s| s <= offset) - 1;
+        let line_start: usize = self.starts[line_index];
+        let column: usize = source[line_start..offset].chars().count() + 1;
+        (line_index + 1, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SourceMap, Span};
+    use crate::json_pointer::JsonPointer;
+
+    fn pointer(s: &str) -> JsonPointer {
+        JsonPointer::try_from(s).expect("valid pointer")
+    }
+
+    #[test]
+    fn records_every_value_with_line_and_column() {
+        let source =
+            "{\n  \"name\": \"Alice\",\n  \"tags\": [1, {\"a\": null}],\n  \"n\": -1.5e3\n}\n";
+        let map: SourceMap = SourceMap::from_json(source);
+        let expected_root = Span {
+            start: 0,
+            end: source.len() - 1,
+            line: 1,
+            column: 1,
+        };
+        assert_eq!(Some(expected_root), map.get(&JsonPointer::root()));
+        let name: Span = map.get(&pointer("/name")).expect("name");
+        assert_eq!(&source[name.start..name.end], "\"Alice\"");
+        assert_eq!((name.line, name.column), (2, 11));
+        let tags_1_a: Span = map.get(&pointer("/tags/1/a")).expect("nested");
+        assert_eq!(&source[tags_1_a.start..tags_1_a.end], "null");
+        let tags_1: Span = map.get(&pointer("/tags/1")).expect("array element");
+        assert_eq!(&source[tags_1.start..tags_1.end], "{\"a\": null}");
+        let n: Span = map.get(&pointer("/n")).expect("number");
+        assert_eq!(&source[n.start..n.end], "-1.5e3");
+        assert_eq!((n.line, n.column), (4, 8));
+        assert_eq!(map.len(), 7);
+    }
+
+    #[test]
+    fn keys_with_escapes_and_pointer_special_characters() {
+        let source = r#"{"a/b": 1, "m~n": 2, "q\"uote": [], "é": "x"}"#;
+        let map: SourceMap = SourceMap::from_json(source);
+        let slash: Span = map.get(&JsonPointer::root().push("a/b")).expect("a/b");
+        assert_eq!(&source[slash.start..slash.end], "1");
+        let tilde: Span = map.get(&pointer("/m~0n")).expect("m~n");
+        assert_eq!(&source[tilde.start..tilde.end], "2");
+        let quote: Span = map
+            .get(&JsonPointer::root().push("q\"uote"))
+            .expect("quote");
+        assert_eq!(&source[quote.start..quote.end], "[]");
+        assert!(map.get(&JsonPointer::root().push("é")).is_some());
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let source = "{\"é\": \"ü\", \"b\": true}";
+        let map: SourceMap = SourceMap::from_json(source);
+        let b: Span = map.get(&pointer("/b")).expect("b");
+        assert_eq!((b.line, b.column), (1, 17));
+        assert_eq!(b.start, 18);
+    }
+
+    #[test]
+    fn get_or_ancestor_falls_back_to_parent() {
+        let source = r#"{"a": {"b": 1}}"#;
+        let map: SourceMap = SourceMap::from_json(source);
+        let actual: Option<Span> = map.get_or_ancestor(&pointer("/a/missing"));
+        let expected: Option<Span> = map.get(&pointer("/a"));
+        assert_eq!(expected, actual);
+        assert!(map.get(&pointer("/a/missing")).is_none());
+    }
+
+    #[test]
+    fn parse_returns_value_and_map() {
+        let (value, map) = SourceMap::parse("[true, \"x\"]").expect("parse");
+        assert_eq!(value, serde_json::json!([true, "x"]));
+        assert!(map.get(&pointer("/1")).is_some());
+        let err = SourceMap::parse("{\n  \"a\": ,\n}").expect_err("invalid");
+        assert_eq!(err.line(), 2);
+    }
+
+    #[test]
+    fn scalar_root_and_empty_containers() {
+        let map: SourceMap = SourceMap::from_json("  42 ");
+        let root: Span = map.get(&JsonPointer::root()).expect("root");
+        assert_eq!((root.start, root.end, root.column), (2, 4, 3));
+        let map: SourceMap = SourceMap::from_json(r#"{"a": [], "b": {}}"#);
+        assert_eq!(map.len(), 3);
+        assert!(map.get(&pointer("/a/0")).is_none());
+    }
+}
@@ -69,10 +69,25 @@ pub enum ValidationError {
         /// JSON type of the instance (for user-facing context).
         got: String,
     },
-    /// Schema had `uniqueItems: true` but the array contained duplicate elements.
+    /// Schema had `type: "null"` or a list of types (e.g. `["object", "boolean"]`) and the instance
+    /// matched none of them.
+    ExpectedType {
+        /// JSON Pointer to the instance that failed.
+        instance_path: JsonPointer,
+        /// The allowed JSON type names, in schema order.
+        expected: Vec<String>,
+        /// JSON type of the instance (for user-facing context).
+        got: String,
+    },
+    /// Schema had `uniqueItems: true` but the array contained duplicate elements (JSON equality, so `1` equals `1.0`).
+    /// One error is reported per duplicate element, paired with the first element it equals.
     DuplicateArrayItems {
         /// JSON Pointer to the array instance that failed.
         instance_path: JsonPointer,
+        /// Index of the first occurrence of the duplicated value.
+        first_index: usize,
+        /// Index of the later element that equals the first occurrence.
+        duplicate_index: usize,
         /// Serialized duplicate value (for user-facing context).
         duplicate_value: String,
     },
@@ -135,6 +150,15 @@ pub enum ValidationError {
         /// Actual instance value (for user-facing context).
         actual: OrderedF64,
     },
+    /// Instance was not greater than the schema's `exclusiveMinimum` (exclusive lower bound).
+    NotAboveExclusiveMinimum {
+        /// JSON Pointer to the instance that failed.
+        instance_path: JsonPointer,
+        /// The schema's exclusive lower bound.
+        exclusive_minimum: OrderedF64,
+        /// Actual instance value (for user-facing context).
+        actual: OrderedF64,
+    },
     /// Instance was above the schema's `maximum` (inclusive upper bound).
     AboveMaximum {
         /// JSON Pointer to the instance that failed.
@@ -186,6 +210,56 @@ pub enum ValidationError {
         /// The invalid string value (for user-facing context).
         value: String,
     },
+    /// The string instance does not conform to the schema's `format` (only emitted when formats are asserted).
+    InvalidFormat {
+        /// JSON Pointer to the instance that failed.
+        instance_path: JsonPointer,
+        /// The schema's format name (e.g. `date-time`).
+        format: String,
+        /// The invalid string value (for user-facing context).
+        value: String,
+    },
+    /// Schema had a `format` that is neither built in nor registered, and
+    /// [`UnknownFormatMode::Error`](crate::UnknownFormatMode::Error) was selected.
+    UnknownFormat {
+        /// JSON Pointer to the instance being validated.
+        instance_path: JsonPointer,
+        /// The unrecognized format name.
+        format: String,
+    },
+    /// A registered [`Keyword`](crate::Keyword) rejected the instance.
+    Custom {
+        /// JSON Pointer to the instance that failed.
+        instance_path: JsonPointer,
+        /// The keyword name (e.g. `x-unique-by`).
+        keyword: String,
+        /// Human-readable reason (for user-facing context).
+        message: String,
+    },
+    /// A registered [`Keyword`](crate::Keyword) could not be compiled from its schema value.
+    InvalidKeywordInSchema {
+        /// JSON Pointer to the instance location where the schema was applied.
+        instance_path: JsonPointer,
+        /// The keyword name.
+        keyword: String,
+        /// Reason returned by [`Keyword::compile`](crate::Keyword::compile).
+        reason: String,
+    },
+    /// The root schema's meta-schema requires a vocabulary that is neither standard nor provided
+    /// by a registered keyword, so the schema cannot be processed.
+    UnsupportedVocabulary {
+        /// Always the root: the whole instance is refused.
+        instance_path: JsonPointer,
+        /// The vocabulary URI.
+        vocabulary: String,
+    },
+    /// No meta-schema is bundled for the schema document's draft (drafts before draft-04).
+    UnsupportedMetaSchema {
+        /// Always the root: the whole schema document is refused.
+        instance_path: JsonPointer,
+        /// The draft's meta-schema URI.
+        schema_uri: String,
+    },
     /// Schema had `anyOf` but the instance did not validate against any of the subschemas.
     NoSubschemaMatched {
         /// JSON Pointer to the instance that failed.
@@ -217,6 +291,53 @@ impl ValidationError {
             | ValidationError::ExpectedNumber { instance_path, .. }
             | ValidationError::ExpectedArray { instance_path, .. }
             | ValidationError::ExpectedBoolean { instance_path, .. }
+            | ValidationError::ExpectedType { instance_path, .. }
+            | ValidationError::DuplicateArrayItems { instance_path, .. }
+            | ValidationError::TooFewItems { instance_path, .. }
+            | ValidationError::TooManyItems { instance_path, .. }
+            | ValidationError::MissingRequired { instance_path, .. }
+            | ValidationError::DisallowedAdditionalProperty { instance_path, .. }
+            | ValidationError::NotInEnum { instance_path, .. }
+            | ValidationError::NotConst { instance_path, .. }
+            | ValidationError::BelowMinimum { instance_path, .. }
+            | ValidationError::NotAboveExclusiveMinimum { instance_path, .. }
+            | ValidationError::AboveMaximum { instance_path, .. }
+            | ValidationError::TooShort { instance_path, .. }
+            | ValidationError::TooLong { instance_path, .. }
+            | ValidationError::PatternMismatch { instance_path, .. }
+            | ValidationError::InvalidPatternInSchema { instance_path, .. }
+            | ValidationError::InvalidFormat { instance_path, .. }
+            | ValidationError::UnknownFormat { instance_path, .. }
+            | ValidationError::Custom { instance_path, .. }
+            | ValidationError::InvalidKeywordInSchema { instance_path, .. }
+            | ValidationError::UnsupportedVocabulary { instance_path, .. }
+            | ValidationError::UnsupportedMetaSchema { instance_path, .. }
+            | ValidationError::NoSubschemaMatched { instance_path, .. }
+            | ValidationError::MultipleSubschemasMatched { instance_path, .. } => instance_path,
+            #[cfg(feature = "uuid")]
+            ValidationError::InvalidUuidFormat { instance_path, .. } => instance_path,
+        }
+    }
+
+    /// Returns this error with `segment` prepended to its instance path: the error was found in
+    /// a value nested under `segment` (a property key or array index) of the value being checked.
+    #[must_use]
+    pub fn within(mut self, segment: &str) -> Self {
+        let path: &mut JsonPointer = self.instance_path_mut();
+        *path = path.prepend(segment);
+        self
+    }
+
+    fn instance_path_mut(&mut self) -> &mut JsonPointer {
+        match self {
+            ValidationError::InvalidRef { instance_path, .. }
+            | ValidationError::ExpectedObject { instance_path, .. }
+            | ValidationError::ExpectedString { instance_path, .. }
+            | ValidationError::ExpectedInteger { instance_path, .. }
+            | ValidationError::ExpectedNumber { instance_path, .. }
+            | ValidationError::ExpectedArray { instance_path, .. }
+            | ValidationError::ExpectedBoolean { instance_path, .. }
+            | ValidationError::ExpectedType { instance_path, .. }
             | ValidationError::DuplicateArrayItems { instance_path, .. }
             | ValidationError::TooFewItems { instance_path, .. }
             | ValidationError::TooManyItems { instance_path, .. }
@@ -225,11 +346,18 @@ impl ValidationError {
             | ValidationError::NotInEnum { instance_path, .. }
             | ValidationError::NotConst { instance_path, .. }
             | ValidationError::BelowMinimum { instance_path, .. }
+            | ValidationError::NotAboveExclusiveMinimum { instance_path, .. }
             | ValidationError::AboveMaximum { instance_path, .. }
             | ValidationError::TooShort { instance_path, .. }
             | ValidationError::TooLong { instance_path, .. }
             | ValidationError::PatternMismatch { instance_path, .. }
             | ValidationError::InvalidPatternInSchema { instance_path, .. }
+            | ValidationError::InvalidFormat { instance_path, .. }
+            | ValidationError::UnknownFormat { instance_path, .. }
+            | ValidationError::Custom { instance_path, .. }
+            | ValidationError::InvalidKeywordInSchema { instance_path, .. }
+            | ValidationError::UnsupportedVocabulary { instance_path, .. }
+            | ValidationError::UnsupportedMetaSchema { instance_path, .. }
             | ValidationError::NoSubschemaMatched { instance_path, .. }
             | ValidationError::MultipleSubschemasMatched { instance_path, .. } => instance_path,
             #[cfg(feature = "uuid")]
@@ -269,12 +397,19 @@ impl fmt::Display for ValidationError {
             ValidationError::ExpectedBoolean { got, .. } => {
                 write!(f, "{location}: expected boolean, got {got}")
             }
+            ValidationError::ExpectedType { expected, got, .. } => {
+                let expected_str: String = expected.join(" or ");
+                write!(f, "{location}: expected {expected_str}, got {got}")
+            }
             ValidationError::DuplicateArrayItems {
-                duplicate_value, ..
+                first_index,
+                duplicate_index,
+                duplicate_value,
+                ..
             } => {
                 write!(
                     f,
-                    "{location}: array has duplicate items (value: {duplicate_value})"
+                    "{location}: array has duplicate items at indices {first_index} and {duplicate_index} (value: {duplicate_value})"
                 )
             }
             ValidationError::TooFewItems {
@@ -334,6 +469,17 @@ impl fmt::Display for ValidationError {
                     actual.0, minimum.0
                 )
             }
+            ValidationError::NotAboveExclusiveMinimum {
+                exclusive_minimum,
+                actual,
+                ..
+            } => {
+                write!(
+                    f,
+                    "{location}: value {} is not above exclusive minimum {}",
+                    actual.0, exclusive_minimum.0
+                )
+            }
             ValidationError::AboveMaximum {
                 maximum, actual, ..
             } => {
@@ -376,6 +522,37 @@ impl fmt::Display for ValidationError {
             ValidationError::InvalidUuidFormat { value, .. } => {
                 write!(f, "{location}: string \"{value}\" is not a valid UUID")
             }
+            ValidationError::InvalidFormat { format, value, .. } => {
+                write!(
+                    f,
+                    "{location}: string \"{value}\" is not a valid \"{format}\""
+                )
+            }
+            ValidationError::UnknownFormat { format, .. } => {
+                write!(f, "{location}: unknown format \"{format}\"")
+            }
+            ValidationError::Custom {
+                keyword, message, ..
+            } => {
+                write!(f, "{location}: {keyword}: {message}")
+            }
+            ValidationError::InvalidKeywordInSchema {
+                keyword, reason, ..
+            } => {
+                write!(
+                    f,
+                    "{location}: invalid \"{keyword}\" keyword in schema: {reason}"
+                )
+            }
+            ValidationError::UnsupportedVocabulary { vocabulary, .. } => {
+                write!(
+                    f,
+                    "{location}: meta-schema requires unsupported vocabulary \"{vocabulary}\""
+                )
+            }
+            ValidationError::UnsupportedMetaSchema { schema_uri, .. } => {
+                write!(f, "{location}: no bundled meta-schema for \"{schema_uri}\"")
+            }
             ValidationError::NoSubschemaMatched {
                 subschema_count, ..
             } => {
@@ -0,0 +1,288 @@
+//! JSON equality and canonical hashing of [`serde_json::Value`] per the JSON Schema data model.
+//!
+//! JSON Schema equality differs from `serde_json::Value` equality in one way that matters:
+//! numbers are compared by mathematical value, so `1` and `1.0` are equal. Object key order
+//! never matters. [`canonical_hash`] is consistent with [`json_equal`] (equal values hash
+//! equally), which lets callers bucket values by hash and only compare within a bucket.
+
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Canonical form of a JSON number: integral values (including integral floats such as `1.0`)
+/// become `Integer`; everything else stays a float with `-0.0` normalized to `0.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CanonicalNumber {
+    Integer(i128),
+    Float(f64),
+}
+
+/// Largest magnitude at which every integral `f64` is exactly representable as `i128` without
+/// losing the value; beyond this we keep the float form.
+const MAX_EXACT_INTEGRAL_F64: f64 = 1.0e38;
+
+fn canonical_number(n: &Number) -> CanonicalNumber {
+    if let Some(i) = n.as_i64() {
+        return CanonicalNumber::Integer(i128::from(i));
+    }
+    if let Some(u) = n.as_u64() {
+        return CanonicalNumber::Integer(i128::from(u));
+    }
+    let f: f64 = n.as_f64().unwrap_or(f64::NAN);
+    if f.fract() == 0.0 && f.abs() < MAX_EXACT_INTEGRAL_F64 {
+        #[expect(clippy::cast_possible_truncation)]
+        return CanonicalNumber::Integer(f as i128);
+    }
+    CanonicalNumber::Float(if f == 0.0 { 0.0 } else { f })
+}
+
+fn numbers_equal(a: &Number, b: &Number) -> bool {
+    match (canonical_number(a), canonical_number(b)) {
+        (CanonicalNumber::Integer(x), CanonicalNumber::Integer(y)) => x == y,
+        (CanonicalNumber::Float(x), CanonicalNumber::Float(y)) => x.to_bits() == y.to_bits(),
+        _ => false,
+    }
+}
+
+fn objects_equal<'a>(
+    a: &'a Map<String, Value>,
+    b: &'a Map<String, Value>,
+    stack: &mut Vec<(&'a Value, &'a Value)>,
+) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    for (key, a_value) in a {
+        let Some(b_value) = b.get(key) else {
+            return false;
+        };
+        stack.push((a_value, b_value));
+    }
+    true
+}
+
+/// Returns true when two JSON values are equal under JSON Schema semantics: numbers compare by
+/// mathematical value (`1 == 1.0`), objects compare without regard to key order, arrays compare
+/// element-wise in order.
+#[must_use]
+pub(crate) fn json_equal(a: &Value, b: &Value) -> bool {
+    let mut stack: Vec<(&Value, &Value)> = vec![(a, b)];
+    while let Some((a, b)) = stack.pop() {
+        let equal: bool = match (a, b) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(x), Value::Bool(y)) => x == y,
+            (Value::String(x), Value::String(y)) => x == y,
+            (Value::Number(x), Value::Number(y)) => numbers_equal(x, y),
+            (Value::Array(x), Value::Array(y)) if x.len() == y.len() => {
+                stack.extend(x.iter().zip(y.iter()));
+                true
+            }
+            (Value::Object(x), Value::Object(y)) => objects_equal(x, y, &mut stack),
+            _ => false,
+        };
+        if !equal {
+            return false;
+        }
+    }
+    true
+}
+
+/// Tags written before each value so that different JSON types never share a hash stream.
+const TAG_NULL: u8 = 0;
+const TAG_BOOL: u8 = 1;
+const TAG_INTEGER: u8 = 2;
+const TAG_FLOAT: u8 = 3;
+const TAG_STRING: u8 = 4;
+const TAG_ARRAY: u8 = 5;
+const TAG_OBJECT: u8 = 6;
+const TAG_KEY: u8 = 7;
+
+/// One unit of work for [`canonical_hash`]: either a value to hash or an object key.
+enum HashItem<'a> {
+    Value(&'a Value),
+    Key(&'a str),
+}
+
+/// Hashes a JSON value in canonical form: numbers are normalized (so `1` and `1.0` hash the
+/// same) and object keys are visited in sorted order. Deterministic across runs.
+#[must_use]
+pub(crate) fn canonical_hash(value: &Value) -> u64 {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+    let mut stack: Vec<HashItem<'_>> = vec![HashItem::Value(value)];
+    while let Some(item) = stack.pop() {
+        let value: &Value = match item {
+            HashItem::Key(key) => {
+                TAG_KEY.hash(&mut hasher);
+                key.hash(&mut hasher);
+                continue;
+            }
+            HashItem::Value(v) => v,
+        };
+        match value {
+            Value::Null => TAG_NULL.hash(&mut hasher),
+            Value::Bool(b) => {
+                TAG_BOOL.hash(&mut hasher);
+                b.hash(&mut hasher);
+            }
+            Value::Number(n) => match canonical_number(n) {
+                CanonicalNumber::Integer(i) => {
+                    TAG_INTEGER.hash(&mut hasher);
+                    i.hash(&mut hasher);
+                }
+                CanonicalNumber::Float(f) => {
+                    TAG_FLOAT.hash(&mut hasher);
+                    f.to_bits().hash(&mut hasher);
+                }
+            },
+            Value::String(s) => {
+                TAG_STRING.hash(&mut hasher);
+                s.hash(&mut hasher);
+            }
+            Value::Array(arr) => {
+                TAG_ARRAY.hash(&mut hasher);
+                arr.len().hash(&mut hasher);
+                // Push in reverse so elements are hashed first to last.
+                for elem in arr.iter().rev() {
+                    stack.push(HashItem::Value(elem));
+                }
+            }
+            Value::Object(obj) => {
+                TAG_OBJECT.hash(&mut hasher);
+                obj.len().hash(&mut hasher);
+                let mut entries: Vec<(&String, &Value)> = obj.iter().collect();
+                entries.sort_by_key(|(key, _)| *key);
+                // Push in reverse so entries are hashed in ascending key order.
+                for (key, v) in entries.into_iter().rev() {
+                    stack.push(HashItem::Value(v));
+                    stack.push(HashItem::Key(key));
+                }
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/// Finds every element that equals an earlier element under [`json_equal`].
+///
+/// Returns `(first_index, duplicate_index)` pairs in ascending `duplicate_index` order, where
+/// `first_index` is the earliest occurrence of the value. Runs in linear expected time by
+/// bucketing elements on [`canonical_hash`].
+#[must_use]
+pub(crate) fn duplicate_index_pairs(items: &[Value]) -> Vec<(usize, usize)> {
+    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::with_capacity(items.len());
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let bucket: &mut Vec<usize> = buckets.entry(canonical_hash(item)).or_default();
+        match bucket
+            .iter()
+            .copied()
+            .find(|&first| json_equal(&items[first], item))
+        {
+            Some(first) => pairs.push((first, index)),
+            None => bucket.push(index),
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_hash, duplicate_index_pairs, json_equal};
+    use serde_json::{Value, json};
+
+    #[test]
+    fn json_equal_integer_and_integral_float() {
+        assert!(json_equal(&json!(1), &json!(1.0)));
+    }
+
+    #[test]
+    fn json_equal_negative_zero_and_zero() {
+        assert!(json_equal(&json!(-0.0), &json!(0)));
+    }
+
+    #[test]
+    fn json_equal_different_numbers() {
+        assert!(!json_equal(&json!(1), &json!(1.5)));
+    }
+
+    #[test]
+    fn json_equal_number_and_string_differ() {
+        assert!(!json_equal(&json!(1), &json!("1")));
+    }
+
+    #[test]
+    fn json_equal_bool_and_number_differ() {
+        assert!(!json_equal(&json!(true), &json!(1)));
+    }
+
+    #[test]
+    fn json_equal_nested_objects_with_normalized_numbers() {
+        let a: Value = json!({"a": [1, {"b": 2.0}], "c": null});
+        let b: Value = json!({"c": null, "a": [1.0, {"b": 2}]});
+        assert!(json_equal(&a, &b));
+    }
+
+    #[test]
+    fn json_equal_arrays_order_matters() {
+        assert!(!json_equal(&json!([1, 2]), &json!([2, 1])));
+    }
+
+    #[test]
+    fn json_equal_objects_with_different_keys() {
+        assert!(!json_equal(&json!({"a": 1}), &json!({"b": 1})));
+    }
+
+    #[test]
+    fn canonical_hash_consistent_with_json_equal() {
+        let a: Value = json!({"x": [1, 2.0, {"y": -0.0}], "z": "s"});
+        let b: Value = json!({"z": "s", "x": [1.0, 2, {"y": 0}]});
+        assert_eq!(canonical_hash(&a), canonical_hash(&b));
+    }
+
+    #[test]
+    fn canonical_hash_distinguishes_types() {
+        assert_ne!(canonical_hash(&json!(1)), canonical_hash(&json!("1")));
+        assert_ne!(canonical_hash(&json!([])), canonical_hash(&json!({})));
+        assert_ne!(canonical_hash(&json!(null)), canonical_hash(&json!(false)));
+    }
+
+    #[test]
+    fn canonical_hash_nested_structure_differs() {
+        assert_ne!(
+            canonical_hash(&json!([[1], 2])),
+            canonical_hash(&json!([[1, 2]]))
+        );
+    }
+
+    #[test]
+    fn duplicate_index_pairs_none() {
+        let items: Vec<Value> = vec![json!(1), json!("1"), json!(true), json!(null)];
+        let expected: Vec<(usize, usize)> = vec![];
+        let actual: Vec<(usize, usize)> = duplicate_index_pairs(&items);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn duplicate_index_pairs_reports_every_duplicate_against_first_occurrence() {
+        let items: Vec<Value> = vec![
+            json!(1),
+            json!("a"),
+            json!(1.0),
+            json!("a"),
+            json!({"k": 1}),
+            json!(1),
+        ];
+        let expected: Vec<(usize, usize)> = vec![(0, 2), (1, 3), (0, 5)];
+        let actual: Vec<(usize, usize)> = duplicate_index_pairs(&items);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn duplicate_index_pairs_large_array_of_ids() {
+        let mut items: Vec<Value> = (0..10_000).map(|i| json!(format!("id-{i}"))).collect();
+        items.push(json!("id-42"));
+        let expected: Vec<(usize, usize)> = vec![(42, 10_000)];
+        let actual: Vec<(usize, usize)> = duplicate_index_pairs(&items);
+        assert_eq!(expected, actual);
+    }
+}
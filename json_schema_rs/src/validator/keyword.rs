@@ -0,0 +1,192 @@
+//! User-defined keywords and vocabularies.
+//!
+//! A [`Keyword`] is registered on [`ValidatorSettings`](super::ValidatorSettings) under a keyword
+//! name. When a schema object carries that keyword (captured in [`JsonSchema::extensions`]), the
+//! keyword is compiled once per validation run from its raw value and then applied to every
+//! instance the schema object is evaluated against.
+//!
+//! Keywords may be registered as part of a vocabulary URI. Such keywords are only active when the
+//! root schema's `$schema` names a meta-schema registered with the settings whose `$vocabulary`
+//! lists that URI, mirroring how draft 2019-09+ meta-schemas enable vocabularies.
+
+use super::error::ValidationError;
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::JsonSchema;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+/// A user-defined schema keyword (e.g. `x-unique-by`).
+///
+/// [`compile`](Keyword::compile) receives the keyword's raw value from the schema;
+/// [`validate`](Keyword::validate) receives each instance the schema object applies to and its
+/// location, and returns one error (normally [`ValidationError::Custom`]) per failure.
+pub trait Keyword: Send + Sync {
+    /// Builds the keyword from its raw schema value.
+    ///
+    /// # Errors
+    ///
+    /// Returns a human-readable reason when the value is malformed; the validator reports it as
+    /// [`ValidationError::InvalidKeywordInSchema`].
+    fn compile(value: &Value) -> Result<Self, String>
+    where
+        Self: Sized;
+
+    /// Validates `instance`, located at `instance_path`. Returns no errors when it conforms.
+    fn validate(&self, instance: &Value, instance_path: &JsonPointer) -> Vec<ValidationError>;
+}
+
+/// Result of compiling a keyword: the keyword, or the reason its schema value was rejected.
+type CompiledKeyword = Result<Box<dyn Keyword>, String>;
+
+type CompileFn = dyn Fn(&Value) -> CompiledKeyword + Send + Sync;
+
+/// A keyword registration: how to compile the keyword, and the vocabulary it belongs to (if any).
+///
+/// Two registrations are equal only when they share the same compile function and vocabulary.
+#[derive(Clone)]
+pub struct KeywordRegistration {
+    compile: Arc<CompileFn>,
+    vocabulary: Option<String>,
+}
+
+impl KeywordRegistration {
+    /// Registration for keyword type `K`, optionally scoped to a vocabulary URI.
+    #[must_use]
+    pub(crate) fn new<K: Keyword + 'static>(vocabulary: Option<String>) -> Self {
+        Self {
+            compile: Arc::new(|value: &Value| {
+                K::compile(value).map(|k| Box::new(k) as Box<dyn Keyword>)
+            }),
+            vocabulary,
+        }
+    }
+
+    /// The vocabulary URI this keyword belongs to, if it was registered with one.
+    #[must_use]
+    pub fn vocabulary(&self) -> Option<&str> {
+        self.vocabulary.as_deref()
+    }
+}
+
+impl fmt::Debug for KeywordRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeywordRegistration")
+            .field("vocabulary", &self.vocabulary)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for KeywordRegistration {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.compile, &other.compile) && self.vocabulary == other.vocabulary
+    }
+}
+
+impl Eq for KeywordRegistration {}
+
+/// Prefix shared by the vocabularies defined by the JSON Schema specification itself
+/// (e.g. `https://json-schema.org/draft/2020-12/vocab/core`).
+const STANDARD_VOCABULARY_PREFIX: &str = "https://json-schema.org/draft/";
+
+/// Per-run keyword state: which registered keywords are active and their compiled forms.
+pub(crate) struct KeywordState<'a> {
+    active: Vec<(&'a str, &'a KeywordRegistration)>,
+    /// Compiled keywords keyed by (schema object address, keyword name). The schema is borrowed
+    /// for the whole run, so addresses are stable.
+    compiled: HashMap<(usize, &'a str), CompiledKeyword>,
+}
+
+impl<'a> KeywordState<'a> {
+    /// Selects the active keywords for `root`. Returns an error per required vocabulary in the
+    /// root's meta-schema that is neither standard nor provided by a registered keyword.
+    pub(crate) fn new(
+        root: &JsonSchema,
+        keywords: &'a BTreeMap<String, KeywordRegistration>,
+        meta_schemas: &BTreeMap<String, BTreeMap<String, bool>>,
+    ) -> Result<Self, Vec<ValidationError>> {
+        let declared: Option<&BTreeMap<String, bool>> = root
+            .schema
+            .as_deref()
+            .and_then(|uri| meta_schemas.get(uri.trim_end_matches('#')));
+        if let Some(declared) = declared {
+            let unsupported: Vec<ValidationError> = declared
+                .iter()
+                .filter(|(uri, required)| {
+                    **required
+                        && !uri.starts_with(STANDARD_VOCABULARY_PREFIX)
+                        && !keywords
+                            .values()
+                            .any(|k| k.vocabulary() == Some(uri.as_str()))
+                })
+                .map(|(uri, _)| ValidationError::UnsupportedVocabulary {
+                    instance_path: JsonPointer::root(),
+                    vocabulary: uri.clone(),
+                })
+                .collect();
+            if !unsupported.is_empty() {
+                return Err(unsupported);
+            }
+        }
+        let active: Vec<(&str, &KeywordRegistration)> = keywords
+            .iter()
+            .filter(|(_, registration)| match registration.vocabulary() {
+                None => true,
+                Some(vocabulary) => declared.is_some_and(|d| d.contains_key(vocabulary)),
+            })
+            .map(|(name, registration)| (name.as_str(), registration))
+            .collect();
+        Ok(Self {
+            active,
+            compiled: HashMap::new(),
+        })
+    }
+
+    /// Applies every active keyword present on `schema` to `instance`.
+    pub(crate) fn validate(
+        &mut self,
+        schema: &JsonSchema,
+        instance: &Value,
+        instance_path: &JsonPointer,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if schema.extensions.is_empty() {
+            return;
+        }
+        let address: usize = std::ptr::from_ref(schema).addr();
+        for &(name, registration) in &self.active {
+            let Some(value) = schema.extensions.get(name) else {
+                continue;
+            };
+            let compiled = self
+                .compiled
+                .entry((address, name))
+                .or_insert_with(|| (registration.compile)(value));
+            match compiled {
+                Ok(keyword) => errors.extend(keyword.validate(instance, instance_path)),
+                Err(reason) => errors.push(ValidationError::InvalidKeywordInSchema {
+                    instance_path: instance_path.clone(),
+                    keyword: name.to_string(),
+                    reason: reason.clone(),
+                }),
+            }
+        }
+    }
+}
+
+/// Reads the `$vocabulary` object of a meta-schema document (URI → required). Entries whose value
+/// is not a boolean are skipped.
+#[must_use]
+pub(crate) fn vocabularies_of(meta_schema: &Value) -> BTreeMap<String, bool> {
+    meta_schema
+        .get("$vocabulary")
+        .and_then(Value::as_object)
+        .map(|vocabularies| {
+            vocabularies
+                .iter()
+                .filter_map(|(uri, required)| required.as_bool().map(|r| (uri.clone(), r)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,735 @@
+//! Meta-schema validation: checks a raw schema document against its draft's official meta-schema.
+//!
+//! The official meta-schemas for draft-04, draft-06, draft-07, 2019-09, and 2020-12 (including the
+//! 2019-09 and 2020-12 vocabulary meta-schemas) are bundled from `meta_schemas/`, so no network
+//! access is needed. They are evaluated directly as JSON rather than through [`JsonSchema`], which
+//! cannot represent boolean schemas or `type` arrays. The evaluator covers exactly the keywords
+//! those documents use; `format` is an annotation, as the meta-schemas intend.
+//!
+//! `$recursiveRef` (2019-09) and `$dynamicRef` (2020-12) always resolve to the entry meta-schema:
+//! when checking against an unextended official meta-schema, that is the outermost dynamic scope.
+//!
+//! [`JsonSchema`]: crate::json_schema::JsonSchema
+
+use super::error::{OrderedF64, ValidationError, ValidationResult};
+use super::json_equality::{duplicate_index_pairs, json_equal};
+use super::{json_type_name, value_to_display_string};
+use crate::json_pointer::JsonPointer;
+use crate::json_schema::SpecVersion;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Bundled meta-schema documents, keyed by their `$id` (or `id`) without a trailing `#`.
+const BUNDLED: &[(&str, &str)] = &[
+    (
+        "http://json-schema.org/draft-04/schema",
+        include_str!("../../meta_schemas/draft-04/schema.json"),
+    ),
+    (
+        "http://json-schema.org/draft-06/schema",
+        include_str!("../../meta_schemas/draft-06/schema.json"),
+    ),
+    (
+        "http://json-schema.org/draft-07/schema",
+        include_str!("../../meta_schemas/draft-07/schema.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/schema",
+        include_str!("../../meta_schemas/draft-2019-09/schema.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/core",
+        include_str!("../../meta_schemas/draft-2019-09/meta/core.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/applicator",
+        include_str!("../../meta_schemas/draft-2019-09/meta/applicator.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/validation",
+        include_str!("../../meta_schemas/draft-2019-09/meta/validation.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/meta-data",
+        include_str!("../../meta_schemas/draft-2019-09/meta/meta-data.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/format",
+        include_str!("../../meta_schemas/draft-2019-09/meta/format.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2019-09/meta/content",
+        include_str!("../../meta_schemas/draft-2019-09/meta/content.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/schema",
+        include_str!("../../meta_schemas/draft-2020-12/schema.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/core",
+        include_str!("../../meta_schemas/draft-2020-12/meta/core.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/applicator",
+        include_str!("../../meta_schemas/draft-2020-12/meta/applicator.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/unevaluated",
+        include_str!("../../meta_schemas/draft-2020-12/meta/unevaluated.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/validation",
+        include_str!("../../meta_schemas/draft-2020-12/meta/validation.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/meta-data",
+        include_str!("../../meta_schemas/draft-2020-12/meta/meta-data.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/format-annotation",
+        include_str!("../../meta_schemas/draft-2020-12/meta/format-annotation.json"),
+    ),
+    (
+        "https://json-schema.org/draft/2020-12/meta/content",
+        include_str!("../../meta_schemas/draft-2020-12/meta/content.json"),
+    ),
+];
+
+/// Parsed bundled documents, keyed like [`BUNDLED`]. Parsed once per process.
+fn documents() -> &'static HashMap<&'static str, Value> {
+    static DOCUMENTS: OnceLock<HashMap<&'static str, Value>> = OnceLock::new();
+    DOCUMENTS.get_or_init(|| {
+        BUNDLED
+            .iter()
+            .map(|(uri, text)| {
+                let doc: Value =
+                    serde_json::from_str(text).expect("bundled meta-schema is valid JSON");
+                (*uri, doc)
+            })
+            .collect()
+    })
+}
+
+/// Key of the bundled entry meta-schema for `spec`. Draft-05 only clarified draft-04's wording
+/// and published no meta-schema of its own, so it uses draft-04's. Drafts 00–03 have none.
+fn entry_uri(spec: SpecVersion) -> Option<&'static str> {
+    match spec {
+        SpecVersion::Draft04 | SpecVersion::Draft05 => {
+            Some("http://json-schema.org/draft-04/schema")
+        }
+        SpecVersion::Draft06 => Some("http://json-schema.org/draft-06/schema"),
+        SpecVersion::Draft07 => Some("http://json-schema.org/draft-07/schema"),
+        SpecVersion::Draft201909 => Some("https://json-schema.org/draft/2019-09/schema"),
+        SpecVersion::Draft202012 => Some("https://json-schema.org/draft/2020-12/schema"),
+        SpecVersion::Draft00
+        | SpecVersion::Draft01
+        | SpecVersion::Draft02
+        | SpecVersion::Draft03 => None,
+    }
+}
+
+/// The bundled official meta-schema for `spec`, or `None` for drafts 00–03.
+#[must_use]
+pub(crate) fn meta_schema(spec: SpecVersion) -> Option<&'static Value> {
+    entry_uri(spec).and_then(|uri| documents().get(uri))
+}
+
+/// Validates `document` (a raw schema) against the bundled meta-schema for `spec`. Error paths
+/// point into `document`.
+pub(crate) fn validate_against_meta_schema(
+    document: &Value,
+    spec: SpecVersion,
+) -> ValidationResult {
+    let Some(entry) = entry_uri(spec) else {
+        return Err(vec![ValidationError::UnsupportedMetaSchema {
+            instance_path: JsonPointer::root(),
+            schema_uri: spec.schema_uri().to_string(),
+        }]);
+    };
+    let Some(schema) = documents().get(entry) else {
+        return Ok(());
+    };
+    let mut errors: Vec<ValidationError> =
+        evaluate_subschema(entry, entry, schema, document, &JsonPointer::root());
+    // The 2019-09+ vocabulary meta-schemas repeat checks such as `type`; report each failure once.
+    let mut seen: Vec<ValidationError> = Vec::with_capacity(errors.len());
+    errors.retain(|e| {
+        if seen.contains(e) {
+            false
+        } else {
+            seen.push(e.clone());
+            true
+        }
+    });
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Resolves `reference` against the document `base` to (document key, subschema). Supports the
+/// forms the bundled meta-schemas use: `#`, `#/json/pointer`, and relative or absolute URIs of
+/// other bundled documents with an optional pointer fragment.
+fn resolve(base: &'static str, reference: &str) -> Option<(&'static str, &'static Value)> {
+    let (uri, fragment): (&str, &str) = reference.split_once('#').unwrap_or((reference, ""));
+    let target: String = if uri.is_empty() {
+        base.to_string()
+    } else if uri.contains("://") {
+        uri.to_string()
+    } else {
+        let dir: &str = base.rsplit_once('/').map_or(base, |(dir, _)| dir);
+        format!("{dir}/{uri}")
+    };
+    let (key, doc): (&&'static str, &'static Value) = documents().get_key_value(target.as_str())?;
+    let schema: &'static Value = if fragment.is_empty() {
+        doc
+    } else {
+        doc.pointer(fragment)?
+    };
+    Some((key, schema))
+}
+
+/// One pending check: `schema` (from bundled document `base`) applied to `instance` at `path`.
+struct Frame<'a> {
+    schema: &'static Value,
+    base: &'static str,
+    instance: &'a Value,
+    path: JsonPointer,
+}
+
+/// Evaluates the subschema `schema` of bundled document `base` against `instance`, collecting
+/// errors into a fresh list. Iterative except for `anyOf`/`oneOf` branches and `propertyNames`,
+/// which need their own error lists.
+fn evaluate_subschema(
+    entry: &'static str,
+    base: &'static str,
+    schema: &'static Value,
+    instance: &Value,
+    path: &JsonPointer,
+) -> Vec<ValidationError> {
+    let mut errors: Vec<ValidationError> = Vec::new();
+    let mut stack: Vec<Frame<'_>> = vec![Frame {
+        schema,
+        base,
+        instance,
+        path: path.clone(),
+    }];
+    while let Some(frame) = stack.pop() {
+        let Some(keywords) = frame.schema.as_object() else {
+            continue;
+        };
+        evaluate_object(entry, &frame, keywords, &mut stack, &mut errors);
+    }
+    errors
+}
+
+/// True when `error` is a type mismatch located exactly at `path`.
+fn is_type_error_at(error: &ValidationError, path: &JsonPointer) -> bool {
+    matches!(
+        error,
+        ValidationError::ExpectedObject { .. }
+            | ValidationError::ExpectedString { .. }
+            | ValidationError::ExpectedInteger { .. }
+            | ValidationError::ExpectedNumber { .. }
+            | ValidationError::ExpectedArray { .. }
+            | ValidationError::ExpectedBoolean { .. }
+            | ValidationError::ExpectedType { .. }
+    ) && error.instance_path() == path
+}
+
+/// True when `instance` has JSON Schema type `type_name` (integers are numbers without a
+/// fractional part).
+fn has_type(instance: &Value, type_name: &str) -> bool {
+    match type_name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+/// The error for `instance` not having any of `types`, using the single-type variants when
+/// there is exactly one.
+fn type_error(types: &[&str], instance: &Value, path: &JsonPointer) -> ValidationError {
+    let instance_path: JsonPointer = path.clone();
+    let got: String = json_type_name(instance).to_string();
+    match types {
+        ["object"] => ValidationError::ExpectedObject { instance_path, got },
+        ["string"] => ValidationError::ExpectedString { instance_path, got },
+        ["integer"] => ValidationError::ExpectedInteger { instance_path, got },
+        ["number"] => ValidationError::ExpectedNumber { instance_path, got },
+        ["array"] => ValidationError::ExpectedArray { instance_path, got },
+        ["boolean"] => ValidationError::ExpectedBoolean { instance_path, got },
+        _ => ValidationError::ExpectedType {
+            instance_path,
+            expected: types.iter().map(ToString::to_string).collect(),
+            got,
+        },
+    }
+}
+
+#[expect(clippy::too_many_lines)]
+fn evaluate_object<'a>(
+    entry: &'static str,
+    frame: &Frame<'a>,
+    keywords: &'static Map<String, Value>,
+    stack: &mut Vec<Frame<'a>>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let instance: &'a Value = frame.instance;
+    let path: &JsonPointer = &frame.path;
+    let push = |stack: &mut Vec<Frame<'a>>, base: &'static str, schema: &'static Value| {
+        stack.push(Frame {
+            schema,
+            base,
+            instance,
+            path: path.clone(),
+        });
+    };
+
+    if let Some(reference) = keywords.get("$ref").and_then(Value::as_str) {
+        match resolve(frame.base, reference) {
+            Some((base, schema)) => push(stack, base, schema),
+            None => errors.push(ValidationError::InvalidRef {
+                instance_path: path.clone(),
+                ref_str: reference.to_string(),
+                reason: "not a bundled meta-schema location".to_string(),
+            }),
+        }
+    }
+    if (keywords.contains_key("$recursiveRef") || keywords.contains_key("$dynamicRef"))
+        && let Some(schema) = documents().get(entry)
+    {
+        push(stack, entry, schema);
+    }
+    if let Some(all_of) = keywords.get("allOf").and_then(Value::as_array) {
+        for subschema in all_of.iter().rev() {
+            push(stack, frame.base, subschema);
+        }
+    }
+    if let Some(any_of) = keywords.get("anyOf").and_then(Value::as_array) {
+        let branches: Vec<Vec<ValidationError>> = any_of
+            .iter()
+            .map(|s| evaluate_subschema(entry, frame.base, s, instance, path))
+            .collect();
+        if !branches.iter().any(Vec::is_empty) {
+            // Report the one branch whose type fits the instance, if any, since its errors say
+            // what is actually wrong; otherwise the instance fits none of the alternatives.
+            let fitting: Vec<&Vec<ValidationError>> = branches
+                .iter()
+                .filter(|b| !b.iter().any(|e| is_type_error_at(e, path)))
+                .collect();
+            if let [only] = fitting.as_slice() {
+                errors.extend(only.iter().cloned());
+            } else {
+                errors.push(ValidationError::NoSubschemaMatched {
+                    instance_path: path.clone(),
+                    subschema_count: any_of.len(),
+                });
+            }
+        }
+    }
+    if let Some(one_of) = keywords.get("oneOf").and_then(Value::as_array) {
+        let match_count: usize = one_of
+            .iter()
+            .filter(|s| evaluate_subschema(entry, frame.base, s, instance, path).is_empty())
+            .count();
+        if match_count == 0 {
+            errors.push(ValidationError::NoSubschemaMatched {
+                instance_path: path.clone(),
+                subschema_count: one_of.len(),
+            });
+        } else if match_count > 1 {
+            errors.push(ValidationError::MultipleSubschemasMatched {
+                instance_path: path.clone(),
+                subschema_count: one_of.len(),
+                match_count,
+            });
+        }
+    }
+
+    if let Some(type_value) = keywords.get("type") {
+        let types: Vec<&str> = match type_value {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(instance, t)) {
+            errors.push(type_error(&types, instance, path));
+            return;
+        }
+    }
+    if let Some(allowed) = keywords.get("enum").and_then(Value::as_array)
+        && !allowed.iter().any(|a| json_equal(a, instance))
+    {
+        errors.push(ValidationError::NotInEnum {
+            instance_path: path.clone(),
+            invalid_value: value_to_display_string(instance),
+            allowed: allowed.iter().map(value_to_display_string).collect(),
+        });
+    }
+    if let Some(expected) = keywords.get("const")
+        && !json_equal(expected, instance)
+    {
+        errors.push(ValidationError::NotConst {
+            instance_path: path.clone(),
+            expected: value_to_display_string(expected),
+            actual: value_to_display_string(instance),
+        });
+    }
+
+    if let Some(actual) = instance.as_f64() {
+        let minimum: Option<f64> = keywords.get("minimum").and_then(Value::as_f64);
+        // Draft-04 `exclusiveMinimum` is a boolean that makes `minimum` exclusive.
+        let exclusive_minimum: Option<f64> = match keywords.get("exclusiveMinimum") {
+            Some(Value::Bool(true)) => minimum,
+            Some(v) => v.as_f64(),
+            None => None,
+        };
+        if let Some(min) = minimum
+            && actual < min
+        {
+            errors.push(ValidationError::BelowMinimum {
+                instance_path: path.clone(),
+                minimum: OrderedF64(min),
+                actual: OrderedF64(actual),
+            });
+        } else if let Some(min) = exclusive_minimum
+            && actual <= min
+        {
+            errors.push(ValidationError::NotAboveExclusiveMinimum {
+                instance_path: path.clone(),
+                exclusive_minimum: OrderedF64(min),
+                actual: OrderedF64(actual),
+            });
+        }
+    }
+
+    if let Some(s) = instance.as_str()
+        && let Some(pattern) = keywords.get("pattern").and_then(Value::as_str)
+        && let Ok(re) = regress::Regex::new(pattern)
+        && re.find(s).is_none()
+    {
+        errors.push(ValidationError::PatternMismatch {
+            instance_path: path.clone(),
+            pattern: pattern.to_string(),
+            value: s.to_string(),
+        });
+    }
+
+    if let Some(items) = instance.as_array() {
+        if let Some(min_items) = keywords.get("minItems").and_then(Value::as_u64) {
+            let actual_count: u64 = items.len() as u64;
+            if actual_count < min_items {
+                errors.push(ValidationError::TooFewItems {
+                    instance_path: path.clone(),
+                    min_items,
+                    actual_count,
+                });
+            }
+        }
+        if keywords.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (first_index, duplicate_index) in duplicate_index_pairs(items) {
+                errors.push(ValidationError::DuplicateArrayItems {
+                    instance_path: path.clone(),
+                    first_index,
+                    duplicate_index,
+                    duplicate_value: value_to_display_string(&items[duplicate_index]),
+                });
+            }
+        }
+        if let Some(items_schema) = keywords.get("items").filter(|s| s.is_object()) {
+            for (i, item) in items.iter().enumerate().rev() {
+                stack.push(Frame {
+                    schema: items_schema,
+                    base: frame.base,
+                    instance: item,
+                    path: path.push(&i.to_string()),
+                });
+            }
+        }
+    }
+
+    if let Some(object) = instance.as_object() {
+        if let Some(required) = keywords.get("required").and_then(Value::as_array) {
+            for property in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(property) {
+                    errors.push(ValidationError::MissingRequired {
+                        instance_path: path.clone(),
+                        property: property.to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(dependencies) = keywords.get("dependencies").and_then(Value::as_object) {
+            for (property, dependency) in dependencies {
+                if !object.contains_key(property) {
+                    continue;
+                }
+                match dependency {
+                    Value::Array(names) => {
+                        for name in names.iter().filter_map(Value::as_str) {
+                            if !object.contains_key(name) {
+                                errors.push(ValidationError::MissingRequired {
+                                    instance_path: path.clone(),
+                                    property: name.to_string(),
+                                });
+                            }
+                        }
+                    }
+                    schema => push(stack, frame.base, schema),
+                }
+            }
+        }
+        if let Some(property_names) = keywords.get("propertyNames") {
+            for key in object.keys() {
+                let name: Value = Value::String(key.clone());
+                errors.extend(evaluate_subschema(
+                    entry,
+                    frame.base,
+                    property_names,
+                    &name,
+                    &path.push(key),
+                ));
+            }
+        }
+        let properties: Option<&Map<String, Value>> =
+            keywords.get("properties").and_then(Value::as_object);
+        let additional: Option<&'static Value> = keywords.get("additionalProperties");
+        for (key, value) in object.iter().rev() {
+            let subschema: Option<&'static Value> =
+                properties.and_then(|p| p.get(key)).or(additional);
+            match subschema {
+                Some(Value::Bool(false)) => {
+                    errors.push(ValidationError::DisallowedAdditionalProperty {
+                        instance_path: path.push(key),
+                        property: key.clone(),
+                    });
+                }
+                Some(schema) => stack.push(Frame {
+                    schema,
+                    base: frame.base,
+                    instance: value,
+                    path: path.push(key),
+                }),
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUNDLED, documents, meta_schema, validate_against_meta_schema};
+    use crate::json_pointer::JsonPointer;
+    use crate::json_schema::SpecVersion;
+    use crate::validator::error::{OrderedF64, ValidationError, ValidationResult};
+    use serde_json::json;
+
+    const CHECKED_DRAFTS: [SpecVersion; 5] = [
+        SpecVersion::Draft04,
+        SpecVersion::Draft06,
+        SpecVersion::Draft07,
+        SpecVersion::Draft201909,
+        SpecVersion::Draft202012,
+    ];
+
+    fn pointer(s: &str) -> JsonPointer {
+        JsonPointer::try_from(s).expect("valid pointer")
+    }
+
+    #[test]
+    fn bundled_documents_are_keyed_by_their_id() {
+        for (uri, _) in BUNDLED {
+            let doc = &documents()[uri];
+            let id: &str = doc
+                .get("$id")
+                .or_else(|| doc.get("id"))
+                .and_then(|v| v.as_str())
+                .expect("meta-schema has an id");
+            assert_eq!(*uri, id.trim_end_matches('#'));
+        }
+    }
+
+    #[test]
+    fn every_meta_schema_validates_against_itself() {
+        for spec in CHECKED_DRAFTS {
+            let doc = meta_schema(spec).expect("bundled");
+            let actual: ValidationResult = validate_against_meta_schema(doc, spec);
+            assert_eq!(Ok(()), actual, "{spec:?}");
+        }
+    }
+
+    #[test]
+    fn valid_schema_passes_every_draft() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1, "pattern": "^[a-z]+$"},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+            },
+            "required": ["name"],
+            "additionalProperties": false
+        });
+        for spec in CHECKED_DRAFTS {
+            let actual: ValidationResult = validate_against_meta_schema(&schema, spec);
+            assert_eq!(Ok(()), actual, "{spec:?}");
+        }
+    }
+
+    #[test]
+    fn negative_min_length_reports_below_minimum_at_keyword() {
+        let schema = json!({"properties": {"name": {"type": "string", "minLength": -1}}});
+        let expected: ValidationResult = Err(vec![ValidationError::BelowMinimum {
+            instance_path: pointer("/properties/name/minLength"),
+            minimum: OrderedF64(0.0),
+            actual: OrderedF64(-1.0),
+        }]);
+        for spec in CHECKED_DRAFTS {
+            let actual: ValidationResult = validate_against_meta_schema(&schema, spec);
+            assert_eq!(expected, actual, "{spec:?}");
+        }
+    }
+
+    #[test]
+    fn misspelled_type_reports_allowed_type_names() {
+        let schema = json!({"type": "strng"});
+        let actual: ValidationResult =
+            validate_against_meta_schema(&schema, SpecVersion::Draft202012);
+        let errors: Vec<ValidationError> = actual.expect_err("invalid type name");
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        let ValidationError::NotInEnum {
+            instance_path,
+            invalid_value,
+            allowed,
+        } = &errors[0]
+        else {
+            panic!("expected NotInEnum, got {errors:?}");
+        };
+        assert_eq!(instance_path, &pointer("/type"));
+        assert_eq!(invalid_value, "\"strng\"");
+        assert!(allowed.contains(&"\"string\"".to_string()));
+    }
+
+    #[test]
+    fn wrong_keyword_type_reports_expected_type() {
+        let schema = json!({"properties": {"a": {"required": "yes"}}});
+        let expected: ValidationResult = Err(vec![ValidationError::ExpectedArray {
+            instance_path: pointer("/properties/a/required"),
+            got: "string".to_string(),
+        }]);
+        let actual: ValidationResult =
+            validate_against_meta_schema(&schema, SpecVersion::Draft202012);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn nested_subschema_must_be_object_or_boolean() {
+        let schema = json!({"items": 5});
+        let expected: ValidationResult = Err(vec![ValidationError::ExpectedType {
+            instance_path: pointer("/items"),
+            expected: vec!["object".to_string(), "boolean".to_string()],
+            got: "number".to_string(),
+        }]);
+        let actual: ValidationResult =
+            validate_against_meta_schema(&schema, SpecVersion::Draft202012);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn draft_04_exclusive_multiple_of() {
+        let schema = json!({"multipleOf": 0});
+        let expected: ValidationResult = Err(vec![ValidationError::NotAboveExclusiveMinimum {
+            instance_path: pointer("/multipleOf"),
+            exclusive_minimum: OrderedF64(0.0),
+            actual: OrderedF64(0.0),
+        }]);
+        let actual: ValidationResult = validate_against_meta_schema(&schema, SpecVersion::Draft04);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn draft_04_exclusive_minimum_requires_minimum() {
+        let schema = json!({"exclusiveMinimum": true});
+        let expected: ValidationResult = Err(vec![ValidationError::MissingRequired {
+            instance_path: JsonPointer::root(),
+            property: "minimum".to_string(),
+        }]);
+        let actual: ValidationResult = validate_against_meta_schema(&schema, SpecVersion::Draft04);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn duplicate_required_entries_reported() {
+        let schema = json!({"required": ["a", "a"]});
+        let expected: ValidationResult = Err(vec![ValidationError::DuplicateArrayItems {
+            instance_path: pointer("/required"),
+            first_index: 0,
+            duplicate_index: 1,
+            duplicate_value: "\"a\"".to_string(),
+        }]);
+        let actual: ValidationResult = validate_against_meta_schema(&schema, SpecVersion::Draft07);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn errors_in_defs_use_dynamic_ref_to_full_meta_schema() {
+        let schema = json!({"$defs": {"a": {"maxLength": "ten"}}});
+        let expected: ValidationResult = Err(vec![ValidationError::ExpectedInteger {
+            instance_path: pointer("/$defs/a/maxLength"),
+            got: "string".to_string(),
+        }]);
+        for spec in [SpecVersion::Draft201909, SpecVersion::Draft202012] {
+            let actual: ValidationResult = validate_against_meta_schema(&schema, spec);
+            assert_eq!(expected, actual, "{spec:?}");
+        }
+    }
+
+    #[test]
+    fn anchor_pattern_checked() {
+        let schema = json!({"$anchor": "1bad"});
+        let actual: ValidationResult =
+            validate_against_meta_schema(&schema, SpecVersion::Draft202012);
+        let errors: Vec<ValidationError> = actual.expect_err("invalid anchor");
+        assert!(
+            errors
+                .iter()
+                .all(|e| e.instance_path() == &pointer("/$anchor")),
+            "{errors:?}"
+        );
+        assert!(
+            matches!(errors[0], ValidationError::PatternMismatch { .. }),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn boolean_schemas_accepted_from_draft_06() {
+        let schema = json!({"properties": {"a": true, "b": false}});
+        let actual: ValidationResult = validate_against_meta_schema(&schema, SpecVersion::Draft07);
+        assert_eq!(Ok(()), actual);
+        let actual: ValidationResult = validate_against_meta_schema(&schema, SpecVersion::Draft04);
+        assert!(actual.is_err(), "draft-04 has no boolean schemas");
+    }
+
+    #[test]
+    fn drafts_before_04_are_unsupported() {
+        let expected: ValidationResult = Err(vec![ValidationError::UnsupportedMetaSchema {
+            instance_path: JsonPointer::root(),
+            schema_uri: "http://json-schema.org/draft-03/schema#".to_string(),
+        }]);
+        let actual: ValidationResult =
+            validate_against_meta_schema(&json!({}), SpecVersion::Draft03);
+        assert_eq!(expected, actual);
+    }
+}
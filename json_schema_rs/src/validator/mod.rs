@@ -3,12 +3,22 @@
 //! Collects every validation error (no fail-fast) and returns them in a single result.
 
 mod error;
+pub(crate) mod formats;
+mod json_equality;
+mod keyword;
+pub(crate) mod meta_schema;
+mod settings;
 pub use error::{OrderedF64, ValidationError, ValidationResult};
+pub use keyword::{Keyword, KeywordRegistration};
+pub use settings::{
+    FormatChecker, FormatMode, UnknownFormatMode, ValidatorSettings, ValidatorSettingsBuilder,
+};
 
 use crate::json_pointer::JsonPointer;
 use crate::json_schema::JsonSchema;
 use crate::json_schema::json_schema::AdditionalProperties;
 use crate::json_schema::ref_resolver;
+use keyword::KeywordState;
 use serde_json::Value;
 
 /// Returns the JSON type name of the value for use in "got" error messages.
@@ -51,14 +61,100 @@ fn value_to_display_string(v: &Value) -> String {
 /// assert!(result.is_ok());
 /// ```
 pub fn validate(schema: &JsonSchema, instance: &Value) -> ValidationResult {
-    validate_with_root(schema, schema, instance)
+    validate_with_settings(schema, instance, &ValidatorSettings::default())
 }
 
-#[expect(clippy::too_many_lines)]
-fn validate_with_root(
+/// Validates a JSON instance against a schema with the given [`ValidatorSettings`].
+///
+/// Same as [`validate`], which uses [`ValidatorSettings::default()`].
+///
+/// # Errors
+///
+/// Returns `Err(errors)` when the instance does not conform to the schema, with
+/// one or more [`ValidationError`] values describing each failure.
+pub fn validate_with_settings(
+    schema: &JsonSchema,
+    instance: &Value,
+    settings: &ValidatorSettings,
+) -> ValidationResult {
+    let mut keywords: KeywordState<'_> =
+        KeywordState::new(schema, &settings.keywords, &settings.meta_schemas)?;
+    validate_with_root(schema, schema, instance, settings, &mut keywords)
+}
+
+/// Validates `instance` against `schema`, a subschema of `root`; `$ref`s resolve against `root`.
+/// Used by the linter to check `default` values in place.
+pub(crate) fn validate_subschema(
     root: &JsonSchema,
     schema: &JsonSchema,
     instance: &Value,
+    settings: &ValidatorSettings,
+) -> ValidationResult {
+    let mut keywords: KeywordState<'_> =
+        KeywordState::new(root, &settings.keywords, &settings.meta_schemas)?;
+    validate_with_root(root, schema, instance, settings, &mut keywords)
+}
+
+/// Checks a string instance against the schema's `format` when formats are asserted.
+/// Registered custom formats take precedence over built-in ones; unknown formats are ignored
+/// unless [`UnknownFormatMode::Error`] is selected.
+fn validate_format(
+    schema: &JsonSchema,
+    instance: &Value,
+    instance_path: &JsonPointer,
+    settings: &ValidatorSettings,
+    errors: &mut Vec<ValidationError>,
+) {
+    if settings.format_mode == FormatMode::Annotation {
+        return;
+    }
+    let (Some(format), Some(s)) = (schema.format.as_deref(), instance.as_str()) else {
+        return;
+    };
+    let custom: Option<&FormatChecker> = settings.custom_formats.get(format);
+    let known: bool = custom.is_some()
+        || formats::BUILT_IN_FORMATS.contains(&format)
+        || (cfg!(feature = "uuid") && format == "uuid");
+    if !known {
+        if settings.unknown_format_mode == UnknownFormatMode::Error {
+            errors.push(ValidationError::UnknownFormat {
+                instance_path: instance_path.clone(),
+                format: format.to_string(),
+            });
+        }
+        return;
+    }
+    let valid: bool = if let Some(custom) = custom {
+        custom.check(s)
+    } else {
+        #[cfg(feature = "uuid")]
+        if format == "uuid" {
+            if uuid::Uuid::parse_str(s).is_err() {
+                errors.push(ValidationError::InvalidUuidFormat {
+                    instance_path: instance_path.clone(),
+                    value: s.to_string(),
+                });
+            }
+            return;
+        }
+        formats::check_format(format, s) != Some(false)
+    };
+    if !valid {
+        errors.push(ValidationError::InvalidFormat {
+            instance_path: instance_path.clone(),
+            format: format.to_string(),
+            value: s.to_string(),
+        });
+    }
+}
+
+#[expect(clippy::too_many_lines)]
+fn validate_with_root<'a>(
+    root: &'a JsonSchema,
+    schema: &'a JsonSchema,
+    instance: &Value,
+    settings: &ValidatorSettings,
+    keywords: &mut KeywordState<'a>,
 ) -> ValidationResult {
     let mut errors: Vec<ValidationError> = Vec::new();
     let mut stack: Vec<(&JsonSchema, &Value, JsonPointer)> = Vec::new();
@@ -81,6 +177,7 @@ fn validate_with_root(
             }
         };
 
+        keywords.validate(schema, instance, &instance_path, &mut errors);
         if let Some(ref expected) = schema.const_value
             && instance != expected
         {
@@ -116,7 +213,7 @@ fn validate_with_root(
                 let mut at_least_one_passed: bool = false;
                 for subschema in any_of {
                     let sub_result: ValidationResult =
-                        validate_with_root(root, subschema, instance);
+                        validate_with_root(root, subschema, instance, settings, keywords);
                     if sub_result.is_ok() {
                         at_least_one_passed = true;
                         break;
@@ -141,7 +238,7 @@ fn validate_with_root(
                 let mut pass_count: usize = 0;
                 for subschema in one_of {
                     let sub_result: ValidationResult =
-                        validate_with_root(root, subschema, instance);
+                        validate_with_root(root, subschema, instance, settings, keywords);
                     if sub_result.is_ok() {
                         pass_count += 1;
                     }
@@ -203,7 +300,7 @@ fn validate_with_root(
                 // additionalProperties: keys not in properties are "additional"
                 let additional_keys: Vec<&str> = obj
                     .keys()
-                    .filter(|k| !schema.properties.contains_key(*k))
+                    .filter(|k| !schema.properties.contains_key(k))
                     .map(String::as_str)
                     .collect();
                 if !additional_keys.is_empty() {
@@ -276,17 +373,7 @@ fn validate_with_root(
                         }
                     }
                 }
-                #[cfg(feature = "uuid")]
-                if schema.format.as_deref() == Some("uuid") {
-                    if let Some(s) = instance.as_str() {
-                        if uuid::Uuid::parse_str(s).is_err() {
-                            errors.push(ValidationError::InvalidUuidFormat {
-                                instance_path: instance_path.clone(),
-                                value: s.to_string(),
-                            });
-                        }
-                    }
-                }
+                validate_format(schema, instance, &instance_path, settings, &mut errors);
             }
             Some("integer") => {
                 let valid = instance.as_number().is_some_and(|n| n.as_i64().is_some());
@@ -376,22 +463,13 @@ fn validate_with_root(
                     });
                 }
                 if schema.unique_items == Some(true) {
-                    let mut duplicate_value_opt: Option<String> = None;
-                    for i in 0..arr.len() {
-                        for j in (i + 1)..arr.len() {
-                            if arr[i] == arr[j] {
-                                duplicate_value_opt = Some(value_to_display_string(&arr[i]));
-                                break;
-                            }
-                        }
-                        if duplicate_value_opt.is_some() {
-                            break;
-                        }
-                    }
-                    if let Some(duplicate_value) = duplicate_value_opt {
+                    for (first_index, duplicate_index) in json_equality::duplicate_index_pairs(arr)
+                    {
                         errors.push(ValidationError::DuplicateArrayItems {
                             instance_path: instance_path.clone(),
-                            duplicate_value,
+                            first_index,
+                            duplicate_index,
+                            duplicate_value: value_to_display_string(&arr[duplicate_index]),
                         });
                     }
                 }
@@ -414,7 +492,17 @@ fn validate_with_root(
                     });
                 }
             }
+            Some("null") => {
+                if !instance.is_null() {
+                    errors.push(ValidationError::ExpectedType {
+                        instance_path: instance_path.clone(),
+                        expected: vec!["null".to_string()],
+                        got: json_type_name(instance).to_string(),
+                    });
+                }
+            }
             None | Some(_) => {
+                validate_format(schema, instance, &instance_path, settings, &mut errors);
                 // Type absent or not enforced: validate required/properties when instance is object
                 if let Some(obj) = instance.as_object() {
                     if let Some(ref required) = schema.required {
@@ -451,18 +539,17 @@ fn validate_with_root(
 
 #[cfg(test)]
 mod tests {
-    use super::{OrderedF64, ValidationError, ValidationResult, validate};
+    use super::{
+        FormatMode, Keyword, OrderedF64, UnknownFormatMode, ValidationError, ValidationResult,
+        ValidatorSettings, validate, validate_with_settings,
+    };
     use crate::json_pointer::JsonPointer;
-    use crate::json_schema::JsonSchema;
     use crate::json_schema::json_schema::AdditionalProperties;
     use crate::json_schema::ref_resolver::RefResolutionError;
+    use crate::json_schema::{JsonSchema, PropertyMap};
     use serde_json::json;
-    use std::collections::BTreeMap;
 
-    fn schema_object_with_required(
-        required: Vec<&str>,
-        properties: BTreeMap<String, JsonSchema>,
-    ) -> JsonSchema {
+    fn schema_object_with_required(required: Vec<&str>, properties: PropertyMap) -> JsonSchema {
         JsonSchema {
             type_: Some("object".to_string()),
             properties,
@@ -551,7 +638,7 @@ mod tests {
     #[test]
     fn valid_object_with_required_and_properties() {
         let schema = schema_object_with_required(vec!["a"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -577,7 +664,7 @@ mod tests {
     #[test]
     fn missing_required_property() {
         let schema = schema_object_with_required(vec!["name"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "name".to_string(),
                 JsonSchema {
@@ -599,7 +686,7 @@ mod tests {
     #[test]
     fn additional_properties_absent_allows_extra_key() {
         let schema = schema_object_with_required(vec!["a"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -618,7 +705,7 @@ mod tests {
     #[test]
     fn additional_properties_false_rejects_one_extra_key() {
         let mut schema = schema_object_with_required(vec!["a"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -641,7 +728,7 @@ mod tests {
     #[test]
     fn additional_properties_false_rejects_multiple_extra_keys() {
         let mut schema = schema_object_with_required(vec!["a"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -684,7 +771,7 @@ mod tests {
     #[test]
     fn additional_properties_false_empty_object_valid() {
         let mut schema = schema_object_with_required(vec![], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -704,7 +791,7 @@ mod tests {
     #[test]
     fn additional_properties_false_only_known_keys_valid() {
         let mut schema = schema_object_with_required(vec!["a"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -730,7 +817,7 @@ mod tests {
         let schema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "a".to_string(),
                     JsonSchema {
@@ -758,7 +845,7 @@ mod tests {
         let schema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "a".to_string(),
                     JsonSchema {
@@ -787,7 +874,7 @@ mod tests {
                 JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "a".to_string(),
                             JsonSchema {
@@ -802,7 +889,7 @@ mod tests {
                 JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "b".to_string(),
                             JsonSchema {
@@ -829,14 +916,14 @@ mod tests {
             all_of: Some(vec![
                 JsonSchema {
                     type_: Some("object".to_string()),
-                    properties: BTreeMap::new(),
+                    properties: PropertyMap::new(),
                     ..Default::default()
                 },
                 JsonSchema {
                     type_: Some("object".to_string()),
                     required: Some(vec!["b".to_string()]),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "b".to_string(),
                             JsonSchema {
@@ -867,7 +954,7 @@ mod tests {
                     type_: Some("object".to_string()),
                     required: Some(vec!["a".to_string()]),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "a".to_string(),
                             JsonSchema {
@@ -883,7 +970,7 @@ mod tests {
                     type_: Some("object".to_string()),
                     required: Some(vec!["b".to_string()]),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "b".to_string(),
                             JsonSchema {
@@ -924,13 +1011,13 @@ mod tests {
             all_of: Some(vec![
                 JsonSchema {
                     type_: Some("object".to_string()),
-                    properties: BTreeMap::new(),
+                    properties: PropertyMap::new(),
                     ..Default::default()
                 },
                 JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut m = BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "s".to_string(),
                             JsonSchema {
@@ -1222,12 +1309,12 @@ mod tests {
         let schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "name".to_string(),
                     JsonSchema {
                         type_: Some("string".to_string()),
-                        properties: BTreeMap::new(),
+                        properties: PropertyMap::new(),
                         additional_properties: None,
                         required: None,
                         title: None,
@@ -1328,7 +1415,7 @@ mod tests {
     fn schema_with_comment_validates_same_as_without() {
         let schema_without: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1354,7 +1441,7 @@ mod tests {
         };
         let schema_with_comment: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1391,12 +1478,12 @@ mod tests {
     #[test]
     #[expect(clippy::too_many_lines)]
     fn schema_with_default_validates_same_as_without() {
-        let mut properties_without: BTreeMap<String, JsonSchema> = BTreeMap::new();
+        let mut properties_without = PropertyMap::new();
         properties_without.insert(
             "opt".to_string(),
             JsonSchema {
                 type_: Some("string".to_string()),
-                properties: BTreeMap::new(),
+                properties: PropertyMap::new(),
                 additional_properties: None,
                 required: None,
                 title: None,
@@ -1421,12 +1508,12 @@ mod tests {
                 ..Default::default()
             },
         );
-        let mut properties_with_default: BTreeMap<String, JsonSchema> = BTreeMap::new();
+        let mut properties_with_default = PropertyMap::new();
         properties_with_default.insert(
             "opt".to_string(),
             JsonSchema {
                 type_: Some("string".to_string()),
-                properties: BTreeMap::new(),
+                properties: PropertyMap::new(),
                 additional_properties: None,
                 required: None,
                 title: None,
@@ -1513,7 +1600,7 @@ mod tests {
     fn root_type_string_valid_nonempty() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1547,7 +1634,7 @@ mod tests {
     fn root_type_string_valid_empty() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1581,7 +1668,7 @@ mod tests {
     fn wrong_type_object_instead_of_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1618,7 +1705,7 @@ mod tests {
     fn wrong_type_number_instead_of_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1655,7 +1742,7 @@ mod tests {
     fn wrong_type_null_instead_of_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1692,7 +1779,7 @@ mod tests {
     fn wrong_type_boolean_instead_of_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1764,11 +1851,32 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn root_type_null() {
+        let schema: JsonSchema = JsonSchema {
+            type_: Some("null".to_string()),
+            ..Default::default()
+        };
+        let actual: (ValidationResult, ValidationResult) = (
+            validate(&schema, &json!(null)),
+            validate(&schema, &json!(0)),
+        );
+        let expected: (ValidationResult, ValidationResult) = (
+            Ok(()),
+            Err(vec![ValidationError::ExpectedType {
+                instance_path: JsonPointer::root(),
+                expected: vec!["null".to_string()],
+                got: "number".to_string(),
+            }]),
+        );
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn wrong_type_array_instead_of_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1919,7 +2027,7 @@ mod tests {
     fn const_with_enum_instance_equals_const_valid() {
         let schema: JsonSchema = JsonSchema {
             type_: None,
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -1956,7 +2064,7 @@ mod tests {
     fn const_with_enum_instance_not_const_not_const_error() {
         let schema: JsonSchema = JsonSchema {
             type_: None,
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2007,7 +2115,7 @@ mod tests {
     fn root_type_integer_valid_42() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2041,7 +2149,7 @@ mod tests {
     fn root_type_integer_valid_0() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2075,7 +2183,7 @@ mod tests {
     fn root_type_integer_valid_negative() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2109,7 +2217,7 @@ mod tests {
     fn root_type_integer_invalid_float() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2146,7 +2254,7 @@ mod tests {
     fn root_type_integer_invalid_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2183,7 +2291,7 @@ mod tests {
     fn root_type_integer_invalid_null() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2220,7 +2328,7 @@ mod tests {
     fn root_type_integer_invalid_object() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2257,7 +2365,7 @@ mod tests {
     fn root_type_integer_invalid_array() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2294,7 +2402,7 @@ mod tests {
     fn root_type_integer_invalid_bool() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2330,7 +2438,7 @@ mod tests {
     #[test]
     fn nested_property_type_integer_valid() {
         let schema = schema_object_with_required(vec!["count"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "count".to_string(),
                 JsonSchema {
@@ -2349,7 +2457,7 @@ mod tests {
     #[test]
     fn nested_property_type_integer_invalid_float() {
         let schema = schema_object_with_required(vec!["count"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "count".to_string(),
                 JsonSchema {
@@ -2371,7 +2479,7 @@ mod tests {
     #[test]
     fn nested_required_integer_missing() {
         let schema = schema_object_with_required(vec!["count"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "count".to_string(),
                 JsonSchema {
@@ -2394,7 +2502,7 @@ mod tests {
     fn root_type_number_valid_float() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2428,7 +2536,7 @@ mod tests {
     fn root_type_number_valid_integer() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2462,7 +2570,7 @@ mod tests {
     fn root_type_number_invalid_string() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2499,7 +2607,7 @@ mod tests {
     fn root_type_number_invalid_null() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2536,7 +2644,7 @@ mod tests {
     fn root_type_number_invalid_object() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2573,7 +2681,7 @@ mod tests {
     fn root_type_number_invalid_array() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2610,7 +2718,7 @@ mod tests {
     fn root_type_number_invalid_bool() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2647,7 +2755,7 @@ mod tests {
     fn integer_with_minimum_maximum_valid_in_range() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2681,7 +2789,7 @@ mod tests {
     fn integer_below_minimum() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2719,7 +2827,7 @@ mod tests {
     fn integer_above_maximum() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2757,7 +2865,7 @@ mod tests {
     fn integer_no_minimum_maximum_no_extra_errors() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("integer".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2791,7 +2899,7 @@ mod tests {
     fn number_with_minimum_maximum_valid_in_range() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2825,7 +2933,7 @@ mod tests {
     fn number_below_minimum() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2863,7 +2971,7 @@ mod tests {
     fn number_above_maximum() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("number".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -2903,12 +3011,12 @@ mod tests {
         let schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
-                    "low".to_string(),
+                    "high".to_string(),
                     JsonSchema {
                         type_: Some("integer".to_string()),
-                        properties: BTreeMap::new(),
+                        properties: PropertyMap::new(),
                         additional_properties: None,
                         required: None,
                         title: None,
@@ -2934,10 +3042,10 @@ mod tests {
                     },
                 );
                 m.insert(
-                    "high".to_string(),
+                    "low".to_string(),
                     JsonSchema {
                         type_: Some("integer".to_string()),
-                        properties: BTreeMap::new(),
+                        properties: PropertyMap::new(),
                         additional_properties: None,
                         required: None,
                         title: None,
@@ -3008,7 +3116,7 @@ mod tests {
     fn root_type_array_valid_empty() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3042,7 +3150,7 @@ mod tests {
     fn root_type_array_valid_non_empty() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3076,7 +3184,7 @@ mod tests {
     fn root_type_array_invalid_not_array() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3113,7 +3221,7 @@ mod tests {
     fn root_type_array_with_items_valid() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3139,7 +3247,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3173,7 +3281,7 @@ mod tests {
     fn root_type_array_with_items_invalid_element() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3199,7 +3307,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3236,7 +3344,7 @@ mod tests {
     fn unique_items_true_no_duplicates_valid() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3262,7 +3370,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3296,7 +3404,7 @@ mod tests {
     fn unique_items_true_duplicates_invalid() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3322,7 +3430,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3362,7 +3470,7 @@ mod tests {
     fn unique_items_false_duplicates_valid() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3388,7 +3496,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3418,11 +3526,99 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn unique_items_integer_and_integral_float_are_duplicates() {
+        let schema = JsonSchema {
+            type_: Some("array".to_string()),
+            unique_items: Some(true),
+            ..Default::default()
+        };
+        let instance = json!([1, 2, 1.0]);
+        let expected: ValidationResult = Err(vec![ValidationError::DuplicateArrayItems {
+            instance_path: JsonPointer::root(),
+            first_index: 0,
+            duplicate_index: 2,
+            duplicate_value: "1.0".to_string(),
+        }]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unique_items_objects_with_different_key_order_are_duplicates() {
+        let schema = JsonSchema {
+            type_: Some("array".to_string()),
+            unique_items: Some(true),
+            ..Default::default()
+        };
+        let instance = json!([{"a": 1, "b": 2}, {"b": 2, "a": 1}]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        let expected_pairs: Vec<(usize, usize)> = vec![(0, 1)];
+        let actual_pairs: Vec<(usize, usize)> = actual
+            .unwrap_err()
+            .iter()
+            .filter_map(|e| match e {
+                ValidationError::DuplicateArrayItems {
+                    first_index,
+                    duplicate_index,
+                    ..
+                } => Some((*first_index, *duplicate_index)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(expected_pairs, actual_pairs);
+    }
+
+    #[test]
+    fn unique_items_reports_every_duplicate_pair() {
+        let schema = JsonSchema {
+            type_: Some("array".to_string()),
+            unique_items: Some(true),
+            ..Default::default()
+        };
+        let instance = json!(["a", "b", "a", "b", "a"]);
+        let expected: ValidationResult = Err(vec![
+            ValidationError::DuplicateArrayItems {
+                instance_path: JsonPointer::root(),
+                first_index: 0,
+                duplicate_index: 2,
+                duplicate_value: r#""a""#.to_string(),
+            },
+            ValidationError::DuplicateArrayItems {
+                instance_path: JsonPointer::root(),
+                first_index: 1,
+                duplicate_index: 3,
+                duplicate_value: r#""b""#.to_string(),
+            },
+            ValidationError::DuplicateArrayItems {
+                instance_path: JsonPointer::root(),
+                first_index: 0,
+                duplicate_index: 4,
+                duplicate_value: r#""a""#.to_string(),
+            },
+        ]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unique_items_duplicate_display_includes_indices() {
+        let error = ValidationError::DuplicateArrayItems {
+            instance_path: JsonPointer::root(),
+            first_index: 0,
+            duplicate_index: 2,
+            duplicate_value: "1".to_string(),
+        };
+        let expected: &str = "root: array has duplicate items at indices 0 and 2 (value: 1)";
+        let actual: String = error.to_string();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn unique_items_absent_duplicates_valid() {
         let item_schema: JsonSchema = JsonSchema {
             type_: Some("string".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3448,7 +3644,7 @@ mod tests {
         };
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3482,7 +3678,7 @@ mod tests {
     fn unique_items_true_empty_array_valid() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3516,7 +3712,7 @@ mod tests {
     fn min_items_only_pass() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3550,7 +3746,7 @@ mod tests {
     fn min_items_only_fail() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3588,7 +3784,7 @@ mod tests {
     fn min_items_only_edge_len_equals_min() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3622,7 +3818,7 @@ mod tests {
     fn max_items_only_pass() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3656,7 +3852,7 @@ mod tests {
     fn max_items_only_fail() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3694,7 +3890,7 @@ mod tests {
     fn max_items_only_edge_len_equals_max() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3728,7 +3924,7 @@ mod tests {
     fn min_items_max_items_both_pass() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3762,7 +3958,7 @@ mod tests {
     fn min_items_max_items_fail_too_few() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3800,7 +3996,7 @@ mod tests {
     fn min_items_max_items_fail_too_many() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3838,7 +4034,7 @@ mod tests {
     fn min_items_max_items_absent_unchanged() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3872,7 +4068,7 @@ mod tests {
     fn min_items_max_items_not_array_expected_array_only() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("array".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -3908,7 +4104,7 @@ mod tests {
     #[test]
     fn nested_property_type_number_valid() {
         let schema = schema_object_with_required(vec!["value"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "value".to_string(),
                 JsonSchema {
@@ -3927,7 +4123,7 @@ mod tests {
     #[test]
     fn nested_property_type_number_invalid_string() {
         let schema = schema_object_with_required(vec!["value"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "value".to_string(),
                 JsonSchema {
@@ -3949,7 +4145,7 @@ mod tests {
     #[test]
     fn nested_required_number_missing() {
         let schema = schema_object_with_required(vec!["value"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "value".to_string(),
                 JsonSchema {
@@ -3972,7 +4168,7 @@ mod tests {
     fn wrong_type_object_with_number() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -4009,7 +4205,7 @@ mod tests {
     fn wrong_type_object_with_null() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -4046,7 +4242,7 @@ mod tests {
     fn wrong_type_object_with_array() {
         let schema: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
-            properties: BTreeMap::new(),
+            properties: PropertyMap::new(),
             additional_properties: None,
             required: None,
             title: None,
@@ -4084,7 +4280,7 @@ mod tests {
         let schema: JsonSchema = JsonSchema {
             type_: None,
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "name".to_string(),
                     JsonSchema {
@@ -4126,13 +4322,13 @@ mod tests {
     #[test]
     fn nested_object_validation() {
         let schema = schema_object_with_required(vec!["address"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "address".to_string(),
                 JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut inner = BTreeMap::new();
+                        let mut inner = PropertyMap::new();
                         inner.insert(
                             "city".to_string(),
                             JsonSchema {
@@ -4180,7 +4376,7 @@ mod tests {
     #[test]
     fn optional_property_absent_valid() {
         let schema = schema_object_with_required(vec![], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "opt".to_string(),
                 JsonSchema {
@@ -4199,7 +4395,7 @@ mod tests {
     #[test]
     fn multiple_failures_all_errors_returned() {
         let schema = schema_object_with_required(vec!["a", "b", "c"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -4245,7 +4441,7 @@ mod tests {
     #[test]
     fn multiple_failures_type_and_required_and_nested() {
         let schema = schema_object_with_required(vec!["x", "nested"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "x".to_string(),
                 JsonSchema {
@@ -4258,7 +4454,7 @@ mod tests {
                 JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut inner = BTreeMap::new();
+                        let mut inner = PropertyMap::new();
                         inner.insert(
                             "y".to_string(),
                             JsonSchema {
@@ -4315,7 +4511,7 @@ mod tests {
         let mut inner: JsonSchema = JsonSchema {
             type_: Some("object".to_string()),
             properties: {
-                let mut m = BTreeMap::new();
+                let mut m = PropertyMap::new();
                 m.insert(
                     "value".to_string(),
                     JsonSchema {
@@ -4331,7 +4527,7 @@ mod tests {
         for _ in 0..DEPTH {
             let mut wrap: JsonSchema = JsonSchema {
                 type_: Some("object".to_string()),
-                properties: BTreeMap::new(),
+                properties: PropertyMap::new(),
                 required: Some(vec!["child".to_string()]),
                 ..Default::default()
             };
@@ -4359,7 +4555,7 @@ mod tests {
     #[test]
     fn pointer_escaping() {
         let schema = schema_object_with_required(vec!["a/b"], {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a/b".to_string(),
                 JsonSchema {
@@ -4700,4 +4896,467 @@ mod tests {
         let actual: ValidationResult = validate(&schema, &instance);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn validate_date_time_format_valid() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","format":"date-time"}"#).unwrap();
+        let instance = json!("1998-12-31T23:59:60Z");
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_date_time_format_invalid() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","format":"date-time"}"#).unwrap();
+        let instance = json!("1990-02-31T15:59:59.123-08:00");
+        let expected: ValidationResult = Err(vec![ValidationError::InvalidFormat {
+            instance_path: JsonPointer::root(),
+            format: "date-time".to_string(),
+            value: "1990-02-31T15:59:59.123-08:00".to_string(),
+        }]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_date_format_without_type_keyword() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"date"}"#).unwrap();
+        let instance = json!("2021-02-29");
+        let expected: ValidationResult = Err(vec![ValidationError::InvalidFormat {
+            instance_path: JsonPointer::root(),
+            format: "date".to_string(),
+            value: "2021-02-29".to_string(),
+        }]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_format_ignores_non_string_instance() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"duration"}"#).unwrap();
+        let instance = json!(12);
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_format_unknown_is_ignored() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","format":"no-such-format"}"#).unwrap();
+        let instance = json!("anything");
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_time_and_duration_in_object_properties() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"at":{"type":"string","format":"time"},"for":{"type":"string","format":"duration"}}}"#,
+        )
+        .unwrap();
+        let instance = json!({"at": "23:59:60+01:00", "for": "P1D2H"});
+        let expected: ValidationResult = Err(vec![
+            ValidationError::InvalidFormat {
+                instance_path: JsonPointer::root().push("at"),
+                format: "time".to_string(),
+                value: "23:59:60+01:00".to_string(),
+            },
+            ValidationError::InvalidFormat {
+                instance_path: JsonPointer::root().push("for"),
+                format: "duration".to_string(),
+                value: "P1D2H".to_string(),
+            },
+        ]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_network_formats_in_object_properties() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"home":{"type":"string","format":"uri"},"ip":{"type":"string","format":"ipv4"},"mail":{"type":"string","format":"email"},"ptr":{"type":"string","format":"json-pointer"}}}"#,
+        )
+        .unwrap();
+        let instance = json!({
+            "home": "not a uri",
+            "ip": "256.1.1.1",
+            "mail": "joe.bloggs@example.com",
+            "ptr": "no-leading-slash"
+        });
+        let expected: ValidationResult = Err(vec![
+            ValidationError::InvalidFormat {
+                instance_path: JsonPointer::root().push("home"),
+                format: "uri".to_string(),
+                value: "not a uri".to_string(),
+            },
+            ValidationError::InvalidFormat {
+                instance_path: JsonPointer::root().push("ip"),
+                format: "ipv4".to_string(),
+                value: "256.1.1.1".to_string(),
+            },
+            ValidationError::InvalidFormat {
+                instance_path: JsonPointer::root().push("ptr"),
+                format: "json-pointer".to_string(),
+                value: "no-leading-slash".to_string(),
+            },
+        ]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_regex_format_invalid() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"regex"}"#).unwrap();
+        let instance = json!("^(abc]");
+        let expected: ValidationResult = Err(vec![ValidationError::InvalidFormat {
+            instance_path: JsonPointer::root(),
+            format: "regex".to_string(),
+            value: "^(abc]".to_string(),
+        }]);
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_format_annotation_mode_skips_format_checks() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","format":"date"}"#).unwrap();
+        let instance = json!("not-a-date");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .format_mode(FormatMode::Annotation)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_format_annotation_mode_applies_inside_any_of() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"anyOf":[{"type":"string","format":"date"}]}"#).unwrap();
+        let instance = json!("not-a-date");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .format_mode(FormatMode::Annotation)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn invalid_format_display() {
+        let error = ValidationError::InvalidFormat {
+            instance_path: JsonPointer::root().push("at"),
+            format: "date-time".to_string(),
+            value: "yesterday".to_string(),
+        };
+        let expected: &str = r#"/at: string "yesterday" is not a valid "date-time""#;
+        let actual: String = error.to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_format_invalid() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"v":{"type":"string","format":"semver"}}}"#,
+        )
+        .unwrap();
+        let instance = json!({"v": "1.2"});
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .custom_format("semver", |s: &str| {
+                let parts: Vec<&str> = s.split('.').collect();
+                parts.len() == 3 && parts.iter().all(|p| p.parse::<u64>().is_ok())
+            })
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::InvalidFormat {
+            instance_path: JsonPointer::root().push("v"),
+            format: "semver".to_string(),
+            value: "1.2".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_format_valid() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"iso-country"}"#).unwrap();
+        let instance = json!("NL");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .custom_format("iso-country", |s: &str| {
+                s.len() == 2 && s.bytes().all(|b| b.is_ascii_uppercase())
+            })
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_format_overrides_built_in() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"date"}"#).unwrap();
+        let instance = json!("2021-02-29");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .custom_format("date", |_: &str| true)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_format_skipped_in_annotation_mode() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"never"}"#).unwrap();
+        let instance = json!("x");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .format_mode(FormatMode::Annotation)
+            .custom_format("never", |_: &str| false)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_unknown_format_error_mode() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"string","format":"cron"}"#).unwrap();
+        let instance = json!("* * * * *");
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .unknown_format_mode(UnknownFormatMode::Error)
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::UnknownFormat {
+            instance_path: JsonPointer::root(),
+            format: "cron".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_unknown_format_error_mode_accepts_known_and_registered() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"a":{"format":"date"},"b":{"format":"cron"}}}"#,
+        )
+        .unwrap();
+        let instance = json!({"a": "2024-01-31", "b": "* * * * *"});
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .unknown_format_mode(UnknownFormatMode::Error)
+            .custom_format("cron", |s: &str| s.split_whitespace().count() == 5)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_unknown_format_error_mode_ignores_non_string_instance() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"format":"int32"}"#).unwrap();
+        let instance = json!(7);
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .unknown_format_mode(UnknownFormatMode::Error)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn unknown_format_display() {
+        let error = ValidationError::UnknownFormat {
+            instance_path: JsonPointer::root(),
+            format: "cron".to_string(),
+        };
+        let expected: &str = r#"root: unknown format "cron""#;
+        let actual: String = error.to_string();
+        assert_eq!(expected, actual);
+    }
+
+    /// Test keyword `x-sum-equals`: an array of numbers must sum to the keyword's value.
+    struct SumEquals(f64);
+
+    impl Keyword for SumEquals {
+        fn compile(value: &serde_json::Value) -> Result<Self, String> {
+            value
+                .as_f64()
+                .map(SumEquals)
+                .ok_or_else(|| "expected a number".to_string())
+        }
+
+        fn validate(
+            &self,
+            instance: &serde_json::Value,
+            instance_path: &JsonPointer,
+        ) -> Vec<ValidationError> {
+            let Some(arr) = instance.as_array() else {
+                return Vec::new();
+            };
+            let sum: f64 = arr.iter().filter_map(serde_json::Value::as_f64).sum();
+            if (sum - self.0).abs() < f64::EPSILON {
+                Vec::new()
+            } else {
+                vec![ValidationError::Custom {
+                    instance_path: instance_path.clone(),
+                    keyword: "x-sum-equals".to_string(),
+                    message: format!("items sum to {sum}, expected {}", self.0),
+                }]
+            }
+        }
+    }
+
+    const TEST_VOCABULARY: &str = "https://example.com/vocab/sums";
+    const TEST_META_SCHEMA: &str = "https://example.com/meta/sums";
+
+    #[test]
+    fn validate_custom_keyword_reports_custom_error_at_instance_path() {
+        let schema: JsonSchema = serde_json::from_str(
+            r#"{"type":"object","properties":{"parts":{"type":"array","x-sum-equals":10}}}"#,
+        )
+        .unwrap();
+        let instance = json!({"parts": [1, 2, 3]});
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .keyword::<SumEquals>("x-sum-equals")
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::Custom {
+            instance_path: JsonPointer::root().push("parts"),
+            keyword: "x-sum-equals".to_string(),
+            message: "items sum to 6, expected 10".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_keyword_passes() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"x-sum-equals":6}"#).unwrap();
+        let instance = json!([1, 2, 3]);
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .keyword::<SumEquals>("x-sum-equals")
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_unregistered_keyword_is_ignored() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"x-sum-equals":10}"#).unwrap();
+        let instance = json!([1, 2, 3]);
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate(&schema, &instance);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_keyword_invalid_schema_value() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"x-sum-equals":"ten"}"#).unwrap();
+        let instance = json!([1]);
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .keyword::<SumEquals>("x-sum-equals")
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::InvalidKeywordInSchema {
+            instance_path: JsonPointer::root(),
+            keyword: "x-sum-equals".to_string(),
+            reason: "expected a number".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_custom_keyword_applies_to_every_array_item() {
+        let schema: JsonSchema =
+            serde_json::from_str(r#"{"type":"array","items":{"type":"array","x-sum-equals":3}}"#)
+                .unwrap();
+        let instance = json!([[1, 2], [3], [4]]);
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .keyword::<SumEquals>("x-sum-equals")
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::Custom {
+            instance_path: JsonPointer::root().push("2"),
+            keyword: "x-sum-equals".to_string(),
+            message: "items sum to 4, expected 3".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_vocabulary_keyword_inactive_without_declaring_meta_schema() {
+        let schema: JsonSchema = serde_json::from_str(r#"{"x-sum-equals":10}"#).unwrap();
+        let instance = json!([1, 2, 3]);
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .vocabulary_keyword::<SumEquals>("x-sum-equals", TEST_VOCABULARY)
+            .build();
+        let expected: ValidationResult = Ok(());
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_vocabulary_keyword_active_when_meta_schema_declares_vocabulary() {
+        let schema: JsonSchema = serde_json::from_str(&format!(
+            r#"{{"$schema":"{TEST_META_SCHEMA}","x-sum-equals":10}}"#
+        ))
+        .unwrap();
+        let instance = json!([1, 2, 3]);
+        let meta_schema = json!({
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                TEST_VOCABULARY: false
+            }
+        });
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .vocabulary_keyword::<SumEquals>("x-sum-equals", TEST_VOCABULARY)
+            .meta_schema(TEST_META_SCHEMA, &meta_schema)
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::Custom {
+            instance_path: JsonPointer::root(),
+            keyword: "x-sum-equals".to_string(),
+            message: "items sum to 6, expected 10".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_meta_schema_requiring_unknown_vocabulary_is_refused() {
+        let schema: JsonSchema = serde_json::from_str(&format!(
+            r#"{{"$schema":"{TEST_META_SCHEMA}#","type":"array"}}"#
+        ))
+        .unwrap();
+        let instance = json!([]);
+        let meta_schema = json!({
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://example.com/vocab/unknown": true,
+                "https://example.com/vocab/optional": false
+            }
+        });
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .meta_schema(TEST_META_SCHEMA, &meta_schema)
+            .build();
+        let expected: ValidationResult = Err(vec![ValidationError::UnsupportedVocabulary {
+            instance_path: JsonPointer::root(),
+            vocabulary: "https://example.com/vocab/unknown".to_string(),
+        }]);
+        let actual: ValidationResult = validate_with_settings(&schema, &instance, &settings);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn custom_keyword_error_display() {
+        let error = ValidationError::Custom {
+            instance_path: JsonPointer::root().push("parts"),
+            keyword: "x-sum-equals".to_string(),
+            message: "items sum to 6, expected 10".to_string(),
+        };
+        let expected: &str = "/parts: x-sum-equals: items sum to 6, expected 10";
+        let actual: String = error.to_string();
+        assert_eq!(expected, actual);
+    }
 }
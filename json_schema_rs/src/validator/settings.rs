@@ -0,0 +1,220 @@
+//! Validator settings (format assertion vs annotation, custom formats and keywords, etc.).
+
+use super::keyword::{Keyword, KeywordRegistration, vocabularies_of};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// How the `format` keyword is treated during validation.
+///
+/// Draft 2020-12 splits `format` into two vocabularies: format-annotation (the spec's default
+/// vocabulary, where `format` is informational only) and format-assertion (where an instance
+/// that does not conform to a known format is invalid). This crate defaults to assertion, so
+/// `validate` rejects strings that do not match a built-in format such as `date-time`, `email`,
+/// or `ipv4`; select [`FormatMode::Annotation`] for the spec's default behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatMode {
+    /// Format-annotation vocabulary: `format` never produces validation errors.
+    Annotation,
+    /// Format-assertion vocabulary: known formats are checked and non-conforming strings are
+    /// errors. The default.
+    #[default]
+    Assertion,
+}
+
+/// What happens when a schema's `format` is neither built in nor registered as a custom format.
+/// Only consulted when formats are asserted ([`FormatMode::Assertion`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownFormatMode {
+    /// Unknown formats are ignored. Default.
+    #[default]
+    Ignore,
+    /// Unknown formats produce a [`ValidationError::UnknownFormat`](crate::ValidationError::UnknownFormat).
+    Error,
+}
+
+/// A user-supplied check for a custom `format`: returns true when the string conforms.
+///
+/// Cheap to clone (shared via [`Arc`]). Two checkers are equal only when they share the same
+/// underlying function.
+#[derive(Clone)]
+pub struct FormatChecker(Arc<dyn Fn(&str) -> bool + Send + Sync>);
+
+impl FormatChecker {
+    /// Wraps a function as a format checker.
+    #[must_use]
+    pub fn new(check: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(check))
+    }
+
+    /// Returns true when `value` conforms to the format.
+    #[must_use]
+    pub fn check(&self, value: &str) -> bool {
+        (self.0)(value)
+    }
+}
+
+impl fmt::Debug for FormatChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FormatChecker(..)")
+    }
+}
+
+impl PartialEq for FormatChecker {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for FormatChecker {}
+
+/// Settings that affect how instances are validated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorSettings {
+    /// Whether `format` is asserted or treated as an annotation only.
+    pub format_mode: FormatMode,
+    /// Whether unknown `format` names are ignored or reported as errors.
+    pub unknown_format_mode: UnknownFormatMode,
+    /// User-registered formats by name. A registered name takes precedence over a built-in
+    /// format of the same name.
+    pub custom_formats: BTreeMap<String, FormatChecker>,
+    /// User-registered keywords by name (see [`Keyword`]).
+    pub keywords: BTreeMap<String, KeywordRegistration>,
+    /// Registered meta-schemas: `$schema` URI → `$vocabulary` (vocabulary URI → required).
+    pub meta_schemas: BTreeMap<String, BTreeMap<String, bool>>,
+}
+
+/// Builder for [`ValidatorSettings`].
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorSettingsBuilder {
+    format_mode: Option<FormatMode>,
+    unknown_format_mode: Option<UnknownFormatMode>,
+    custom_formats: BTreeMap<String, FormatChecker>,
+    keywords: BTreeMap<String, KeywordRegistration>,
+    meta_schemas: BTreeMap<String, BTreeMap<String, bool>>,
+}
+
+impl ValidatorSettingsBuilder {
+    /// Set the format mode (annotation-only or assertion).
+    #[must_use]
+    pub fn format_mode(mut self, value: FormatMode) -> Self {
+        self.format_mode = Some(value);
+        self
+    }
+
+    /// Set whether unknown formats are ignored or reported as errors.
+    #[must_use]
+    pub fn unknown_format_mode(mut self, value: UnknownFormatMode) -> Self {
+        self.unknown_format_mode = Some(value);
+        self
+    }
+
+    /// Register a custom format. Registering the same name again replaces the earlier checker.
+    #[must_use]
+    pub fn custom_format(
+        mut self,
+        name: impl Into<String>,
+        check: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.custom_formats
+            .insert(name.into(), FormatChecker::new(check));
+        self
+    }
+
+    /// Register a custom keyword that is always active. Registering the same name again replaces
+    /// the earlier registration.
+    #[must_use]
+    pub fn keyword<K: Keyword + 'static>(mut self, name: impl Into<String>) -> Self {
+        self.keywords
+            .insert(name.into(), KeywordRegistration::new::<K>(None));
+        self
+    }
+
+    /// Register a custom keyword that belongs to a vocabulary. It is only active when the root
+    /// schema's `$schema` names a registered meta-schema whose `$vocabulary` lists `vocabulary`.
+    #[must_use]
+    pub fn vocabulary_keyword<K: Keyword + 'static>(
+        mut self,
+        name: impl Into<String>,
+        vocabulary: impl Into<String>,
+    ) -> Self {
+        self.keywords.insert(
+            name.into(),
+            KeywordRegistration::new::<K>(Some(vocabulary.into())),
+        );
+        self
+    }
+
+    /// Register a custom meta-schema under its URI (the value schemas use in `$schema`). Only its
+    /// `$vocabulary` object is read; a trailing `#` on the URI is ignored.
+    #[must_use]
+    pub fn meta_schema(mut self, uri: impl Into<String>, document: &Value) -> Self {
+        let uri: String = uri.into();
+        self.meta_schemas.insert(
+            uri.trim_end_matches('#').to_string(),
+            vocabularies_of(document),
+        );
+        self
+    }
+
+    /// Build the settings. Any option not set uses its per-option default.
+    #[must_use]
+    pub fn build(self) -> ValidatorSettings {
+        ValidatorSettings {
+            format_mode: self.format_mode.unwrap_or_default(),
+            unknown_format_mode: self.unknown_format_mode.unwrap_or_default(),
+            custom_formats: self.custom_formats,
+            keywords: self.keywords,
+            meta_schemas: self.meta_schemas,
+        }
+    }
+}
+
+impl ValidatorSettings {
+    /// Start a builder with all options unset (per-option defaults will be used on [`build`](ValidatorSettingsBuilder::build)).
+    #[must_use]
+    pub fn builder() -> ValidatorSettingsBuilder {
+        ValidatorSettingsBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatMode, UnknownFormatMode, ValidatorSettings};
+
+    #[test]
+    fn default_format_mode_is_assertion() {
+        let settings: ValidatorSettings = ValidatorSettings::default();
+        assert_eq!(FormatMode::Assertion, settings.format_mode);
+    }
+
+    #[test]
+    fn builder_format_mode_annotation() {
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .format_mode(FormatMode::Annotation)
+            .build();
+        assert_eq!(FormatMode::Annotation, settings.format_mode);
+    }
+
+    #[test]
+    fn default_unknown_format_mode_is_ignore() {
+        let settings: ValidatorSettings = ValidatorSettings::default();
+        assert_eq!(UnknownFormatMode::Ignore, settings.unknown_format_mode);
+        assert!(settings.custom_formats.is_empty());
+    }
+
+    #[test]
+    fn builder_custom_format_registers_and_replaces() {
+        let settings: ValidatorSettings = ValidatorSettings::builder()
+            .custom_format("even-length", |s: &str| s.len().is_multiple_of(2))
+            .custom_format("even-length", |s: &str| s.is_empty())
+            .build();
+        let checker = settings
+            .custom_formats
+            .get("even-length")
+            .expect("registered");
+        assert!(checker.check(""));
+        assert!(!checker.check("ab"));
+    }
+}
@@ -0,0 +1,282 @@
+//! YAML input: converts YAML documents to JSON values (requires the `yaml` feature).
+//!
+//! Schemas maintained as YAML (`OpenAPI` components, Kubernetes CRDs) and YAML instance payloads
+//! go through the same parse and validation paths as JSON once converted. Only the JSON subset of
+//! YAML is accepted: anchors, aliases, and `<<` merge keys are expanded, and scalar mapping keys
+//! become strings (e.g. `200:` under an `OpenAPI` `responses` map becomes `"200"`). Tags, non-scalar
+//! keys, and non-finite numbers have no JSON equivalent and are rejected as
+//! [`YamlError::Unsupported`], located by the JSON Pointer of the offending value.
+
+use crate::json_pointer::JsonPointer;
+use serde_json::{Map, Number, Value};
+use serde_yaml_ng::Value as YamlValue;
+use std::fmt;
+
+/// Error when reading a YAML document as JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YamlError {
+    /// The text is not well-formed YAML (including duplicate keys and invalid merge keys).
+    Syntax {
+        message: String,
+        /// 1-based line of the problem, when known.
+        line: Option<usize>,
+        /// 1-based column of the problem, when known.
+        column: Option<usize>,
+    },
+    /// The document is valid YAML but the value at `pointer` cannot be represented as JSON.
+    Unsupported {
+        /// JSON Pointer to the value (for a bad key, the mapping that contains it).
+        pointer: JsonPointer,
+        /// Human-readable reason (for user-facing context).
+        reason: String,
+    },
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YamlError::Syntax { message, .. } => write!(f, "{message}"),
+            YamlError::Unsupported { pointer, reason } => {
+                write!(f, "{reason} at {}", pointer.display_root_or_path())
+            }
+        }
+    }
+}
+
+impl std::error::Error for YamlError {}
+
+impl From<serde_yaml_ng::Error> for YamlError {
+    fn from(e: serde_yaml_ng::Error) -> Self {
+        let location: Option<serde_yaml_ng::Location> = e.location();
+        YamlError::Syntax {
+            message: e.to_string(),
+            line: location.as_ref().map(serde_yaml_ng::Location::line),
+            column: location.as_ref().map(serde_yaml_ng::Location::column),
+        }
+    }
+}
+
+/// Parses a single YAML document into a JSON value.
+///
+/// # Errors
+///
+/// Returns [`YamlError::Syntax`] when `yaml` is not a well-formed YAML document, and
+/// [`YamlError::Unsupported`] when it uses a construct JSON cannot represent.
+pub fn from_str(yaml: &str) -> Result<Value, YamlError> {
+    let mut document: YamlValue = serde_yaml_ng::from_str(yaml)?;
+    document.apply_merge()?;
+    to_json(&document)
+}
+
+/// Parses a single YAML document from bytes into a JSON value.
+///
+/// # Errors
+///
+/// Same as [`from_str`].
+pub fn from_slice(yaml: &[u8]) -> Result<Value, YamlError> {
+    let mut document: YamlValue = serde_yaml_ng::from_slice(yaml)?;
+    document.apply_merge()?;
+    to_json(&document)
+}
+
+/// Returns true when `path` has a `.yaml` or `.yml` extension.
+#[must_use]
+pub fn is_yaml_path(path: &std::path::Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+}
+
+/// Pending work while converting: visit a YAML value, or assemble a container from the last
+/// converted values once all its children are done.
+enum Task<'a> {
+    Visit(&'a YamlValue, JsonPointer),
+    BuildArray(usize),
+    BuildObject(Vec<String>),
+}
+
+/// Converts `document` to JSON with an explicit stack (post-order), so deep documents cannot
+/// overflow the call stack.
+fn to_json(document: &YamlValue) -> Result<Value, YamlError> {
+    let mut tasks: Vec<Task<'_>> = vec![Task::Visit(document, JsonPointer::root())];
+    let mut done: Vec<Value> = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(value, pointer) => match value {
+                YamlValue::Null => done.push(Value::Null),
+                YamlValue::Bool(b) => done.push(Value::Bool(*b)),
+                YamlValue::Number(n) => done.push(Value::Number(json_number(n, &pointer)?)),
+                YamlValue::String(s) => done.push(Value::String(s.clone())),
+                YamlValue::Sequence(items) => {
+                    tasks.push(Task::BuildArray(items.len()));
+                    for (i, item) in items.iter().enumerate().rev() {
+                        tasks.push(Task::Visit(item, pointer.push(&i.to_string())));
+                    }
+                }
+                YamlValue::Mapping(mapping) => {
+                    let mut keys: Vec<String> = Vec::with_capacity(mapping.len());
+                    for key in mapping.keys() {
+                        let key: String = json_key(key, &pointer)?;
+                        if keys.contains(&key) {
+                            return Err(YamlError::Unsupported {
+                                pointer,
+                                reason: format!("duplicate key \"{key}\" after converting to JSON"),
+                            });
+                        }
+                        keys.push(key);
+                    }
+                    let children: Vec<Task<'_>> = keys
+                        .iter()
+                        .zip(mapping.values())
+                        .map(|(key, child)| Task::Visit(child, pointer.push(key)))
+                        .collect();
+                    tasks.push(Task::BuildObject(keys));
+                    tasks.extend(children.into_iter().rev());
+                }
+                YamlValue::Tagged(tagged) => {
+                    return Err(YamlError::Unsupported {
+                        pointer,
+                        reason: format!("YAML tag {} has no JSON equivalent", tagged.tag),
+                    });
+                }
+            },
+            Task::BuildArray(len) => {
+                let items: Vec<Value> = done.split_off(done.len() - len);
+                done.push(Value::Array(items));
+            }
+            Task::BuildObject(keys) => {
+                let values: Vec<Value> = done.split_off(done.len() - keys.len());
+                let object: Map<String, Value> = keys.into_iter().zip(values).collect();
+                done.push(Value::Object(object));
+            }
+        }
+    }
+    Ok(done.pop().unwrap_or(Value::Null))
+}
+
+fn json_number(n: &serde_yaml_ng::Number, pointer: &JsonPointer) -> Result<Number, YamlError> {
+    if let Some(u) = n.as_u64() {
+        return Ok(Number::from(u));
+    }
+    if let Some(i) = n.as_i64() {
+        return Ok(Number::from(i));
+    }
+    n.as_f64()
+        .and_then(Number::from_f64)
+        .ok_or_else(|| YamlError::Unsupported {
+            pointer: pointer.clone(),
+            reason: format!("number {n} is not finite"),
+        })
+}
+
+/// Mapping keys must be strings in JSON; other scalars are written out as their YAML text.
+fn json_key(key: &YamlValue, pointer: &JsonPointer) -> Result<String, YamlError> {
+    match key {
+        YamlValue::String(s) => Ok(s.clone()),
+        YamlValue::Bool(b) => Ok(b.to_string()),
+        YamlValue::Number(n) => Ok(n.to_string()),
+        YamlValue::Null => Ok("null".to_string()),
+        YamlValue::Sequence(_) | YamlValue::Mapping(_) | YamlValue::Tagged(_) => {
+            Err(YamlError::Unsupported {
+                pointer: pointer.clone(),
+                reason: "mapping key must be a string, number, boolean, or null".to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_nested_mappings_and_sequences() {
+        let yaml = "type: object\nproperties:\n  tags:\n    type: array\n    items: {type: string}\nrequired: [tags]\n";
+        let expected: Value = json!({
+            "type": "object",
+            "properties": {"tags": {"type": "array", "items": {"type": "string"}}},
+            "required": ["tags"]
+        });
+        let actual: Value = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn keeps_integers_and_floats_apart() {
+        let yaml = "a: 3\nb: -4\nc: 1.5\nd: ~\ne: true\n";
+        let expected: Value = json!({"a": 3, "b": -4, "c": 1.5, "d": null, "e": true});
+        let actual: Value = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn scalar_keys_become_strings() {
+        let yaml = "responses:\n  200: {description: ok}\n  true: yes\n";
+        let expected: Value = json!({"responses": {"200": {"description": "ok"}, "true": "yes"}});
+        let actual: Value = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn expands_anchors_and_merge_keys() {
+        let yaml = "base: &base {type: string, minLength: 1}\nname:\n  <<: *base\n  maxLength: 5\n";
+        let expected: Value = json!({
+            "base": {"type": "string", "minLength": 1},
+            "name": {"type": "string", "minLength": 1, "maxLength": 5}
+        });
+        let actual: Value = from_str(yaml).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn tag_is_unsupported_at_its_pointer() {
+        let yaml = "properties:\n  id:\n    type: !Ref Id\n";
+        let expected = YamlError::Unsupported {
+            pointer: JsonPointer::try_from("/properties/id/type").unwrap(),
+            reason: "YAML tag !Ref has no JSON equivalent".to_string(),
+        };
+        let actual: YamlError = from_str(yaml).unwrap_err();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn non_finite_number_is_unsupported_at_its_pointer() {
+        let yaml = "items:\n  - 1\n  - .nan\n";
+        let expected = YamlError::Unsupported {
+            pointer: JsonPointer::try_from("/items/1").unwrap(),
+            reason: "number .nan is not finite".to_string(),
+        };
+        let actual: YamlError = from_str(yaml).unwrap_err();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn complex_key_is_unsupported_at_its_mapping() {
+        let yaml = "a:\n  ? [1, 2]\n  : x\n";
+        let expected = YamlError::Unsupported {
+            pointer: JsonPointer::try_from("/a").unwrap(),
+            reason: "mapping key must be a string, number, boolean, or null".to_string(),
+        };
+        let actual: YamlError = from_str(yaml).unwrap_err();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn syntax_error_has_location() {
+        let actual: YamlError = from_str("a: [1, 2\nb: 3\n").unwrap_err();
+        let YamlError::Syntax { line, column, .. } = actual else {
+            panic!("expected syntax error, got {actual:?}");
+        };
+        assert!(line.is_some() && column.is_some());
+    }
+
+    #[test]
+    fn is_yaml_path_matches_yaml_and_yml() {
+        let actual: Vec<bool> = ["a.yaml", "a.YML", "a.json", "a"]
+            .iter()
+            .map(|p| is_yaml_path(std::path::Path::new(p)))
+            .collect();
+        let expected: Vec<bool> = vec![true, true, false, false];
+        assert_eq!(expected, actual);
+    }
+}
@@ -1,8 +1,8 @@
 //! Integration test: public API for schema parsing and code generation, and CLI binary.
 
 use json_schema_rs::{
-    CodeGenSettings, DedupeMode, JsonSchema, JsonSchemaSettings, ModelNameSource, SpecVersion,
-    generate_rust, resolved_spec_version,
+    CodeGenSettings, DedupeMode, JsonSchema, JsonSchemaSettings, ModelNameSource, PropertyMap,
+    SpecVersion, generate_rust, resolved_spec_version,
 };
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
@@ -290,6 +290,180 @@ fn cli_generate_rust_enum_dedupe() {
     assert!(actual.contains("pub b: Option<A>"));
 }
 
+#[test]
+fn cli_generate_rust_name_collision_parent_prefix() {
+    let schema_json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"]},"shipment":{"type":"object","properties":{"status":{"enum":["pending","shipped"]}}}}}"#;
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    std::fs::write(&schema_path, schema_json).expect("write schema");
+    let out_dir = tempfile::tempdir().expect("temp out dir");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            "--cgs-name-collision",
+            "parent-prefix",
+            "-o",
+            out_dir.path().to_str().unwrap(),
+            schema_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "exit success: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let out_path = out_dir.path().join("schema.rs");
+    let actual = std::fs::read_to_string(&out_path).expect("read output");
+    assert!(actual.contains("pub enum Status {"));
+    assert!(actual.contains("pub enum ShipmentStatus {"));
+    assert!(actual.contains("pub status: Option<ShipmentStatus>"));
+}
+
+#[test]
+fn cli_generate_rust_title_name_collision_fails() {
+    let schema_json = r#"{"type":"object","properties":{"a":{"type":"object","title":"Item","properties":{"x":{"type":"string"}}},"b":{"type":"object","title":"Item","properties":{"y":{"type":"string"}}}}}"#;
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    std::fs::write(&schema_path, schema_json).expect("write schema");
+    let out_dir = tempfile::tempdir().expect("temp out dir");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            "-o",
+            out_dir.path().to_str().unwrap(),
+            schema_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "should exit non-zero");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("both named \"Item\""),
+        "stderr should name the colliding type: {stderr}"
+    );
+}
+
+#[test]
+fn cli_generate_rust_emit_all_defs() {
+    let schema_json = r#"{"$defs":{"Lib":{"type":"object","properties":{"k":{"type":"boolean"}}},"UnusedArr":{"type":"array","items":{"type":"string"}}},"type":"object","properties":{"id":{"type":"integer"}}}"#;
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    std::fs::write(&schema_path, schema_json).expect("write schema");
+    let out_dir = tempfile::tempdir().expect("temp out dir");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            "--cgs-emit-all-defs",
+            "-o",
+            out_dir.path().to_str().unwrap(),
+            schema_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "exit success: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let out_path = out_dir.path().join("schema.rs");
+    let actual = std::fs::read_to_string(&out_path).expect("read output");
+    assert!(actual.contains("pub struct Lib {"));
+    assert!(actual.contains("pub struct Root {"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("warning: no type generated for #/$defs/UnusedArr in schema 0"),
+        "stderr should list the skipped def: {stderr}"
+    );
+}
+
+#[test]
+fn cli_generate_rust_extra_derives_and_attributes() {
+    let schema_json =
+        r#"{"type":"object","properties":{"id":{"type":"string"},"score":{"type":"number"}}}"#;
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    std::fs::write(&schema_path, schema_json).expect("write schema");
+    let out_dir = tempfile::tempdir().expect("temp out dir");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            "--cgs-derive",
+            "PartialEq",
+            "--cgs-type-derive",
+            "Root=Hash",
+            "--cgs-type-attribute",
+            "#=#[non_exhaustive]",
+            "--cgs-type-derive",
+            "Nope=Hash",
+            "-o",
+            out_dir.path().to_str().unwrap(),
+            schema_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "exit success: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let out_path = out_dir.path().join("schema.rs");
+    let actual = std::fs::read_to_string(&out_path).expect("read output");
+    assert!(actual.contains(
+        "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]\n#[non_exhaustive]\npub struct Root {"
+    ));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "warning: skipped derive(Hash) on Root: `score: Option<f64>` does not implement Hash"
+        ),
+        "stderr should report the skipped derive: {stderr}"
+    );
+    assert!(
+        stderr.contains(
+            "warning: type selector \"Nope\" matches no generated type; its derives and attributes were not applied"
+        ),
+        "stderr should report the unmatched selector: {stderr}"
+    );
+}
+
+#[test]
+fn cli_generate_rust_type_mapping() {
+    let schema_json = r##"{"type":"object","properties":{"price":{"$ref":"#/$defs/Money"},"at":{"$ref":"https://example.com/timestamp.json"}},"$defs":{"Money":{"type":"object","properties":{"cents":{"type":"integer"}}}}}"##;
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path = temp_dir.path().join("schema.json");
+    std::fs::write(&schema_path, schema_json).expect("write schema");
+    let out_dir = tempfile::tempdir().expect("temp out dir");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            "--cgs-type-mapping",
+            "#/$defs/Money=crate::money::Money",
+            "--cgs-type-mapping",
+            "https://example.com/timestamp.json=crate::time::Timestamp",
+            "-o",
+            out_dir.path().to_str().unwrap(),
+            schema_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "exit success: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let out_path = out_dir.path().join("schema.rs");
+    let actual = std::fs::read_to_string(&out_path).expect("read output");
+    assert!(actual.contains("    pub at: Option<crate::time::Timestamp>,\n"));
+    assert!(actual.contains("    pub price: Option<crate::money::Money>,\n"));
+    assert!(!actual.contains("pub struct Money"));
+}
+
 #[test]
 fn cli_generate_rust_enum_duplicate_values() {
     let schema_json = r#"{"type":"object","properties":{"t":{"enum":["A","A","A","a","a","a","a","a","a","a"]}},"required":["t"]}"#;
@@ -1359,7 +1533,7 @@ fn cli_generate_rust_model_name_source_property_key() {
 
 #[test]
 fn cli_generate_rust_root_not_object_exits_nonzero() {
-    let schema_json = r#"{"type":"string"}"#;
+    let schema_json = r#"{"type":"object"}"#;
     let temp_dir = tempfile::tempdir().expect("temp dir");
     let schema_path = temp_dir.path().join("schema.json");
     std::fs::write(&schema_path, schema_json).expect("write schema");
@@ -1469,89 +1643,677 @@ fn cli_validate_invalid_payload_exit_nonzero_and_stderr() {
 }
 
 #[test]
-fn integration_parse_and_generate() {
-    let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
-    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
-    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
-    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
-    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
-    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub id: String,
-}
+fn cli_validate_format_assertion_and_annotation() {
+    let schema_json = r#"{"type":"string","format":"date"}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let mut payload_file = tempfile::NamedTempFile::new().expect("temp payload");
+    std::io::Write::write_all(&mut payload_file, br#""2021-02-29""#).expect("write payload");
+    payload_file.flush().expect("flush payload");
+    let schema_arg: &str = schema_file.path().to_str().unwrap();
+    let payload_arg: &str = payload_file.path().to_str().unwrap();
+
+    let asserted = Command::new(jsonschemars_bin())
+        .args(["validate", "-s", schema_arg, "-p", payload_arg])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!asserted.status.success(), "format assertion should fail");
+    let stderr = String::from_utf8(asserted.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains(r#"is not a valid "date""#),
+        "stderr should mention invalid date: {stderr}"
+    );
 
-";
-    assert_eq!(expected, actual);
+    let annotated = Command::new(jsonschemars_bin())
+        .args([
+            "validate",
+            "-s",
+            schema_arg,
+            "-p",
+            payload_arg,
+            "--vs-format-mode",
+            "annotation",
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        annotated.status.success(),
+        "format annotation should succeed: stderr={}",
+        String::from_utf8_lossy(&annotated.stderr)
+    );
 }
 
 #[test]
-fn integration_parse_and_generate_defs_ref() {
-    let json = r##"{
-  "$defs": {
-    "Address": {
-      "type": "object",
-      "properties": { "city": { "type": "string" } },
-      "required": ["city"]
-    }
-  },
-  "type": "object",
-  "properties": { "address": { "$ref": "#/$defs/Address" } },
-  "required": ["address"]
-}"##;
-    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
-    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
-    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
-    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
-    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
+fn cli_validate_custom_formats_file_and_unknown_format_error() {
+    let schema_json = r#"{"type":"object","properties":{"v":{"type":"string","format":"semver"},"c":{"type":"string","format":"cron"}}}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let mut payload_file = tempfile::NamedTempFile::new().expect("temp payload");
+    std::io::Write::write_all(&mut payload_file, br#"{"v":"1.2","c":"* * * * *"}"#)
+        .expect("write payload");
+    payload_file.flush().expect("flush payload");
+    let mut formats_file = tempfile::NamedTempFile::new().expect("temp formats");
+    std::io::Write::write_all(&mut formats_file, br#"{"semver":"^\\d+\\.\\d+\\.\\d+$"}"#)
+        .expect("write formats");
+    formats_file.flush().expect("flush formats");
+    let schema_arg: &str = schema_file.path().to_str().unwrap();
+    let payload_arg: &str = payload_file.path().to_str().unwrap();
+    let formats_arg: &str = formats_file.path().to_str().unwrap();
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Address {
-    pub city: String,
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "validate",
+            "-s",
+            schema_arg,
+            "-p",
+            payload_arg,
+            "--vs-formats-file",
+            formats_arg,
+            "--vs-unknown-format",
+            "error",
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "validation should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains(r#"/v: string "1.2" is not a valid "semver""#),
+        "stderr should mention invalid semver: {stderr}"
+    );
+    assert!(
+        stderr.contains(r#"/c: unknown format "cron""#),
+        "stderr should mention unknown cron format: {stderr}"
+    );
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub address: Address,
-}
+#[test]
+fn cli_validate_formats_file_with_invalid_regex_fails() {
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, br#"{"type":"string"}"#).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let mut payload_file = tempfile::NamedTempFile::new().expect("temp payload");
+    std::io::Write::write_all(&mut payload_file, br#""x""#).expect("write payload");
+    payload_file.flush().expect("flush payload");
+    let mut formats_file = tempfile::NamedTempFile::new().expect("temp formats");
+    std::io::Write::write_all(&mut formats_file, br#"{"broken":"(abc"}"#).expect("write formats");
+    formats_file.flush().expect("flush formats");
 
-";
-    assert_eq!(expected, actual);
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "validate",
+            "-s",
+            schema_file.path().to_str().unwrap(),
+            "-p",
+            payload_file.path().to_str().unwrap(),
+            "--vs-formats-file",
+            formats_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "bad formats file should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains(r#"format "broken" has an invalid regex"#),
+        "stderr should mention the bad regex: {stderr}"
+    );
 }
 
 #[test]
-fn integration_parse_and_generate_optional_string() {
-    let json = r#"{"type":"object","properties":{"name":{"type":"string"}}}"#;
-    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
-    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
-    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
-    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
-    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
-
-use serde::{Deserialize, Serialize};
+fn cli_validate_errors_quote_payload_source_line() {
+    let schema_json = r#"{"type":"object","properties":{"age":{"type":"integer"}}}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let mut payload_file = tempfile::NamedTempFile::new().expect("temp payload");
+    std::io::Write::write_all(&mut payload_file, b"{\n  \"age\": \"ten\"\n}\n")
+        .expect("write payload");
+    payload_file.flush().expect("flush payload");
+    let payload_arg: &str = payload_file.path().to_str().unwrap();
 
-#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
-pub struct Root {
-    pub name: Option<String>,
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "validate",
+            "-s",
+            schema_file.path().to_str().unwrap(),
+            "-p",
+            payload_arg,
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "validation should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    let expected: String = format!(
+        "/age: expected integer, got string\n --> {payload_arg}:2:10\n  |\n2 |   \"age\": \"ten\"\n  |          ^^^^^"
+    );
+    assert!(
+        stderr.contains(&expected),
+        "stderr should quote the payload line: {stderr}"
+    );
 }
 
-";
-    assert_eq!(expected, actual);
+#[test]
+fn cli_validate_invalid_payload_json_quotes_position() {
+    let schema_json = r#"{"type":"object"}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let mut payload_file = tempfile::NamedTempFile::new().expect("temp payload");
+    std::io::Write::write_all(&mut payload_file, b"{\n  \"age\": ,\n}\n").expect("write payload");
+    payload_file.flush().expect("flush payload");
+
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "validate",
+            "-s",
+            schema_file.path().to_str().unwrap(),
+            "-p",
+            payload_file.path().to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "invalid JSON should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains("2 |   \"age\": ,\n  |          ^"),
+        "stderr should point at the syntax error: {stderr}"
+    );
 }
 
 #[test]
-fn integration_parse_and_generate_enum_required() {
-    let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"]}},"required":["status"]}"#;
-    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
-    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
-    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
-    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
-    let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
+fn cli_check_schema_valid_schema_exit_zero() {
+    let schema_json = r#"{"$schema":"http://json-schema.org/draft-07/schema#","type":"object","properties":{"x":{"type":"string","minLength":1}}}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let output = Command::new(jsonschemars_bin())
+        .args(["check-schema", schema_file.path().to_str().unwrap()])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "check-schema should succeed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn cli_check_schema_invalid_schema_reports_pointer_paths() {
+    let schema_json = r#"{"type":"strng","properties":{"x":{"minLength":-1}}}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let output = Command::new(jsonschemars_bin())
+        .args(["check-schema", schema_file.path().to_str().unwrap()])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "check-schema should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains("/properties/x/minLength: value -1 is below minimum 0"),
+        "stderr should locate minLength: {stderr}"
+    );
+    assert!(
+        stderr.contains(r#"/type: value "strng" not in enum"#),
+        "stderr should locate type: {stderr}"
+    );
+    assert!(
+        stderr.contains("schema check failed with 2 error(s)"),
+        "stderr should count errors: {stderr}"
+    );
+}
+
+#[test]
+fn cli_lint_reports_errors_and_warnings_with_excerpts() {
+    let schema_json = "{\n  \"type\": \"integer\",\n  \"minimum\": 10,\n  \"maximum\": 1,\n  \"$defs\": {\"Unused\": {}}\n}";
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let output = Command::new(jsonschemars_bin())
+        .args(["lint", schema_file.path().to_str().unwrap()])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "lint should fail on errors");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains("error: /minimum: minimum 10 is greater than maximum 1"),
+        "stderr should report the bounds error: {stderr}"
+    );
+    assert!(
+        stderr.contains("3 |   \"minimum\": 10,\n  |              ^^"),
+        "stderr should point at minimum: {stderr}"
+    );
+    assert!(
+        stderr.contains("warning: /$defs/Unused: definition \"Unused\" is never referenced"),
+        "stderr should report the unused definition: {stderr}"
+    );
+    assert!(
+        stderr.contains("lint failed with 1 error(s) and 1 warning(s)"),
+        "stderr should count diagnostics: {stderr}"
+    );
+}
+
+#[test]
+fn cli_lint_warnings_only_pass_unless_denied() {
+    let schema_json = r#"{"type":"string","$defs":{"Unused":{}}}"#;
+    let mut schema_file = tempfile::NamedTempFile::new().expect("temp schema");
+    std::io::Write::write_all(&mut schema_file, schema_json.as_bytes()).expect("write schema");
+    schema_file.flush().expect("flush schema");
+    let path: &str = schema_file.path().to_str().unwrap();
+    let output = Command::new(jsonschemars_bin())
+        .args(["lint", path])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "warnings alone should pass: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = Command::new(jsonschemars_bin())
+        .args(["lint", "--deny-warnings", path])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        !output.status.success(),
+        "--deny-warnings should fail on warnings"
+    );
+}
+
+#[test]
+fn cli_bundle_embeds_external_refs_under_defs() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    fs::write(
+        temp_dir.path().join("address.json"),
+        r#"{"$id":"https://example.com/address","type":"object","properties":{"city":{"type":"string"}}}"#,
+    )
+    .expect("write address schema");
+    let root_path: PathBuf = temp_dir.path().join("root.json");
+    fs::write(
+        &root_path,
+        r#"{"type":"object","properties":{"home":{"$ref":"address.json"}}}"#,
+    )
+    .expect("write root schema");
+    let out_path: PathBuf = temp_dir.path().join("bundled.json");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "bundle",
+            root_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out_path).expect("read bundled")).unwrap();
+    let expected: serde_json::Value = serde_json::json!({
+        "type": "object",
+        "properties": {"home": {"$ref": "#/$defs/address"}},
+        "$defs": {
+            "address": {"type": "object", "properties": {"city": {"type": "string"}}}
+        }
+    });
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cli_deref_inlines_refs_to_stdout() {
+    let schema_json = r##"{"type":"object","properties":{"id":{"$ref":"#/$defs/Id"}},"$defs":{"Id":{"type":"string"}}}"##;
+    let mut child = Command::new(jsonschemars_bin())
+        .args(["deref", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn jsonschemars");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(schema_json.as_bytes())
+        .expect("write stdin");
+    let output = child.wait_with_output().expect("wait jsonschemars");
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    let expected: serde_json::Value = serde_json::json!({
+        "type": "object",
+        "properties": {"id": {"type": "string"}}
+    });
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cli_diff_reports_changes_as_json_and_fails_on_breaking() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let old_path: PathBuf = temp_dir.path().join("old.json");
+    fs::write(
+        &old_path,
+        r#"{"type":"object","properties":{"kind":{"type":"string","enum":["a","b"]}}}"#,
+    )
+    .expect("write old schema");
+    let compatible_path: PathBuf = temp_dir.path().join("compatible.json");
+    fs::write(
+        &compatible_path,
+        r#"{"type":"object","properties":{"kind":{"type":"string","enum":["a","b","c"]}}}"#,
+    )
+    .expect("write compatible schema");
+    let breaking_path: PathBuf = temp_dir.path().join("breaking.json");
+    fs::write(
+        &breaking_path,
+        r#"{"type":"object","properties":{"kind":{"type":"integer","enum":["a","b"]}}}"#,
+    )
+    .expect("write breaking schema");
+
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "diff",
+            old_path.to_str().unwrap(),
+            compatible_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    let expected: serde_json::Value = serde_json::json!([{
+        "path": "/properties/kind/enum",
+        "kind": "enum_changed",
+        "compatibility": "backward-compatible",
+        "message": "/properties/kind/enum: enum changed from [\"a\",\"b\"] to [\"a\",\"b\",\"c\"]"
+    }]);
+    assert_eq!(expected, actual);
+
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "diff",
+            old_path.to_str().unwrap(),
+            breaking_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "breaking change should fail");
+    let actual: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    assert_eq!(
+        Some("breaking"),
+        actual[0]["compatibility"].as_str(),
+        "stdout={actual}"
+    );
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(stderr.contains("1 breaking change(s)"), "stderr={stderr}");
+}
+
+#[test]
+fn cli_infer_merges_json_and_ndjson_samples() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let json_path: PathBuf = temp_dir.path().join("one.json");
+    fs::write(&json_path, r#"{"id": 1, "name": "a"}"#).expect("write json sample");
+    let ndjson_path: PathBuf = temp_dir.path().join("more.ndjson");
+    fs::write(&ndjson_path, "{\"id\": 2, \"name\": null}\n{\"id\": 3}\n").expect("write ndjson");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "infer",
+            json_path.to_str().unwrap(),
+            ndjson_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let actual: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout json");
+    let expected: serde_json::Value = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "id": {"type": "integer"},
+            "name": {"anyOf": [{"type": "string"}, {"type": "null"}]}
+        },
+        "required": ["id"]
+    });
+    assert_eq!(expected, actual);
+    let schema: JsonSchema = JsonSchema::try_from(&actual).expect("inferred schema parses");
+    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
+    let output = generate_rust(&[schema], &code_gen_settings).expect("inferred schema generates");
+    let rust: String = String::from_utf8(output.per_schema[0].clone()).expect("utf-8");
+    let actual: Vec<&str> = rust
+        .lines()
+        .filter(|line| line.trim_start().starts_with("pub "))
+        .collect();
+    let expected: Vec<&str> = vec![
+        "pub struct Root {",
+        "    pub id: i64,",
+        "    pub name: Option<String>,",
+    ];
+    assert_eq!(expected, actual);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn cli_validate_accepts_yaml_schema_and_payload() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path: PathBuf = temp_dir.path().join("schema.yaml");
+    fs::write(
+        &schema_path,
+        "type: object\nproperties:\n  replicas: {type: integer, minimum: 1}\nrequired: [replicas]\n",
+    )
+    .expect("write schema");
+    let valid_path: PathBuf = temp_dir.path().join("valid.yml");
+    fs::write(&valid_path, "replicas: 3\n").expect("write valid payload");
+    let invalid_path: PathBuf = temp_dir.path().join("invalid.yml");
+    fs::write(&invalid_path, "replicas: 0\n").expect("write invalid payload");
+    let run = |payload: &Path| {
+        Command::new(jsonschemars_bin())
+            .args([
+                "validate",
+                "-s",
+                schema_path.to_str().unwrap(),
+                "-p",
+                payload.to_str().unwrap(),
+            ])
+            .output()
+            .expect("run jsonschemars")
+    };
+    let valid = run(&valid_path);
+    assert!(
+        valid.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&valid.stderr)
+    );
+    let invalid = run(&invalid_path);
+    assert!(!invalid.status.success(), "replicas: 0 should fail");
+    let stderr = String::from_utf8(invalid.stderr).expect("utf8 stderr");
+    assert!(stderr.contains("/replicas"), "stderr: {stderr}");
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn cli_validate_reads_yaml_from_stdin_with_format_flags() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_yaml = "type: object\nproperties:\n  a: {type: integer}\nrequired: [a]\n";
+    let schema_path: PathBuf = temp_dir.path().join("schema.yaml");
+    fs::write(&schema_path, schema_yaml).expect("write schema");
+    let payload_path: PathBuf = temp_dir.path().join("payload.yaml");
+    fs::write(&payload_path, "a: 1\n").expect("write payload");
+    let run = |args: &[&str], stdin: &str| {
+        let mut child = Command::new(jsonschemars_bin())
+            .arg("validate")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn jsonschemars");
+        std::io::Write::write_all(child.stdin.as_mut().unwrap(), stdin.as_bytes())
+            .expect("write stdin");
+        child.wait_with_output().expect("wait jsonschemars")
+    };
+    let schema_arg: &str = schema_path.to_str().unwrap();
+    let payload_arg: &str = payload_path.to_str().unwrap();
+
+    let output = run(&["-s", schema_arg], "a: 1\n");
+    assert!(!output.status.success(), "stdin payload defaults to JSON");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid JSON payload"), "stderr: {stderr}");
+
+    let output = run(&["-s", schema_arg, "--payload-format", "yaml"], "a: 1\n");
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run(&["-s", schema_arg, "--payload-format", "yaml"], "a: x\n");
+    assert!(!output.status.success(), "a: x should fail");
+
+    let output = run(
+        &["-s", "-", "--schema-format", "yaml", "-p", payload_arg],
+        schema_yaml,
+    );
+    assert!(
+        output.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn cli_generate_reports_yaml_tag_by_pointer() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path: PathBuf = temp_dir.path().join("schema.yaml");
+    fs::write(&schema_path, "properties:\n  id: !Ref Id\n").expect("write schema");
+    let output = Command::new(jsonschemars_bin())
+        .args([
+            "generate",
+            "rust",
+            schema_path.to_str().unwrap(),
+            "-o",
+            temp_dir.path().join("out").to_str().unwrap(),
+        ])
+        .output()
+        .expect("run jsonschemars");
+    assert!(!output.status.success(), "tagged YAML should fail");
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(
+        stderr.contains("YAML tag !Ref has no JSON equivalent at /properties/id"),
+        "stderr: {stderr}"
+    );
+}
+
+#[cfg(not(feature = "yaml"))]
+#[test]
+fn cli_yaml_input_requires_yaml_feature() {
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+    let schema_path: PathBuf = temp_dir.path().join("schema.yaml");
+    fs::write(&schema_path, "type: string\n").expect("write schema");
+    let output = Command::new(jsonschemars_bin())
+        .args(["lint", schema_path.to_str().unwrap()])
+        .output()
+        .expect("run jsonschemars");
+    assert!(
+        !output.status.success(),
+        "YAML without the feature should fail"
+    );
+    let stderr = String::from_utf8(output.stderr).expect("utf8 stderr");
+    assert!(stderr.contains("`yaml` feature"), "stderr: {stderr}");
+}
+
+#[test]
+fn integration_parse_and_generate() {
+    let json = r#"{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}"#;
+    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
+    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
+    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
+    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
+    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub id: String,
+}
+
+";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn integration_parse_and_generate_defs_ref() {
+    let json = r##"{
+  "$defs": {
+    "Address": {
+      "type": "object",
+      "properties": { "city": { "type": "string" } },
+      "required": ["city"]
+    }
+  },
+  "type": "object",
+  "properties": { "address": { "$ref": "#/$defs/Address" } },
+  "required": ["address"]
+}"##;
+    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
+    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
+    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
+    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
+    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Address {
+    pub city: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub address: Address,
+}
+
+";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn integration_parse_and_generate_optional_string() {
+    let json = r#"{"type":"object","properties":{"name":{"type":"string"}}}"#;
+    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
+    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
+    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
+    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
+    let expected = r"//! Generated by json-schema-rs. Do not edit manually.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+pub struct Root {
+    pub name: Option<String>,
+}
+
+";
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn integration_parse_and_generate_enum_required() {
+    let json = r#"{"type":"object","properties":{"status":{"enum":["open","closed"]}},"required":["status"]}"#;
+    let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
+    let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
+    let output = generate_rust(&[schema], &code_gen_settings).expect("generate");
+    let actual = String::from_utf8(output.per_schema[0].clone()).expect("utf8");
+    let expected = r#"//! Generated by json-schema-rs. Do not edit manually.
 
 use serde::{Deserialize, Serialize};
 
@@ -1669,14 +2431,14 @@ fn integration_parse_and_generate_nested_object() {
     let json = r#"{
           "type": "object",
           "properties": {
-            "first_name": { "type": "string" },
             "address": {
               "type": "object",
               "properties": {
-                "street_address": { "type": "string" },
-                "city": { "type": "string" }
+                "city": { "type": "string" },
+                "street_address": { "type": "string" }
               }
-            }
+            },
+            "first_name": { "type": "string" }
           }
         }"#;
     let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
@@ -2122,7 +2884,7 @@ pub struct Root {
 
 #[test]
 fn integration_parse_and_generate_root_not_object_errors() {
-    let json = r#"{"type":"string"}"#;
+    let json = r#"{"type":"object"}"#;
     let schema: JsonSchema = JsonSchema::try_from(json).expect("parse schema");
     let code_gen_settings: CodeGenSettings = CodeGenSettings::builder().build();
     let actual = generate_rust(&[schema], &code_gen_settings);
@@ -2328,6 +3090,67 @@ fn write_workspace_scenario_member(
     let v: compile_test::Root = serde_json::from_str(r#"{"address":{"city":"NYC"}}"#).unwrap();
     assert_eq!(v.address.city.as_str(), "NYC");
 }
+"##;
+                (output.per_schema[0].clone(), vec![], main_rs)
+            }
+            "recursive_types" => {
+                let schema_json = r##"{
+  "$defs": {
+    "Node": {
+      "type": "object",
+      "properties": {
+        "value": { "type": "integer" },
+        "next": { "$ref": "#/$defs/Node" }
+      },
+      "required": ["value"]
+    },
+    "Tree": {
+      "type": "object",
+      "properties": {
+        "name": { "type": "string" },
+        "children": { "type": "array", "items": { "$ref": "#/$defs/Tree" } }
+      },
+      "required": ["name"]
+    }
+  },
+  "type": "object",
+  "title": "Doc",
+  "properties": {
+    "list": { "$ref": "#/$defs/Node" },
+    "tree": { "$ref": "#/$defs/Tree" },
+    "parent": { "$ref": "#" }
+  },
+  "required": ["list"]
+}"##;
+                let schema: JsonSchema = JsonSchema::try_from(schema_json).expect("parse schema");
+                let output = generate_rust(&[schema], default_code_gen).expect("generate");
+                let main_rs = r##"fn main() {
+    let v: compile_test::Doc = serde_json::from_str(
+        r#"{"list":{"value":1,"next":{"value":2}},"tree":{"name":"a","children":[{"name":"b"}]},"parent":{"list":{"value":3}}}"#,
+    )
+    .unwrap();
+    assert_eq!(v.list.next.as_ref().unwrap().value, 2);
+    assert_eq!(v.tree.unwrap().children.unwrap()[0].name.as_str(), "b");
+    assert_eq!(v.parent.unwrap().list.value, 3);
+}
+"##;
+                (output.per_schema[0].clone(), vec![], main_rs)
+            }
+            "name_collisions" => {
+                let schema_json = r#"{"type":"object","title":"Order","properties":{"status":{"enum":["open","closed"]},"billing":{"type":"object","properties":{"address":{"type":"object","properties":{"zip":{"type":"string"}},"required":["zip"]}}},"shipment":{"type":"object","properties":{"status":{"enum":["pending","shipped"]},"address":{"type":"object","properties":{"city":{"type":"string"}},"required":["city"]}}}}}"#;
+                let schema: JsonSchema = JsonSchema::try_from(schema_json).expect("parse schema");
+                let output = generate_rust(&[schema], default_code_gen).expect("generate");
+                let main_rs = r##"fn main() {
+    let v: compile_test::Order = serde_json::from_str(
+        r#"{"status":"open","billing":{"address":{"zip":"1"}},"shipment":{"status":"shipped","address":{"city":"x"}}}"#,
+    )
+    .unwrap();
+    assert_eq!(v.status, Some(compile_test::Status::Open));
+    assert_eq!(v.billing.unwrap().address.unwrap().zip.as_str(), "1");
+    let shipment = v.shipment.unwrap();
+    assert_eq!(shipment.status, Some(compile_test::Status2::Shipped));
+    assert_eq!(shipment.address.unwrap().city.as_str(), "x");
+}
 "##;
                 (output.per_schema[0].clone(), vec![], main_rs)
             }
@@ -2394,6 +3217,45 @@ fn write_workspace_scenario_member(
                 ];
                 (lib_rs, extra, main_rs)
             }
+            "non_object_roots" => {
+                let schemas: Vec<JsonSchema> = [
+                    r#"{"type":"array","items":{"type":"object","properties":{"id":{"type":"string"}},"required":["id"]}}"#,
+                    r#"{"title":"Prices","type":"object","additionalProperties":{"type":"number"}}"#,
+                    r#"{"type":"string","enum":["red","green"]}"#,
+                    r#"{"title":"UserId","type":"string","description":"A user id."}"#,
+                ]
+                .into_iter()
+                .map(|json| JsonSchema::try_from(json).expect("parse schema"))
+                .collect();
+                let output = generate_rust(&schemas, default_code_gen).expect("generate");
+                let main_rs = r##"use json_schema_rs::ToJsonSchema;
+use compile_test::{color, list, prices, user_id};
+
+fn main() {
+    let items: list::Root = serde_json::from_str(r#"[{"id":"a"},{"id":"b"}]"#).unwrap();
+    assert_eq!("b", items[1].id);
+    let p: prices::Prices = serde_json::from_str(r#"{"apple":1.5}"#).unwrap();
+    assert_eq!(Some(&1.5), p.get("apple"));
+    let c: color::Root = serde_json::from_str(r#""green""#).unwrap();
+    assert_eq!(color::Root::Green, c);
+    let id: user_id::UserId = serde_json::from_str(r#""u1""#).unwrap();
+    assert_eq!("u1", id.0);
+    assert_eq!(r#""u1""#, serde_json::to_string(&id).unwrap());
+    let schema = user_id::UserId::json_schema();
+    assert_eq!(Some("string"), schema.type_.as_deref());
+    assert_eq!(Some("UserId"), schema.title.as_deref());
+}
+"##;
+                let lib_rs: Vec<u8> =
+                    "pub mod color;\npub mod list;\npub mod prices;\npub mod user_id;\n".into();
+                let extra: Vec<(PathBuf, Vec<u8>)> = vec![
+                    (PathBuf::from("list.rs"), output.per_schema[0].clone()),
+                    (PathBuf::from("prices.rs"), output.per_schema[1].clone()),
+                    (PathBuf::from("color.rs"), output.per_schema[2].clone()),
+                    (PathBuf::from("user_id.rs"), output.per_schema[3].clone()),
+                ];
+                (lib_rs, extra, main_rs)
+            }
             "hyphenated_paths" => {
                 let schema_a = r#"{"type":"object","properties":{"a":{"type":"string"}}}"#;
                 let schema_b = r#"{"type":"object","properties":{"b":{"type":"string"}}}"#;
@@ -2504,7 +3366,7 @@ fn write_workspace_scenario_member(
                 let mut inner: JsonSchema = JsonSchema {
                     type_: Some("object".to_string()),
                     properties: {
-                        let mut m = std::collections::BTreeMap::new();
+                        let mut m = PropertyMap::new();
                         m.insert(
                             "value".to_string(),
                             JsonSchema {
@@ -2520,7 +3382,7 @@ fn write_workspace_scenario_member(
                 for i in (0..DEPTH).rev() {
                     let mut wrap: JsonSchema = JsonSchema {
                         type_: Some("object".to_string()),
-                        properties: std::collections::BTreeMap::new(),
+                        properties: PropertyMap::new(),
                         title: Some(format!("Level{i}")),
                         ..Default::default()
                     };
@@ -2546,15 +3408,216 @@ fn write_workspace_scenario_member(
                 (output.per_schema[0].clone(), vec![], main_rs)
             }
             "oneof_union" => {
-                let schema_json = r#"{"oneOf":[{"type":"object","properties":{"a":{"type":"string"}}},{"type":"object","properties":{"b":{"type":"integer"}}}]}"#;
+                let schema_json = r#"{"oneOf":[{"type":"object","properties":{"a":{"type":"string"}},"required":["a"]},{"type":"object","properties":{"b":{"type":"integer"}},"required":["b"]},{"type":"string"},{"type":"integer"}]}"#;
                 let schema: JsonSchema =
                     JsonSchema::try_from(schema_json).expect("parse oneOf schema");
                 let output = generate_rust(&[schema], default_code_gen).expect("generate");
-                let main_rs = r##"fn main() {
-    let v0: compile_test::RootOneOf = serde_json::from_str(r#"{"Variant0":{"a":"x"}}"#).unwrap();
-    let v1: compile_test::RootOneOf = serde_json::from_str(r#"{"Variant1":{"b":42}}"#).unwrap();
+                let main_rs = r##"use json_schema_rs::ToJsonSchema;
+
+fn main() {
+    let v0: compile_test::RootOneOf = serde_json::from_str(r#"{"a":"x"}"#).unwrap();
+    let v1: compile_test::RootOneOf = serde_json::from_str(r#"{"b":42}"#).unwrap();
+    let v2: compile_test::RootOneOf = serde_json::from_str(r#""text""#).unwrap();
+    let v3: compile_test::RootOneOf = serde_json::from_str("7").unwrap();
     assert!(matches!(v0, compile_test::RootOneOf::Variant0(_)));
     assert!(matches!(v1, compile_test::RootOneOf::Variant1(_)));
+    assert!(matches!(v2, compile_test::RootOneOf::Variant2(_)));
+    assert!(matches!(v3, compile_test::RootOneOf::Variant3(7)));
+    assert_eq!(r#"{"b":42}"#, serde_json::to_string(&v1).unwrap());
+    assert_eq!(4, compile_test::RootOneOf::json_schema().one_of.map_or(0, |b| b.len()));
+}
+"##;
+                (output.per_schema[0].clone(), vec![], main_rs)
+            }
+            "discriminated_oneof" => {
+                let schema_json = r##"{"type":"object","properties":{"event":{"oneOf":[{"type":"object","properties":{"type":{"const":"created"},"id":{"type":"string"},"count":{"type":"integer","default":3}},"required":["type","id"]},{"$ref":"#/$defs/Deleted"}]},"pet":{"oneOf":[{"$ref":"#/$defs/Cat"},{"$ref":"#/$defs/Dog"}],"discriminator":{"propertyName":"kind"}}},"required":["event"],"$defs":{"Deleted":{"type":"object","properties":{"type":{"const":"deleted"},"reason":{"type":"string"}}},"Cat":{"type":"object","properties":{"kind":{"type":"string"},"lives":{"type":"integer"}}},"Dog":{"type":"object","properties":{"kind":{"type":"string"},"good":{"type":"boolean"}}}}}"##;
+                let schema: JsonSchema =
+                    JsonSchema::try_from(schema_json).expect("parse discriminated oneOf schema");
+                let output = generate_rust(&[schema], default_code_gen).expect("generate");
+                let main_rs = r##"use json_schema_rs::ToJsonSchema;
+use compile_test::{EventOneOf, PetOneOf, Root};
+
+fn main() {
+    let created: Root = serde_json::from_str(r#"{"event":{"type":"created","id":"e1"}}"#).unwrap();
+    let EventOneOf::Created { id, count } = &created.event else {
+        panic!("expected Created, got {:?}", created.event);
+    };
+    assert_eq!("e1", id);
+    assert_eq!(Some(3), *count);
+    let deleted: Root = serde_json::from_str(
+        r#"{"event":{"type":"deleted","reason":"gone"},"pet":{"kind":"Dog","good":true}}"#,
+    )
+    .unwrap();
+    assert!(matches!(deleted.event, EventOneOf::Deleted { .. }));
+    assert!(matches!(deleted.pet, Some(PetOneOf::Dog { good: Some(true) })));
+    assert_eq!(
+        r#"{"type":"deleted","reason":"gone"}"#,
+        serde_json::to_string(&deleted.event).unwrap()
+    );
+    assert!(serde_json::from_str::<Root>(r#"{"event":{"type":"moved"}}"#).is_err());
+    let branches = EventOneOf::json_schema().one_of.unwrap();
+    assert_eq!(2, branches.len());
+    assert_eq!(
+        Some(&serde_json::json!("created")),
+        branches[0].properties["type"].const_value.as_ref()
+    );
+}
+"##;
+                (output.per_schema[0].clone(), vec![], main_rs)
+            }
+            "enforce_constraints" => {
+                let schema_json = r#"{"type":"object","properties":{"name":{"type":"string","minLength":2,"default":"anon"},"age":{"type":"integer","minimum":0,"maximum":150},"tags":{"type":"array","items":{"type":"string","pattern":"^[a-z]+$"},"minItems":1},"shape":{"oneOf":[{"type":"object","properties":{"kind":{"const":"square"},"sides":{"type":"array","items":{"type":"integer"},"maxItems":4}},"required":["kind"]},{"type":"object","properties":{"kind":{"const":"dot"}},"required":["kind"]}]}},"required":["age"]}"#;
+                let schema: JsonSchema = JsonSchema::try_from(schema_json).expect("parse schema");
+                let code_gen: CodeGenSettings =
+                    CodeGenSettings::builder().enforce_constraints(true).build();
+                let output = generate_rust(&[schema], &code_gen).expect("generate");
+                let main_rs = r##"use json_schema_rs::Validate;
+use compile_test::{Root, RootAge, ShapeOneOf};
+
+fn main() {
+    let root: Root = serde_json::from_str(r#"{"age":30,"tags":["a","b"]}"#).unwrap();
+    assert_eq!(30, *root.age.get());
+    assert_eq!("anon", root.name.as_ref().unwrap().get());
+    assert_eq!(Ok(()), root.validate());
+    let err = serde_json::from_str::<Root>(r#"{"age":200}"#).unwrap_err();
+    assert!(err.to_string().contains("above maximum 150"), "{err}");
+    let err = serde_json::from_str::<Root>(r#"{"age":1,"name":"x"}"#).unwrap_err();
+    assert_eq!(
+        "string has 1 code points, minLength is 2 at line 1 column 20",
+        err.to_string()
+    );
+    let err = serde_json::from_str::<Root>(r#"{"age":1,"tags":["A"]}"#).unwrap_err();
+    assert_eq!(
+        "string \"A\" does not match pattern \"^[a-z]+$\" at line 1 column 21",
+        err.to_string()
+    );
+    let err = serde_json::from_str::<Root>(r#"{"age":1,"tags":[]}"#).unwrap_err();
+    assert!(err.to_string().contains("/tags: array has 0 item(s), minimum is 1"), "{err}");
+    assert!(RootAge::try_from(151).is_err());
+    let mut root: Root = serde_json::from_str(r#"{"age":1,"shape":{"kind":"square","sides":[1]}}"#).unwrap();
+    if let Some(ShapeOneOf::Square { sides: Some(sides) }) = &mut root.shape {
+        sides.extend([2, 3, 4, 5]);
+    }
+    assert_eq!(
+        "/shape/sides: array has 5 item(s), maximum is 4",
+        root.validate().unwrap_err().to_string()
+    );
+}
+"##;
+                (output.per_schema[0].clone(), vec![], main_rs)
+            }
+            "type_mappings" => {
+                let schema_json = r##"{"type":"object","properties":{"total":{"$ref":"#/$defs/Money"},"history":{"type":"array","items":{"$ref":"#/$defs/Money"}},"placed_at":{"$ref":"https://example.com/timestamp.json"},"note":{"type":"string","x-rust-type":"crate::domain::Note"}},"required":["total"],"$defs":{"Money":{"type":"object","properties":{"cents":{"type":"integer"}}}}}"##;
+                let schema: JsonSchema = JsonSchema::try_from(schema_json).expect("parse schema");
+                let code_gen: CodeGenSettings = CodeGenSettings::builder()
+                    .type_mapping("#/$defs/Money", "crate::domain::Money")
+                    .type_mapping(
+                        "https://example.com/timestamp.json",
+                        "crate::domain::Timestamp",
+                    )
+                    .build();
+                let output = generate_rust(&[schema], &code_gen).expect("generate");
+                let domain = r"
+pub mod domain {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+    pub struct Money {
+        pub cents: i64,
+        pub currency: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+    #[serde(transparent)]
+    pub struct Timestamp(pub String);
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+    #[serde(transparent)]
+    pub struct Note(pub String);
+}
+";
+                let mut lib_rs: Vec<u8> = output.per_schema[0].clone();
+                lib_rs.extend_from_slice(domain.as_bytes());
+                let main_rs = r##"use compile_test::domain::{Money, Note, Timestamp};
+
+fn main() {
+    let v: compile_test::Root = serde_json::from_str(
+        r#"{"total":{"cents":5,"currency":"EUR"},"history":[{"cents":1,"currency":"USD"}],"placed_at":"2024-01-01T00:00:00Z","note":"hi"}"#,
+    )
+    .unwrap();
+    assert_eq!(Money { cents: 5, currency: "EUR".to_string() }, v.total);
+    assert_eq!(1, v.history.unwrap()[0].cents);
+    assert_eq!(Some(Timestamp("2024-01-01T00:00:00Z".to_string())), v.placed_at);
+    assert_eq!(Some(Note("hi".to_string())), v.note);
+}
+"##;
+                (lib_rs, vec![], main_rs)
+            }
+            "enforced_type_mappings" => {
+                let schema_json = r##"{"type":"object","properties":{"price":{"$ref":"#/$defs/Money"},"history":{"type":"array","items":{"$ref":"#/$defs/Money"},"maxItems":2},"either":{"anyOf":[{"$ref":"#/$defs/Money"},{"type":"string","minLength":2}]},"name":{"type":"string","minLength":1}},"required":["price"],"$defs":{"Money":{"type":"object","properties":{"cents":{"type":"integer","minimum":0}}}}}"##;
+                let schema: JsonSchema = JsonSchema::try_from(schema_json).expect("parse schema");
+                let code_gen: CodeGenSettings = CodeGenSettings::builder()
+                    .enforce_constraints(true)
+                    .type_mapping("#/$defs/Money", "crate::domain::Money")
+                    .build();
+                let output = generate_rust(&[schema], &code_gen).expect("generate");
+                let domain = r"
+pub mod domain {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, json_schema_rs_macro::ToJsonSchema)]
+    pub struct Money {
+        pub cents: i64,
+    }
+}
+";
+                let mut lib_rs: Vec<u8> = output.per_schema[0].clone();
+                lib_rs.extend_from_slice(domain.as_bytes());
+                let main_rs = r##"use compile_test::domain::Money;
+use json_schema_rs::Validate;
+
+fn main() {
+    let mut v: compile_test::Root = serde_json::from_str(
+        r#"{"price":{"cents":-5},"history":[{"cents":1}],"either":{"cents":2},"name":"x"}"#,
+    )
+    .unwrap();
+    assert_eq!(Money { cents: -5 }, v.price);
+    assert_eq!(Ok(()), v.validate());
+    let err = serde_json::from_str::<compile_test::Root>(
+        r#"{"price":{"cents":1},"history":[{"cents":1},{"cents":2},{"cents":3}]}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("maximum is 2"), "{err}");
+    v.history.as_mut().unwrap().extend([Money { cents: 2 }, Money { cents: 3 }]);
+    assert_eq!(
+        "/history: array has 3 item(s), maximum is 2",
+        v.validate().unwrap_err().to_string()
+    );
+}
+"##;
+                (lib_rs, vec![], main_rs)
+            }
+            "anyof_field" => {
+                let schema_json = r#"{"type":"object","properties":{"value":{"anyOf":[{"type":"string"},{"type":"integer"},{"type":"object","properties":{"k":{"type":"boolean"}},"required":["k"]}]}},"required":["value"]}"#;
+                let schema: JsonSchema =
+                    JsonSchema::try_from(schema_json).expect("parse anyOf schema");
+                let output = generate_rust(&[schema], default_code_gen).expect("generate");
+                let main_rs = r##"use json_schema_rs::ToJsonSchema;
+
+fn main() {
+    let s: compile_test::Root = serde_json::from_str(r#"{"value":"x"}"#).unwrap();
+    let n: compile_test::Root = serde_json::from_str(r#"{"value":3}"#).unwrap();
+    let o: compile_test::Root = serde_json::from_str(r#"{"value":{"k":true}}"#).unwrap();
+    assert!(matches!(s.value, compile_test::ValueAnyOf::Variant0(_)));
+    assert!(matches!(n.value, compile_test::ValueAnyOf::Variant1(3)));
+    assert!(matches!(o.value, compile_test::ValueAnyOf::Variant2(_)));
+    let schema = compile_test::Root::json_schema();
+    assert_eq!(
+        Some("#/$defs/ValueAnyOf"),
+        schema.properties["value"].ref_.as_deref()
+    );
+    let defs = schema.defs.unwrap();
+    assert_eq!(3, defs["ValueAnyOf"].any_of.as_ref().map_or(0, Vec::len));
 }
 "##;
                 (output.per_schema[0].clone(), vec![], main_rs)
@@ -3042,14 +4105,22 @@ fn generated_rust_build_and_deserialize_all_scenarios() {
         "enum_variants",
         "nested_modules",
         "multi_schema",
+        "non_object_roots",
         "hyphenated_paths",
         "dedupe_two_identical",
         "dedupe_nested_output",
         "model_name_source_property_key",
         "defs_ref",
+        "recursive_types",
+        "name_collisions",
         "deep_nesting",
         "allof_merged",
         "oneof_union",
+        "discriminated_oneof",
+        "enforce_constraints",
+        "type_mappings",
+        "enforced_type_mappings",
+        "anyof_field",
         "additional_properties_false",
         "additional_properties_schema",
         "string_pattern",
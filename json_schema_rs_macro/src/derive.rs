@@ -464,6 +464,71 @@ fn field_default(field: &Field) -> SynResult<Option<Expr>> {
     Ok(None)
 }
 
+/// One `"key" = value` pair inside `extension(...)`. The value is every token up to the next
+/// top-level comma, so any `serde_json::json!` input (strings, numbers, arrays, objects) works.
+struct ExtensionPair {
+    key: LitStr,
+    value: TokenStream2,
+}
+
+impl syn::parse::Parse for ExtensionPair {
+    fn parse(input: syn::parse::ParseStream) -> SynResult<Self> {
+        let key: LitStr = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let mut value: TokenStream2 = TokenStream2::new();
+        while !input.is_empty() && !input.peek(Token![,]) {
+            let tt: proc_macro2::TokenTree = input.parse()?;
+            value.extend(std::iter::once(tt));
+        }
+        if value.is_empty() {
+            return Err(Error::new(
+                key.span(),
+                "json_schema(extension(\"key\" = ...)) requires a value",
+            ));
+        }
+        Ok(Self { key, value })
+    }
+}
+
+/// Extracts every `extension("x-key" = value, ...)` entry from `#[json_schema(...)]` attributes
+/// (container or field) as an expression building the extensions map. Later keys win.
+fn extensions_expr(attrs: &[Attribute]) -> SynResult<Option<TokenStream2>> {
+    let mut inserts: Vec<TokenStream2> = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("json_schema") {
+            continue;
+        }
+        let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+        let metas: Punctuated<Meta, Token![,]> = attr.parse_args_with(parser)?;
+        for meta in metas {
+            let Meta::List(list) = meta else {
+                continue;
+            };
+            if !list.path.is_ident("extension") {
+                continue;
+            }
+            let pairs: Punctuated<ExtensionPair, Token![,]> =
+                list.parse_args_with(Punctuated::parse_terminated)?;
+            for ExtensionPair { key, value } in pairs {
+                inserts.push(quote! {
+                    extensions.insert(#key.to_string(), ::serde_json::json!(#value));
+                });
+            }
+        }
+    }
+    if inserts.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(quote! {
+        {
+            let mut extensions: ::std::collections::BTreeMap<::std::string::String, ::serde_json::Value> =
+                ::std::collections::BTreeMap::new();
+            #(#inserts)*
+            extensions
+        }
+    }))
+}
+
 /// Returns the JSON property key for a field: serde rename or field name.
 fn field_property_key(field: &Field) -> SynResult<String> {
     for attr in &field.attrs {
@@ -554,68 +619,20 @@ fn optional_inner_type(ty: &Type) -> &Type {
     t
 }
 
-#[expect(clippy::too_many_lines)]
-pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
-    let name: Ident = input.ident;
-
-    if let syn::Data::Enum(data_enum) = &input.data {
-        return expand_enum_to_json_schema(&name, &input.attrs, data_enum);
-    }
-
-    let fields = match &input.data {
-        syn::Data::Struct(s) => match &s.fields {
-            Fields::Named(named) => &named.named,
-            Fields::Unnamed(_) | Fields::Unit => {
-                return Err(Error::new_spanned(
-                    name,
-                    "ToJsonSchema derive only supports structs with named fields",
-                ));
-            }
-        },
-        syn::Data::Enum(_) | syn::Data::Union(_) => {
-            return Err(Error::new_spanned(
-                name,
-                "ToJsonSchema derive only supports structs and unit enums",
-            ));
-        }
-    };
-
-    let title: Option<String> = container_title(&input.attrs)?;
-    let title_expr = title
-        .as_ref()
-        .map(|t| {
-            let lit = LitStr::new(t, proc_macro2::Span::call_site());
-            quote! { Some(#lit.to_string()) }
-        })
-        .unwrap_or(quote! { None });
-
-    let description: Option<String> = container_description(&input.attrs)?;
-    let description_expr = description
-        .as_ref()
-        .map(|d| {
-            let lit = LitStr::new(d, proc_macro2::Span::call_site());
-            quote! { Some(#lit.to_string()) }
-        })
-        .unwrap_or(quote! { None });
-
-    let comment: Option<String> = container_comment(&input.attrs)?;
-    let comment_expr = comment
-        .as_ref()
-        .map(|c| {
-            let lit = LitStr::new(c, proc_macro2::Span::call_site());
-            quote! { Some(#lit.to_string()) }
-        })
-        .unwrap_or(quote! { None });
-
-    let id: Option<String> = container_id(&input.attrs)?;
-    let id_expr = id
-        .as_ref()
-        .map(|i| {
-            let lit = LitStr::new(i, proc_macro2::Span::call_site());
-            quote! { Some(#lit.to_string()) }
-        })
-        .unwrap_or(quote! { None });
+/// Schema pieces for named fields: inserts into the `properties` and `defs` maps of the generated
+/// code, the required keys, and whether a field refers back to `owner` (recursive `$ref`).
+struct ObjectFields {
+    property_inserts: Vec<TokenStream2>,
+    defs_inserts: Vec<TokenStream2>,
+    required_keys: Vec<String>,
+    has_recursive_field: bool,
+}
 
+#[expect(clippy::too_many_lines)]
+fn object_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+    owner: &Ident,
+) -> SynResult<ObjectFields> {
     let mut property_inserts: Vec<TokenStream2> = Vec::new();
     let mut required_keys: Vec<String> = Vec::new();
     let mut defs_inserts: Vec<TokenStream2> = Vec::new();
@@ -651,6 +668,11 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
         let field_pattern_val: Option<String> = field_pattern(field)?;
         let field_deprecated_val: Option<bool> = field_deprecated(field)?;
         let field_default_expr: Option<Expr> = field_default(field)?;
+        let field_extensions_expr: Option<TokenStream2> = extensions_expr(&field.attrs)?;
+        let extend_extensions: TokenStream2 = match field_extensions_expr {
+            Some(ref ext) => quote! { schema.extensions.extend(#ext); },
+            None => quote! {},
+        };
         let default_value_override: Option<TokenStream2> =
             field_default_expr.as_ref().map(|expr| {
                 quote! { Some(::serde_json::json!(#expr)) }
@@ -718,9 +740,10 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
             || field_max_length.is_some()
             || field_pattern_val.is_some()
             || field_deprecated_val.is_some()
-            || field_default_expr.is_some();
+            || field_default_expr.is_some()
+            || field_extensions_expr.is_some();
 
-        let field_def_info: Option<(String, bool, bool)> = def_key_for_field_type(schema_ty, &name);
+        let field_def_info: Option<(String, bool, bool)> = def_key_for_field_type(schema_ty, owner);
 
         if let Some((def_key, is_array_like, is_recursive)) = field_def_info
             && !has_overrides
@@ -843,25 +866,130 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
                     schema.pattern = #pattern_expr.or(schema.pattern);
                     schema.deprecated = #deprecated_expr.or(schema.deprecated);
                     #set_default_value
+                    #extend_extensions
                     properties.insert(#key_lit.to_string(), schema);
                 }
             });
         }
     }
+    Ok(ObjectFields {
+        property_inserts,
+        defs_inserts,
+        required_keys,
+        has_recursive_field,
+    })
+}
 
-    let required_expr = if required_keys.is_empty() {
-        quote! { None }
-    } else {
-        let keys: Vec<TokenStream2> = required_keys
-            .iter()
-            .map(|k| {
-                let lit = LitStr::new(k, name.span());
-                quote! { #lit.to_string() }
-            })
-            .collect();
-        quote! { Some(vec![#(#keys),*]) }
+/// `Some(vec![...])` of the required keys, or `None` when there are none.
+fn required_expr(required_keys: &[String], name: &Ident) -> TokenStream2 {
+    if required_keys.is_empty() {
+        return quote! { None };
+    }
+    let keys: Vec<TokenStream2> = required_keys
+        .iter()
+        .map(|k| {
+            let lit = LitStr::new(k, name.span());
+            quote! { #lit.to_string() }
+        })
+        .collect();
+    quote! { Some(vec![#(#keys),*]) }
+}
+
+#[expect(clippy::too_many_lines)]
+pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
+    let name: Ident = input.ident;
+
+    if let syn::Data::Enum(data_enum) = &input.data {
+        return expand_enum_to_json_schema(&name, &input.attrs, data_enum);
+    }
+
+    // A newtype struct (`struct Name(Inner);`) has the schema of its field.
+    let (fields, newtype_ty) = match &input.data {
+        syn::Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => (Some(&named.named), None),
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                (None, Some(&unnamed.unnamed[0].ty))
+            }
+            Fields::Unnamed(_) | Fields::Unit => {
+                return Err(Error::new_spanned(
+                    name,
+                    "ToJsonSchema derive only supports structs with named fields and newtype structs",
+                ));
+            }
+        },
+        syn::Data::Enum(_) | syn::Data::Union(_) => {
+            return Err(Error::new_spanned(
+                name,
+                "ToJsonSchema derive only supports structs and unit enums",
+            ));
+        }
+    };
+
+    let title: Option<String> = container_title(&input.attrs)?;
+    let title_expr = title
+        .as_ref()
+        .map(|t| {
+            let lit = LitStr::new(t, proc_macro2::Span::call_site());
+            quote! { Some(#lit.to_string()) }
+        })
+        .unwrap_or(quote! { None });
+
+    let description: Option<String> = container_description(&input.attrs)?;
+    let description_expr = description
+        .as_ref()
+        .map(|d| {
+            let lit = LitStr::new(d, proc_macro2::Span::call_site());
+            quote! { Some(#lit.to_string()) }
+        })
+        .unwrap_or(quote! { None });
+
+    let comment: Option<String> = container_comment(&input.attrs)?;
+    let comment_expr = comment
+        .as_ref()
+        .map(|c| {
+            let lit = LitStr::new(c, proc_macro2::Span::call_site());
+            quote! { Some(#lit.to_string()) }
+        })
+        .unwrap_or(quote! { None });
+
+    let id: Option<String> = container_id(&input.attrs)?;
+    let id_expr = id
+        .as_ref()
+        .map(|i| {
+            let lit = LitStr::new(i, proc_macro2::Span::call_site());
+            quote! { Some(#lit.to_string()) }
+        })
+        .unwrap_or(quote! { None });
+    let container_extensions_expr: TokenStream2 =
+        extensions_expr(&input.attrs)?.unwrap_or(quote! { ::std::collections::BTreeMap::new() });
+
+    let Some(fields) = fields else {
+        return Ok(quote! {
+            impl ::json_schema_rs::ToJsonSchema for #name {
+                fn json_schema() -> ::json_schema_rs::JsonSchema {
+                    ::json_schema_rs::JsonSchema {
+                        schema: Some(::json_schema_rs::SpecVersion::Draft202012.schema_uri().to_string()),
+                        id: #id_expr,
+                        title: #title_expr,
+                        description: #description_expr,
+                        comment: #comment_expr,
+                        extensions: #container_extensions_expr,
+                        ..<#newtype_ty as ::json_schema_rs::ToJsonSchema>::json_schema()
+                    }
+                }
+            }
+        });
     };
 
+    let ObjectFields {
+        property_inserts,
+        defs_inserts,
+        required_keys,
+        has_recursive_field,
+    } = object_fields(fields, &name)?;
+
+    let required_expr: TokenStream2 = required_expr(&required_keys, &name);
+
     let struct_name_str: String = name.to_string();
     let recursive_def_insert: TokenStream2 = if has_recursive_field {
         let name_lit: LitStr = LitStr::new(&struct_name_str, name.span());
@@ -884,7 +1012,7 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
     Ok(quote! {
         impl ::json_schema_rs::ToJsonSchema for #name {
             fn json_schema() -> ::json_schema_rs::JsonSchema {
-                let mut properties = ::std::collections::BTreeMap::new();
+                let mut properties = ::json_schema_rs::PropertyMap::new();
                 let mut defs = ::std::collections::BTreeMap::new();
                 #(#defs_inserts)*
                 #(#property_inserts)*
@@ -901,6 +1029,7 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
                     title: #title_expr,
                     description: #description_expr,
                     comment: #comment_expr,
+                    extensions: #container_extensions_expr,
                     ..Default::default()
                 }
             }
@@ -908,7 +1037,10 @@ pub fn expand_to_json_schema(input: DeriveInput) -> SynResult<TokenStream2> {
     })
 }
 
-/// Expand `ToJsonSchema` for a unit enum: emit schema with type "string" and `enum_values`.
+/// Expand `ToJsonSchema` for an enum: a unit enum emits type "string" and `enum_values`; an
+/// untagged enum of single-field tuple variants emits `anyOf` (or `oneOf`) of the variant types;
+/// an internally tagged enum emits `oneOf` of one object per variant.
+#[expect(clippy::too_many_lines)]
 fn expand_enum_to_json_schema(
     name: &Ident,
     attrs: &[Attribute],
@@ -949,6 +1081,36 @@ fn expand_enum_to_json_schema(
             quote! { Some(#lit.to_string()) }
         })
         .unwrap_or(quote! { None });
+    let container_extensions_expr: TokenStream2 =
+        extensions_expr(attrs)?.unwrap_or(quote! { ::std::collections::BTreeMap::new() });
+
+    let union: Option<TokenStream2> = if let Some(tag) = serde_tag(attrs)? {
+        Some(tagged_union_schema_expr(name, &tag, data_enum)?)
+    } else if data_enum
+        .variants
+        .iter()
+        .any(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        Some(union_schema_expr(name, attrs, data_enum)?)
+    } else {
+        None
+    };
+    if let Some(union) = union {
+        return Ok(quote! {
+            impl ::json_schema_rs::ToJsonSchema for #name {
+                fn json_schema() -> ::json_schema_rs::JsonSchema {
+                    let mut schema: ::json_schema_rs::JsonSchema = #union;
+                    schema.schema = Some(::json_schema_rs::SpecVersion::Draft202012.schema_uri().to_string());
+                    schema.id = #id_expr;
+                    schema.title = #title_expr;
+                    schema.description = #description_expr;
+                    schema.comment = #comment_expr;
+                    schema.extensions = #container_extensions_expr;
+                    schema
+                }
+            }
+        });
+    }
 
     let mut enum_value_lits: Vec<LitStr> = Vec::new();
     for variant in &data_enum.variants {
@@ -996,9 +1158,229 @@ fn expand_enum_to_json_schema(
                     comment: #comment_expr,
                     enum_values: #enum_values_expr,
                     const_value: #const_value_expr,
+                    extensions: #container_extensions_expr,
                     ..Default::default()
                 }
             }
         }
     })
 }
+
+/// Returns true if the container has `#[serde(untagged)]`.
+fn serde_untagged(attrs: &[Attribute]) -> SynResult<bool> {
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+        let metas: Punctuated<Meta, Token![,]> = attr.parse_args_with(parser)?;
+        if metas
+            .iter()
+            .any(|meta| matches!(meta, Meta::Path(p) if p.is_ident("untagged")))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The tag property of an internally tagged enum (`#[serde(tag = "...")]`), if any. Adjacently
+/// tagged enums (`tag` with `content`) are rejected.
+fn serde_tag(attrs: &[Attribute]) -> SynResult<Option<String>> {
+    let mut tag: Option<String> = None;
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+        let metas: Punctuated<Meta, Token![,]> = attr.parse_args_with(parser)?;
+        for meta in metas {
+            let Meta::NameValue(nv) = meta else {
+                continue;
+            };
+            if nv.path.is_ident("content") {
+                return Err(Error::new_spanned(
+                    nv,
+                    "ToJsonSchema derive does not support adjacently tagged enums",
+                ));
+            }
+            if nv.path.is_ident("tag")
+                && let syn::Expr::Lit(expr_lit) = nv.value
+                && let syn::Lit::Str(s) = expr_lit.lit
+            {
+                tag = Some(s.value());
+            }
+        }
+    }
+    Ok(tag)
+}
+
+/// Returns true if the container has the bare flag `#[json_schema(<key>)]` (e.g. `one_of`).
+fn container_flag(attrs: &[Attribute], key: &str) -> SynResult<bool> {
+    for attr in attrs {
+        if !attr.path().is_ident("json_schema") {
+            continue;
+        }
+        let parser = Punctuated::<Meta, Token![,]>::parse_terminated;
+        let metas: Punctuated<Meta, Token![,]> = attr.parse_args_with(parser)?;
+        if metas
+            .iter()
+            .any(|meta| matches!(meta, Meta::Path(p) if p.is_ident(key)))
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Schema expression for an untagged enum with single-field tuple variants: one `anyOf` branch
+/// per variant in declaration order (`oneOf` with `#[json_schema(one_of)]`). Variant types that
+/// are custom types go to `$defs` and are referenced with `$ref`, as struct fields do.
+fn union_schema_expr(
+    name: &Ident,
+    attrs: &[Attribute],
+    data_enum: &syn::DataEnum,
+) -> SynResult<TokenStream2> {
+    if !serde_untagged(attrs)? {
+        return Err(Error::new_spanned(
+            name,
+            "ToJsonSchema derive for an enum with data requires #[serde(untagged)]",
+        ));
+    }
+    let mut branches: Vec<TokenStream2> = Vec::new();
+    for variant in &data_enum.variants {
+        let ty: &Type = match &variant.fields {
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => &unnamed.unnamed[0].ty,
+            Fields::Unit | Fields::Unnamed(_) | Fields::Named(_) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "ToJsonSchema derive for an untagged enum only supports single-field tuple variants",
+                ));
+            }
+        };
+        // Peel `Box<T>` (recursive variants) so `T` is referenced through `$defs`.
+        let is_box: bool = matches!(ty, Type::Path(tp) if tp.path.segments.last().is_some_and(|s| s.ident == "Box"));
+        let effective_ty: &Type = if is_box {
+            inner_type_for_container(ty).unwrap_or(ty)
+        } else {
+            ty
+        };
+        if let Some(def_key) = def_key_for_type(effective_ty) {
+            let def_key_lit: LitStr = LitStr::new(&def_key, variant.ident.span());
+            let ref_lit: LitStr = LitStr::new(&format!("#/$defs/{def_key}"), variant.ident.span());
+            branches.push(quote! {
+                {
+                    if !defs.contains_key(&#def_key_lit.to_string()) {
+                        let raw = <#effective_ty as ::json_schema_rs::ToJsonSchema>::json_schema();
+                        let flat = ::json_schema_rs::reverse_code_gen::merge_nested_defs_into_root(raw, &mut defs);
+                        defs.insert(#def_key_lit.to_string(), flat);
+                    }
+                    ::json_schema_rs::JsonSchema {
+                        ref_: Some(#ref_lit.to_string()),
+                        ..::json_schema_rs::JsonSchema::default()
+                    }
+                }
+            });
+        } else {
+            branches.push(quote! {
+                {
+                    let raw = <#ty as ::json_schema_rs::ToJsonSchema>::json_schema();
+                    ::json_schema_rs::reverse_code_gen::merge_nested_defs_into_root(raw, &mut defs)
+                }
+            });
+        }
+    }
+    let field: TokenStream2 = if container_flag(attrs, "one_of")? {
+        quote! { one_of }
+    } else {
+        quote! { any_of }
+    };
+    Ok(quote! {
+        {
+            let mut defs = ::std::collections::BTreeMap::new();
+            let branches: ::std::vec::Vec<::json_schema_rs::JsonSchema> = vec![#(#branches),*];
+            ::json_schema_rs::JsonSchema {
+                #field: Some(branches),
+                defs: if defs.is_empty() { None } else { Some(defs) },
+                ..::json_schema_rs::JsonSchema::default()
+            }
+        }
+    })
+}
+
+/// Schema expression for an internally tagged enum (`#[serde(tag = "...")]`): one `oneOf` object
+/// branch per variant, holding the variant's fields plus the tag property pinned with `const` to
+/// the variant's serde name. Unit variants carry only the tag.
+fn tagged_union_schema_expr(
+    name: &Ident,
+    tag: &str,
+    data_enum: &syn::DataEnum,
+) -> SynResult<TokenStream2> {
+    let tag_lit: LitStr = LitStr::new(tag, name.span());
+    let mut branches: Vec<TokenStream2> = Vec::new();
+    let mut has_recursive_field: bool = false;
+    for variant in &data_enum.variants {
+        let fields: Vec<&Field> = match &variant.fields {
+            Fields::Named(named) => named.named.iter().collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "ToJsonSchema derive for an internally tagged enum only supports struct and unit variants",
+                ));
+            }
+        };
+        let ObjectFields {
+            property_inserts,
+            defs_inserts,
+            mut required_keys,
+            has_recursive_field: variant_is_recursive,
+        } = object_fields(fields, name)?;
+        has_recursive_field |= variant_is_recursive;
+        required_keys.insert(0, tag.to_string());
+        let required: TokenStream2 = required_expr(&required_keys, name);
+        let value_lit: LitStr = LitStr::new(&variant_external_name(variant)?, variant.ident.span());
+        branches.push(quote! {
+            {
+                let mut properties = ::json_schema_rs::PropertyMap::new();
+                #(#defs_inserts)*
+                properties.insert(#tag_lit.to_string(), ::json_schema_rs::JsonSchema {
+                    type_: Some("string".to_string()),
+                    const_value: Some(::serde_json::Value::String(#value_lit.to_string())),
+                    ..::json_schema_rs::JsonSchema::default()
+                });
+                #(#property_inserts)*
+                ::json_schema_rs::JsonSchema {
+                    type_: Some("object".to_string()),
+                    properties,
+                    additional_properties: Some(::json_schema_rs::json_schema::json_schema::AdditionalProperties::Forbid),
+                    required: #required,
+                    ..::json_schema_rs::JsonSchema::default()
+                }
+            }
+        });
+    }
+    let recursive_def_insert: TokenStream2 = if has_recursive_field {
+        let name_lit: LitStr = LitStr::new(&name.to_string(), name.span());
+        quote! {
+            defs.insert(#name_lit.to_string(), ::json_schema_rs::JsonSchema {
+                one_of: Some(branches.clone()),
+                ..::json_schema_rs::JsonSchema::default()
+            });
+        }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        {
+            let mut defs = ::std::collections::BTreeMap::new();
+            let branches: ::std::vec::Vec<::json_schema_rs::JsonSchema> = vec![#(#branches),*];
+            #recursive_def_insert
+            ::json_schema_rs::JsonSchema {
+                one_of: Some(branches),
+                defs: if defs.is_empty() { None } else { Some(defs) },
+                ..::json_schema_rs::JsonSchema::default()
+            }
+        }
+    })
+}
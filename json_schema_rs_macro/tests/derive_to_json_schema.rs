@@ -1,7 +1,7 @@
 //! Integration test: `#[derive(ToJsonSchema)]` produces the expected JSON Schema.
 
 use json_schema_rs::json_schema::json_schema::AdditionalProperties;
-use json_schema_rs::{JsonSchema, ToJsonSchema};
+use json_schema_rs::{JsonSchema, PropertyMap, ToJsonSchema};
 use json_schema_rs_macro::ToJsonSchema;
 use std::collections::{BTreeMap, HashSet};
 
@@ -95,7 +95,7 @@ fn derive_root_json_schema() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("id".to_string(), String::json_schema());
             m.insert("name".to_string(), Option::<String>::json_schema());
             m
@@ -115,7 +115,7 @@ fn derive_address_json_schema() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("city".to_string(), String::json_schema());
             m.insert("street".to_string(), String::json_schema());
             m
@@ -140,7 +140,7 @@ fn derive_nested_struct_emits_defs_and_ref() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "address".to_string(),
                 JsonSchema {
@@ -170,7 +170,7 @@ fn derive_two_fields_same_type_one_defs_entry() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "addr1".to_string(),
                 JsonSchema {
@@ -200,7 +200,7 @@ fn derive_recursive_type_emits_defs_and_ref_no_stack_overflow() {
     let tree_def_schema: JsonSchema = JsonSchema {
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "children".to_string(),
                 JsonSchema {
@@ -227,7 +227,7 @@ fn derive_recursive_type_emits_defs_and_ref_no_stack_overflow() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "children".to_string(),
                 JsonSchema {
@@ -261,7 +261,7 @@ fn derive_option_nested_struct_emits_defs() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "address".to_string(),
                 JsonSchema {
@@ -291,7 +291,7 @@ fn derive_vec_nested_struct_emits_defs() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "addresses".to_string(),
                 JsonSchema {
@@ -331,7 +331,7 @@ fn derive_three_level_nested_emits_flat_defs() {
             Some(m)
         },
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert(
                 "a".to_string(),
                 JsonSchema {
@@ -477,7 +477,7 @@ fn derive_field_minimum_maximum_integer() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("byte".to_string(), byte_schema);
             m
         },
@@ -505,7 +505,7 @@ fn derive_field_minimum_maximum_float() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("score".to_string(), score_schema);
             m
         },
@@ -548,7 +548,7 @@ fn derive_field_only_minimum() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("value".to_string(), value_schema);
             m
         },
@@ -575,7 +575,7 @@ fn derive_field_only_maximum() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("value".to_string(), value_schema);
             m
         },
@@ -843,7 +843,7 @@ fn derive_field_deprecated_emits_deprecated_in_schema() {
         schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
         type_: Some("object".to_string()),
         properties: {
-            let mut m = BTreeMap::new();
+            let mut m = PropertyMap::new();
             m.insert("legacy".to_string(), legacy_schema);
             m
         },
@@ -864,3 +864,185 @@ fn derive_deprecated_round_trip() {
     let actual: JsonSchema = parsed;
     assert_eq!(expected, actual);
 }
+
+#[derive(ToJsonSchema)]
+#[json_schema(extension("x-owner" = "team-a", "x-version" = 2))]
+#[expect(dead_code)]
+struct WithExtensions {
+    #[json_schema(extension("x-db" = {"column": "user_id", "indexed": true}))]
+    id: String,
+}
+
+#[test]
+fn derive_extension_attributes_populate_extensions() {
+    let mut id_schema: JsonSchema = String::json_schema();
+    id_schema.extensions.insert(
+        "x-db".to_string(),
+        serde_json::json!({"column": "user_id", "indexed": true}),
+    );
+    let expected: JsonSchema = JsonSchema {
+        schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
+        type_: Some("object".to_string()),
+        properties: {
+            let mut m = PropertyMap::new();
+            m.insert("id".to_string(), id_schema);
+            m
+        },
+        additional_properties: Some(AdditionalProperties::Forbid),
+        required: Some(vec!["id".to_string()]),
+        extensions: BTreeMap::from([
+            ("x-owner".to_string(), serde_json::json!("team-a")),
+            ("x-version".to_string(), serde_json::json!(2)),
+        ]),
+        ..Default::default()
+    };
+    let actual: JsonSchema = WithExtensions::json_schema();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn derive_extensions_round_trip() {
+    let schema: JsonSchema = WithExtensions::json_schema();
+    let json: String = (&schema).try_into().expect("serialize");
+    assert!(json.contains(r#""x-owner":"team-a""#), "{json}");
+    let parsed: JsonSchema = JsonSchema::try_from(json.as_str()).expect("parse");
+    let expected: JsonSchema = schema;
+    let actual: JsonSchema = parsed;
+    assert_eq!(expected, actual);
+}
+
+#[derive(serde::Deserialize, ToJsonSchema)]
+#[expect(dead_code)]
+struct Point {
+    x: i64,
+}
+
+#[derive(serde::Deserialize, ToJsonSchema)]
+#[serde(untagged)]
+#[expect(dead_code)]
+enum PointOrName {
+    Point(Point),
+    Name(String),
+}
+
+#[derive(serde::Deserialize, ToJsonSchema)]
+#[serde(untagged)]
+#[json_schema(one_of)]
+#[expect(dead_code)]
+enum IdOrList {
+    Id(i64),
+    List(Vec<String>),
+}
+
+#[test]
+fn derive_untagged_enum_emits_any_of_with_defs() {
+    let expected: JsonSchema = JsonSchema {
+        schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
+        any_of: Some(vec![
+            JsonSchema {
+                ref_: Some("#/$defs/Point".to_string()),
+                ..Default::default()
+            },
+            String::json_schema(),
+        ]),
+        defs: Some(BTreeMap::from([(
+            "Point".to_string(),
+            Point::json_schema(),
+        )])),
+        ..Default::default()
+    };
+    let actual: JsonSchema = PointOrName::json_schema();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn derive_untagged_enum_one_of_attribute_emits_one_of() {
+    let expected: JsonSchema = JsonSchema {
+        schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
+        one_of: Some(vec![i64::json_schema(), Vec::<String>::json_schema()]),
+        ..Default::default()
+    };
+    let actual: JsonSchema = IdOrList::json_schema();
+    assert_eq!(expected, actual);
+}
+
+#[derive(serde::Deserialize, ToJsonSchema)]
+#[serde(tag = "type")]
+#[expect(dead_code)]
+enum Shape {
+    #[serde(rename = "circle")]
+    Circle {
+        radius: f64,
+    },
+    Empty,
+}
+
+#[test]
+fn derive_internally_tagged_enum_emits_one_of_with_const_tags() {
+    let tag = |value: &str| JsonSchema {
+        type_: Some("string".to_string()),
+        const_value: Some(serde_json::Value::String(value.to_string())),
+        ..Default::default()
+    };
+    let expected: JsonSchema = JsonSchema {
+        schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
+        one_of: Some(vec![
+            JsonSchema {
+                type_: Some("object".to_string()),
+                properties: PropertyMap::from([
+                    ("type".to_string(), tag("circle")),
+                    ("radius".to_string(), f64::json_schema()),
+                ]),
+                additional_properties: Some(AdditionalProperties::Forbid),
+                required: Some(vec!["type".to_string(), "radius".to_string()]),
+                ..Default::default()
+            },
+            JsonSchema {
+                type_: Some("object".to_string()),
+                properties: PropertyMap::from([("type".to_string(), tag("Empty"))]),
+                additional_properties: Some(AdditionalProperties::Forbid),
+                required: Some(vec!["type".to_string()]),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    };
+    let actual: JsonSchema = Shape::json_schema();
+    assert_eq!(expected, actual);
+}
+
+#[derive(ToJsonSchema)]
+#[json_schema(title = "UserId", description = "A user id.")]
+#[expect(dead_code)]
+struct UserId(String);
+
+#[test]
+fn derive_newtype_struct_emits_field_schema_with_container_attributes() {
+    let expected: JsonSchema = JsonSchema {
+        schema: Some("https://json-schema.org/draft/2020-12/schema".to_string()),
+        type_: Some("string".to_string()),
+        title: Some("UserId".to_string()),
+        description: Some("A user id.".to_string()),
+        ..Default::default()
+    };
+    let actual: JsonSchema = UserId::json_schema();
+    assert_eq!(expected, actual);
+}
+
+#[cfg(feature = "preserve_order")]
+#[derive(ToJsonSchema)]
+#[expect(dead_code)]
+struct Person {
+    name: String,
+    age: i64,
+    email: Option<String>,
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn derive_properties_follow_struct_field_order() {
+    let schema: JsonSchema = Person::json_schema();
+    let expected: Vec<&str> = vec!["name", "age", "email"];
+    let actual: Vec<&str> = schema.properties.keys().map(String::as_str).collect();
+    assert_eq!(expected, actual);
+}
@@ -0,0 +1,21 @@
+//! Integration test: `json_schema_to_rust!` with `date-time` and `date` properties.
+//! Only compiled and run when the `chrono` feature is enabled.
+#![cfg(feature = "chrono")]
+
+use json_schema_rs_macro::json_schema_to_rust;
+
+json_schema_to_rust!(
+    r#"{"type":"object","properties":{"at":{"type":"string","format":"date-time"},"on":{"type":"string","format":"date"}},"required":["at","on"]}"#
+);
+
+#[test]
+fn chrono_macro_round_trip() {
+    let json = r#"{"at":"1996-12-19T16:39:57-08:00","on":"2020-02-29"}"#;
+    let root: schema_0::Root = serde_json::from_str(json).unwrap();
+    let expected_on = chrono::NaiveDate::from_ymd_opt(2020, 2, 29).unwrap();
+    assert_eq!(root.on, expected_on);
+    assert_eq!(root.at.offset().local_minus_utc(), -8 * 3600);
+    let serialized = serde_json::to_string(&root).unwrap();
+    let parsed: schema_0::Root = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(parsed.at, root.at);
+}
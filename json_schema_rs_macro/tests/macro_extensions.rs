@@ -0,0 +1,20 @@
+//! Integration test: `json_schema_to_rust!` carries `x-*` extension keywords into the generated
+//! types' derived schema.
+
+use json_schema_rs::ToJsonSchema;
+use json_schema_rs_macro::json_schema_to_rust;
+
+json_schema_to_rust!(
+    r#"{"type":"object","x-owner":"team-a","properties":{"id":{"type":"string","x-pii":true}},"required":["id"]}"#
+);
+
+#[test]
+fn extensions_macro_round_trip() {
+    let schema = schema_0::Root::json_schema();
+    assert_eq!(
+        schema.extensions.get("x-owner"),
+        Some(&serde_json::json!("team-a"))
+    );
+    let id = schema.properties.get("id").expect("id property");
+    assert_eq!(id.extensions.get("x-pii"), Some(&serde_json::json!(true)));
+}
@@ -0,0 +1,27 @@
+//! Integration test: `json_schema_to_rust!` with `ipv4` and `ipv6` properties.
+
+use json_schema_rs_macro::json_schema_to_rust;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+json_schema_to_rust!(
+    r#"{"type":"object","properties":{"v4":{"type":"string","format":"ipv4"},"v6":{"type":"string","format":"ipv6"}},"required":["v4"]}"#
+);
+
+#[test]
+fn ip_address_macro_round_trip() {
+    let json = r#"{"v4":"192.168.0.1","v6":"::1"}"#;
+    let root: schema_0::Root = serde_json::from_str(json).unwrap();
+    assert_eq!(root.v4, Ipv4Addr::new(192, 168, 0, 1));
+    assert_eq!(root.v6, Some(Ipv6Addr::LOCALHOST));
+    let serialized = serde_json::to_string(&root).unwrap();
+    let parsed: schema_0::Root = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(parsed.v4, root.v4);
+    assert_eq!(parsed.v6, root.v6);
+}
+
+#[test]
+fn ip_address_macro_rejects_malformed_address() {
+    let json = r#"{"v4":"256.0.0.1"}"#;
+    let result: Result<schema_0::Root, _> = serde_json::from_str(json);
+    assert!(result.is_err());
+}